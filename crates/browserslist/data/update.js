@@ -0,0 +1,19 @@
+// Regenerates browsers.json from the caniuse data, e.g.
+//
+//   npm install caniuse-db
+//   node update.js node_modules/caniuse-db/fulldata-json/data-2.0.json
+
+const fs = require('fs')
+const path = require('path')
+
+const data = JSON.parse(fs.readFileSync(process.argv[2], 'utf8'))
+const browsers = Object.keys(data.agents)
+  .sort()
+  .map(name => {
+    const releases = data.agents[name].version_list.map(release => {
+      const fields = [release.version, release.global_usage, release.release_date]
+      return `[${fields.map(field => JSON.stringify(field)).join(', ')}]`
+    })
+    return `  "${name}": [\n    ${releases.join(',\n    ')}\n  ]`
+  })
+fs.writeFileSync(path.join(__dirname, 'browsers.json'), `{\n${browsers.join(',\n')}\n}\n`)
@@ -0,0 +1,109 @@
+use crate::query::Error;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Names of the files with only a query, looked up before `package.json`.
+pub const FILE_NAMES: &[&str] = &[".browserslistrc", "browserslist"];
+
+/// A query found in a configuration file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+  pub path: PathBuf,
+  pub query: String,
+}
+
+/// Looks for the query of the `env` environment, e.g. `production`, in
+/// `dir` and its ancestors: in a `.browserslistrc` or `browserslist` file,
+/// or under the `browserslist` key of a `package.json`.
+///
+/// A file without a query for the environment gives `defaults`.
+pub fn find_config(dir: &Path, env: &str) -> Result<Option<Config>, Error> {
+  let dir = std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf());
+  for dir in dir.ancestors() {
+    for name in FILE_NAMES {
+      let path = dir.join(name);
+      if path.is_file() {
+        let query = parse_config(&read(&path)?, env);
+        return Ok(Some(config(path, query)));
+      }
+    }
+    let path = dir.join("package.json");
+    if path.is_file() {
+      let package: Value = serde_json::from_str(&read(&path)?)
+        .map_err(|error| Error::new(format!("{}: {}", path.display(), error)))?;
+      if let Some(value) = package.get("browserslist") {
+        let value = match value {
+          Value::Object(envs) => envs.get(env).or_else(|| envs.get("defaults")),
+          value => Some(value),
+        };
+        let query = match value {
+          None => None,
+          Some(Value::String(query)) => Some(query.clone()),
+          Some(Value::Array(queries)) => Some(
+            queries
+              .iter()
+              .filter_map(Value::as_str)
+              .collect::<Vec<_>>()
+              .join(", "),
+          ),
+          Some(_) => {
+            return Err(Error::new(format!(
+              "{}: `browserslist` must be a query or a list of queries",
+              path.display()
+            )))
+          }
+        };
+        return Ok(Some(config(path, query)));
+      }
+    }
+  }
+  Ok(None)
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+  fs::read_to_string(path)
+    .map_err(|error| Error::new(format!("Can not read {}: {}", path.display(), error)))
+}
+
+fn config(path: PathBuf, query: Option<String>) -> Config {
+  Config {
+    path,
+    query: query
+      .filter(|query| !query.trim().is_empty())
+      .unwrap_or_else(|| "defaults".to_string()),
+  }
+}
+
+/// Parses a `.browserslistrc`, with a query per line, `#` comments and
+/// `[production staging]` sections for environments. The queries of the
+/// section of `env` replace those before any section.
+pub fn parse_config(text: &str, env: &str) -> Option<String> {
+  let mut defaults = vec![];
+  let mut selected: Option<Vec<&str>> = None;
+  let mut current: Option<bool> = None;
+  for line in text.lines() {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    if line.is_empty() {
+      continue;
+    }
+    if let Some(names) = line
+      .strip_prefix('[')
+      .and_then(|line| line.strip_suffix(']'))
+    {
+      let matches = names.split_whitespace().any(|name| name == env);
+      if matches {
+        selected.get_or_insert_with(Vec::new);
+      }
+      current = Some(matches);
+      continue;
+    }
+    match current {
+      None => defaults.push(line),
+      Some(true) => selected.get_or_insert_with(Vec::new).push(line),
+      Some(false) => {}
+    }
+  }
+  let queries = selected.unwrap_or(defaults);
+  (!queries.is_empty()).then(|| queries.join(", "))
+}
@@ -0,0 +1,65 @@
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+
+/// A version of a browser in the usage data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Release {
+  /// The version as caniuse names it, e.g. `17.4`, `15.2-15.3` or `TP`.
+  pub version: String,
+  /// The share of global usage, in percent.
+  pub usage: f64,
+  /// The release date as a Unix timestamp, `None` for unreleased versions.
+  pub released: Option<i64>,
+}
+
+/// The caniuse usage data, embedded so that no network access is needed.
+/// It is under the CC-BY-4.0 and frozen at May 2024, see `data/README.md`
+/// for how to regenerate it.
+static BROWSERS: Lazy<BTreeMap<String, Vec<Release>>> = Lazy::new(|| {
+  let data: BTreeMap<String, Vec<(String, f64, Option<i64>)>> =
+    serde_json::from_str(include_str!("../data/browsers.json")).unwrap();
+  data
+    .into_iter()
+    .map(|(name, releases)| {
+      let releases = releases
+        .into_iter()
+        .map(|(version, usage, released)| Release {
+          version,
+          usage,
+          released,
+        })
+        .collect();
+      (name, releases)
+    })
+    .collect()
+});
+
+/// The Firefox Extended Support Releases at the time of the usage data,
+/// which has none of them. Update it with the data.
+pub(crate) const FIREFOX_ESR: &[&str] = &["115"];
+
+/// The browsers of the usage data by caniuse name, e.g. `ios_saf`, with
+/// their versions from the oldest to the newest.
+pub fn browsers() -> &'static BTreeMap<String, Vec<Release>> {
+  &BROWSERS
+}
+
+/// The caniuse name of a browser name of a query.
+pub(crate) fn normalize(name: &str) -> Option<&'static str> {
+  let name = name.to_ascii_lowercase();
+  let name = match name.as_str() {
+    "fx" | "ff" => "firefox",
+    "explorer" => "ie",
+    "blackberry" => "bb",
+    "explorermobile" => "ie_mob",
+    "operamini" => "op_mini",
+    "operamobile" => "op_mob",
+    "chromeandroid" => "and_chr",
+    "firefoxandroid" => "and_ff",
+    "ucandroid" => "and_uc",
+    "qqandroid" => "and_qq",
+    "ios" => "ios_saf",
+    name => name,
+  };
+  BROWSERS.get_key_value(name).map(|(name, _)| name.as_str())
+}
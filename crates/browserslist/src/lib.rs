@@ -0,0 +1,9 @@
+mod config;
+mod data;
+mod query;
+mod version;
+
+pub use crate::config::{find_config, parse_config, Config, FILE_NAMES};
+pub use crate::data::{browsers, Release};
+pub use crate::query::{resolve, targets_or_defaults, Error, Target, DEFAULTS};
+pub use crate::version::Version;
@@ -0,0 +1,445 @@
+use crate::data::{browsers, normalize, Release, FIREFOX_ESR};
+use crate::version::Version;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The browsers of `defaults`.
+pub const DEFAULTS: &str = "> 0.5%, last 2 versions, Firefox ESR, not dead";
+
+/// The browsers of `dead`, without updates for 24 months.
+const DEAD: &str = "Baidu >= 0, ie <= 11, ie_mob <= 11, bb <= 10, op_mob <= 12.1, samsung 4";
+
+/// A version of a browser selected by a query, e.g. `safari 17.4`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Target {
+  /// The caniuse name of the browser, e.g. `ios_saf`.
+  pub browser: String,
+  /// The version as caniuse names it, e.g. `15.2-15.3`.
+  pub version: String,
+}
+
+impl Target {
+  pub fn new(browser: &str, version: &str) -> Self {
+    Self {
+      browser: browser.to_string(),
+      version: version.to_string(),
+    }
+  }
+
+  /// The numeric version, see [`Version::parse`].
+  pub fn numeric_version(&self) -> Option<Version> {
+    Version::parse(&self.version)
+  }
+
+  /// The browser in the support tables of the plugins, which only have
+  /// the versions of the desktop browsers for Chrome, Firefox and Opera on
+  /// Android, as caniuse only has the last version of those.
+  pub fn table_browser(&self) -> &str {
+    match self.browser.as_str() {
+      "and_chr" => "chrome",
+      "and_ff" => "firefox",
+      "op_mob" => "opera",
+      browser => browser,
+    }
+  }
+}
+
+/// The first targets set, e.g. of the options of a plugin or else of the
+/// processor, or the targets of [`DEFAULTS`] if none are.
+pub fn targets_or_defaults(targets: Option<&[Target]>) -> Cow<'_, [Target]> {
+  match targets {
+    Some(targets) => Cow::Borrowed(targets),
+    None => Cow::Owned(resolve(DEFAULTS).unwrap()),
+  }
+}
+
+impl fmt::Display for Target {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} {}", self.browser, self.version)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+  pub reason: String,
+}
+
+impl Error {
+  pub(crate) fn new(reason: String) -> Self {
+    Self { reason }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(&self.reason)
+  }
+}
+
+impl std::error::Error for Error {}
+
+/// Selects the browsers of a browserslist query, like
+/// `> 0.5%, last 2 versions, not ie 11`, sorted by name and from the newest
+/// version to the oldest.
+///
+/// The queries are separated by `,` or `or` to add their browsers, and by
+/// `and` to keep only the browsers of both sides. A query starting with
+/// `not` removes its browsers from the ones before, so that it can not come
+/// first: `not dead` alone is an error, write `defaults, not dead`. The
+/// supported queries are:
+///
+/// - `last 2 versions` and `last 2 major versions`, of every browser;
+/// - `last 2 chrome versions` and `last 2 chrome major versions`;
+/// - `last 1 version`, `last 1 chrome version` and so on, in the singular;
+/// - `> 0.5%`, with `>=`, `<` and `<=` too, by global usage;
+/// - `cover 99.5%`, the most used versions up to that usage;
+/// - `safari >= 15`, with `>`, `<` and `<=` too, of the released versions;
+/// - `ie 11`, a single version, and `chrome 100-110`, a range of released
+///   versions;
+/// - `since 2020`, `since 2020-06` and `since 2020-06-15`, by release date,
+///   and `last 2 years`, which is an error when the usage data is older than
+///   that;
+/// - `unreleased versions` and `unreleased chrome versions`;
+/// - `Firefox ESR`, `dead` and `defaults`, see [`DEFAULTS`].
+///
+/// Browser names are case-insensitive and can be aliases like `ff` or `ios`.
+pub fn resolve(query: &str) -> Result<Vec<Target>, Error> {
+  let mut result = BTreeSet::new();
+  for (i, (and, query)) in split(&query.to_ascii_lowercase()).into_iter().enumerate() {
+    let (not, query) = match query.strip_prefix("not ") {
+      Some(query) => (true, query),
+      None => (false, query.as_str()),
+    };
+    if not && i == 0 {
+      return Err(Error::new(format!(
+        "`not {}` must follow the queries it removes browsers from",
+        query
+      )));
+    }
+    let targets: BTreeSet<Target> = select(query)?.into_iter().collect();
+    if not {
+      result.retain(|target| !targets.contains(target));
+    } else if and {
+      result.retain(|target| targets.contains(target));
+    } else {
+      result.extend(targets);
+    }
+  }
+  let mut result: Vec<Target> = result.into_iter().collect();
+  result.sort_by_key(|target| {
+    (
+      target.browser.clone(),
+      Reverse(target.numeric_version().unwrap_or(Version(u32::MAX, 0, 0))),
+    )
+  });
+  Ok(result)
+}
+
+/// The queries of `query`, with `true` for those joined with `and` to the
+/// ones before.
+fn split(query: &str) -> Vec<(bool, String)> {
+  let mut queries = vec![];
+  for part in query.split(',') {
+    let mut and = false;
+    let mut words = vec![];
+    for word in part.split_whitespace() {
+      match word {
+        "or" | "and" if !words.is_empty() => {
+          queries.push((and, words.join(" ")));
+          words.clear();
+          and = word == "and";
+        }
+        word => words.push(word),
+      }
+    }
+    if !words.is_empty() {
+      queries.push((and, words.join(" ")));
+    }
+  }
+  queries
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+  Gt,
+  Ge,
+  Lt,
+  Le,
+}
+
+impl Op {
+  /// The operator at the start of `query`, and the rest of it.
+  fn strip(query: &str) -> Option<(Op, &str)> {
+    [(">=", Op::Ge), ("<=", Op::Le), (">", Op::Gt), ("<", Op::Lt)]
+      .into_iter()
+      .find_map(|(text, op)| query.strip_prefix(text).map(|rest| (op, rest.trim())))
+  }
+
+  fn compare<T: PartialOrd>(self, left: T, right: T) -> bool {
+    match self {
+      Op::Gt => left > right,
+      Op::Ge => left >= right,
+      Op::Lt => left < right,
+      Op::Le => left <= right,
+    }
+  }
+}
+
+/// The browsers of a single query, without `not`.
+fn select(query: &str) -> Result<Vec<Target>, Error> {
+  let words: Vec<&str> = query.split(' ').collect();
+  match words.as_slice() {
+    ["last", count, "versions" | "version"] => {
+      let count = parse_count(count)?;
+      all(|name, releases| last(name, releases, count, false))
+    }
+    ["last", count, "major", "versions" | "version"] => {
+      let count = parse_count(count)?;
+      all(|name, releases| last(name, releases, count, true))
+    }
+    ["last", count, name, "versions" | "version"] => {
+      let (name, releases) = browser(name)?;
+      last(name, releases, parse_count(count)?, false)
+    }
+    ["last", count, name, "major", "versions" | "version"] => {
+      let (name, releases) = browser(name)?;
+      last(name, releases, parse_count(count)?, true)
+    }
+    ["defaults"] => resolve(DEFAULTS),
+    ["dead"] => resolve(DEAD),
+    [name, "esr"] if normalize(name) == Some("firefox") => Ok(
+      FIREFOX_ESR
+        .iter()
+        .map(|version| Target::new("firefox", version))
+        .collect(),
+    ),
+    ["unreleased", "versions"] => all(|name, releases| Ok(unreleased(name, releases))),
+    ["unreleased", name, "versions"] => {
+      let (name, releases) = browser(name)?;
+      Ok(unreleased(name, releases))
+    }
+    ["since", date] => released_since(parse_date(date)?),
+    ["last", years, "years" | "year"] => {
+      let years: f64 = years
+        .parse()
+        .map_err(|_| Error::new(format!("`{}` is not a number of years", years)))?;
+      let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64);
+      let since = now - (years * 365.259641 * 24.0 * 3600.0) as i64;
+      if last_release() < since {
+        return Err(Error::new(format!(
+          "The browser data is older than `{}`, its last release is before the window",
+          query
+        )));
+      }
+      released_since(since)
+    }
+    ["cover", coverage] => {
+      let coverage = coverage
+        .strip_suffix('%')
+        .and_then(|coverage| coverage.parse::<f64>().ok())
+        .ok_or_else(|| Error::new(format!("Unknown browser query `{}`", query)))?;
+      Ok(cover(coverage))
+    }
+    _ => {
+      if let Some((op, usage)) = Op::strip(query) {
+        let usage = usage
+          .strip_suffix('%')
+          .and_then(|usage| usage.trim().parse::<f64>().ok())
+          .ok_or_else(|| Error::new(format!("Unknown browser query `{}`", query)))?;
+        return all(|name, releases| {
+          Ok(
+            releases
+              .iter()
+              .filter(|release| op.compare(release.usage, usage))
+              .map(|release| Target::new(name, &release.version))
+              .collect(),
+          )
+        });
+      }
+      match query.split_once(' ') {
+        Some((name, version)) => {
+          let (name, releases) = browser(name)?;
+          versions(name, releases, version.trim())
+        }
+        None => Err(Error::new(format!("Unknown browser query `{}`", query))),
+      }
+    }
+  }
+}
+
+/// The targets of `select` for every browser.
+fn all<F>(mut select: F) -> Result<Vec<Target>, Error>
+where
+  F: FnMut(&str, &[Release]) -> Result<Vec<Target>, Error>,
+{
+  let mut targets = vec![];
+  for (name, releases) in browsers() {
+    targets.extend(select(name, releases)?);
+  }
+  Ok(targets)
+}
+
+fn browser(name: &str) -> Result<(&'static str, &'static [Release]), Error> {
+  match normalize(name) {
+    Some(name) => Ok((name, &browsers()[name])),
+    None => Err(Error::new(format!("Unknown browser {}", name))),
+  }
+}
+
+fn parse_count(count: &str) -> Result<usize, Error> {
+  count
+    .parse()
+    .map_err(|_| Error::new(format!("`{}` is not a number of versions", count)))
+}
+
+/// The last `count` released versions, or the versions of the last `count`
+/// major versions.
+fn last(name: &str, releases: &[Release], count: usize, major: bool) -> Result<Vec<Target>, Error> {
+  let released: Vec<&Release> = releases
+    .iter()
+    .filter(|release| release.released.is_some())
+    // The Android browser follows Chrome since version 37, its older
+    // versions are not counted as the last ones.
+    .filter(|release| {
+      name != "android" || Version::parse(&release.version).is_some_and(|v| v.0 >= 37)
+    })
+    .collect();
+  let selected = if major {
+    let mut majors: Vec<Option<u32>> = released
+      .iter()
+      .map(|release| Version::parse(&release.version).map(|version| version.0))
+      .collect();
+    majors.dedup();
+    let majors = &majors[majors.len().saturating_sub(count)..];
+    released
+      .into_iter()
+      .filter(|release| majors.contains(&Version::parse(&release.version).map(|v| v.0)))
+      .collect()
+  } else {
+    released[released.len().saturating_sub(count)..].to_vec()
+  };
+  Ok(
+    selected
+      .into_iter()
+      .map(|release| Target::new(name, &release.version))
+      .collect(),
+  )
+}
+
+/// The versions without a release date.
+fn unreleased(name: &str, releases: &[Release]) -> Vec<Target> {
+  releases
+    .iter()
+    .filter(|release| release.released.is_none())
+    .map(|release| Target::new(name, &release.version))
+    .collect()
+}
+
+/// The release date of the newest version in the usage data.
+fn last_release() -> i64 {
+  browsers()
+    .values()
+    .flatten()
+    .filter_map(|release| release.released)
+    .max()
+    .unwrap_or(0)
+}
+
+/// The versions released at `time` or after, a Unix timestamp.
+fn released_since(time: i64) -> Result<Vec<Target>, Error> {
+  all(|name, releases| {
+    Ok(
+      releases
+        .iter()
+        .filter(|release| release.released.is_some_and(|released| released >= time))
+        .map(|release| Target::new(name, &release.version))
+        .collect(),
+    )
+  })
+}
+
+/// The Unix timestamp of a `YYYY`, `YYYY-MM` or `YYYY-MM-DD` date, in UTC.
+fn parse_date(date: &str) -> Result<i64, Error> {
+  let error = || Error::new(format!("Unknown date {}", date));
+  let mut parts = date
+    .split('-')
+    .map(|part| part.parse::<i64>().map_err(|_| error()));
+  let year = parts.next().ok_or_else(error)??;
+  let month = parts.next().transpose()?.unwrap_or(1);
+  let day = parts.next().transpose()?.unwrap_or(1);
+  if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+    return Err(error());
+  }
+  // Days from the civil calendar, with years starting in March.
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  Ok((era * 146097 + day_of_era - 719468) * 86400)
+}
+
+/// The most used versions, until their usage reaches `coverage` percent.
+fn cover(coverage: f64) -> Vec<Target> {
+  let mut releases: Vec<(&str, &Release)> = browsers()
+    .iter()
+    .flat_map(|(name, releases)| releases.iter().map(move |release| (name.as_str(), release)))
+    .collect();
+  releases.sort_by(|a, b| b.1.usage.total_cmp(&a.1.usage));
+  let mut total = 0.0;
+  let mut targets = vec![];
+  for (name, release) in releases {
+    if total >= coverage || release.usage == 0.0 {
+      break;
+    }
+    total += release.usage;
+    targets.push(Target::new(name, &release.version));
+  }
+  targets
+}
+
+/// The versions of `version`, which may be a comparison, a range or a
+/// single version. Comparisons and ranges only select released versions,
+/// a single version may be an unreleased one.
+fn versions(name: &str, releases: &[Release], version: &str) -> Result<Vec<Target>, Error> {
+  let parse = |version: &str| {
+    Version::parse(version)
+      .ok_or_else(|| Error::new(format!("Unknown version {} of {}", version, name)))
+  };
+  let released = || releases.iter().filter(|release| release.released.is_some());
+  let matches: Vec<&Release> = if let Some((op, version)) = Op::strip(version) {
+    let version = parse(version)?;
+    released()
+      .filter(|release| Version::parse(&release.version).is_some_and(|v| op.compare(v, version)))
+      .collect()
+  } else if let Some(release) = releases.iter().find(|release| {
+    release
+      .version
+      .to_ascii_lowercase()
+      .split('-')
+      .any(|part| part == version)
+  }) {
+    vec![release]
+  } else if let Some((from, to)) = version.split_once('-') {
+    let (from, to) = (parse(from)?, parse(to)?);
+    released()
+      .filter(|release| Version::parse(&release.version).is_some_and(|v| v >= from && v <= to))
+      .collect()
+  } else {
+    return Err(Error::new(format!(
+      "Unknown version {} of {}",
+      version, name
+    )));
+  };
+  Ok(
+    matches
+      .into_iter()
+      .map(|release| Target::new(name, &release.version))
+      .collect(),
+  )
+}
@@ -0,0 +1,39 @@
+use std::fmt;
+
+/// A numeric browser version, e.g. `15.2` for Safari or `4.4.3` for the
+/// Android browser.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version(pub u32, pub u32, pub u32);
+
+impl Version {
+  /// Parses a version of the usage data.
+  ///
+  /// Ranges like `15.2-15.3` give their first version, and versions that
+  /// are not numbers, like `TP` or `all`, give `None`.
+  pub fn parse(version: &str) -> Option<Version> {
+    let first = version.split('-').next()?;
+    let mut parts = first.split('.');
+    let mut next = || match parts.next() {
+      Some(part) => part.parse().ok(),
+      None => Some(0),
+    };
+    let version = Version(next()?, next()?, next()?);
+    match parts.next() {
+      Some(_) => None,
+      None => Some(version),
+    }
+  }
+}
+
+impl fmt::Display for Version {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.0)?;
+    if self.1 > 0 || self.2 > 0 {
+      write!(f, ".{}", self.1)?;
+    }
+    if self.2 > 0 {
+      write!(f, ".{}", self.2)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,178 @@
+use browserslist::{
+  find_config, parse_config, resolve, targets_or_defaults, Target, Version, DEFAULTS,
+};
+use std::fs;
+
+fn names(query: &str) -> Vec<String> {
+  resolve(query)
+    .unwrap()
+    .iter()
+    .map(Target::to_string)
+    .collect()
+}
+
+#[test]
+fn parses_versions() {
+  assert_eq!(Version::parse("17.4"), Some(Version(17, 4, 0)));
+  assert_eq!(Version::parse("4.4.3-4.4.4"), Some(Version(4, 4, 3)));
+  assert_eq!(Version::parse("TP"), None);
+  assert_eq!(Version(15, 2, 0).to_string(), "15.2");
+}
+
+#[test]
+fn resolves_queries() {
+  assert_eq!(
+    names("last 2 Chrome versions"),
+    ["chrome 125", "chrome 124"]
+  );
+  assert_eq!(names("last 1 ff versions"), ["firefox 126"]);
+  assert_eq!(names("last 1 chrome version"), ["chrome 125"]);
+  assert_eq!(names("last 1 version"), names("last 1 versions"));
+  assert_eq!(
+    names("last 1 safari major version"),
+    names("last 1 safari major versions")
+  );
+  assert_eq!(names("ie 11, IE 10"), ["ie 11", "ie 10"]);
+  assert_eq!(names("ios 15.3"), ["ios_saf 15.2-15.3"]);
+  assert_eq!(
+    names("safari >= 17.3 and > 0.1%"),
+    ["safari 17.5", "safari 17.4"]
+  );
+  assert_eq!(names("safari >= 17.5"), ["safari 17.5"]);
+  assert_eq!(names("chrome > 124"), ["chrome 125"]);
+  assert_eq!(names("chrome 125-130"), ["chrome 125"]);
+  assert_eq!(names("chrome 126"), ["chrome 126"]);
+  assert_eq!(
+    names("chrome 120-122"),
+    ["chrome 122", "chrome 121", "chrome 120"]
+  );
+  assert_eq!(
+    names("last 2 major versions and last 1 safari major versions"),
+    [
+      "safari 17.5",
+      "safari 17.4",
+      "safari 17.3",
+      "safari 17.2",
+      "safari 17.1",
+      "safari 17.0"
+    ]
+  );
+  assert_eq!(
+    names("last 2 firefox versions, not firefox 125"),
+    ["firefox 126"]
+  );
+  assert_eq!(names("last 1 android versions"), ["android 125"]);
+  assert!(names("> 0.5%").contains(&"chrome 125".to_string()));
+}
+
+#[test]
+fn reports_invalid_queries() {
+  assert_eq!(
+    resolve("netscape 4").unwrap_err().reason,
+    "Unknown browser netscape"
+  );
+  assert_eq!(
+    resolve("ie 12").unwrap_err().reason,
+    "Unknown version 12 of ie"
+  );
+  assert!(resolve("not ie 11").is_err());
+  assert_eq!(
+    resolve("not dead").unwrap_err().reason,
+    "`not dead` must follow the queries it removes browsers from"
+  );
+  assert!(resolve("last two versions").is_err());
+}
+
+#[test]
+fn resolves_special_queries() {
+  let defaults = names("defaults");
+  assert!(defaults.contains(&"firefox 115".to_string()));
+  assert!(!defaults.contains(&"ie 11".to_string()));
+  assert_eq!(
+    names("defaults"),
+    names("> 0.5%, last 2 versions, Firefox ESR, not dead")
+  );
+  assert!(names("dead").contains(&"ie 11".to_string()));
+  assert_eq!(names("firefox esr"), ["firefox 115"]);
+  assert_eq!(
+    names("unreleased chrome versions"),
+    ["chrome 128", "chrome 127", "chrome 126"]
+  );
+  assert!(names("unreleased versions").contains(&"safari 17.6".to_string()));
+  assert_eq!(names("since 2024-05-01 and chrome > 0"), ["chrome 125"]);
+  assert!(names("last 100 years").contains(&"ie 6".to_string()));
+  // The usage data is from May 2024.
+  assert!(resolve("last 0.1 years")
+    .unwrap_err()
+    .reason
+    .starts_with("The browser data is older than `last 0.1 years`"));
+  assert_eq!(names("cover 50%"), ["and_chr 125", "chrome 124"]);
+  assert!(resolve("since 2024-13-01").is_err());
+}
+
+#[test]
+fn falls_back_to_defaults() {
+  let targets = [Target::new("and_chr", "125")];
+  assert_eq!(&*targets_or_defaults(Some(&targets)), targets);
+  assert_eq!(
+    targets_or_defaults(None).into_owned(),
+    resolve(DEFAULTS).unwrap()
+  );
+  assert_eq!(targets[0].table_browser(), "chrome");
+  assert_eq!(Target::new("ios_saf", "17.4").table_browser(), "ios_saf");
+}
+
+#[test]
+fn parses_config_files() {
+  let text = "# supported\n> 1%\nlast 2 versions # recent\n\n[development test]\nlast 1 chrome version\n\n[production]\n";
+  assert_eq!(
+    parse_config(text, "staging").as_deref(),
+    Some("> 1%, last 2 versions")
+  );
+  assert_eq!(
+    parse_config(text, "test").as_deref(),
+    Some("last 1 chrome version")
+  );
+  assert_eq!(parse_config(text, "production"), None);
+  assert_eq!(parse_config("# nothing", "production"), None);
+}
+
+#[test]
+fn finds_config_files() {
+  let dir = tempfile::tempdir().unwrap();
+  let nested = dir.path().join("src/styles");
+  fs::create_dir_all(&nested).unwrap();
+  assert_eq!(find_config(&nested, "production").unwrap(), None);
+
+  fs::write(
+    dir.path().join("package.json"),
+    r#"{ "browserslist": { "production": ["> 1%", "not dead"], "development": "last 1 chrome version" } }"#,
+  )
+  .unwrap();
+  let config = find_config(&nested, "production").unwrap().unwrap();
+  assert_eq!(config.path, dir.path().join("package.json"));
+  assert_eq!(config.query, "> 1%, not dead");
+  let config = find_config(&nested, "staging").unwrap().unwrap();
+  assert_eq!(config.query, "defaults");
+
+  fs::write(
+    dir.path().join("src/.browserslistrc"),
+    "ie 11\n[development]\nchrome 125",
+  )
+  .unwrap();
+  let config = find_config(&nested, "development").unwrap().unwrap();
+  assert_eq!(config.path, dir.path().join("src/.browserslistrc"));
+  assert_eq!(config.query, "chrome 125");
+  assert_eq!(
+    find_config(&nested, "production").unwrap().unwrap().query,
+    "ie 11"
+  );
+
+  fs::write(dir.path().join("src/.browserslistrc"), "").unwrap();
+  fs::write(
+    dir.path().join("src/styles/package.json"),
+    r#"{ "browserslist": 5 }"#,
+  )
+  .unwrap();
+  assert!(find_config(&nested, "production").is_err());
+}
@@ -0,0 +1,5 @@
+mod plugin;
+mod reduce;
+
+pub use crate::plugin::{Calc, Options};
+pub use crate::reduce::{reduce, Error};
@@ -0,0 +1,83 @@
+use crate::reduce::reduce;
+use node::rewrite::{rewrite_decls, Preserve, Visit};
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// The decimals numbers are rounded to.
+  pub precision: u32,
+  /// Keeps the declarations after their reduced copies.
+  pub preserve: bool,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      precision: 5,
+      preserve: false,
+    }
+  }
+}
+
+/// Reduces the `calc()`, `min()`, `max()` and `clamp()` functions of
+/// declaration values, see [`reduce`](crate::reduce).
+///
+/// A value with a function that can not be parsed, or dividing by zero, is
+/// left alone with a warning.
+#[derive(Debug, Clone, Default)]
+pub struct Calc {
+  pub options: Options,
+}
+
+impl Calc {
+  /// Reduces the declarations in `parent` and its descendants.
+  fn reduce(&self, parent: &Rc<RefCell<Node>>, result: &mut ProcessResult) {
+    let preserve = if self.options.preserve {
+      Preserve::Before
+    } else {
+      Preserve::No
+    };
+    rewrite_decls(parent, preserve, &mut |child| {
+      let reduced = match &*child.borrow() {
+        Node::Decl(decl) if has_math(&decl.value) => {
+          match reduce(&decl.value, self.options.precision) {
+            Ok(value) => Some(value).filter(|value| *value != decl.value),
+            Err(error) => {
+              result.warn(&error.to_string(), Some(&child.borrow()));
+              None
+            }
+          }
+        }
+        _ => None,
+      };
+      reduced.map_or(Visit::Descend, Visit::Rewrite)
+    });
+  }
+}
+
+impl Plugin for Calc {
+  fn name(&self) -> &str {
+    "calc"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    self.reduce(root, result);
+    Ok(())
+  }
+}
+
+fn has_math(value: &str) -> bool {
+  let value = value.to_ascii_lowercase();
+  ["calc(", "min(", "max(", "clamp("]
+    .iter()
+    .any(|name| value.contains(name))
+}
@@ -0,0 +1,441 @@
+use std::fmt;
+use value_parser::{dimension, format_number, parse, trim, Function, Node};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+  pub reason: String,
+}
+
+impl Error {
+  fn new(reason: impl Into<String>) -> Self {
+    Self {
+      reason: reason.into(),
+    }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.reason)
+  }
+}
+
+impl std::error::Error for Error {}
+
+/// What a term is a multiple of.
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+  /// A unit in lowercase, `""` for numbers.
+  Unit(String),
+  /// An expression that can not be reduced further, like `var(--gap)` or
+  /// `100% * var(--ratio)`.
+  Raw(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+  factor: f64,
+  kind: Kind,
+}
+
+/// A reduced expression, as a sum of terms of different kinds in the order
+/// they first appear.
+#[derive(Debug, Clone, PartialEq)]
+struct Sum(Vec<Term>);
+
+impl Sum {
+  fn unit(factor: f64, unit: String) -> Self {
+    Sum(vec![Term {
+      factor,
+      kind: Kind::Unit(unit),
+    }])
+  }
+
+  fn raw(raw: String) -> Self {
+    Sum(vec![Term {
+      factor: 1.0,
+      kind: Kind::Raw(raw),
+    }])
+  }
+
+  /// The value and unit of a sum of a single number or dimension.
+  fn single(&self) -> Option<(f64, &str)> {
+    match self.0.as_slice() {
+      [Term {
+        factor,
+        kind: Kind::Unit(unit),
+      }] => Some((*factor, unit)),
+      _ => None,
+    }
+  }
+
+  fn number(&self) -> Option<f64> {
+    self
+      .single()
+      .filter(|(_, unit)| unit.is_empty())
+      .map(|(value, _)| value)
+  }
+
+  fn add(mut self, other: Sum) -> Self {
+    for term in other.0 {
+      match self
+        .0
+        .iter_mut()
+        .find(|existing| existing.kind == term.kind)
+      {
+        Some(existing) => existing.factor += term.factor,
+        None => self.0.push(term),
+      }
+    }
+    self
+  }
+
+  /// Leaves out the terms that cancel out, keeping one if all do.
+  fn compact(mut self, precision: u32) -> Self {
+    let is_zero = |term: &Term| format_number(term.factor, precision) == "0";
+    if self.0.iter().any(|term| !is_zero(term)) {
+      self.0.retain(|term| !is_zero(term));
+    } else {
+      self.0.truncate(1);
+    }
+    self
+  }
+
+  fn scale(mut self, factor: f64) -> Self {
+    for term in &mut self.0 {
+      term.factor *= factor;
+    }
+    self
+  }
+
+  /// The sum in CSS, without `calc()`. Terms that cancel out are left out.
+  fn to_css(&self, precision: u32) -> String {
+    let terms: Vec<_> = self
+      .0
+      .iter()
+      .filter(|term| format_number(term.factor, precision) != "0")
+      .collect();
+    if terms.is_empty() {
+      return match self.0.first().map(|term| &term.kind) {
+        Some(Kind::Unit(unit)) => format!("0{}", unit),
+        _ => "0".to_string(),
+      };
+    }
+    let mut css = String::new();
+    for (i, term) in terms.iter().enumerate() {
+      let factor = match i {
+        0 => term.factor,
+        _ => {
+          css.push_str(if term.factor < 0.0 { " - " } else { " + " });
+          term.factor.abs()
+        }
+      };
+      let factor = format_number(factor, precision);
+      match &term.kind {
+        Kind::Unit(unit) => css.push_str(&format!("{}{}", factor, unit)),
+        Kind::Raw(raw) if factor == "1" => css.push_str(raw),
+        Kind::Raw(raw) => css.push_str(&format!("{} * {}", factor, raw)),
+      }
+    }
+    css
+  }
+
+  /// The sum as an operand of `*` or `/`.
+  fn to_operand(&self, precision: u32) -> String {
+    let css = self.to_css(precision);
+    match self.0.len() {
+      1 => css,
+      _ => format!("({})", css),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+enum Token {
+  Number(f64, String),
+  Op(char),
+  /// Parentheses or a nested `calc()`.
+  Group(Vec<Node>),
+  /// `min()`, `max()` or `clamp()`.
+  Math(Function),
+  /// Anything else, like `var()` or a keyword.
+  Raw(String),
+}
+
+/// Returns `true` for `calc` and its prefixed versions, like `-webkit-calc`.
+fn is_calc(name: &str) -> bool {
+  name == "calc" || (name.starts_with('-') && name.ends_with("-calc"))
+}
+
+fn is_math(name: &str) -> bool {
+  matches!(name, "min" | "max" | "clamp")
+}
+
+fn tokenize(nodes: &[Node]) -> Result<Vec<Token>, Error> {
+  let mut tokens = vec![];
+  for node in nodes {
+    match node {
+      Node::Space(_) | Node::Comment { .. } => {}
+      Node::Div('/') => tokens.push(Token::Op('/')),
+      Node::Div(div) => return Err(Error::new(format!("Unexpected \"{}\"", div))),
+      Node::String { .. } => return Err(Error::new("Unexpected string")),
+      Node::Word(word) if matches!(word.as_str(), "+" | "-" | "*") => {
+        tokens.push(Token::Op(word.as_bytes()[0] as char))
+      }
+      Node::Word(word) => {
+        // Only `+` and `-` need whitespace around them.
+        for (i, part) in word.split('*').enumerate() {
+          if i > 0 {
+            tokens.push(Token::Op('*'));
+          }
+          if part.is_empty() {
+            continue;
+          }
+          tokens.push(match dimension(part) {
+            Some(dimension) => Token::Number(dimension.value, dimension.unit.to_ascii_lowercase()),
+            None => Token::Raw(part.to_string()),
+          });
+        }
+      }
+      Node::Function(function) => {
+        let name = function.lower_name();
+        tokens.push(if name.is_empty() || is_calc(&name) {
+          Token::Group(function.nodes.clone())
+        } else if is_math(&name) {
+          Token::Math(function.clone())
+        } else {
+          Token::Raw(function.to_string())
+        });
+      }
+    }
+  }
+  Ok(tokens)
+}
+
+struct Tokens {
+  tokens: Vec<Token>,
+  pos: usize,
+}
+
+impl Tokens {
+  fn peek_op(&self) -> Option<char> {
+    match self.tokens.get(self.pos) {
+      Some(Token::Op(op)) => Some(*op),
+      _ => None,
+    }
+  }
+}
+
+struct Reducer {
+  precision: u32,
+}
+
+impl Reducer {
+  /// Reduces a `calc()` expression, like the content of parentheses.
+  fn expression(&self, nodes: &[Node]) -> Result<Sum, Error> {
+    let mut tokens = Tokens {
+      tokens: tokenize(nodes)?,
+      pos: 0,
+    };
+    let sum = self.sum(&mut tokens)?;
+    match tokens.tokens.get(tokens.pos) {
+      None => Ok(sum),
+      Some(Token::Op(op)) => Err(Error::new(format!("Unexpected \"{}\"", op))),
+      Some(_) => Err(Error::new("Missing operator")),
+    }
+  }
+
+  fn sum(&self, tokens: &mut Tokens) -> Result<Sum, Error> {
+    let mut sum = self.product(tokens)?;
+    while let Some(op @ ('+' | '-')) = tokens.peek_op() {
+      tokens.pos += 1;
+      let operand = self.product(tokens)?;
+      sum = sum.add(if op == '-' {
+        operand.scale(-1.0)
+      } else {
+        operand
+      });
+    }
+    Ok(sum.compact(self.precision))
+  }
+
+  fn product(&self, tokens: &mut Tokens) -> Result<Sum, Error> {
+    let mut product = self.value(tokens)?;
+    while let Some(op @ ('*' | '/')) = tokens.peek_op() {
+      tokens.pos += 1;
+      let operand = self.value(tokens)?;
+      product = if op == '*' {
+        self.multiply(product, operand)
+      } else {
+        self.divide(product, operand)?
+      };
+    }
+    Ok(product)
+  }
+
+  fn value(&self, tokens: &mut Tokens) -> Result<Sum, Error> {
+    let token = tokens.tokens.get(tokens.pos).cloned();
+    tokens.pos += 1;
+    match token {
+      Some(Token::Number(value, unit)) => Ok(Sum::unit(value, unit)),
+      Some(Token::Group(nodes)) => self.expression(&nodes),
+      Some(Token::Math(function)) => self.math(&function),
+      Some(Token::Raw(raw)) => Ok(Sum::raw(raw)),
+      Some(Token::Op(op)) => Err(Error::new(format!("Unexpected \"{}\"", op))),
+      None => Err(Error::new("Missing value")),
+    }
+  }
+
+  fn multiply(&self, left: Sum, right: Sum) -> Sum {
+    match (left.number(), right.number()) {
+      (Some(factor), _) => right.scale(factor),
+      (_, Some(factor)) => left.scale(factor),
+      _ => Sum::raw(format!(
+        "{} * {}",
+        left.to_operand(self.precision),
+        right.to_operand(self.precision)
+      )),
+    }
+  }
+
+  fn divide(&self, left: Sum, right: Sum) -> Result<Sum, Error> {
+    match right.number() {
+      Some(0.0) => Err(Error::new("Cannot divide by zero")),
+      Some(divisor) => Ok(left.scale(1.0 / divisor)),
+      None => Ok(Sum::raw(format!(
+        "{} / {}",
+        left.to_operand(self.precision),
+        right.to_operand(self.precision)
+      ))),
+    }
+  }
+
+  /// Reduces `min()`, `max()` or `clamp()`: arguments with the same unit
+  /// are compared, and the function is left out if a single one remains.
+  fn math(&self, function: &Function) -> Result<Sum, Error> {
+    let name = function.lower_name();
+    let args = function
+      .args()
+      .into_iter()
+      .map(|arg| self.expression(trim(arg)))
+      .collect::<Result<Vec<_>, _>>()?;
+    if name == "clamp" {
+      let [min, value, max] = args.as_slice() else {
+        return Err(Error::new("clamp() needs 3 arguments"));
+      };
+      if let (Some((min, unit)), Some((value, value_unit)), Some((max, max_unit))) =
+        (min.single(), value.single(), max.single())
+      {
+        if unit == value_unit && unit == max_unit {
+          return Ok(Sum::unit(value.min(max).max(min), unit.to_string()));
+        }
+      }
+      return Ok(Sum::raw(self.math_css("clamp", &args)));
+    }
+    let mut kept: Vec<Sum> = vec![];
+    for arg in args {
+      let same_unit = arg.single().and_then(|(value, unit)| {
+        let index = kept.iter().position(|kept| {
+          kept
+            .single()
+            .is_some_and(|(_, kept_unit)| kept_unit == unit)
+        })?;
+        Some((index, value))
+      });
+      match same_unit {
+        Some((index, value)) => {
+          let current = kept[index].single().unwrap().0;
+          if (name == "min" && value < current) || (name == "max" && value > current) {
+            kept[index] = arg;
+          }
+        }
+        None => kept.push(arg),
+      }
+    }
+    match kept.len() {
+      1 => Ok(kept.pop().unwrap()),
+      _ => Ok(Sum::raw(self.math_css(&name, &kept))),
+    }
+  }
+
+  fn math_css(&self, name: &str, args: &[Sum]) -> String {
+    let args: Vec<_> = args.iter().map(|arg| arg.to_css(self.precision)).collect();
+    format!("{}({})", name, args.join(", "))
+  }
+
+  /// The reduced CSS of a math function.
+  fn function(&self, function: &Function, name: &str) -> Result<String, Error> {
+    let sum = if is_calc(name) {
+      self.expression(&function.nodes)
+    } else {
+      self.math(function)
+    }
+    .map_err(|error| Error::new(format!("{} in {}", error.reason, function)))?;
+    let is_math_call = match sum.0.as_slice() {
+      [Term {
+        factor,
+        kind: Kind::Raw(raw),
+      }] => {
+        *factor == 1.0
+          && ["min(", "max(", "clamp("]
+            .iter()
+            .any(|name| raw.starts_with(name))
+      }
+      _ => false,
+    };
+    if sum.single().is_some() || is_math_call {
+      return Ok(sum.to_css(self.precision));
+    }
+    let name = if is_calc(name) {
+      &function.name
+    } else {
+      "calc"
+    };
+    Ok(format!("{}({})", name, sum.to_css(self.precision)))
+  }
+}
+
+/// Reduces the `calc()`, `min()`, `max()` and `clamp()` functions of a
+/// value, with numbers rounded to `precision` decimals.
+///
+/// Constant arithmetic is folded and terms with the same unit are combined.
+/// Terms that can not be combined, like `100%` and `-10px`, stay in a
+/// `calc()` in the order they first appear. `var()` and other functions are
+/// kept as written, and so is the value if a function can not be parsed.
+///
+/// ```
+/// assert_eq!(calc::reduce("calc(10px + 2px)", 5).unwrap(), "12px");
+/// assert_eq!(
+///   calc::reduce("calc(100% - (2 * 8px + var(--gap)))", 5).unwrap(),
+///   "calc(100% - 16px - var(--gap))"
+/// );
+/// ```
+pub fn reduce(value: &str, precision: u32) -> Result<String, Error> {
+  let reducer = Reducer { precision };
+  let mut parsed = parse(value);
+  let mut error = None;
+  parsed.walk(|node| {
+    let reduced = match &*node {
+      Node::Function(function) if error.is_none() => {
+        let name = function.lower_name();
+        if name == "var" {
+          return false;
+        }
+        if !is_calc(&name) && !is_math(&name) {
+          return true;
+        }
+        reducer.function(function, &name)
+      }
+      _ => return false,
+    };
+    match reduced {
+      Ok(css) => *node = Node::Word(css),
+      Err(reduce_error) => error = Some(reduce_error),
+    }
+    false
+  });
+  match error {
+    Some(error) => Err(error),
+    None => Ok(parsed.to_string()),
+  }
+}
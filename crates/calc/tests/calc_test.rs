@@ -0,0 +1,113 @@
+use calc::{reduce, Calc, Options};
+use postcss::{ProcessResult, Processor};
+
+fn reduced(value: &str) -> String {
+  reduce(value, 5).unwrap()
+}
+
+fn process(options: Options, css: &str) -> ProcessResult {
+  Processor::run_one(Calc { options }, css).unwrap()
+}
+
+#[test]
+fn folds_constants() {
+  assert_eq!(reduced("calc(10px + 2px)"), "12px");
+  assert_eq!(reduced("calc(1 + 2 * 3)"), "7");
+  assert_eq!(reduced("calc((1 + 2) * 3)"), "9");
+  assert_eq!(reduced("calc(2*3px)"), "6px");
+  assert_eq!(reduced("calc(10PX / 4)"), "2.5px");
+  assert_eq!(reduced("calc(10px - 10px)"), "0px");
+  assert_eq!(reduced("calc(-1 * (5% + 2%))"), "-7%");
+  assert_eq!(reduced("calc(1px + calc(2px * 2))"), "5px");
+  assert_eq!(reduced("calc(100% / 3)"), "33.33333%");
+  assert_eq!(reduce("calc(100% / 3)", 2).unwrap(), "33.33%");
+  assert_eq!(
+    reduced("1px calc(1px + 1px) translate(calc(2 * 1em), 0)"),
+    "1px 2px translate(2em, 0)"
+  );
+}
+
+#[test]
+fn keeps_mixed_units_normalized() {
+  assert_eq!(
+    reduced("calc(100% - 10px + 20% - 5px)"),
+    "calc(120% - 15px)"
+  );
+  assert_eq!(reduced("calc(1em + (2px - 1em))"), "2px");
+  assert_eq!(reduced("calc(-10px + 100%)"), "calc(-10px + 100%)");
+  assert_eq!(reduced("calc(2 * (100% - 1rem) / 4)"), "calc(50% - 0.5rem)");
+  assert_eq!(
+    reduced("-webkit-calc(100% - 2 * 4px)"),
+    "-webkit-calc(100% - 8px)"
+  );
+  assert_eq!(reduced("calc(1px * 2em)"), "calc(1px * 2em)");
+}
+
+#[test]
+fn keeps_var_intact() {
+  assert_eq!(
+    reduced("calc(var(--gap) * 2 + 4px + 4px)"),
+    "calc(2 * var(--gap) + 8px)"
+  );
+  assert_eq!(
+    reduced("calc(100% - (2 * 8px + var(--gap)))"),
+    "calc(100% - 16px - var(--gap))"
+  );
+  assert_eq!(reduced("calc(var(--a) - var(--a) + 1px)"), "1px");
+  assert_eq!(
+    reduced("var(--gap, calc(1px + 1px)) calc(var(--x, 2px) / (1px + var(--y)))"),
+    "var(--gap, calc(1px + 1px)) calc(var(--x, 2px) / (1px + var(--y)))"
+  );
+  assert_eq!(reduced("calc(var(--x))"), "calc(var(--x))");
+}
+
+#[test]
+fn reduces_comparison_functions() {
+  assert_eq!(reduced("min(10px, 2px * 3, 8px)"), "6px");
+  assert_eq!(reduced("max(1em, 20%, 2em)"), "max(2em, 20%)");
+  assert_eq!(reduced("min(100% - 10px, 50px)"), "min(100% - 10px, 50px)");
+  assert_eq!(reduced("clamp(1rem, 2.5rem, 2rem)"), "2rem");
+  assert_eq!(reduced("clamp(1rem, 0.5rem, 2rem)"), "1rem");
+  assert_eq!(
+    reduced("clamp(1rem, 2vw + 1rem, 3rem)"),
+    "clamp(1rem, 2vw + 1rem, 3rem)"
+  );
+  assert_eq!(reduced("calc(min(2px, 4px) * 2)"), "4px");
+  assert_eq!(
+    reduced("calc(max(1px, 1%) + 1px)"),
+    "calc(max(1px, 1%) + 1px)"
+  );
+}
+
+#[test]
+fn reports_invalid_expressions() {
+  assert_eq!(
+    reduce("calc(1px / 0)", 5).unwrap_err().reason,
+    "Cannot divide by zero in calc(1px / 0)"
+  );
+  assert!(reduce("calc(1px +)", 5).is_err());
+  assert!(reduce("calc(1px 2px)", 5).is_err());
+  assert!(reduce("calc(1px, 2px)", 5).is_err());
+  assert!(reduce("clamp(1px, 2px)", 5).is_err());
+}
+
+#[test]
+fn reduces_declarations() {
+  let css = "a { width: calc(100% - 2 * 10px); height: calc(1px / 0); margin: 0 }\n@media print { b { top: calc(1px + 1px) } }";
+  let result = process(Options::default(), css);
+  assert_eq!(
+    result.css,
+    "a { width: calc(100% - 20px); height: calc(1px / 0); margin: 0 }\n@media print { b { top: 2px } }"
+  );
+  let warnings = result.warnings();
+  assert_eq!(warnings.len(), 1);
+  assert_eq!(warnings[0].text, "Cannot divide by zero in calc(1px / 0)");
+
+  let options = Options {
+    precision: 2,
+    preserve: true,
+  };
+  let result = process(options.clone(), "a { width: calc(100% / 3) }");
+  assert_eq!(result.css, "a { width: 33.33%; width: calc(100% / 3) }");
+  assert_eq!(process(options, &result.css).css, result.css);
+}
@@ -0,0 +1,104 @@
+/// Command-line options of `postcss`.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Args {
+  /// Input files or globs, `-` is stdin.
+  pub inputs: Vec<String>,
+  pub output: Option<String>,
+  pub dir: Option<String>,
+  /// Extension of the files written to `dir`, with the dot.
+  pub ext: Option<String>,
+  /// `Some(true)` for external maps, `Some(false)` for no maps and `None`
+  /// for the default inline maps.
+  pub map: Option<bool>,
+  pub plugins: Vec<String>,
+  pub parser: Option<String>,
+  pub syntax: Option<String>,
+  /// A configuration file, or a directory with one.
+  pub config: Option<String>,
+  pub watch: bool,
+  pub help: bool,
+  pub version: bool,
+}
+
+pub(crate) const USAGE: &str = "\
+Usage:
+  postcss [input.css] [OPTIONS] [-o|--output output.css]
+  postcss <input.css>... [OPTIONS] --dir <output-directory>
+  cat input.css | postcss [OPTIONS] > output.css
+
+Options:
+  -o, --output   Output file
+  -d, --dir      Output directory, keeping the paths below the base of
+                 the input globs
+      --ext      Override the output file extension, for use with --dir
+  -m, --map      Create an external sourcemap
+      --no-map   Disable the default inline sourcemaps
+  -u, --use      List of built-in plugins to use, comma separated, replaces
+                 the plugins of the config
+  -p, --parser   Parser to use: postcss, safe or json
+  -s, --syntax   Syntax to use: css or json
+  -c, --config   Config file, or directory with one, instead of looking for
+                 postcss.toml or postcss.json next to the inputs
+  -w, --watch    Watch the inputs and their dependencies, rebuild on change
+  -h, --help     Show help
+  -v, --version  Show version number
+
+Reads stdin if there are no input files or an input is `-`, and writes to
+stdout if there is one input and no --output or --dir.
+
+The browsers to support are the `browsers` of the config, else the query of
+the BROWSERSLIST variable, else the one of a .browserslistrc, browserslist or
+package.json next to the inputs, for the BROWSERSLIST_ENV or NODE_ENV
+environment.";
+
+impl Args {
+  pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut result = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+      let (name, inline_value) = match arg.split_once('=') {
+        Some((name, value)) if name.starts_with("--") => {
+          (name.to_string(), Some(value.to_string()))
+        }
+        _ => (arg.clone(), None),
+      };
+      let mut value = |name: &str| {
+        inline_value
+          .clone()
+          .or_else(|| args.next())
+          .ok_or_else(|| format!("Missing value for {}", name))
+      };
+      match name.as_str() {
+        "-o" | "--output" => result.output = Some(value(&name)?),
+        "-d" | "--dir" => result.dir = Some(value(&name)?),
+        "--ext" => {
+          let ext = value(&name)?;
+          result.ext = Some(if ext.starts_with('.') {
+            ext
+          } else {
+            format!(".{}", ext)
+          });
+        }
+        "-m" | "--map" => result.map = Some(true),
+        "--no-map" => result.map = Some(false),
+        "-u" | "--use" => result.plugins.extend(
+          value(&name)?
+            .split(',')
+            .map(str::trim)
+            .filter(|plugin| !plugin.is_empty())
+            .map(str::to_string),
+        ),
+        "-p" | "--parser" => result.parser = Some(value(&name)?),
+        "-s" | "--syntax" => result.syntax = Some(value(&name)?),
+        "-c" | "--config" => result.config = Some(value(&name)?),
+        "-w" | "--watch" => result.watch = true,
+        "-h" | "--help" => result.help = true,
+        "-v" | "--version" => result.version = true,
+        "-" => result.inputs.push(arg),
+        _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+        _ => result.inputs.push(arg),
+      }
+    }
+    Ok(result)
+  }
+}
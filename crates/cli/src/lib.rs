@@ -0,0 +1,410 @@
+mod args;
+mod plugins;
+mod watch;
+
+pub use crate::plugins::registry;
+pub use crate::watch::Watcher;
+
+use crate::args::{Args, USAGE};
+use postcss::config::{Config, MapConfig, FILE_NAMES};
+use postcss::{ConfigError, CssSyntaxError, MapOptions, ProcessOptions, ProcessResult, Processor};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum Error {
+  Usage(String),
+  Io(String),
+  Config(ConfigError),
+  Syntax(CssSyntaxError),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::Usage(message) => write!(f, "postcss: {}\n\nRun postcss --help for usage.", message),
+      Error::Io(message) => write!(f, "postcss: {}", message),
+      Error::Config(error) => write!(f, "postcss: {}", error),
+      Error::Syntax(error) => {
+        write!(f, "CssSyntaxError: {}", error)?;
+        match error.show_source_code() {
+          Some(code) => write!(f, "\n\n{}\n", code),
+          None => Ok(()),
+        }
+      }
+    }
+  }
+}
+
+/// One file to build, `None` is stdin or stdout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+  pub input: Option<PathBuf>,
+  pub output: Option<PathBuf>,
+  /// Source map options, `None` for no map.
+  pub map: Option<MapOptions>,
+}
+
+/// Runs `postcss` with the command-line arguments, without the program
+/// name, and returns the exit code.
+pub fn cli(args: impl IntoIterator<Item = String>) -> i32 {
+  match Args::parse(args).map_err(Error::Usage).and_then(run) {
+    Ok(()) => 0,
+    Err(error) => {
+      eprintln!("{}", error);
+      1
+    }
+  }
+}
+
+fn run(args: Args) -> Result<(), Error> {
+  if args.help {
+    println!(
+      "{}\n\nBuilt-in plugins: {}",
+      USAGE,
+      registry().names().join(", ")
+    );
+    return Ok(());
+  }
+  if args.version {
+    println!("{}", env!("CARGO_PKG_VERSION"));
+    return Ok(());
+  }
+
+  let config = match &args.config {
+    Some(path) => Some(load_config(Path::new(path))?),
+    None => None,
+  };
+  let jobs = configured_jobs(&args, config.as_ref())?;
+  if args.watch {
+    if jobs.iter().any(|(job, _)| job.input.is_none()) {
+      return Err(Error::Usage("Can not read stdin in watch mode".into()));
+    }
+    if jobs.iter().any(|(job, _)| job.output.is_none()) {
+      return Err(Error::Usage("Can not write to stdout in watch mode".into()));
+    }
+    let bases = args
+      .inputs
+      .iter()
+      .filter(|input| is_glob(input))
+      .map(|input| glob_base(input))
+      .collect();
+    let mut watcher = Watcher::new(jobs);
+    watcher.expand_with(bases, move || configured_jobs(&args, config.as_ref()));
+    report_builds(watcher.build_all());
+    eprintln!("Waiting for file changes...");
+    loop {
+      std::thread::sleep(Duration::from_millis(100));
+      report_builds(watcher.poll());
+    }
+  }
+
+  for (job, processor) in jobs {
+    build(&processor, &job)?;
+  }
+  Ok(())
+}
+
+/// Reports the builds of watch mode, errors do not stop watching.
+fn report_builds(builds: Vec<(PathBuf, Result<(), Error>)>) {
+  for (output, result) in builds {
+    match result {
+      Ok(()) => eprintln!("Built {}", output.display()),
+      Err(error) => eprintln!("{}", error),
+    }
+  }
+}
+
+/// The jobs of the inputs, with their processors.
+fn configured_jobs(args: &Args, config: Option<&Config>) -> Result<Vec<(Job, Processor)>, Error> {
+  jobs(args)?
+    .into_iter()
+    .map(|job| configure(args, config, job))
+    .collect()
+}
+
+/// Loads `--config`, a configuration file or a directory with one.
+fn load_config(path: &Path) -> Result<Config, Error> {
+  let path = if path.is_dir() {
+    FILE_NAMES
+      .iter()
+      .map(|name| path.join(name))
+      .find(|path| path.is_file())
+      .ok_or_else(|| Error::Io(format!("No config file in {}", path.display())))?
+  } else {
+    path.to_path_buf()
+  };
+  Config::load(&path).map_err(Error::Config)
+}
+
+/// Creates the processor and the map options of a job, from the config
+/// found next to its input, or `config`, and the command-line options.
+fn configure(
+  args: &Args,
+  config: Option<&Config>,
+  mut job: Job,
+) -> Result<(Job, Processor), Error> {
+  let config = match config {
+    Some(config) => config.clone(),
+    None => {
+      let dir = match job.input.as_deref().and_then(Path::parent) {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+      };
+      Config::find(&dir)
+        .map_err(Error::Config)?
+        .unwrap_or_default()
+    }
+  };
+  let mut config = match &job.input {
+    Some(input) => config.for_file(input),
+    None => config,
+  };
+  if args.parser.is_some() {
+    config.parser = args.parser.clone();
+  }
+  if args.syntax.is_some() {
+    config.syntax = args.syntax.clone();
+  }
+  if !args.plugins.is_empty() {
+    config.plugins = args
+      .plugins
+      .iter()
+      .map(|name| postcss::config::PluginConfig {
+        name: name.clone(),
+        options: Default::default(),
+        enabled: true,
+      })
+      .collect();
+  }
+  match args.map {
+    Some(false) => config.map = Some(MapConfig::Enabled(false)),
+    Some(true) => {
+      config.map = Some(MapConfig::Options(postcss::config::MapTable {
+        inline: false,
+        ..Default::default()
+      }))
+    }
+    None => {}
+  }
+
+  if config.browsers.is_none() {
+    config.browsers = browsers(job.input.as_deref())?;
+  }
+
+  job.map = match &config.map {
+    Some(map) => map.options(),
+    None => Some(MapOptions::default()),
+  };
+  if let Some(map) = job.map.as_mut().filter(|_| job.output.is_none()) {
+    // an external map needs a file to go next to
+    map.inline = true;
+  }
+  let processor =
+    Processor::from_config(&config, &registry()).map_err(|error| match error.path {
+      Some(_) => Error::Config(error),
+      None => Error::Usage(error.message),
+    })?;
+  Ok((job, processor))
+}
+
+/// The browserslist query of the `BROWSERSLIST` variable, or of the
+/// browserslist configuration next to `input`, for the environment of
+/// `BROWSERSLIST_ENV` or `NODE_ENV`, `production` by default.
+fn browsers(input: Option<&Path>) -> Result<Option<String>, Error> {
+  if let Ok(query) = std::env::var("BROWSERSLIST") {
+    return Ok(Some(query));
+  }
+  let env = std::env::var("BROWSERSLIST_ENV")
+    .or_else(|_| std::env::var("NODE_ENV"))
+    .unwrap_or_else(|_| "production".to_string());
+  let dir = match input.and_then(Path::parent) {
+    Some(dir) if !dir.as_os_str().is_empty() => dir,
+    _ => Path::new("."),
+  };
+  let config = browserslist::find_config(dir, &env).map_err(|error| Error::Io(error.reason))?;
+  Ok(config.map(|config| config.query))
+}
+
+fn is_glob(input: &str) -> bool {
+  input.contains(['*', '?', '['])
+}
+
+/// The directory of a glob before its first wildcard, like `src` for
+/// `src/**/*.css`.
+fn glob_base(pattern: &str) -> PathBuf {
+  Path::new(pattern)
+    .components()
+    .take_while(|component| {
+      !component
+        .as_os_str()
+        .to_string_lossy()
+        .contains(['*', '?', '['])
+    })
+    .collect()
+}
+
+/// Expands the globs of the inputs and pairs every input with its output.
+///
+/// With `--dir`, the outputs keep the path of their input relative to the
+/// base of its glob, so `src/**/*.css` writes `src/a/x.css` to
+/// `<dir>/a/x.css`, and inputs that are no globs keep their file name.
+fn jobs(args: &Args) -> Result<Vec<Job>, Error> {
+  // The inputs, with the directory their output path is relative to.
+  let mut inputs: Vec<(Option<PathBuf>, PathBuf)> = vec![];
+  for input in &args.inputs {
+    if input == "-" {
+      inputs.push((None, PathBuf::new()));
+    } else if is_glob(input) {
+      let paths = glob::glob(input).map_err(|error| Error::Usage(error.to_string()))?;
+      let mut paths: Vec<_> = paths.filter_map(Result::ok).collect();
+      if paths.is_empty() {
+        return Err(Error::Usage(format!("No files match \"{}\"", input)));
+      }
+      paths.sort();
+      let base = glob_base(input);
+      inputs.extend(paths.into_iter().map(|path| (Some(path), base.clone())));
+    } else {
+      let path = PathBuf::from(input);
+      let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+      inputs.push((Some(path), base));
+    }
+  }
+  if inputs.is_empty() {
+    inputs.push((None, PathBuf::new()));
+  }
+
+  if let Some(dir) = &args.dir {
+    if args.output.is_some() {
+      return Err(Error::Usage("Can not use both --output and --dir".into()));
+    }
+    let mut jobs: Vec<Job> = Vec::with_capacity(inputs.len());
+    for (input, base) in inputs {
+      let path = input
+        .as_deref()
+        .ok_or_else(|| Error::Usage("Can not use --dir with stdin".into()))?;
+      let mut name = match path.strip_prefix(&base) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => PathBuf::from(path.file_name().unwrap_or_default()),
+      };
+      if let Some(ext) = &args.ext {
+        name.set_extension(ext.trim_start_matches('.'));
+      }
+      let output = Path::new(dir).join(name);
+      if let Some(other) = jobs.iter().find(|job| job.output.as_ref() == Some(&output)) {
+        return Err(Error::Usage(format!(
+          "Both {} and {} would be written to {}",
+          other.input.as_deref().unwrap_or(Path::new("-")).display(),
+          path.display(),
+          output.display()
+        )));
+      }
+      jobs.push(Job {
+        output: Some(output),
+        input,
+        map: None,
+      });
+    }
+    return Ok(jobs);
+  }
+
+  if inputs.len() > 1 {
+    return Err(Error::Usage(
+      "Must use --dir with multiple input files".into(),
+    ));
+  }
+  Ok(vec![Job {
+    input: inputs.pop().unwrap().0,
+    output: args.output.as_ref().map(PathBuf::from),
+    map: None,
+  }])
+}
+
+fn build(processor: &Processor, job: &Job) -> Result<(), Error> {
+  let css = match &job.input {
+    Some(path) => fs::read_to_string(path)
+      .map_err(|error| Error::Io(format!("Can not read {}: {}", path.display(), error)))?,
+    None => {
+      let mut css = String::new();
+      io::stdin()
+        .read_to_string(&mut css)
+        .map_err(|error| Error::Io(format!("Can not read stdin: {}", error)))?;
+      css
+    }
+  };
+
+  let result = processor
+    .process(&css, options(job))
+    .map_err(Error::Syntax)?;
+  report_warnings(job, &result);
+  write(job, &result)
+}
+
+fn options(job: &Job) -> ProcessOptions {
+  ProcessOptions {
+    from: job.input.as_ref().map(|path| path.display().to_string()),
+    to: job.output.as_ref().map(|path| path.display().to_string()),
+    map: job.map.clone(),
+  }
+}
+
+fn report_warnings(job: &Job, result: &ProcessResult) {
+  let input = job
+    .input
+    .as_ref()
+    .map_or_else(|| "<stdin>".to_string(), |path| path.display().to_string());
+  let dir = env::current_dir().unwrap_or_default();
+  for warning in result.warnings() {
+    // A warning in an imported file points to that file.
+    let file = match &warning.file {
+      Some(file) => {
+        let path = Path::new(file);
+        path
+          .strip_prefix(&dir)
+          .unwrap_or(path)
+          .display()
+          .to_string()
+      }
+      None => input.clone(),
+    };
+    let plugin = warning
+      .plugin
+      .as_ref()
+      .map(|plugin| format!(" [{}]", plugin))
+      .unwrap_or_default();
+    eprintln!(
+      "{}:{}:{}: {}{}",
+      file, warning.line, warning.column, warning.text, plugin
+    );
+  }
+}
+
+fn write(job: &Job, result: &ProcessResult) -> Result<(), Error> {
+  let path = match &job.output {
+    Some(path) => path,
+    None => {
+      return io::stdout()
+        .write_all(result.css.as_bytes())
+        .map_err(|error| Error::Io(format!("Can not write stdout: {}", error)));
+    }
+  };
+  write_file(path, &result.css)?;
+  if let Some(map) = &result.map {
+    let mut map_path = path.as_os_str().to_owned();
+    map_path.push(".map");
+    write_file(Path::new(&map_path), map)?;
+  }
+  Ok(())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), Error> {
+  let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+  dir
+    .map_or(Ok(()), fs::create_dir_all)
+    .and_then(|_| fs::write(path, contents))
+    .map_err(|error| Error::Io(format!("Can not write {}: {}", path.display(), error)))
+}
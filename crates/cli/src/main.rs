@@ -0,0 +1,3 @@
+fn main() {
+  std::process::exit(postcss_cli::cli(std::env::args().skip(1)));
+}
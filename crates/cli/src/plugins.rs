@@ -0,0 +1,106 @@
+use calc::Calc;
+use color::ColorFallbacks;
+use container_query::ContainerNames;
+use custom_properties::CustomProperties;
+use import::{FileResolver, Import};
+use logical::Logical;
+use media_query::{MediaFlatten, SortMediaQueries};
+use merge_rules::MergeRules;
+use minify::Minify;
+use modules::Modules;
+use nesting::Nesting;
+use postcss::{options, Registry};
+use prefixer::Prefixer;
+use purge::Purge;
+use px_to_rem::PxToRem;
+use rtl::Rtl;
+use scoped::Scoped;
+use sort_declarations::SortDeclarations;
+use supports::SupportsFlatten;
+
+/// The built-in plugins, for `--use` and configuration files.
+///
+/// `import` takes the `paths` of a `FileResolver`, `media-flatten` an
+/// `Environment`, `calc`, `color-fallbacks`, `custom-properties`, `logical`,
+/// `minify`, `modules`, `prefixer`, `purge`, `px-to-rem`, `rtl`,
+/// `sort-declarations` and `sort-media-queries` their `Options`, `scoped` the
+/// `id` of the component and `supports-flatten` a `Support` as options, with
+/// kebab-case keys.
+pub fn registry() -> Registry {
+  let mut registry = Registry::new();
+  registry
+    .register("calc", |value| {
+      Ok(Box::new(Calc {
+        options: options(value)?,
+      }))
+    })
+    .register("color-fallbacks", |value| {
+      ColorFallbacks::new(options(value)?)
+        .map(|plugin| Box::new(plugin) as _)
+        .map_err(|error| error.to_string())
+    })
+    .register("container-names", |_| Ok(Box::new(ContainerNames)))
+    .register("custom-properties", |value| {
+      Ok(Box::new(CustomProperties {
+        options: options(value)?,
+      }))
+    })
+    .register("import", |value| {
+      Ok(Box::new(Import::new(Box::new(options::<FileResolver>(
+        value,
+      )?))))
+    })
+    .register("logical", |value| {
+      Ok(Box::new(Logical {
+        options: options(value)?,
+      }))
+    })
+    .register("media-flatten", |value| {
+      Ok(Box::new(MediaFlatten {
+        env: options(value)?,
+      }))
+    })
+    .register("merge-rules", |_| Ok(Box::new(MergeRules)))
+    .register("minify", |value| {
+      Minify::new(options(value)?)
+        .map(|plugin| Box::new(plugin) as _)
+        .map_err(|error| error.to_string())
+    })
+    .register("modules", |value| {
+      Ok(Box::new(Modules::new(options(value)?)))
+    })
+    .register("nesting", |_| Ok(Box::new(Nesting)))
+    .register("prefixer", |value| {
+      Prefixer::new(options(value)?)
+        .map(|prefixer| Box::new(prefixer) as _)
+        .map_err(|error| error.to_string())
+    })
+    .register("purge", |value| {
+      Purge::new(options(value)?)
+        .map(|purge| Box::new(purge) as _)
+        .map_err(|error| error.to_string())
+    })
+    .register("px-to-rem", |value| {
+      Ok(Box::new(PxToRem {
+        options: options(value)?,
+      }))
+    })
+    .register("rtl", |value| Ok(Box::new(Rtl::new(options(value)?))))
+    .register("scoped", |value| Ok(Box::new(options::<Scoped>(value)?)))
+    .register("sort-declarations", |value| {
+      Ok(Box::new(SortDeclarations {
+        options: options(value)?,
+      }))
+    })
+    .register("sort-media-queries", |value| {
+      Ok(Box::new(SortMediaQueries {
+        options: options(value)?,
+      }))
+    })
+    .register("supports-flatten", |value| {
+      Ok(Box::new(SupportsFlatten {
+        support: options(value)?,
+      }))
+    });
+  registry
+}
@@ -0,0 +1,247 @@
+use crate::{options, report_warnings, write, Error, Job};
+use node::Node;
+use postcss::path::absolute;
+use postcss::Processor;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+/// What a file looked like at the last poll, `None` if it did not exist.
+type Fingerprint = Option<(SystemTime, u64)>;
+
+/// Expands the inputs into their jobs.
+type Expand = Box<dyn Fn() -> Result<Vec<(Job, Processor)>, Error>>;
+
+/// Rebuilds the jobs whose input or dependencies changed.
+///
+/// Dependencies are the files of the `dependency` messages of the last
+/// build. Inputs are parsed once per change: a job rebuilt for a dependency
+/// reuses a copy of the cached tree, which owns its source and replaces the
+/// previous one.
+pub struct Watcher {
+  jobs: Vec<(Job, Processor)>,
+  /// The trees of the inputs, as parsed.
+  trees: HashMap<PathBuf, Rc<RefCell<Node<'static>>>>,
+  /// The absolute paths of the input and the dependencies of every job.
+  watched: Vec<HashSet<PathBuf>>,
+  fingerprints: HashMap<PathBuf, Fingerprint>,
+  /// The absolute bases of the globs of the inputs, see [`Watcher::expand_with`].
+  bases: Vec<PathBuf>,
+  expand: Option<Expand>,
+  /// The directories from the bases to the inputs.
+  dirs: HashMap<PathBuf, Fingerprint>,
+  /// The absolute paths of the outputs of the jobs, also the removed ones.
+  outputs: HashSet<PathBuf>,
+}
+
+impl Watcher {
+  /// `jobs` must have an input and an output file.
+  pub fn new(jobs: Vec<(Job, Processor)>) -> Self {
+    let watched = jobs
+      .iter()
+      .map(|(job, _)| HashSet::from([input_of(job)]))
+      .collect();
+    let outputs = jobs.iter().map(|(job, _)| output_of(job)).collect();
+    Self {
+      jobs,
+      trees: HashMap::new(),
+      watched,
+      fingerprints: HashMap::new(),
+      bases: vec![],
+      expand: None,
+      dirs: HashMap::new(),
+      outputs,
+    }
+  }
+
+  /// Expands the inputs again with `expand` when a directory from one of
+  /// `bases` to the inputs below it changes, to build the files added to
+  /// the globs and drop the jobs of the removed ones. The outputs of the
+  /// jobs are never taken as new inputs.
+  pub fn expand_with(
+    &mut self,
+    bases: Vec<PathBuf>,
+    expand: impl Fn() -> Result<Vec<(Job, Processor)>, Error> + 'static,
+  ) {
+    self.bases = bases.iter().map(|base| absolute(base)).collect();
+    self.expand = Some(Box::new(expand));
+    self.dirs = self.dirs();
+  }
+
+  /// Builds every job, returns the outputs with their results.
+  pub fn build_all(&mut self) -> Vec<(PathBuf, Result<(), Error>)> {
+    (0..self.jobs.len())
+      .map(|index| self.build(index))
+      .collect()
+  }
+
+  /// Builds the inputs added to the globs and rebuilds the jobs affected by
+  /// the files changed since the last poll.
+  pub fn poll(&mut self) -> Vec<(PathBuf, Result<(), Error>)> {
+    let mut builds = self.expand();
+    builds.extend(self.rebuild());
+    builds
+  }
+
+  /// Expands the inputs again if a directory changed, builds the new inputs.
+  fn expand(&mut self) -> Vec<(PathBuf, Result<(), Error>)> {
+    let expand = match &self.expand {
+      Some(expand) => expand,
+      None => return vec![],
+    };
+    if self
+      .dirs
+      .iter()
+      .all(|(dir, fingerprint)| fingerprint_of(dir) == *fingerprint)
+    {
+      return vec![];
+    }
+    let jobs = match expand() {
+      Ok(jobs) => jobs,
+      Err(error) => {
+        self.dirs = self.dirs();
+        return vec![(self.bases[0].clone(), Err(error))];
+      }
+    };
+
+    let inputs: HashSet<PathBuf> = jobs.iter().map(|(job, _)| input_of(job)).collect();
+    let mut index = 0;
+    while index < self.jobs.len() {
+      let input = input_of(&self.jobs[index].0);
+      if inputs.contains(&input) {
+        index += 1;
+        continue;
+      }
+      self.jobs.remove(index);
+      self.watched.remove(index);
+      self.trees.remove(&input);
+    }
+    let watched = &self.watched;
+    self
+      .fingerprints
+      .retain(|path, _| watched.iter().any(|paths| paths.contains(path)));
+
+    let known: HashSet<PathBuf> = self.jobs.iter().map(|(job, _)| input_of(job)).collect();
+    let mut builds = vec![];
+    for (job, processor) in jobs {
+      let input = input_of(&job);
+      if known.contains(&input) || self.outputs.contains(&input) {
+        continue;
+      }
+      self.outputs.insert(output_of(&job));
+      self.watched.push(HashSet::from([input]));
+      self.jobs.push((job, processor));
+      builds.push(self.build(self.jobs.len() - 1));
+    }
+    self.dirs = self.dirs();
+    builds
+  }
+
+  /// The directories from the bases to the inputs below them.
+  fn dirs(&self) -> HashMap<PathBuf, Fingerprint> {
+    let mut dirs: HashSet<PathBuf> = self.bases.iter().cloned().collect();
+    for (job, _) in &self.jobs {
+      let input = input_of(job);
+      for base in &self.bases {
+        if input.starts_with(base) {
+          let below = input
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(base));
+          dirs.extend(below.map(Path::to_path_buf));
+        }
+      }
+    }
+    dirs
+      .into_iter()
+      .map(|dir| {
+        let fingerprint = fingerprint_of(&dir);
+        (dir, fingerprint)
+      })
+      .collect()
+  }
+
+  /// Rebuilds the jobs affected by the files changed since the last poll.
+  fn rebuild(&mut self) -> Vec<(PathBuf, Result<(), Error>)> {
+    let mut changed = HashSet::new();
+    for (path, fingerprint) in self.fingerprints.iter_mut() {
+      let current = fingerprint_of(path);
+      if current != *fingerprint {
+        *fingerprint = current;
+        changed.insert(path.clone());
+      }
+    }
+    if changed.is_empty() {
+      return vec![];
+    }
+    self.trees.retain(|input, _| !changed.contains(input));
+    let affected: Vec<usize> = (0..self.jobs.len())
+      .filter(|&index| !self.watched[index].is_disjoint(&changed))
+      .collect();
+    affected
+      .into_iter()
+      .map(|index| self.build(index))
+      .collect()
+  }
+
+  fn build(&mut self, index: usize) -> (PathBuf, Result<(), Error>) {
+    let output = self.jobs[index].0.output.clone().unwrap();
+    (output, self.try_build(index))
+  }
+
+  fn try_build(&mut self, index: usize) -> Result<(), Error> {
+    let (job, processor) = &self.jobs[index];
+    let input = input_of(job);
+    let opts = options(job);
+    self
+      .fingerprints
+      .entry(input.clone())
+      .or_insert_with(|| fingerprint_of(&input));
+
+    let tree = match self.trees.get(&input) {
+      Some(tree) => tree.clone(),
+      None => {
+        let css = fs::read_to_string(&input)
+          .map_err(|error| Error::Io(format!("Can not read {}: {}", input.display(), error)))?;
+        let tree = processor.parse(&css, &opts).map_err(Error::Syntax)?;
+        let tree = Rc::new(RefCell::new(tree.borrow().deep_clone_owned()));
+        self.trees.insert(input.clone(), tree.clone());
+        tree
+      }
+    };
+    let root = Rc::new(RefCell::new(tree.borrow().deep_clone()));
+    let result = processor.process_root(&root, opts).map_err(Error::Syntax)?;
+
+    let mut watched = HashSet::from([input]);
+    for dependency in result.dependencies() {
+      let dependency = absolute(Path::new(dependency));
+      self
+        .fingerprints
+        .entry(dependency.clone())
+        .or_insert_with(|| fingerprint_of(&dependency));
+      watched.insert(dependency);
+    }
+    self.watched[index] = watched;
+
+    report_warnings(job, &result);
+    write(job, &result)
+  }
+}
+
+/// The absolute path of the input of a job of the watcher.
+fn input_of(job: &Job) -> PathBuf {
+  absolute(job.input.as_deref().unwrap())
+}
+
+/// The absolute path of the output of a job of the watcher.
+fn output_of(job: &Job) -> PathBuf {
+  absolute(job.output.as_deref().unwrap())
+}
+
+fn fingerprint_of(path: &Path) -> Fingerprint {
+  let metadata = fs::metadata(path).ok()?;
+  Some((metadata.modified().ok()?, metadata.len()))
+}
@@ -0,0 +1,295 @@
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use tempfile::TempDir;
+
+fn postcss(dir: &TempDir, args: &[&str], stdin: &str) -> Output {
+  let mut child = Command::new(env!("CARGO_BIN_EXE_postcss"))
+    .args(args)
+    .current_dir(dir.path())
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+  child
+    .stdin
+    .take()
+    .unwrap()
+    .write_all(stdin.as_bytes())
+    .unwrap();
+  child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+  String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+  String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn pipes_stdin_to_stdout() {
+  let dir = TempDir::new().unwrap();
+  let output = postcss(
+    &dir,
+    &["--no-map", "--use", "media-flatten"],
+    "d { e: f }\n@media not all { a { b: c } }",
+  );
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert_eq!(stdout(&output), "d { e: f }");
+}
+
+#[test]
+fn writes_files_and_external_maps() {
+  let dir = TempDir::new().unwrap();
+  fs::write(dir.path().join("a.css"), "a { b: c }").unwrap();
+  let output = postcss(&dir, &["a.css", "-o", "out/a.css", "--map"], "");
+  assert!(output.status.success(), "{}", stderr(&output));
+  let css = fs::read_to_string(dir.path().join("out/a.css")).unwrap();
+  assert_eq!(css, "a { b: c }\n/*# sourceMappingURL=a.css.map */");
+  let map = fs::read_to_string(dir.path().join("out/a.css.map")).unwrap();
+  assert!(map.contains(r#""sources":["../a.css"]"#), "{}", map);
+
+  let output = postcss(&dir, &["a.css"], "");
+  assert!(stdout(&output).contains("sourceMappingURL=data:application/json;base64,"));
+}
+
+#[test]
+fn expands_globs_into_a_directory() {
+  let dir = TempDir::new().unwrap();
+  fs::create_dir(dir.path().join("src")).unwrap();
+  fs::write(dir.path().join("src/a.css"), "a {}").unwrap();
+  fs::write(dir.path().join("src/b.css"), "b {}").unwrap();
+  fs::write(dir.path().join("src/c.txt"), "c").unwrap();
+  let output = postcss(
+    &dir,
+    &["src/*.css", "--dir", "dist", "--ext", "min.css", "--no-map"],
+    "",
+  );
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert_eq!(
+    fs::read_to_string(dir.path().join("dist/a.min.css")).unwrap(),
+    "a {}"
+  );
+  assert_eq!(
+    fs::read_to_string(dir.path().join("dist/b.min.css")).unwrap(),
+    "b {}"
+  );
+  assert!(!dir.path().join("dist/c.min.css").exists());
+
+  let output = postcss(&dir, &["src/*.css", "-o", "out.css"], "");
+  assert!(!output.status.success());
+  assert!(stderr(&output).contains("Must use --dir with multiple input files"));
+}
+
+#[test]
+fn keeps_the_paths_below_the_glob_base() {
+  let dir = TempDir::new().unwrap();
+  fs::create_dir_all(dir.path().join("src/a")).unwrap();
+  fs::create_dir_all(dir.path().join("src/b")).unwrap();
+  fs::write(dir.path().join("src/a/x.css"), "a {}").unwrap();
+  fs::write(dir.path().join("src/b/x.css"), "b {}").unwrap();
+  let output = postcss(&dir, &["src/**/*.css", "--dir", "dist", "--no-map"], "");
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert_eq!(
+    fs::read_to_string(dir.path().join("dist/a/x.css")).unwrap(),
+    "a {}"
+  );
+  assert_eq!(
+    fs::read_to_string(dir.path().join("dist/b/x.css")).unwrap(),
+    "b {}"
+  );
+
+  let output = postcss(
+    &dir,
+    &["src/a/x.css", "src/b/x.css", "--dir", "out", "--no-map"],
+    "",
+  );
+  assert!(!output.status.success());
+  assert!(stderr(&output).contains("would be written to out/x.css"));
+  assert!(!dir.path().join("out").exists());
+}
+
+#[test]
+fn reports_syntax_errors() {
+  let dir = TempDir::new().unwrap();
+  fs::write(dir.path().join("broken.css"), "a {\n  b c;\n}\n").unwrap();
+  let output = postcss(&dir, &["broken.css"], "");
+  assert_eq!(output.status.code(), Some(1));
+  let stderr = stderr(&output);
+  assert!(stderr.starts_with("CssSyntaxError: "), "{}", stderr);
+  assert!(
+    stderr.contains("broken.css:2:3: Unknown word"),
+    "{}",
+    stderr
+  );
+  assert!(stderr.contains("> 2 |   b c;\n    |   ^"), "{}", stderr);
+
+  let output = postcss(&dir, &["broken.css", "--parser", "safe", "--no-map"], "");
+  assert!(output.status.success());
+  assert_eq!(stdout(&output), "a {\n  b c;\n}\n");
+}
+
+#[test]
+fn reports_warnings_and_usage_errors() {
+  let dir = TempDir::new().unwrap();
+  let output = postcss(
+    &dir,
+    &["--no-map", "-u", "container-names"],
+    "@container sidebar (width > 1px) {}",
+  );
+  assert!(output.status.success());
+  assert!(
+    stderr(&output).starts_with("<stdin>:1:1: Container name \"sidebar\""),
+    "{}",
+    stderr(&output)
+  );
+  assert!(stderr(&output).contains("[container-names]"));
+
+  fs::write(dir.path().join("a.css"), "@import \"b.css\";\na {}").unwrap();
+  fs::write(
+    dir.path().join("b.css"),
+    "@container sidebar (width > 1px) {}",
+  )
+  .unwrap();
+  let args = ["a.css", "--no-map", "-u", "import", "-u", "container-names"];
+  let output = postcss(&dir, &args, "");
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert!(
+    stderr(&output).starts_with("b.css:1:1: Container name \"sidebar\""),
+    "{}",
+    stderr(&output)
+  );
+
+  let output = postcss(&dir, &["--use", "nope"], "");
+  assert_eq!(output.status.code(), Some(1));
+  assert!(stderr(&output).contains("Unknown plugin \"nope\""));
+
+  let output = postcss(&dir, &["--syntax", "json", "--parser", "postcss"], "a {}");
+  assert!(output.status.success());
+  assert!(stdout(&output).contains(r#""selector": "a""#));
+}
+
+/// Waits until `read` returns `Some`, for at most 10 seconds.
+#[test]
+fn reads_config_files() {
+  let dir = TempDir::new().unwrap();
+  fs::write(
+    dir.path().join("postcss.toml"),
+    r#"
+map = false
+plugins = [{ name = "media-flatten", options = { media-type = "print" } }]
+
+[[overrides]]
+files = "src/legacy/*.css"
+plugins = [{ name = "media-flatten", options = { media-type = "screen" } }]
+"#,
+  )
+  .unwrap();
+  fs::create_dir_all(dir.path().join("src/legacy")).unwrap();
+  let css = "@media screen { a { b: c } }";
+  fs::write(dir.path().join("src/a.css"), css).unwrap();
+  fs::write(dir.path().join("src/legacy/a.css"), css).unwrap();
+
+  let output = postcss(&dir, &["src/a.css"], "");
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert_eq!(stdout(&output), "");
+
+  let output = postcss(&dir, &["src/legacy/a.css"], "");
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert_eq!(stdout(&output), "a { b: c }");
+
+  let output = postcss(&dir, &["src/a.css", "--use", "supports-flatten"], "");
+  assert_eq!(stdout(&output), css);
+
+  fs::write(
+    dir.path().join("other.json"),
+    r#"{ "plugins": ["unknown"] }"#,
+  )
+  .unwrap();
+  let output = postcss(&dir, &["src/a.css", "--config", "other.json"], "");
+  assert_eq!(output.status.code(), Some(1));
+  assert!(
+    stderr(&output).contains("other.json: Unknown plugin \"unknown\""),
+    "{}",
+    stderr(&output)
+  );
+}
+
+#[test]
+fn reads_browserslist_configs() {
+  let dir = TempDir::new().unwrap();
+  fs::write(
+    dir.path().join("postcss.toml"),
+    "map = false\nplugins = [\"prefixer\"]",
+  )
+  .unwrap();
+  fs::write(
+    dir.path().join(".browserslistrc"),
+    "last 2 chrome versions\n\n[legacy]\nchrome 20",
+  )
+  .unwrap();
+  fs::write(dir.path().join("a.css"), "a { transition: opacity 1s }").unwrap();
+
+  let output = postcss(&dir, &["a.css"], "");
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert_eq!(stdout(&output), "a { transition: opacity 1s }");
+
+  let output = Command::new(env!("CARGO_BIN_EXE_postcss"))
+    .arg("a.css")
+    .env("BROWSERSLIST_ENV", "legacy")
+    .current_dir(dir.path())
+    .output()
+    .unwrap();
+  assert_eq!(
+    stdout(&output),
+    "a { -webkit-transition: opacity 1s; transition: opacity 1s }"
+  );
+}
+
+#[test]
+fn inlines_imports() {
+  let dir = TempDir::new().unwrap();
+  fs::create_dir_all(dir.path().join("src/partials")).unwrap();
+  fs::write(dir.path().join("src/a.css"), "@import 'partials/b' print;").unwrap();
+  fs::write(dir.path().join("src/partials/b.css"), "b { c: d }").unwrap();
+  let output = postcss(&dir, &["src/a.css", "--no-map", "--use", "import"], "");
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert_eq!(stdout(&output), "@media print {\nb { c: d }\n}");
+}
+
+fn wait_for<T>(mut read: impl FnMut() -> Option<T>) -> T {
+  for _ in 0..200 {
+    if let Some(value) = read() {
+      return value;
+    }
+    std::thread::sleep(std::time::Duration::from_millis(50));
+  }
+  panic!("timed out");
+}
+
+#[test]
+fn rebuilds_in_watch_mode() {
+  let dir = TempDir::new().unwrap();
+  let input = dir.path().join("a.css");
+  let output = dir.path().join("out.css");
+  fs::write(&input, "a {}").unwrap();
+  let mut child = Command::new(env!("CARGO_BIN_EXE_postcss"))
+    .args(["a.css", "-o", "out.css", "--no-map", "--watch"])
+    .current_dir(dir.path())
+    .stderr(Stdio::null())
+    .spawn()
+    .unwrap();
+
+  wait_for(|| fs::read_to_string(&output).ok().filter(|css| css == "a {}"));
+  fs::write(&input, "a { b: c }").unwrap();
+  let rebuilt = wait_for(|| fs::read_to_string(&output).ok().filter(|css| css != "a {}"));
+  child.kill().unwrap();
+  child.wait().unwrap();
+  assert_eq!(rebuilt, "a { b: c }");
+
+  let output = postcss(&dir, &["a.css", "--watch"], "");
+  assert!(stderr(&output).contains("Can not write to stdout in watch mode"));
+}
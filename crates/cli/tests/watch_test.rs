@@ -0,0 +1,184 @@
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessOptions, ProcessResult, Processor};
+use postcss_cli::{Job, Watcher};
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+/// Replaces the params of `@include <file>` with the content of the file.
+struct Include;
+
+impl Plugin for Include {
+  fn name(&self) -> &str {
+    "include"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    for node in root.borrow().as_shared().get_nodes().unwrap() {
+      if let Some(at_rule) = node.borrow_mut().as_at_rule_mut() {
+        let file = at_rule.params.clone();
+        at_rule.params = fs::read_to_string(&file).unwrap().trim().to_string();
+        result.add_dependency(&file, None);
+      }
+    }
+    Ok(())
+  }
+}
+
+static PARSED: AtomicUsize = AtomicUsize::new(0);
+
+fn counting_parse(
+  css: &str,
+  opts: Option<ProcessOptions>,
+) -> Result<Rc<RefCell<Node<'_>>>, CssSyntaxError> {
+  PARSED.fetch_add(1, Ordering::SeqCst);
+  (postcss::syntax::CSS.parse)(css, opts)
+}
+
+static TOUCHED: AtomicUsize = AtomicUsize::new(1);
+
+/// Writes a file with a newer modification time than the previous write.
+fn touch(path: &Path, contents: &str) {
+  fs::write(path, contents).unwrap();
+  let seconds = TOUCHED.fetch_add(1, Ordering::SeqCst) as u64;
+  let file = fs::File::options().write(true).open(path).unwrap();
+  file
+    .set_modified(SystemTime::now() + Duration::from_secs(seconds))
+    .unwrap();
+}
+
+fn outputs(builds: Vec<(PathBuf, Result<(), postcss_cli::Error>)>) -> Vec<PathBuf> {
+  builds
+    .into_iter()
+    .map(|(output, result)| {
+      result.unwrap();
+      output
+    })
+    .collect()
+}
+
+#[test]
+fn rebuilds_outputs_affected_by_changes() {
+  let dir = TempDir::new().unwrap();
+  let path = |name: &str| dir.path().join(name);
+  fs::write(path("dep.txt"), "one").unwrap();
+  fs::write(
+    path("a.css"),
+    format!("@include {}", path("dep.txt").display()),
+  )
+  .unwrap();
+  fs::write(path("b.css"), "b {}").unwrap();
+
+  let job = |name: &str| {
+    let mut processor = Processor::new();
+    processor
+      .use_plugin(Box::new(Include))
+      .set_parser(counting_parse);
+    let job = Job {
+      input: Some(path(name)),
+      output: Some(path(&format!("out/{}", name))),
+      map: None,
+    };
+    (job, processor)
+  };
+  let mut watcher = Watcher::new(vec![job("a.css"), job("b.css")]);
+  assert_eq!(
+    outputs(watcher.build_all()),
+    vec![path("out/a.css"), path("out/b.css")]
+  );
+  assert_eq!(
+    fs::read_to_string(path("out/a.css")).unwrap(),
+    "@include one"
+  );
+  assert_eq!(PARSED.load(Ordering::SeqCst), 2);
+  assert!(watcher.poll().is_empty());
+
+  // the dependency changed, `a.css` is rebuilt from its cached tree
+  touch(&path("dep.txt"), "two");
+  assert_eq!(outputs(watcher.poll()), vec![path("out/a.css")]);
+  assert_eq!(
+    fs::read_to_string(path("out/a.css")).unwrap(),
+    "@include two"
+  );
+  assert_eq!(PARSED.load(Ordering::SeqCst), 2);
+
+  touch(&path("b.css"), "b { c: d }");
+  assert_eq!(outputs(watcher.poll()), vec![path("out/b.css")]);
+  assert_eq!(fs::read_to_string(path("out/b.css")).unwrap(), "b { c: d }");
+  assert_eq!(PARSED.load(Ordering::SeqCst), 3);
+
+  // errors are returned and the input is rebuilt once fixed
+  touch(&path("b.css"), "b {");
+  let builds = watcher.poll();
+  assert_eq!(builds.len(), 1);
+  assert!(builds[0].1.is_err());
+  touch(&path("b.css"), "b { e: f }");
+  assert_eq!(outputs(watcher.poll()), vec![path("out/b.css")]);
+  assert_eq!(fs::read_to_string(path("out/b.css")).unwrap(), "b { e: f }");
+}
+
+#[test]
+fn builds_files_added_to_the_globs() {
+  let dir = TempDir::new().unwrap();
+  let src = dir.path().join("src");
+  fs::create_dir(&src).unwrap();
+  fs::write(src.join("a.css"), "a {}").unwrap();
+
+  // every file of `src` and its subdirectories, written to `src/out`
+  let expand = {
+    let src = src.clone();
+    move || {
+      fn files(dir: &Path, paths: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+          let path = entry.unwrap().path();
+          match path.is_dir() {
+            true => files(&path, paths),
+            false => paths.push(path),
+          }
+        }
+      }
+      let mut paths = vec![];
+      files(&src, &mut paths);
+      paths.sort();
+      let jobs = paths.into_iter().map(|path| {
+        let output = src.join("out").join(path.strip_prefix(&src).unwrap());
+        let job = Job {
+          input: Some(path),
+          output: Some(output),
+          map: None,
+        };
+        (job, Processor::new())
+      });
+      Ok(jobs.collect())
+    }
+  };
+  let mut watcher = Watcher::new(expand().unwrap());
+  watcher.expand_with(vec![src.clone()], expand);
+  assert_eq!(outputs(watcher.build_all()), vec![src.join("out/a.css")]);
+  // the output directory is not built again
+  assert!(watcher.poll().is_empty());
+
+  fs::create_dir(src.join("nested")).unwrap();
+  fs::write(src.join("nested/b.css"), "b {}").unwrap();
+  assert_eq!(outputs(watcher.poll()), vec![src.join("out/nested/b.css")]);
+  fs::write(src.join("nested/c.css"), "c {}").unwrap();
+  assert_eq!(outputs(watcher.poll()), vec![src.join("out/nested/c.css")]);
+  assert_eq!(
+    fs::read_to_string(src.join("out/nested/c.css")).unwrap(),
+    "c {}"
+  );
+
+  // removed files are dropped instead of failing to build
+  fs::remove_file(src.join("nested/b.css")).unwrap();
+  assert!(watcher.poll().is_empty());
+  touch(&src.join("nested/c.css"), "c { d: e }");
+  assert_eq!(outputs(watcher.poll()), vec![src.join("out/nested/c.css")]);
+}
@@ -0,0 +1,256 @@
+use crate::convert::convert;
+use std::fmt;
+use value_parser::format_number;
+
+/// The color spaces of CSS Color 4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+  Srgb,
+  SrgbLinear,
+  DisplayP3,
+  A98Rgb,
+  ProphotoRgb,
+  Rec2020,
+  XyzD50,
+  XyzD65,
+  Lab,
+  Lch,
+  Oklab,
+  Oklch,
+  Hsl,
+  Hwb,
+}
+
+impl ColorSpace {
+  /// The space of a name of `color()` or `color-mix()`, in lowercase.
+  pub fn from_name(name: &str) -> Option<Self> {
+    Some(match name {
+      "srgb" => ColorSpace::Srgb,
+      "srgb-linear" => ColorSpace::SrgbLinear,
+      "display-p3" => ColorSpace::DisplayP3,
+      "a98-rgb" => ColorSpace::A98Rgb,
+      "prophoto-rgb" => ColorSpace::ProphotoRgb,
+      "rec2020" => ColorSpace::Rec2020,
+      "xyz-d50" => ColorSpace::XyzD50,
+      "xyz" | "xyz-d65" => ColorSpace::XyzD65,
+      "lab" => ColorSpace::Lab,
+      "lch" => ColorSpace::Lch,
+      "oklab" => ColorSpace::Oklab,
+      "oklch" => ColorSpace::Oklch,
+      "hsl" => ColorSpace::Hsl,
+      "hwb" => ColorSpace::Hwb,
+      _ => return None,
+    })
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      ColorSpace::Srgb => "srgb",
+      ColorSpace::SrgbLinear => "srgb-linear",
+      ColorSpace::DisplayP3 => "display-p3",
+      ColorSpace::A98Rgb => "a98-rgb",
+      ColorSpace::ProphotoRgb => "prophoto-rgb",
+      ColorSpace::Rec2020 => "rec2020",
+      ColorSpace::XyzD50 => "xyz-d50",
+      ColorSpace::XyzD65 => "xyz-d65",
+      ColorSpace::Lab => "lab",
+      ColorSpace::Lch => "lch",
+      ColorSpace::Oklab => "oklab",
+      ColorSpace::Oklch => "oklch",
+      ColorSpace::Hsl => "hsl",
+      ColorSpace::Hwb => "hwb",
+    }
+  }
+
+  /// The index of the hue component, for polar spaces.
+  pub fn hue_index(&self) -> Option<usize> {
+    match self {
+      ColorSpace::Hsl | ColorSpace::Hwb => Some(0),
+      ColorSpace::Lch | ColorSpace::Oklch => Some(2),
+      _ => None,
+    }
+  }
+
+  /// The RGB space with the gamut of the space, if it has one.
+  fn gamut(&self) -> Option<ColorSpace> {
+    match self {
+      ColorSpace::Srgb | ColorSpace::Hsl | ColorSpace::Hwb => Some(ColorSpace::Srgb),
+      ColorSpace::SrgbLinear
+      | ColorSpace::DisplayP3
+      | ColorSpace::A98Rgb
+      | ColorSpace::ProphotoRgb
+      | ColorSpace::Rec2020 => Some(*self),
+      _ => None,
+    }
+  }
+}
+
+/// A color, with the components of its space and an alpha in `0..1`.
+///
+/// RGB components are in `0..1`, the saturation, lightness, whiteness and
+/// blackness of HSL and HWB and the lightness of Lab and LCH in `0..100`,
+/// and hues in degrees. Missing components, written `none`, are 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+  pub space: ColorSpace,
+  pub components: [f64; 3],
+  pub alpha: f64,
+}
+
+/// The decimals of the components of [`Color`]'s `Display`.
+const PRECISION: u32 = 5;
+
+/// The difference under which colors look the same, for gamut mapping.
+const JND: f64 = 0.02;
+
+impl Color {
+  pub fn new(space: ColorSpace, components: [f64; 3], alpha: f64) -> Self {
+    Self {
+      space,
+      components,
+      alpha,
+    }
+  }
+
+  /// An sRGB color from 8-bit components.
+  pub fn rgb(r: u8, g: u8, b: u8, alpha: f64) -> Self {
+    let c = |c: u8| f64::from(c) / 255.0;
+    Self::new(ColorSpace::Srgb, [c(r), c(g), c(b)], alpha)
+  }
+
+  /// The same color in another space, which may be out of its gamut.
+  pub fn to(&self, space: ColorSpace) -> Color {
+    Color::new(
+      space,
+      convert(self.space, space, self.components),
+      self.alpha,
+    )
+  }
+
+  /// Returns `true` if the color is in the gamut of `space`, always for
+  /// spaces without one like Lab.
+  pub fn in_gamut(&self, space: ColorSpace) -> bool {
+    let gamut = match space.gamut() {
+      Some(gamut) => gamut,
+      None => return true,
+    };
+    const EPSILON: f64 = 0.000_075;
+    self
+      .to(gamut)
+      .components
+      .iter()
+      .all(|c| (-EPSILON..=1.0 + EPSILON).contains(c))
+  }
+
+  /// The color in `space`, mapped into its gamut by reducing the OKLCh
+  /// chroma like the CSS Color 4 gamut mapping algorithm, so that the
+  /// lightness and hue are kept.
+  pub fn to_gamut(&self, space: ColorSpace) -> Color {
+    let gamut = match space.gamut() {
+      Some(gamut) => gamut,
+      None => return self.to(space),
+    };
+    let clip = |color: Color| {
+      let mut clipped = color.to(gamut);
+      for c in &mut clipped.components {
+        *c = c.clamp(0.0, 1.0);
+      }
+      clipped
+    };
+    let origin = self.to(ColorSpace::Oklch);
+    let lightness = origin.components[0];
+    let mapped = if lightness >= 1.0 {
+      Color::new(gamut, [1.0; 3], self.alpha)
+    } else if lightness <= 0.0 {
+      Color::new(gamut, [0.0; 3], self.alpha)
+    } else if self.in_gamut(gamut) {
+      clip(*self)
+    } else {
+      let mut current = origin;
+      let mut clipped = clip(current);
+      if delta_eok(&clipped, &current) >= JND {
+        const EPSILON: f64 = 0.0001;
+        let (mut min, mut max) = (0.0, origin.components[1]);
+        let mut min_in_gamut = true;
+        while max - min > EPSILON {
+          let chroma = (min + max) / 2.0;
+          current.components[1] = chroma;
+          if min_in_gamut && current.in_gamut(gamut) {
+            min = chroma;
+            continue;
+          }
+          clipped = clip(current);
+          let delta = delta_eok(&clipped, &current);
+          if delta < JND {
+            if JND - delta < EPSILON {
+              break;
+            }
+            min_in_gamut = false;
+            min = chroma;
+          } else {
+            max = chroma;
+          }
+        }
+      }
+      clipped
+    };
+    mapped.to(space)
+  }
+
+  /// The color in the `rgb()` or `rgba()` syntax with commas, that all
+  /// browsers support, mapped into the sRGB gamut.
+  pub fn to_legacy_rgb(&self) -> String {
+    let [r, g, b] = self.rgb8();
+    let alpha = self.alpha.clamp(0.0, 1.0);
+    if alpha == 1.0 {
+      format!("rgb({}, {}, {})", r, g, b)
+    } else {
+      format!("rgba({}, {}, {}, {})", r, g, b, format_number(alpha, 3))
+    }
+  }
+
+  /// The color as `#rrggbb`, or `#rrggbbaa` if it is transparent, mapped
+  /// into the sRGB gamut.
+  pub fn to_hex(&self) -> String {
+    let [r, g, b] = self.rgb8();
+    let alpha = (self.alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+    match alpha {
+      255 => format!("#{:02x}{:02x}{:02x}", r, g, b),
+      alpha => format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, alpha),
+    }
+  }
+
+  fn rgb8(&self) -> [u8; 3] {
+    let rgb = self.to_gamut(ColorSpace::Srgb).components;
+    rgb.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+  }
+}
+
+/// The difference between two colors, in OKLab.
+pub fn delta_eok(a: &Color, b: &Color) -> f64 {
+  let a = a.to(ColorSpace::Oklab).components;
+  let b = b.to(ColorSpace::Oklab).components;
+  ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// Serializes the color in the syntax of its space: `rgb()` for sRGB, HSL
+/// and HWB colors, `lab()`, `lch()`, `oklab()` and `oklch()` for those
+/// spaces and `color()` for the others.
+impl fmt::Display for Color {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let [a, b, c] = self.components.map(|c| format_number(c, PRECISION));
+    match self.space {
+      ColorSpace::Srgb | ColorSpace::Hsl | ColorSpace::Hwb => {
+        return write!(f, "{}", self.to_legacy_rgb())
+      }
+      ColorSpace::Lab | ColorSpace::Lch | ColorSpace::Oklab | ColorSpace::Oklch => {
+        write!(f, "{}({} {} {}", self.space.name(), a, b, c)?
+      }
+      space => write!(f, "color({} {} {} {}", space.name(), a, b, c)?,
+    }
+    if self.alpha < 1.0 {
+      write!(f, " / {}", format_number(self.alpha.max(0.0), PRECISION))?;
+    }
+    write!(f, ")")
+  }
+}
@@ -0,0 +1,412 @@
+//! Conversions between color spaces and CIE XYZ, with the matrices and
+//! transfer functions of the CSS Color 4 sample code.
+
+use crate::color::ColorSpace;
+
+type Matrix = [[f64; 3]; 3];
+
+fn multiply(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+  [
+    m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+    m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+    m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+  ]
+}
+
+const SRGB_TO_XYZ: Matrix = [
+  [0.41239079926595934, 0.357584339383878, 0.1804807884018343],
+  [0.21263900587151027, 0.715168678767756, 0.07219231536073371],
+  [0.01933081871559182, 0.11919477979462598, 0.9505321522496607],
+];
+const XYZ_TO_SRGB: Matrix = [
+  [3.2409699419045226, -1.537383177570094, -0.4986107602930034],
+  [-0.9692436362808796, 1.8759675015077202, 0.04155505740717559],
+  [
+    0.05563007969699366,
+    -0.20397695888897652,
+    1.0569715142428786,
+  ],
+];
+const P3_TO_XYZ: Matrix = [
+  [0.4865709486482162, 0.26566769316909306, 0.1982172852343625],
+  [0.2289745640697488, 0.6917385218365064, 0.079286914093745],
+  [0.0, 0.04511338185890264, 1.043944368900976],
+];
+const XYZ_TO_P3: Matrix = [
+  [2.493496911941425, -0.9313836179191239, -0.40271078445071684],
+  [
+    -0.8294889695615747,
+    1.7626640603183463,
+    0.023624685841943577,
+  ],
+  [
+    0.03584583024378447,
+    -0.07617238926804182,
+    0.9568845240076872,
+  ],
+];
+const A98_TO_XYZ: Matrix = [
+  [0.5766690429101305, 0.1855582379065463, 0.1882286462349947],
+  [0.29734497525053605, 0.6273635662554661, 0.07529145849399788],
+  [0.02703136138641234, 0.07068885253582723, 0.9913375368376388],
+];
+const XYZ_TO_A98: Matrix = [
+  [
+    2.0415879038107465,
+    -0.5650069742788596,
+    -0.34473135077832956,
+  ],
+  [-0.9692436362808795, 1.8759675015077202, 0.04155505740717557],
+  [
+    0.013444280632031142,
+    -0.11836239223101838,
+    1.0151749943912054,
+  ],
+];
+const PROPHOTO_TO_XYZ_D50: Matrix = [
+  [0.7977604896723027, 0.13518583717574031, 0.0313493495815248],
+  [
+    0.2880711282292934,
+    0.7118432178101014,
+    0.00008565396060525902,
+  ],
+  [0.0, 0.0, 0.8251046025104601],
+];
+const XYZ_D50_TO_PROPHOTO: Matrix = [
+  [
+    1.3457989731028281,
+    -0.25558010007997534,
+    -0.05110628506753401,
+  ],
+  [-0.5446224939028347, 1.5082327413132781, 0.02053603239147973],
+  [0.0, 0.0, 1.2119675456389454],
+];
+const REC2020_TO_XYZ: Matrix = [
+  [0.6369580483012914, 0.14461690358620832, 0.1688809751641721],
+  [0.2627002120112671, 0.6779980715188708, 0.05930171646986196],
+  [0.0, 0.028072693049087428, 1.060985057710791],
+];
+const XYZ_TO_REC2020: Matrix = [
+  [1.716651187971268, -0.355670783776392, -0.253366281373660],
+  [-0.666684351832489, 1.616481236634939, 0.0157685458139111],
+  [0.017639857445311, -0.042770613257809, 0.942103121235474],
+];
+/// Bradford chromatic adaptation.
+const D65_TO_D50: Matrix = [
+  [
+    1.0479298208405488,
+    0.022946793341019088,
+    -0.05019222954313557,
+  ],
+  [
+    0.029627815688159344,
+    0.990434484573249,
+    -0.01707382502938514,
+  ],
+  [
+    -0.009243058152591178,
+    0.015055144896577895,
+    0.7518742899580008,
+  ],
+];
+const D50_TO_D65: Matrix = [
+  [
+    0.9554734527042182,
+    -0.023098536874261423,
+    0.0632593086610217,
+  ],
+  [
+    -0.028369706963208136,
+    1.0099954580058226,
+    0.021041398966943008,
+  ],
+  [
+    0.012314001688319899,
+    -0.020507696433477912,
+    1.3303659366080753,
+  ],
+];
+const XYZ_TO_LMS: Matrix = [
+  [
+    0.819_022_437_996_703,
+    0.3619062600528904,
+    -0.1288737815209879,
+  ],
+  [0.0329836539323885, 0.9292868615863434, 0.0361446663506424],
+  [0.0481771893596242, 0.2642395317527308, 0.6335478284694309],
+];
+const LMS_TO_OKLAB: Matrix = [
+  [
+    0.210_454_268_309_314,
+    0.7936177747023054,
+    -0.0040720430116193,
+  ],
+  [
+    1.9779985324311684,
+    -2.428_592_242_048_58,
+    0.450_593_709_617_411,
+  ],
+  [0.0259040424655478, 0.7827717124575296, -0.8086757549230774],
+];
+const OKLAB_TO_LMS: Matrix = [
+  [1.0, 0.3963377773761749, 0.2158037573099136],
+  [1.0, -0.1055613458156586, -0.0638541728258133],
+  [1.0, -0.0894841775298119, -1.2914855480194092],
+];
+const LMS_TO_XYZ: Matrix = [
+  [1.2268798758459243, -0.5578149944602171, 0.2813910456659647],
+  [
+    -0.0405757452148008,
+    1.112_286_803_280_317,
+    -0.0717110580655164,
+  ],
+  [-0.0763729366746601, -0.4214933324022432, 1.5869240198367816],
+];
+
+const D50: [f64; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+fn map(c: [f64; 3], f: impl Fn(f64) -> f64) -> [f64; 3] {
+  [f(c[0]), f(c[1]), f(c[2])]
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+  let abs = c.abs();
+  if abs <= 0.04045 {
+    c / 12.92
+  } else {
+    c.signum() * ((abs + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+fn srgb_from_linear(c: f64) -> f64 {
+  let abs = c.abs();
+  if abs > 0.0031308 {
+    c.signum() * (1.055 * abs.powf(1.0 / 2.4) - 0.055)
+  } else {
+    12.92 * c
+  }
+}
+
+fn a98_to_linear(c: f64) -> f64 {
+  c.signum() * c.abs().powf(563.0 / 256.0)
+}
+
+fn a98_from_linear(c: f64) -> f64 {
+  c.signum() * c.abs().powf(256.0 / 563.0)
+}
+
+fn prophoto_to_linear(c: f64) -> f64 {
+  if c.abs() <= 16.0 / 512.0 {
+    c / 16.0
+  } else {
+    c.signum() * c.abs().powf(1.8)
+  }
+}
+
+fn prophoto_from_linear(c: f64) -> f64 {
+  if c.abs() >= 1.0 / 512.0 {
+    c.signum() * c.abs().powf(1.0 / 1.8)
+  } else {
+    16.0 * c
+  }
+}
+
+const REC2020_ALPHA: f64 = 1.09929682680944;
+const REC2020_BETA: f64 = 0.018053968510807;
+
+fn rec2020_to_linear(c: f64) -> f64 {
+  if c.abs() < REC2020_BETA * 4.5 {
+    c / 4.5
+  } else {
+    c.signum() * ((c.abs() + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+  }
+}
+
+fn rec2020_from_linear(c: f64) -> f64 {
+  if c.abs() > REC2020_BETA {
+    c.signum() * (REC2020_ALPHA * c.abs().powf(0.45) - (REC2020_ALPHA - 1.0))
+  } else {
+    4.5 * c
+  }
+}
+
+const KAPPA: f64 = 24389.0 / 27.0;
+const EPSILON: f64 = 216.0 / 24389.0;
+
+fn xyz_d50_to_lab(xyz: [f64; 3]) -> [f64; 3] {
+  let f = |i: usize| {
+    let v = xyz[i] / D50[i];
+    if v > EPSILON {
+      v.cbrt()
+    } else {
+      (KAPPA * v + 16.0) / 116.0
+    }
+  };
+  let (f0, f1, f2) = (f(0), f(1), f(2));
+  [116.0 * f1 - 16.0, 500.0 * (f0 - f1), 200.0 * (f1 - f2)]
+}
+
+fn lab_to_xyz_d50([l, a, b]: [f64; 3]) -> [f64; 3] {
+  let f1 = (l + 16.0) / 116.0;
+  let f0 = a / 500.0 + f1;
+  let f2 = f1 - b / 200.0;
+  let x = if f0.powi(3) > EPSILON {
+    f0.powi(3)
+  } else {
+    (116.0 * f0 - 16.0) / KAPPA
+  };
+  let y = if l > KAPPA * EPSILON {
+    f1.powi(3)
+  } else {
+    l / KAPPA
+  };
+  let z = if f2.powi(3) > EPSILON {
+    f2.powi(3)
+  } else {
+    (116.0 * f2 - 16.0) / KAPPA
+  };
+  [x * D50[0], y * D50[1], z * D50[2]]
+}
+
+/// Cartesian `a` and `b` to chroma and hue in degrees, with a hue of 0 for
+/// achromatic colors.
+fn to_polar([l, a, b]: [f64; 3]) -> [f64; 3] {
+  let chroma = (a * a + b * b).sqrt();
+  let hue = if chroma < 1e-9 {
+    0.0
+  } else {
+    b.atan2(a).to_degrees().rem_euclid(360.0)
+  };
+  [l, chroma, hue]
+}
+
+fn from_polar([l, c, h]: [f64; 3]) -> [f64; 3] {
+  let h = h.to_radians();
+  [l, c.max(0.0) * h.cos(), c.max(0.0) * h.sin()]
+}
+
+/// sRGB in `0..1` to HSL with saturation and lightness in `0..100`.
+fn srgb_to_hsl([r, g, b]: [f64; 3]) -> [f64; 3] {
+  let max = r.max(g).max(b);
+  let min = r.min(g).min(b);
+  let lightness = (min + max) / 2.0;
+  let d = max - min;
+  let (mut hue, mut saturation) = (0.0, 0.0);
+  if d != 0.0 {
+    saturation = if lightness == 0.0 || lightness == 1.0 {
+      0.0
+    } else {
+      (max - lightness) / lightness.min(1.0 - lightness)
+    };
+    hue = if max == r {
+      (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+      (b - r) / d + 2.0
+    } else {
+      (r - g) / d + 4.0
+    };
+    hue *= 60.0;
+  }
+  if saturation < 0.0 {
+    hue += 180.0;
+    saturation = saturation.abs();
+  }
+  [hue.rem_euclid(360.0), saturation * 100.0, lightness * 100.0]
+}
+
+fn hsl_to_srgb([h, s, l]: [f64; 3]) -> [f64; 3] {
+  let h = h.rem_euclid(360.0);
+  let (s, l) = (s / 100.0, l / 100.0);
+  let f = |n: f64| {
+    let k = (n + h / 30.0) % 12.0;
+    let a = s * l.min(1.0 - l);
+    l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+  };
+  [f(0.0), f(8.0), f(4.0)]
+}
+
+fn srgb_to_hwb(rgb: [f64; 3]) -> [f64; 3] {
+  let hue = srgb_to_hsl(rgb)[0];
+  let white = rgb[0].min(rgb[1]).min(rgb[2]);
+  let black = 1.0 - rgb[0].max(rgb[1]).max(rgb[2]);
+  [hue, white * 100.0, black * 100.0]
+}
+
+fn hwb_to_srgb([h, w, b]: [f64; 3]) -> [f64; 3] {
+  let (w, b) = (w / 100.0, b / 100.0);
+  if w + b >= 1.0 {
+    let gray = w / (w + b);
+    return [gray; 3];
+  }
+  map(hsl_to_srgb([h, 100.0, 50.0]), |c| c * (1.0 - w - b) + w)
+}
+
+/// The components in `space` to CIE XYZ with a D65 white point.
+pub(crate) fn to_xyz(space: ColorSpace, c: [f64; 3]) -> [f64; 3] {
+  match space {
+    ColorSpace::Srgb => multiply(&SRGB_TO_XYZ, map(c, srgb_to_linear)),
+    ColorSpace::SrgbLinear => multiply(&SRGB_TO_XYZ, c),
+    ColorSpace::DisplayP3 => multiply(&P3_TO_XYZ, map(c, srgb_to_linear)),
+    ColorSpace::A98Rgb => multiply(&A98_TO_XYZ, map(c, a98_to_linear)),
+    ColorSpace::ProphotoRgb => multiply(
+      &D50_TO_D65,
+      multiply(&PROPHOTO_TO_XYZ_D50, map(c, prophoto_to_linear)),
+    ),
+    ColorSpace::Rec2020 => multiply(&REC2020_TO_XYZ, map(c, rec2020_to_linear)),
+    ColorSpace::XyzD65 => c,
+    ColorSpace::XyzD50 => multiply(&D50_TO_D65, c),
+    ColorSpace::Lab => multiply(&D50_TO_D65, lab_to_xyz_d50(c)),
+    ColorSpace::Lch => to_xyz(ColorSpace::Lab, from_polar(c)),
+    ColorSpace::Oklab => {
+      let lms = map(multiply(&OKLAB_TO_LMS, c), |c| c.powi(3));
+      multiply(&LMS_TO_XYZ, lms)
+    }
+    ColorSpace::Oklch => to_xyz(ColorSpace::Oklab, from_polar(c)),
+    ColorSpace::Hsl => to_xyz(ColorSpace::Srgb, hsl_to_srgb(c)),
+    ColorSpace::Hwb => to_xyz(ColorSpace::Srgb, hwb_to_srgb(c)),
+  }
+}
+
+/// CIE XYZ with a D65 white point to the components in `space`.
+pub(crate) fn from_xyz(space: ColorSpace, xyz: [f64; 3]) -> [f64; 3] {
+  match space {
+    ColorSpace::Srgb => map(multiply(&XYZ_TO_SRGB, xyz), srgb_from_linear),
+    ColorSpace::SrgbLinear => multiply(&XYZ_TO_SRGB, xyz),
+    ColorSpace::DisplayP3 => map(multiply(&XYZ_TO_P3, xyz), srgb_from_linear),
+    ColorSpace::A98Rgb => map(multiply(&XYZ_TO_A98, xyz), a98_from_linear),
+    ColorSpace::ProphotoRgb => map(
+      multiply(&XYZ_D50_TO_PROPHOTO, multiply(&D65_TO_D50, xyz)),
+      prophoto_from_linear,
+    ),
+    ColorSpace::Rec2020 => map(multiply(&XYZ_TO_REC2020, xyz), rec2020_from_linear),
+    ColorSpace::XyzD65 => xyz,
+    ColorSpace::XyzD50 => multiply(&D65_TO_D50, xyz),
+    ColorSpace::Lab => xyz_d50_to_lab(multiply(&D65_TO_D50, xyz)),
+    ColorSpace::Lch => to_polar(from_xyz(ColorSpace::Lab, xyz)),
+    ColorSpace::Oklab => {
+      let lms = map(multiply(&XYZ_TO_LMS, xyz), f64::cbrt);
+      multiply(&LMS_TO_OKLAB, lms)
+    }
+    ColorSpace::Oklch => to_polar(from_xyz(ColorSpace::Oklab, xyz)),
+    ColorSpace::Hsl => srgb_to_hsl(from_xyz(ColorSpace::Srgb, xyz)),
+    ColorSpace::Hwb => srgb_to_hwb(from_xyz(ColorSpace::Srgb, xyz)),
+  }
+}
+
+/// Converts without going through XYZ where a direct conversion exists,
+/// so that sRGB, HSL and HWB colors round trip exactly.
+pub(crate) fn convert(from: ColorSpace, to: ColorSpace, c: [f64; 3]) -> [f64; 3] {
+  use ColorSpace::*;
+  match (from, to) {
+    (from, to) if from == to => c,
+    (Srgb, Hsl) => srgb_to_hsl(c),
+    (Srgb, Hwb) => srgb_to_hwb(c),
+    (Hsl, Srgb) => hsl_to_srgb(c),
+    (Hwb, Srgb) => hwb_to_srgb(c),
+    (Hsl, Hwb) => srgb_to_hwb(hsl_to_srgb(c)),
+    (Hwb, Hsl) => srgb_to_hsl(hwb_to_srgb(c)),
+    (Lab, Lch) | (Oklab, Oklch) => to_polar(c),
+    (Lch, Lab) | (Oklch, Oklab) => from_polar(c),
+    (from, to) => from_xyz(to, to_xyz(from, c)),
+  }
+}
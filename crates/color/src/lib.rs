@@ -0,0 +1,11 @@
+mod color;
+mod convert;
+mod named;
+mod parse;
+mod plugin;
+mod support;
+
+pub use crate::color::{delta_eok, Color, ColorSpace};
+pub use crate::parse::HueInterpolation;
+pub use crate::plugin::{ColorFallbacks, Options};
+pub use crate::support::{feature, Feature, Support};
@@ -0,0 +1,373 @@
+use crate::color::{Color, ColorSpace};
+use crate::named::named;
+use value_parser::{dimension, trim, Function, Node};
+
+/// How hues are interpolated by `color-mix()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HueInterpolation {
+  #[default]
+  Shorter,
+  Longer,
+  Increasing,
+  Decreasing,
+}
+
+/// A component of a color function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Component {
+  Number(f64),
+  Percentage(f64),
+  /// An angle in degrees.
+  Angle(f64),
+  None,
+}
+
+impl Component {
+  fn parse(node: &Node) -> Option<Component> {
+    let word = node.as_word()?;
+    if word.eq_ignore_ascii_case("none") {
+      return Some(Component::None);
+    }
+    let dimension = dimension(word)?;
+    let value = dimension.value;
+    Some(match dimension.unit.to_ascii_lowercase().as_str() {
+      "" => Component::Number(value),
+      "%" => Component::Percentage(value),
+      "deg" => Component::Angle(value),
+      "grad" => Component::Angle(value * 0.9),
+      "rad" => Component::Angle(value.to_degrees()),
+      "turn" => Component::Angle(value * 360.0),
+      _ => return None,
+    })
+  }
+
+  /// A number, or a percentage of `percent`.
+  fn number(self, percent: f64) -> Option<f64> {
+    match self {
+      Component::Number(value) => Some(value),
+      Component::Percentage(value) => Some(value / 100.0 * percent),
+      Component::None => Some(0.0),
+      Component::Angle(_) => None,
+    }
+  }
+
+  fn hue(self) -> Option<f64> {
+    match self {
+      Component::Number(value) | Component::Angle(value) => Some(value),
+      Component::None => Some(0.0),
+      Component::Percentage(_) => None,
+    }
+  }
+
+  fn alpha(self) -> Option<f64> {
+    self.number(1.0).map(|alpha| alpha.clamp(0.0, 1.0))
+  }
+}
+
+/// The components and alpha of a color function, and whether they are
+/// separated by commas.
+struct Arguments {
+  components: Vec<Component>,
+  alpha: Option<Component>,
+  legacy: bool,
+}
+
+/// Splits the arguments of a color function, `a, b, c[, alpha]` or
+/// `a b c [/ alpha]`. The first `skip` words, like the space of `color()`,
+/// are left out.
+fn arguments(nodes: &[Node], skip: usize) -> Option<Arguments> {
+  let nodes: Vec<&Node> = nodes.iter().filter(|node| !node.is_space()).collect();
+  let nodes = nodes.get(skip..)?;
+  if nodes.iter().any(|node| **node == Node::Div(',')) {
+    let parts: Vec<_> = nodes.split(|node| **node == Node::Div(',')).collect();
+    let components = parts
+      .iter()
+      .map(|part| match part {
+        [node] => Component::parse(node),
+        _ => None,
+      })
+      .collect::<Option<Vec<_>>>()?;
+    if !(3..=4).contains(&components.len()) || components.contains(&Component::None) {
+      return None;
+    }
+    return Some(Arguments {
+      alpha: components.get(3).copied(),
+      components: components[..3].to_vec(),
+      legacy: true,
+    });
+  }
+  let (components, alpha) = match nodes.iter().position(|node| **node == Node::Div('/')) {
+    Some(slash) => match &nodes[slash + 1..] {
+      [alpha] => (&nodes[..slash], Some(Component::parse(alpha)?)),
+      _ => return None,
+    },
+    None => (nodes, None),
+  };
+  let components = components
+    .iter()
+    .map(|node| Component::parse(node))
+    .collect::<Option<Vec<_>>>()?;
+  (components.len() == 3).then_some(Arguments {
+    components,
+    alpha,
+    legacy: false,
+  })
+}
+
+/// Returns `true` if a function is a color function that needs CSS Color 4
+/// support, as opposed to the `rgb()`, `rgba()`, `hsl()` and `hsla()` with
+/// commas that all browsers know.
+pub(crate) fn is_legacy(function: &Function) -> bool {
+  match function.lower_name().as_str() {
+    "rgb" | "hsl" => {
+      arguments(&function.nodes, 0).is_some_and(|args| args.legacy && args.alpha.is_none())
+    }
+    "rgba" | "hsla" => arguments(&function.nodes, 0).is_some_and(|args| args.legacy),
+    _ => false,
+  }
+}
+
+impl Color {
+  /// Parses a color, e.g. `#0f08`, `rebeccapurple`, `hsl(120deg 50% 50%)`,
+  /// `oklch(70% 0.1 200 / 50%)`, `color(display-p3 1 0 0)` or
+  /// `color-mix(in lch, red 40%, blue)`.
+  ///
+  /// Keywords depending on the context, like `currentcolor`, are not colors.
+  pub fn parse(input: &str) -> Option<Color> {
+    let value = value_parser::parse(input);
+    match trim(&value.nodes) {
+      [node] => Color::from_node(node),
+      _ => None,
+    }
+  }
+
+  /// The color of a word or function of a parsed value.
+  pub fn from_node(node: &Node) -> Option<Color> {
+    match node {
+      Node::Word(word) => match word.strip_prefix('#') {
+        Some(hex) => parse_hex(hex),
+        None => {
+          let name = word.to_ascii_lowercase();
+          if name == "transparent" {
+            return Some(Color::rgb(0, 0, 0, 0.0));
+          }
+          named(&name).map(|[r, g, b]| Color::rgb(r, g, b, 1.0))
+        }
+      },
+      Node::Function(function) if !function.unclosed => parse_function(function),
+      _ => None,
+    }
+  }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+  if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+    return None;
+  }
+  let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap();
+  let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+  let (rgb, alpha) = match hex.len() {
+    3 => ([digit(0) * 17, digit(1) * 17, digit(2) * 17], 255),
+    4 => ([digit(0) * 17, digit(1) * 17, digit(2) * 17], digit(3) * 17),
+    6 => ([pair(0), pair(2), pair(4)], 255),
+    8 => ([pair(0), pair(2), pair(4)], pair(6)),
+    _ => return None,
+  };
+  Some(Color::rgb(rgb[0], rgb[1], rgb[2], f64::from(alpha) / 255.0))
+}
+
+fn parse_function(function: &Function) -> Option<Color> {
+  let name = function.lower_name();
+  if name == "color-mix" {
+    return parse_mix(function);
+  }
+  let (space, skip) = match name.as_str() {
+    "rgb" | "rgba" => (ColorSpace::Srgb, 0),
+    "hsl" | "hsla" => (ColorSpace::Hsl, 0),
+    "hwb" => (ColorSpace::Hwb, 0),
+    "lab" => (ColorSpace::Lab, 0),
+    "lch" => (ColorSpace::Lch, 0),
+    "oklab" => (ColorSpace::Oklab, 0),
+    "oklch" => (ColorSpace::Oklch, 0),
+    "color" => {
+      let first = function.nodes.iter().find(|node| !node.is_space())?;
+      let space = ColorSpace::from_name(&first.as_word()?.to_ascii_lowercase())?;
+      match space {
+        ColorSpace::Lab
+        | ColorSpace::Lch
+        | ColorSpace::Oklab
+        | ColorSpace::Oklch
+        | ColorSpace::Hsl
+        | ColorSpace::Hwb => return None,
+        space => (space, 1),
+      }
+    }
+    _ => return None,
+  };
+  let args = arguments(&function.nodes, skip)?;
+  if args.legacy && !matches!(space, ColorSpace::Srgb | ColorSpace::Hsl) {
+    return None;
+  }
+  let [a, b, c] = [args.components[0], args.components[1], args.components[2]];
+  let components = match space {
+    ColorSpace::Srgb if skip == 0 => {
+      [a, b, c].map(|c| c.number(255.0).map(|c| (c / 255.0).clamp(0.0, 1.0)))
+    }
+    ColorSpace::Hsl | ColorSpace::Hwb => [a.hue(), b.number(100.0), c.number(100.0)],
+    ColorSpace::Lab => [
+      a.number(100.0).map(|l| l.clamp(0.0, 100.0)),
+      b.number(125.0),
+      c.number(125.0),
+    ],
+    ColorSpace::Lch => [
+      a.number(100.0).map(|l| l.clamp(0.0, 100.0)),
+      b.number(150.0).map(|c| c.max(0.0)),
+      c.hue(),
+    ],
+    ColorSpace::Oklab => [
+      a.number(1.0).map(|l| l.clamp(0.0, 1.0)),
+      b.number(0.4),
+      c.number(0.4),
+    ],
+    ColorSpace::Oklch => [
+      a.number(1.0).map(|l| l.clamp(0.0, 1.0)),
+      b.number(0.4).map(|c| c.max(0.0)),
+      c.hue(),
+    ],
+    _ => [a.number(1.0), b.number(1.0), c.number(1.0)],
+  };
+  let [a, b, c] = components;
+  let alpha = match args.alpha {
+    Some(alpha) => alpha.alpha()?,
+    None => 1.0,
+  };
+  Some(Color::new(space, [a?, b?, c?], alpha))
+}
+
+/// Parses `color-mix(in <space> [<hue> hue], <color> [<p>], <color> [<p>])`.
+fn parse_mix(function: &Function) -> Option<Color> {
+  let args = function.args();
+  let (method, colors) = match args.as_slice() {
+    [method, first, second] => (Some(*method), [*first, *second]),
+    [first, second] => (None, [*first, *second]),
+    _ => return None,
+  };
+  let (space, hue) = match method {
+    Some(method) => parse_method(method)?,
+    None => (ColorSpace::Oklab, HueInterpolation::default()),
+  };
+  let [(first, p1), (second, p2)] = [parse_mix_color(colors[0])?, parse_mix_color(colors[1])?];
+  let (p1, p2) = match (p1, p2) {
+    (None, None) => (50.0, 50.0),
+    (Some(p1), None) => (p1, 100.0 - p1),
+    (None, Some(p2)) => (100.0 - p2, p2),
+    (Some(p1), Some(p2)) => (p1, p2),
+  };
+  let sum = p1 + p2;
+  if !(0.0..=100.0).contains(&p1) || !(0.0..=100.0).contains(&p2) || sum == 0.0 {
+    return None;
+  }
+  let mut mixed = first.mix(&second, p2 / sum, space, hue);
+  if sum < 100.0 {
+    mixed.alpha *= sum / 100.0;
+  }
+  Some(mixed)
+}
+
+fn parse_method(nodes: &[Node]) -> Option<(ColorSpace, HueInterpolation)> {
+  let words = trim(nodes)
+    .iter()
+    .filter(|node| !node.is_space())
+    .map(|node| node.as_word().map(str::to_ascii_lowercase))
+    .collect::<Option<Vec<_>>>()?;
+  let words: Vec<&str> = words.iter().map(String::as_str).collect();
+  let (space, hue) = match words.as_slice() {
+    ["in", space] => (ColorSpace::from_name(space)?, HueInterpolation::default()),
+    ["in", space, method, "hue"] => {
+      let method = match *method {
+        "shorter" => HueInterpolation::Shorter,
+        "longer" => HueInterpolation::Longer,
+        "increasing" => HueInterpolation::Increasing,
+        "decreasing" => HueInterpolation::Decreasing,
+        _ => return None,
+      };
+      let space = ColorSpace::from_name(space)?;
+      space.hue_index()?;
+      (space, method)
+    }
+    _ => return None,
+  };
+  Some((space, hue))
+}
+
+/// A color of `color-mix()` and its percentage, in either order.
+fn parse_mix_color(nodes: &[Node]) -> Option<(Color, Option<f64>)> {
+  let nodes: Vec<&Node> = nodes.iter().filter(|node| !node.is_space()).collect();
+  let percentage = |node: &Node| match Component::parse(node)? {
+    Component::Percentage(value) => Some(value),
+    _ => None,
+  };
+  match nodes.as_slice() {
+    [color] => Some((Color::from_node(color)?, None)),
+    [color, p] | [p, color] if percentage(p).is_some() => {
+      Some((Color::from_node(color)?, percentage(p)))
+    }
+    _ => None,
+  }
+}
+
+impl Color {
+  /// Mixes `amount` of `other` into the color, in `space`, with
+  /// premultiplied alpha like `color-mix()`.
+  pub fn mix(&self, other: &Color, amount: f64, space: ColorSpace, hue: HueInterpolation) -> Color {
+    let mut a = self.to(space);
+    let mut b = other.to(space);
+    let hue_index = space.hue_index();
+    if let Some(i) = hue_index {
+      // The hue of a gray is powerless, the one of the other color is used.
+      match (is_achromatic(&a), is_achromatic(&b)) {
+        (true, false) => a.components[i] = b.components[i],
+        (false, true) => b.components[i] = a.components[i],
+        _ => {}
+      }
+      let (mut h1, mut h2) = (
+        a.components[i].rem_euclid(360.0),
+        b.components[i].rem_euclid(360.0),
+      );
+      let delta = h2 - h1;
+      match hue {
+        HueInterpolation::Shorter if delta > 180.0 => h1 += 360.0,
+        HueInterpolation::Shorter if delta < -180.0 => h2 += 360.0,
+        HueInterpolation::Longer if 0.0 < delta && delta < 180.0 => h1 += 360.0,
+        HueInterpolation::Longer if -180.0 < delta && delta <= 0.0 => h2 += 360.0,
+        HueInterpolation::Increasing if h2 < h1 => h2 += 360.0,
+        HueInterpolation::Decreasing if h1 < h2 => h1 += 360.0,
+        _ => {}
+      }
+      a.components[i] = h1;
+      b.components[i] = h2;
+    }
+    let alpha = a.alpha * (1.0 - amount) + b.alpha * amount;
+    let mut components = [0.0; 3];
+    for (i, component) in components.iter_mut().enumerate() {
+      if hue_index == Some(i) {
+        *component =
+          (a.components[i] * (1.0 - amount) + b.components[i] * amount).rem_euclid(360.0);
+        continue;
+      }
+      let mixed = a.components[i] * a.alpha * (1.0 - amount) + b.components[i] * b.alpha * amount;
+      *component = if alpha == 0.0 { mixed } else { mixed / alpha };
+    }
+    Color::new(space, components, alpha)
+  }
+}
+
+fn is_achromatic(color: &Color) -> bool {
+  let [_, b, c] = color.components;
+  match color.space {
+    ColorSpace::Hsl => b.abs() < 1e-4,
+    ColorSpace::Hwb => b + c >= 100.0,
+    ColorSpace::Lch | ColorSpace::Oklch => b.abs() < 1e-4,
+    _ => false,
+  }
+}
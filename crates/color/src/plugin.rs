@@ -0,0 +1,119 @@
+use crate::color::Color;
+use crate::support::{feature, Feature, Support};
+use browserslist::Target;
+use node::rewrite::{rewrite_decls, Preserve, Visit};
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+use value_parser::Node as ValueNode;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// The browserslist query of the browsers to support, see
+  /// [`targets_or_defaults`](browserslist::targets_or_defaults).
+  pub browsers: Option<String>,
+  /// Keeps the declarations after their fallbacks, for the browsers that
+  /// know their colors.
+  pub preserve: bool,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      browsers: None,
+      preserve: true,
+    }
+  }
+}
+
+/// Adds `rgb()` and `rgba()` fallbacks for the colors some of the browsers
+/// do not know, like `#rrggbbaa`, `rgb()` with spaces, `hwb()`, `lab()`,
+/// `oklch()`, `color()` or `color-mix()`, and `#639` for `rebeccapurple`.
+///
+/// Colors are mapped into the sRGB gamut. Colors using `var()` or `calc()`,
+/// and custom properties, whose fallbacks would win over the original, are
+/// left alone.
+#[derive(Debug, Clone)]
+pub struct ColorFallbacks {
+  pub options: Options,
+  /// The targets of `options.browsers`, if set.
+  pub targets: Option<Vec<Target>>,
+}
+
+impl ColorFallbacks {
+  /// Creates a plugin for the browsers of `options.browsers`.
+  pub fn new(options: Options) -> Result<Self, browserslist::Error> {
+    let targets = options
+      .browsers
+      .as_deref()
+      .map(browserslist::resolve)
+      .transpose()?;
+    Ok(Self { options, targets })
+  }
+
+  /// Adds the fallbacks of the declarations in `parent` and its
+  /// descendants.
+  fn lower(&self, parent: &Rc<RefCell<Node>>, support: &Support) {
+    let preserve = if self.options.preserve {
+      Preserve::Before
+    } else {
+      Preserve::No
+    };
+    rewrite_decls(parent, preserve, &mut |child| {
+      let lowered = match &*child.borrow() {
+        Node::Decl(decl) if !decl.variable => lower_value(&decl.value, support),
+        _ => None,
+      };
+      lowered.map_or(Visit::Descend, Visit::Rewrite)
+    });
+  }
+}
+
+impl Plugin for ColorFallbacks {
+  fn name(&self) -> &str {
+    "color-fallbacks"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    let targets = self.targets.as_deref().or(result.targets.as_deref());
+    let support = Support::new(&browserslist::targets_or_defaults(targets));
+    self.lower(root, &support);
+    Ok(())
+  }
+}
+
+/// The value with the colors that need unsupported features in `rgb()`,
+/// or `None` if there are none.
+fn lower_value(value: &str, support: &Support) -> Option<String> {
+  let mut parsed = value_parser::parse(value);
+  let mut lowered = false;
+  parsed.walk(|node| {
+    if let ValueNode::Function(function) = node {
+      if function.lower_name() == "var" {
+        return false;
+      }
+    }
+    let feature = match feature(node) {
+      Some(feature) => feature,
+      None => return true,
+    };
+    if !support.supports(feature) {
+      if feature == Feature::RebeccaPurple {
+        *node = ValueNode::Word("#639".to_string());
+        lowered = true;
+      } else if let Some(color) = Color::from_node(node) {
+        *node = ValueNode::Word(color.to_legacy_rgb());
+        lowered = true;
+      }
+    }
+    false
+  });
+  lowered.then(|| parsed.to_string())
+}
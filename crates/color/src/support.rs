@@ -0,0 +1,174 @@
+use crate::parse::is_legacy;
+use browserslist::{Target, Version};
+use value_parser::Node;
+
+/// The color syntaxes that older browsers do not know.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+  /// `#rgba` and `#rrggbbaa`.
+  HexAlpha,
+  /// `rgb()` and `hsl()` with spaces, `none` or an alpha.
+  SpaceSyntax,
+  Hwb,
+  /// `lab()`, `lch()`, `oklab()` and `oklch()`.
+  Lab,
+  /// `color()`.
+  ColorFunction,
+  ColorMix,
+  /// The `rebeccapurple` name, lowered to `#639`.
+  RebeccaPurple,
+}
+
+/// The first versions supporting each feature.
+const SUPPORT: &[(Feature, &[(&str, &str)])] = &[
+  (
+    Feature::HexAlpha,
+    &[
+      ("chrome", "62"),
+      ("edge", "79"),
+      ("firefox", "49"),
+      ("safari", "10"),
+      ("opera", "49"),
+      ("ios_saf", "10"),
+      ("samsung", "8.2"),
+      ("android", "62"),
+    ],
+  ),
+  (
+    Feature::SpaceSyntax,
+    &[
+      ("chrome", "65"),
+      ("edge", "79"),
+      ("firefox", "52"),
+      ("safari", "12.1"),
+      ("opera", "52"),
+      ("ios_saf", "12.2"),
+      ("samsung", "9.2"),
+      ("android", "65"),
+    ],
+  ),
+  (
+    Feature::Hwb,
+    &[
+      ("chrome", "101"),
+      ("edge", "101"),
+      ("firefox", "96"),
+      ("safari", "15"),
+      ("opera", "87"),
+      ("ios_saf", "15"),
+      ("samsung", "19"),
+      ("android", "101"),
+    ],
+  ),
+  (
+    Feature::Lab,
+    &[
+      ("chrome", "111"),
+      ("edge", "111"),
+      ("firefox", "113"),
+      ("safari", "15.4"),
+      ("opera", "97"),
+      ("ios_saf", "15.4"),
+      ("samsung", "22"),
+      ("android", "111"),
+    ],
+  ),
+  (
+    Feature::ColorFunction,
+    &[
+      ("chrome", "111"),
+      ("edge", "111"),
+      ("firefox", "113"),
+      ("safari", "15"),
+      ("opera", "97"),
+      ("ios_saf", "15"),
+      ("samsung", "22"),
+      ("android", "111"),
+    ],
+  ),
+  (
+    Feature::ColorMix,
+    &[
+      ("chrome", "111"),
+      ("edge", "111"),
+      ("firefox", "113"),
+      ("safari", "16.2"),
+      ("opera", "97"),
+      ("ios_saf", "16.2"),
+      ("samsung", "22"),
+      ("android", "111"),
+    ],
+  ),
+  (
+    Feature::RebeccaPurple,
+    &[
+      ("chrome", "38"),
+      ("edge", "12"),
+      ("firefox", "33"),
+      ("safari", "9"),
+      ("opera", "25"),
+      ("ios_saf", "8"),
+      ("samsung", "4"),
+      ("android", "38"),
+    ],
+  ),
+];
+
+/// The color features all of a set of targets support. Browsers missing
+/// from the support table, like Internet Explorer or Opera Mini, support
+/// none.
+#[derive(Debug, Clone, Default)]
+pub struct Support {
+  supported: Vec<Feature>,
+}
+
+impl Support {
+  pub fn new(targets: &[Target]) -> Self {
+    let supported = SUPPORT
+      .iter()
+      .filter(|(_, versions)| {
+        targets.iter().all(|target| {
+          let browser = target.table_browser();
+          versions.iter().any(|(name, first)| {
+            *name == browser
+              && target
+                .numeric_version()
+                .zip(Version::parse(first))
+                .is_some_and(|(version, first)| version >= first)
+          })
+        })
+      })
+      .map(|(feature, _)| *feature)
+      .collect();
+    Self { supported }
+  }
+
+  pub fn supports(&self, feature: Feature) -> bool {
+    self.supported.contains(&feature)
+  }
+}
+
+/// The feature a color of a parsed value needs, `None` for the named colors
+/// but `rebeccapurple`, `#rrggbb` and the legacy functions.
+pub fn feature(node: &Node) -> Option<Feature> {
+  match node {
+    Node::Word(word) if word.eq_ignore_ascii_case("rebeccapurple") => Some(Feature::RebeccaPurple),
+    Node::Word(word) => match word.strip_prefix('#') {
+      Some(hex)
+        if (hex.len() == 4 || hex.len() == 8) && hex.bytes().all(|b| b.is_ascii_hexdigit()) =>
+      {
+        Some(Feature::HexAlpha)
+      }
+      _ => None,
+    },
+    Node::Function(function) => match function.lower_name().as_str() {
+      "rgb" | "rgba" | "hsl" | "hsla" if !is_legacy(function) => Some(Feature::SpaceSyntax),
+      "hwb" => Some(Feature::Hwb),
+      "lab" | "lch" | "oklab" | "oklch" => Some(Feature::Lab),
+      "color" => Some(Feature::ColorFunction),
+      "color-mix" => Some(Feature::ColorMix),
+      _ => None,
+    },
+    _ => None,
+  }
+}
@@ -0,0 +1,165 @@
+use color::{delta_eok, Color, ColorFallbacks, ColorSpace, HueInterpolation, Options};
+use postcss::{ProcessResult, Processor};
+
+fn rgb(input: &str) -> String {
+  Color::parse(input).unwrap().to_legacy_rgb()
+}
+
+fn process(options: Options, css: &str) -> ProcessResult {
+  Processor::run_one(ColorFallbacks::new(options).unwrap(), css).unwrap()
+}
+
+fn legacy() -> Options {
+  Options {
+    browsers: Some("ie 11".to_string()),
+    ..Options::default()
+  }
+}
+
+#[test]
+fn parses_all_syntaxes() {
+  assert_eq!(rgb("#f00"), "rgb(255, 0, 0)");
+  assert_eq!(rgb("#FF000080"), "rgba(255, 0, 0, 0.502)");
+  assert_eq!(rgb("rebeccapurple"), "rgb(102, 51, 153)");
+  assert_eq!(rgb("transparent"), "rgba(0, 0, 0, 0)");
+  assert_eq!(rgb("rgba(255, 0, 0, .5)"), "rgba(255, 0, 0, 0.5)");
+  assert_eq!(rgb("rgb(255 0 0 / 50%)"), "rgba(255, 0, 0, 0.5)");
+  assert_eq!(rgb("rgb(100% 50% none)"), "rgb(255, 128, 0)");
+  assert_eq!(rgb("hsl(120, 100%, 50%)"), "rgb(0, 255, 0)");
+  assert_eq!(rgb("hsl(0.5turn 100% 25%)"), "rgb(0, 128, 128)");
+  assert_eq!(rgb("hwb(0 0% 0%)"), "rgb(255, 0, 0)");
+  assert_eq!(rgb("hwb(0 50% 50%)"), "rgb(128, 128, 128)");
+  assert_eq!(rgb("lab(50% 0 0)"), "rgb(119, 119, 119)");
+  assert_eq!(rgb("lch(54.29 106.84 40.85)"), "rgb(255, 0, 0)");
+  assert_eq!(rgb("oklab(0.628 0.225 0.126)"), "rgb(255, 0, 0)");
+  assert_eq!(rgb("oklch(62.8% 0.2577 29.23)"), "rgb(255, 0, 0)");
+  assert_eq!(rgb("color(srgb 0 0.5 1)"), "rgb(0, 128, 255)");
+  assert_eq!(rgb("color(xyz 0.9505 1 1.089)"), "rgb(255, 255, 255)");
+  assert!(Color::parse("rgb(1, 2)").is_none());
+  assert!(Color::parse("#ggg").is_none());
+  assert!(Color::parse("color(unknown 1 0 0)").is_none());
+}
+
+#[test]
+fn converts_between_spaces() {
+  let red = Color::rgb(255, 0, 0, 1.0);
+  for space in [
+    ColorSpace::SrgbLinear,
+    ColorSpace::DisplayP3,
+    ColorSpace::A98Rgb,
+    ColorSpace::ProphotoRgb,
+    ColorSpace::Rec2020,
+    ColorSpace::XyzD50,
+    ColorSpace::Lab,
+    ColorSpace::Lch,
+    ColorSpace::Oklch,
+    ColorSpace::Hsl,
+    ColorSpace::Hwb,
+  ] {
+    let back = red.to(space).to(ColorSpace::Srgb);
+    assert!(delta_eok(&red, &back) < 1e-6, "{:?}", space);
+  }
+  assert_eq!(
+    red.to(ColorSpace::Oklch).to_string(),
+    "oklch(0.62796 0.25768 29.23388)"
+  );
+  assert_eq!(
+    Color::parse("color(display-p3 1 0 0 / 0.5)")
+      .unwrap()
+      .to_string(),
+    "color(display-p3 1 0 0 / 0.5)"
+  );
+  assert_eq!(Color::rgb(255, 0, 0, 0.5).to_hex(), "#ff000080");
+}
+
+#[test]
+fn maps_into_the_gamut() {
+  let p3 = Color::parse("color(display-p3 1 0 0)").unwrap();
+  assert!(!p3.in_gamut(ColorSpace::Srgb));
+  let mapped = p3.to_gamut(ColorSpace::Srgb);
+  assert!(mapped.in_gamut(ColorSpace::Srgb));
+  assert_eq!(p3.to_legacy_rgb(), "rgb(255, 11, 12)");
+  assert_eq!(rgb("oklch(100% 0.4 120)"), "rgb(255, 255, 255)");
+  assert_eq!(rgb("lab(0 100 100)"), "rgb(0, 0, 0)");
+  let oklch = Color::parse("oklch(0.7 0.35 150)")
+    .unwrap()
+    .to_gamut(ColorSpace::Srgb)
+    .to(ColorSpace::Oklch);
+  assert!((oklch.components[0] - 0.7).abs() < 0.02);
+  assert!((oklch.components[2] - 150.0).abs() < 5.0);
+}
+
+#[test]
+fn mixes_colors() {
+  assert_eq!(rgb("color-mix(in srgb, red, blue)"), "rgb(128, 0, 128)");
+  assert_eq!(rgb("color-mix(in srgb, red 25%, blue)"), "rgb(64, 0, 191)");
+  assert_eq!(
+    rgb("color-mix(in srgb, red 30%, blue 30%)"),
+    "rgba(128, 0, 128, 0.6)"
+  );
+  assert_eq!(
+    rgb("color-mix(in srgb, red, transparent)"),
+    "rgba(255, 0, 0, 0.5)"
+  );
+  assert_eq!(rgb("color-mix(in oklab, white, black)"), "rgb(99, 99, 99)");
+  assert!(Color::parse("color-mix(in srgb longer hue, red, blue)").is_none());
+  assert!(Color::parse("color-mix(in srgb, red 0%, blue 0%)").is_none());
+  let red = Color::parse("hsl(10 100% 50%)").unwrap();
+  let blue = Color::parse("hsl(350 100% 50%)").unwrap();
+  let shorter = red.mix(&blue, 0.5, ColorSpace::Hsl, HueInterpolation::Shorter);
+  let longer = red.mix(&blue, 0.5, ColorSpace::Hsl, HueInterpolation::Longer);
+  assert_eq!(shorter.components[0], 0.0);
+  assert_eq!(longer.components[0], 180.0);
+}
+
+#[test]
+fn adds_fallbacks_for_old_browsers() {
+  let result = process(
+    legacy(),
+    "a { color: oklch(62.8% 0.2577 29.23); border: 1px solid #0000ff80 }",
+  );
+  assert_eq!(
+    result.css,
+    "a { color: rgb(255, 0, 0); color: oklch(62.8% 0.2577 29.23); border: 1px solid rgba(0, 0, 255, 0.502); border: 1px solid #0000ff80 }"
+  );
+  let result = process(
+    Options {
+      preserve: false,
+      ..legacy()
+    },
+    "a { background: linear-gradient(lab(50% 0 0), color-mix(in srgb, red, blue)) }",
+  );
+  assert_eq!(
+    result.css,
+    "a { background: linear-gradient(rgb(119, 119, 119), rgb(128, 0, 128)) }"
+  );
+  let result = process(legacy(), "a { color: RebeccaPurple }");
+  assert_eq!(result.css, "a { color: #639; color: RebeccaPurple }");
+}
+
+#[test]
+fn keeps_what_targets_support() {
+  let css = "a { color: rgb(0 0 0 / 50%); background: #0008; fill: rebeccapurple }";
+  let result = process(
+    Options {
+      browsers: Some("chrome 100".to_string()),
+      ..Options::default()
+    },
+    css,
+  );
+  assert_eq!(result.css, css);
+  let result = process(
+    Options {
+      browsers: Some("chrome 100".to_string()),
+      ..Options::default()
+    },
+    "a { color: lab(50% 0 0) }",
+  );
+  assert_eq!(
+    result.css,
+    "a { color: rgb(119, 119, 119); color: lab(50% 0 0) }"
+  );
+  let css =
+    "a { --brand: lab(50% 0 0); color: rgb(var(--x) / 50%); fill: lab(calc(50% + 1%) 0 0) }";
+  assert_eq!(process(legacy(), css).css, css);
+}
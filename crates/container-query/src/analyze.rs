@@ -0,0 +1,71 @@
+use node::Node;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use tokenizer::warning::Warning;
+
+/// Checks that every named `@container` query refers to a container declared
+/// by a `container-name` or `container` declaration of the same root.
+///
+/// Returns a warning for every query with an undeclared name. At-rules with
+/// params that can not be parsed are skipped.
+pub fn check_names(root: &Rc<RefCell<Node>>) -> Vec<Warning> {
+  let mut declared = HashSet::new();
+  let mut at_rules = vec![];
+  collect(root, &mut declared, &mut at_rules);
+
+  let mut warnings = vec![];
+  for at_rule in at_rules {
+    let node = at_rule.borrow();
+    let params = match node.as_at_rule() {
+      Some(at_rule) => &at_rule.params,
+      None => continue,
+    };
+    let list = match crate::parse(params) {
+      Ok(list) => list,
+      Err(_) => continue,
+    };
+    for name in list.queries.iter().filter_map(|query| query.name.as_ref()) {
+      if !declared.contains(name.as_str()) {
+        let text = format!(
+          "Container name \"{}\" is not declared by any container-name or container declaration",
+          name
+        );
+        warnings.push(node.warning(&text));
+      }
+    }
+  }
+  warnings
+}
+
+fn collect<'a>(
+  node: &Rc<RefCell<Node<'a>>>,
+  declared: &mut HashSet<String>,
+  at_rules: &mut Vec<Rc<RefCell<Node<'a>>>>,
+) {
+  match &*node.borrow() {
+    Node::Decl(decl) => {
+      let names = match decl.prop.to_ascii_lowercase().as_str() {
+        "container-name" => decl.value.as_str(),
+        // `container: <name>+ [ / <type> ]?`
+        "container" => decl.value.split('/').next().unwrap_or_default(),
+        _ => "",
+      };
+      declared.extend(
+        names
+          .split_whitespace()
+          .filter(|name| !name.eq_ignore_ascii_case("none"))
+          .map(str::to_string),
+      );
+    }
+    Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("container") => {
+      at_rules.push(node.clone());
+    }
+    _ => {}
+  }
+  if let Some(children) = node.borrow().as_shared().get_nodes() {
+    for child in &children {
+      collect(child, declared, at_rules);
+    }
+  }
+}
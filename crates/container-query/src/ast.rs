@@ -0,0 +1,155 @@
+use media_query::MediaFeature;
+use std::fmt;
+
+/// A comma separated list of container queries, the params of `@container`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContainerQueryList {
+  pub queries: Vec<ContainerQuery>,
+}
+
+/// A single container query, e.g. `card (min-width: 400px)`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContainerQuery {
+  /// The container name, as written in the source.
+  pub name: Option<String>,
+
+  pub condition: Option<ContainerCondition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContainerCondition {
+  /// `(min-width: 400px)`, `(inline-size > 30em)`
+  Feature(MediaFeature),
+  /// `style(--theme: dark)`
+  Style(StyleCondition),
+  /// `not (width > 400px)`
+  Not(Box<ContainerCondition>),
+  /// `(width > 400px) and style(--a)`
+  And(Vec<ContainerCondition>),
+  /// `(width > 400px) or (height > 400px)`
+  Or(Vec<ContainerCondition>),
+  /// A function or parenthesized block this parser does not understand, kept
+  /// verbatim, e.g. `scroll-state(stuck: top)`.
+  GeneralEnclosed(String),
+}
+
+/// The argument of `style()`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleCondition {
+  /// `--theme: dark`, or `--responsive` without a value.
+  Feature {
+    property: String,
+    value: Option<String>,
+  },
+  /// `not (--theme: dark)`
+  Not(Box<StyleCondition>),
+  /// `(--a: 1) and (--b: 2)`
+  And(Vec<StyleCondition>),
+  /// `(--a: 1) or (--b: 2)`
+  Or(Vec<StyleCondition>),
+  /// A block this parser does not understand, kept verbatim.
+  GeneralEnclosed(String),
+}
+
+impl fmt::Display for ContainerQueryList {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, query) in self.queries.iter().enumerate() {
+      if i > 0 {
+        f.write_str(", ")?;
+      }
+      write!(f, "{}", query)?;
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for ContainerQuery {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(name) = &self.name {
+      f.write_str(name)?;
+      if self.condition.is_some() {
+        f.write_str(" ")?;
+      }
+    }
+    match &self.condition {
+      Some(condition) => write!(f, "{}", condition),
+      None => Ok(()),
+    }
+  }
+}
+
+impl fmt::Display for ContainerCondition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ContainerCondition::Not(condition) => {
+        f.write_str("not ")?;
+        write_in_parens(f, condition)
+      }
+      ContainerCondition::And(conditions) => write_joined(f, conditions, " and "),
+      ContainerCondition::Or(conditions) => write_joined(f, conditions, " or "),
+      _ => write_in_parens(f, self),
+    }
+  }
+}
+
+fn write_joined(
+  f: &mut fmt::Formatter,
+  conditions: &[ContainerCondition],
+  separator: &str,
+) -> fmt::Result {
+  for (i, condition) in conditions.iter().enumerate() {
+    if i > 0 {
+      f.write_str(separator)?;
+    }
+    write_in_parens(f, condition)?;
+  }
+  Ok(())
+}
+
+/// Writes `<query-in-parens>`.
+fn write_in_parens(f: &mut fmt::Formatter, condition: &ContainerCondition) -> fmt::Result {
+  match condition {
+    ContainerCondition::Feature(feature) => write!(f, "({})", feature),
+    ContainerCondition::Style(style) => write!(f, "style({})", style),
+    ContainerCondition::GeneralEnclosed(raw) => f.write_str(raw),
+    _ => write!(f, "({})", condition),
+  }
+}
+
+impl fmt::Display for StyleCondition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      StyleCondition::Feature { property, value } => match value {
+        Some(value) => write!(f, "{}: {}", property, value),
+        None => f.write_str(property),
+      },
+      StyleCondition::Not(condition) => {
+        f.write_str("not ")?;
+        write_style_in_parens(f, condition)
+      }
+      StyleCondition::And(conditions) | StyleCondition::Or(conditions) => {
+        let separator = if matches!(self, StyleCondition::And(_)) {
+          " and "
+        } else {
+          " or "
+        };
+        for (i, condition) in conditions.iter().enumerate() {
+          if i > 0 {
+            f.write_str(separator)?;
+          }
+          write_style_in_parens(f, condition)?;
+        }
+        Ok(())
+      }
+      StyleCondition::GeneralEnclosed(raw) => f.write_str(raw),
+    }
+  }
+}
+
+/// Writes `<style-in-parens>`.
+fn write_style_in_parens(f: &mut fmt::Formatter, condition: &StyleCondition) -> fmt::Result {
+  match condition {
+    StyleCondition::GeneralEnclosed(raw) => f.write_str(raw),
+    _ => write!(f, "({})", condition),
+  }
+}
@@ -0,0 +1,24 @@
+mod analyze;
+mod ast;
+mod parser;
+mod plugin;
+
+pub use crate::analyze::check_names;
+pub use crate::ast::*;
+pub use crate::parser::ParseError;
+pub use crate::plugin::ContainerNames;
+
+use crate::parser::Parser;
+
+/// Parses the params of `@container` into a [`ContainerQueryList`].
+pub fn parse(params: &str) -> Result<ContainerQueryList, ParseError> {
+  Parser::new(params, 0).parse_query_list()
+}
+
+/// Parses a standalone `<container-condition>`, like `(width > 400px) and style(--a)`.
+pub fn parse_condition(input: &str) -> Result<ContainerCondition, ParseError> {
+  let mut parser = Parser::new(input, 0);
+  let condition = parser.parse_condition()?;
+  parser.expect_end()?;
+  Ok(condition)
+}
@@ -0,0 +1,340 @@
+use crate::ast::*;
+use media_query::MediaCondition;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+  pub reason: String,
+  /// Byte offset in the parsed params.
+  pub offset: usize,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} at {}", self.reason, self.offset)
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+type ParseResult<T> = Result<T, ParseError>;
+
+pub(crate) struct Parser<'a> {
+  input: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+  /// Offset of `input` in the params, for error positions.
+  base: usize,
+}
+
+impl<'a> Parser<'a> {
+  pub(crate) fn new(input: &'a str, base: usize) -> Self {
+    Self {
+      input,
+      bytes: input.as_bytes(),
+      pos: 0,
+      base,
+    }
+  }
+
+  pub(crate) fn parse_query_list(&mut self) -> ParseResult<ContainerQueryList> {
+    let mut queries = vec![self.parse_query()?];
+    while self.bytes.get(self.pos) == Some(&b',') {
+      self.pos += 1;
+      queries.push(self.parse_query()?);
+    }
+    self.expect_end()?;
+    Ok(ContainerQueryList { queries })
+  }
+
+  /// Parses `[ <container-name> ]? <container-query>?`.
+  fn parse_query(&mut self) -> ParseResult<ContainerQuery> {
+    self.skip_whitespace();
+    let start = self.pos;
+    let end = consume_ident(self.bytes, start);
+    let mut name = None;
+    if end > start && self.bytes.get(end) != Some(&b'(') {
+      let ident = &self.input[start..end];
+      if ["not", "and", "or", "none"]
+        .iter()
+        .any(|keyword| ident.eq_ignore_ascii_case(keyword))
+      {
+        if !ident.eq_ignore_ascii_case("not") {
+          return Err(self.error("Invalid container name"));
+        }
+      } else {
+        name = Some(ident.to_string());
+        self.pos = end;
+      }
+    }
+    self.skip_whitespace();
+    let condition = match self.bytes.get(self.pos) {
+      None | Some(b',') => None,
+      _ => Some(self.parse_condition()?),
+    };
+    if name.is_none() && condition.is_none() {
+      return Err(self.error("Expected container query"));
+    }
+    self.skip_whitespace();
+    Ok(ContainerQuery { name, condition })
+  }
+
+  pub(crate) fn parse_condition(&mut self) -> ParseResult<ContainerCondition> {
+    self.parse_boolean(
+      Self::parse_in_parens,
+      |condition| ContainerCondition::Not(Box::new(condition)),
+      ContainerCondition::And,
+      ContainerCondition::Or,
+    )
+  }
+
+  /// Parses `<query-in-parens>`.
+  fn parse_in_parens(&mut self) -> ParseResult<ContainerCondition> {
+    self.skip_whitespace();
+    let start = self.pos;
+    if self.bytes.get(start) == Some(&b'(') {
+      let end = self.find_close(start)?;
+      self.pos = end + 1;
+      let inner = &self.input[start + 1..end];
+      let mut parser = Parser::new(inner, self.base + start + 1);
+      if let Ok(condition) = parser.parse_condition().and_then(|condition| {
+        parser.expect_end()?;
+        Ok(condition)
+      }) {
+        return Ok(condition);
+      }
+      if let Ok(MediaCondition::Feature(feature)) =
+        media_query::parse_condition(&self.input[start..=end])
+      {
+        return Ok(ContainerCondition::Feature(feature));
+      }
+      return Ok(ContainerCondition::GeneralEnclosed(
+        self.input[start..=end].to_string(),
+      ));
+    }
+
+    let (name, end) = self.expect_function()?;
+    self.pos = end + 1;
+    if name.eq_ignore_ascii_case("style") {
+      let argument_start = start + name.len() + 1;
+      let argument = &self.input[argument_start..end];
+      if let Ok(style) = Parser::new(argument, self.base + argument_start).parse_style_argument() {
+        return Ok(ContainerCondition::Style(style));
+      }
+    }
+    Ok(ContainerCondition::GeneralEnclosed(
+      self.input[start..=end].to_string(),
+    ))
+  }
+
+  /// Parses the argument of `style()`, a `<style-query>`.
+  fn parse_style_argument(&mut self) -> ParseResult<StyleCondition> {
+    self.skip_whitespace();
+    let start = self.pos;
+    if let Ok(condition) = self.parse_style_condition() {
+      if self.expect_end().is_ok() {
+        return Ok(condition);
+      }
+    }
+    self.pos = start;
+    parse_style_feature(self.input).ok_or_else(|| self.error("Invalid style query"))
+  }
+
+  fn parse_style_condition(&mut self) -> ParseResult<StyleCondition> {
+    self.parse_boolean(
+      Self::parse_style_in_parens,
+      |condition| StyleCondition::Not(Box::new(condition)),
+      StyleCondition::And,
+      StyleCondition::Or,
+    )
+  }
+
+  /// Parses `<style-in-parens>`.
+  fn parse_style_in_parens(&mut self) -> ParseResult<StyleCondition> {
+    self.skip_whitespace();
+    let start = self.pos;
+    if self.bytes.get(start) != Some(&b'(') {
+      let (_, end) = self.expect_function()?;
+      self.pos = end + 1;
+      return Ok(StyleCondition::GeneralEnclosed(
+        self.input[start..=end].to_string(),
+      ));
+    }
+    let end = self.find_close(start)?;
+    self.pos = end + 1;
+    let inner = &self.input[start + 1..end];
+    let mut parser = Parser::new(inner, self.base + start + 1);
+    if let Ok(condition) = parser.parse_style_condition().and_then(|condition| {
+      parser.expect_end()?;
+      Ok(condition)
+    }) {
+      return Ok(condition);
+    }
+    Ok(
+      parse_style_feature(inner)
+        .unwrap_or_else(|| StyleCondition::GeneralEnclosed(self.input[start..=end].to_string())),
+    )
+  }
+
+  /// Parses `not <in-parens> | <in-parens> [ and <in-parens> ]* | <in-parens> [ or <in-parens> ]*`.
+  fn parse_boolean<T>(
+    &mut self,
+    in_parens: fn(&mut Self) -> ParseResult<T>,
+    not: fn(T) -> T,
+    and: fn(Vec<T>) -> T,
+    or: fn(Vec<T>) -> T,
+  ) -> ParseResult<T> {
+    self.skip_whitespace();
+    if self.eat_keyword("not") {
+      return Ok(not(in_parens(self)?));
+    }
+    let first = in_parens(self)?;
+    self.skip_whitespace();
+    let keyword = if self.at_keyword("and") {
+      "and"
+    } else if self.at_keyword("or") {
+      "or"
+    } else {
+      return Ok(first);
+    };
+    let mut conditions = vec![first];
+    while self.eat_keyword(keyword) {
+      conditions.push(in_parens(self)?);
+      self.skip_whitespace();
+    }
+    Ok(if keyword == "and" {
+      and(conditions)
+    } else {
+      or(conditions)
+    })
+  }
+
+  /// Expects `name(`, returns the name and the index of the closing `)`.
+  fn expect_function(&self) -> ParseResult<(&'a str, usize)> {
+    let start = self.pos;
+    let name_end = consume_ident(self.bytes, start);
+    if name_end > start && self.bytes.get(name_end) == Some(&b'(') {
+      let end = self.find_close(name_end)?;
+      return Ok((&self.input[start..name_end], end));
+    }
+    Err(self.error("Expected ("))
+  }
+
+  pub(crate) fn expect_end(&mut self) -> ParseResult<()> {
+    self.skip_whitespace();
+    match self.bytes.get(self.pos) {
+      None => Ok(()),
+      Some(_) => Err(self.error("Unexpected token")),
+    }
+  }
+
+  /// Returns the index of the `)` matching the `(` at `open`.
+  fn find_close(&self, open: usize) -> ParseResult<usize> {
+    let mut depth = 0;
+    let mut pos = open;
+    while pos < self.bytes.len() {
+      match self.bytes[pos] {
+        b'(' => depth += 1,
+        b')' => {
+          depth -= 1;
+          if depth == 0 {
+            return Ok(pos);
+          }
+        }
+        b'\\' => pos += 1,
+        quote @ (b'"' | b'\'') => {
+          pos += 1;
+          while pos < self.bytes.len() && self.bytes[pos] != quote {
+            if self.bytes[pos] == b'\\' {
+              pos += 1;
+            }
+            pos += 1;
+          }
+        }
+        _ => {}
+      }
+      pos += 1;
+    }
+    Err(ParseError {
+      reason: "Unclosed bracket".to_string(),
+      offset: self.base + open,
+    })
+  }
+
+  fn skip_whitespace(&mut self) {
+    while self.pos < self.bytes.len() {
+      match self.bytes[self.pos] {
+        b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' => self.pos += 1,
+        b'/' if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+          self.pos = match self.input[self.pos + 2..].find("*/") {
+            Some(end) => self.pos + end + 4,
+            None => self.bytes.len(),
+          };
+        }
+        _ => break,
+      }
+    }
+  }
+
+  /// Returns `true` if a keyword followed by whitespace is at the position.
+  fn at_keyword(&self, keyword: &str) -> bool {
+    let end = self.pos + keyword.len();
+    self
+      .input
+      .get(self.pos..end)
+      .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+      && matches!(
+        self.bytes.get(end),
+        Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' | b'/')
+      )
+  }
+
+  fn eat_keyword(&mut self, keyword: &str) -> bool {
+    if self.at_keyword(keyword) {
+      self.pos += keyword.len();
+      self.skip_whitespace();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn error(&self, reason: &str) -> ParseError {
+    ParseError {
+      reason: reason.to_string(),
+      offset: self.base + self.pos,
+    }
+  }
+}
+
+/// Parses `<style-feature>`, a declaration or a bare property name.
+fn parse_style_feature(input: &str) -> Option<StyleCondition> {
+  let trimmed = input.trim();
+  let end = consume_ident(trimmed.as_bytes(), 0);
+  if end == 0 {
+    return None;
+  }
+  let property = &trimmed[..end];
+  let rest = trimmed[end..].trim_start();
+  let value = if rest.is_empty() {
+    None
+  } else {
+    Some(rest.strip_prefix(':')?.trim().to_string())
+  };
+  Some(StyleCondition::Feature {
+    property: property.to_string(),
+    value,
+  })
+}
+
+fn consume_ident(bytes: &[u8], mut pos: usize) -> usize {
+  while pos < bytes.len() {
+    match bytes[pos] {
+      b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => pos += 1,
+      b'\\' if pos + 1 < bytes.len() => pos += 2,
+      byte if byte >= 0x80 => pos += 1,
+      _ => break,
+    }
+  }
+  pos
+}
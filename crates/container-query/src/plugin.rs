@@ -0,0 +1,26 @@
+use node::Node;
+use postcss::{CssSyntaxError, Message, Plugin, ProcessResult};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Reports the warnings of [`check_names`](crate::check_names).
+#[derive(Debug, Clone, Default)]
+pub struct ContainerNames;
+
+impl Plugin for ContainerNames {
+  fn name(&self) -> &str {
+    "container-names"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    for mut warning in crate::check_names(root) {
+      warning.plugin = Some(self.name().to_string());
+      result.messages.push(Message::Warning(warning));
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,35 @@
+use container_query::check_names;
+use parser::parse::parse;
+
+#[test]
+fn warns_on_undeclared_names() {
+  let css = ".sidebar { container-name: sidebar aside; }\n\
+             .card { container: card / inline-size; }\n\
+             @container sidebar (width > 400px) { a {} }\n\
+             @container card (width > 400px) { a {} }\n\
+             @container (width > 400px) { a {} }\n\
+             @media screen {\n  @container main (width > 400px) { a {} }\n}";
+  let root = parse(css, None).unwrap();
+  let warnings = check_names(&root);
+  assert_eq!(warnings.len(), 1);
+  assert_eq!(
+    warnings[0].text,
+    "Container name \"main\" is not declared by any container-name or container declaration"
+  );
+  assert_eq!((warnings[0].line, warnings[0].column), (7, 3));
+}
+
+#[test]
+fn declarations_after_the_query_count() {
+  let css =
+    "@container Card style(--a) { a {} } .b { container: Card } .c { container-name: none }";
+  let root = parse(css, None).unwrap();
+  assert!(check_names(&root).is_empty());
+}
+
+#[test]
+fn names_are_case_sensitive() {
+  let css = ".a { container-name: card } @container Card (width > 1px) {}";
+  let root = parse(css, None).unwrap();
+  assert_eq!(check_names(&root).len(), 1);
+}
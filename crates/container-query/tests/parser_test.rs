@@ -0,0 +1,97 @@
+use container_query::*;
+use media_query::{MediaFeature, MediaValue, RangeOperator};
+
+fn roundtrip(params: &str) {
+  assert_eq!(parse(params).unwrap().to_string(), params);
+}
+
+fn width_above(value: f64) -> ContainerCondition {
+  ContainerCondition::Feature(MediaFeature::Range {
+    name: "width".into(),
+    operator: RangeOperator::Gt,
+    value: MediaValue::Dimension(value, "px".into()),
+  })
+}
+
+#[test]
+fn parses_names_and_size_queries() {
+  let list = parse("card (width > 400px)").unwrap();
+  assert_eq!(
+    list.queries,
+    vec![ContainerQuery {
+      name: Some("card".into()),
+      condition: Some(width_above(400.0)),
+    }]
+  );
+
+  let list = parse("(width > 400px) and (width > 500px)").unwrap();
+  assert_eq!(list.queries[0].name, None);
+  assert_eq!(
+    list.queries[0].condition,
+    Some(ContainerCondition::And(vec![
+      width_above(400.0),
+      width_above(500.0)
+    ]))
+  );
+
+  let list = parse("not (width > 400px)").unwrap();
+  assert_eq!(
+    list.queries[0].condition,
+    Some(ContainerCondition::Not(Box::new(width_above(400.0))))
+  );
+}
+
+#[test]
+fn parses_style_queries() {
+  let list = parse("style(--theme: dark)").unwrap();
+  assert_eq!(
+    list.queries[0].condition,
+    Some(ContainerCondition::Style(StyleCondition::Feature {
+      property: "--theme".into(),
+      value: Some("dark".into()),
+    }))
+  );
+
+  let list = parse("sidebar style((--a: 1) or (not (--b)))").unwrap();
+  assert_eq!(
+    list.queries[0].condition,
+    Some(ContainerCondition::Style(StyleCondition::Or(vec![
+      StyleCondition::Feature {
+        property: "--a".into(),
+        value: Some("1".into()),
+      },
+      StyleCondition::Not(Box::new(StyleCondition::Feature {
+        property: "--b".into(),
+        value: None,
+      })),
+    ])))
+  );
+}
+
+#[test]
+fn keeps_unknown_queries_verbatim() {
+  let list = parse("scroll-state(stuck: top) or (foo bar)").unwrap();
+  assert_eq!(
+    list.queries[0].condition,
+    Some(ContainerCondition::Or(vec![
+      ContainerCondition::GeneralEnclosed("scroll-state(stuck: top)".into()),
+      ContainerCondition::GeneralEnclosed("(foo bar)".into()),
+    ]))
+  );
+}
+
+#[test]
+fn serializes_queries() {
+  roundtrip("card (min-width: 400px)");
+  roundtrip("(inline-size > 30em) and style(--responsive)");
+  roundtrip("a (width > 1px), b style(not (--x: 1)), c");
+  roundtrip("not ((width > 1px) or (height > 1px))");
+}
+
+#[test]
+fn reports_errors() {
+  assert!(parse("").is_err());
+  assert!(parse("none (width > 1px)").is_err());
+  assert!(parse("card (width > 1px").is_err());
+  assert!(parse("(a: 1) and (b: 2) or (c: 3)").is_err());
+}
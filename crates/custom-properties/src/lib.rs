@@ -0,0 +1,5 @@
+mod plugin;
+mod properties;
+
+pub use crate::plugin::{CustomProperties, Options};
+pub use crate::properties::{Error, Properties};
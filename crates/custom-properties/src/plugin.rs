@@ -0,0 +1,151 @@
+use crate::properties::{Error, Properties};
+use node::rewrite::{rewrite_decls, Preserve, Visit};
+use node::tree::replace_children;
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// Keeps the declarations with `var()` after their substituted copies,
+  /// and the custom properties of `:root`.
+  pub preserve: bool,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self { preserve: true }
+  }
+}
+
+/// Substitutes `var()` with the custom properties declared in top-level
+/// `:root` rules, for browsers without custom properties.
+///
+/// The last declaration of a custom property wins, and custom properties
+/// can reference each other. With `preserve`, a declaration gets a
+/// substituted copy before it, otherwise its value is replaced and the
+/// custom properties of `:root` are removed.
+///
+/// A declaration referencing an undefined custom property, or custom
+/// properties referencing each other in a cycle, without fallback is left
+/// alone with a warning. Cycles also get a warning at the declaration of
+/// one of their custom properties, even if fallbacks hide them. Custom property declarations are never
+/// substituted, as they may be changed at runtime.
+#[derive(Debug, Clone, Default)]
+pub struct CustomProperties {
+  pub options: Options,
+}
+
+impl CustomProperties {
+  /// Substitutes the declarations in `parent` and its descendants.
+  fn substitute(
+    &self,
+    parent: &Rc<RefCell<Node>>,
+    properties: &Properties,
+    result: &mut ProcessResult,
+  ) {
+    let preserve = if self.options.preserve {
+      Preserve::Before
+    } else {
+      Preserve::No
+    };
+    rewrite_decls(parent, preserve, &mut |child| {
+      let substituted = match &*child.borrow() {
+        Node::Decl(decl) if !decl.variable && has_var(&decl.value) => {
+          match properties.substitute(&decl.value) {
+            Ok(value) => Some(value).filter(|value| *value != decl.value),
+            Err(error) => {
+              result.warn(&error.to_string(), Some(&child.borrow()));
+              None
+            }
+          }
+        }
+        _ => None,
+      };
+      substituted.map_or(Visit::Descend, Visit::Rewrite)
+    });
+  }
+}
+
+impl Plugin for CustomProperties {
+  fn name(&self) -> &str {
+    "custom-properties"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    let mut properties = Properties::new();
+    let mut declarations = HashMap::new();
+    for rule in root_rules(root) {
+      for child in rule.borrow().as_shared().get_nodes().unwrap_or_default() {
+        if let Node::Decl(decl) = &*child.borrow() {
+          if is_custom_property(&decl.prop) {
+            properties.insert(&decl.prop, &decl.value);
+            declarations.insert(decl.prop.clone(), child.clone());
+          }
+        }
+      }
+    }
+    for cycle in properties.cycles() {
+      let text = Error::Cycle(cycle.clone()).to_string();
+      result.warn(&text, Some(&declarations[&cycle[0]].borrow()));
+    }
+    self.substitute(root, &properties, result);
+    if !self.options.preserve {
+      remove_custom_properties(root);
+    }
+    Ok(())
+  }
+}
+
+fn is_custom_property(prop: &str) -> bool {
+  prop.starts_with("--")
+}
+
+fn has_var(value: &str) -> bool {
+  value.to_ascii_lowercase().contains("var(")
+}
+
+/// The top-level rules for `:root`.
+fn root_rules<'a>(root: &Rc<RefCell<Node<'a>>>) -> Vec<Rc<RefCell<Node<'a>>>> {
+  let children = root.borrow().as_shared().get_nodes().unwrap_or_default();
+  children
+    .into_iter()
+    .filter(|child| match &*child.borrow() {
+      Node::Rule(rule) => rule.selector.trim().eq_ignore_ascii_case(":root"),
+      _ => false,
+    })
+    .collect()
+}
+
+/// Removes the custom properties of the `:root` rules, and the rules left
+/// empty.
+fn remove_custom_properties(root: &Rc<RefCell<Node>>) {
+  for rule in root_rules(root) {
+    if let Some(children) = rule.borrow_mut().as_shared_mut().get_nodes_mut() {
+      children.retain(|child| match &*child.borrow() {
+        Node::Decl(decl) => !is_custom_property(&decl.prop),
+        _ => true,
+      });
+    }
+  }
+  let children = root.borrow().as_shared().get_nodes().unwrap_or_default();
+  let nodes = children
+    .into_iter()
+    .filter(|child| match &*child.borrow() {
+      Node::Rule(rule) => {
+        !rule.selector.trim().eq_ignore_ascii_case(":root")
+          || rule.nodes.as_ref().is_some_and(|nodes| !nodes.is_empty())
+      }
+      _ => true,
+    })
+    .collect();
+  replace_children(root, nodes);
+}
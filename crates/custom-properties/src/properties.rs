@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Why a `var()` could not be substituted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+  /// A custom property without value, referenced without fallback.
+  Undefined(String),
+  /// Custom properties referencing each other, from and to the same one.
+  Cycle(Vec<String>),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::Undefined(name) => write!(f, "Undefined custom property {}", name),
+      Error::Cycle(names) => write!(f, "Circular custom property {}", names.join(" -> ")),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+/// Custom properties by name, with their raw values that may reference
+/// other ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Properties {
+  values: HashMap<String, String>,
+}
+
+impl Properties {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets a custom property, like `--gap` to `var(--unit)`.
+  pub fn insert(&mut self, name: &str, value: &str) {
+    self
+      .values
+      .insert(name.to_string(), value.trim().to_string());
+  }
+
+  pub fn get(&self, name: &str) -> Option<&str> {
+    self.values.get(name).map(String::as_str)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// The value of the custom property `name` with its references
+  /// substituted.
+  pub fn resolve(&self, name: &str) -> Result<String, Error> {
+    self.resolve_property(name, &mut vec![])
+  }
+
+  /// The value with its `var()` functions replaced by the custom property
+  /// they reference, or by their fallback if it can not be resolved.
+  /// Strings are left alone.
+  ///
+  /// ```
+  /// use custom_properties::Properties;
+  ///
+  /// let mut properties = Properties::new();
+  /// properties.insert("--unit", "4px");
+  /// properties.insert("--gap", "calc(var(--unit) * 2)");
+  /// assert_eq!(
+  ///   properties.substitute("var(--gap) var(--none, 1px)").unwrap(),
+  ///   "calc(4px * 2) 1px"
+  /// );
+  /// ```
+  pub fn substitute(&self, value: &str) -> Result<String, Error> {
+    self.substitute_value(value, &mut vec![])
+  }
+
+  /// The cycles of references between the custom properties, each from and
+  /// to the same property, like `--a -> --b -> --a`. References in
+  /// fallbacks count too, as every custom property of a cycle is invalid.
+  /// A cycle is listed once, from the first of its properties by name.
+  pub fn cycles(&self) -> Vec<Vec<String>> {
+    let mut names: Vec<&String> = self.values.keys().collect();
+    names.sort();
+    let mut cycles: Vec<Vec<String>> = vec![];
+    for name in names {
+      if cycles.iter().any(|cycle| cycle.contains(name)) {
+        continue;
+      }
+      let mut path = vec![name.clone()];
+      if self.find_cycle(name, &mut path, &mut HashSet::new()) {
+        cycles.push(path);
+      }
+    }
+    cycles
+  }
+
+  /// Whether the references of the last property of `path` lead back to
+  /// its first one, completing `path` with them.
+  fn find_cycle(&self, start: &str, path: &mut Vec<String>, visited: &mut HashSet<String>) -> bool {
+    let value = match path.last().and_then(|name| self.get(name)) {
+      Some(value) => value,
+      None => return false,
+    };
+    for name in references(value) {
+      path.push(name.to_string());
+      if name == start
+        || (visited.insert(name.to_string()) && self.find_cycle(start, path, visited))
+      {
+        return true;
+      }
+      path.pop();
+    }
+    false
+  }
+
+  fn resolve_property(&self, name: &str, stack: &mut Vec<String>) -> Result<String, Error> {
+    if let Some(start) = stack.iter().position(|item| item == name) {
+      let mut cycle = stack[start..].to_vec();
+      cycle.push(name.to_string());
+      return Err(Error::Cycle(cycle));
+    }
+    let value = self
+      .get(name)
+      .ok_or_else(|| Error::Undefined(name.to_string()))?;
+    stack.push(name.to_string());
+    let resolved = self.substitute_value(value, stack);
+    stack.pop();
+    resolved
+  }
+
+  fn substitute_value(&self, value: &str, stack: &mut Vec<String>) -> Result<String, Error> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some((start, args, end)) = find_var(rest) {
+      result.push_str(&rest[..start]);
+      let (name, fallback) = split_args(&rest[args.0..args.1]);
+      if !name.starts_with("--") {
+        result.push_str(&rest[start..end]);
+      } else {
+        let resolved = match (self.resolve_property(name, stack), fallback) {
+          (Ok(resolved), _) => resolved,
+          (Err(_), Some(fallback)) => self.substitute_value(fallback, stack)?,
+          (Err(error), None) => return Err(error),
+        };
+        result.push_str(&resolved);
+      }
+      rest = &rest[end..];
+    }
+    result.push_str(rest);
+    Ok(result)
+  }
+}
+
+fn is_name_char(c: u8) -> bool {
+  c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c >= 0x80
+}
+
+/// The custom properties referenced by the `var()` functions of `value`,
+/// with those of their fallbacks.
+fn references(value: &str) -> Vec<&str> {
+  let mut names = vec![];
+  let mut rest = value;
+  while let Some((_, args, end)) = find_var(rest) {
+    let (name, fallback) = split_args(&rest[args.0..args.1]);
+    if name.starts_with("--") {
+      names.push(name);
+    }
+    if let Some(fallback) = fallback {
+      names.extend(references(fallback));
+    }
+    rest = &rest[end..];
+  }
+  names
+}
+
+/// The start of the first `var(` outside of strings, the range of its
+/// arguments and its end, after the `)` or at the end of an unclosed one.
+fn find_var(value: &str) -> Option<(usize, (usize, usize), usize)> {
+  let bytes = value.as_bytes();
+  let mut quote = None;
+  let mut i = 0;
+  while i < bytes.len() {
+    let c = bytes[i];
+    match quote {
+      Some(_) if c == b'\\' => i += 1,
+      Some(q) if c == q => quote = None,
+      Some(_) => {}
+      None if c == b'"' || c == b'\'' => quote = Some(c),
+      None if c == b'\\' => i += 1,
+      None => {
+        let is_var = bytes.len() >= i + 4
+          && bytes[i..i + 4].eq_ignore_ascii_case(b"var(")
+          && (i == 0 || !is_name_char(bytes[i - 1]));
+        if is_var {
+          let args = i + 4;
+          let close = matching_paren(&value[args..]).map(|close| args + close);
+          return Some(match close {
+            Some(close) => (i, (args, close), close + 1),
+            None => (i, (args, value.len()), value.len()),
+          });
+        }
+      }
+    }
+    i += 1;
+  }
+  None
+}
+
+/// The index of the `)` closing the function `args` are in.
+fn matching_paren(args: &str) -> Option<usize> {
+  top_level(args, b')')
+}
+
+/// The index of the first `target` outside of strings and nested
+/// parentheses.
+fn top_level(text: &str, target: u8) -> Option<usize> {
+  let bytes = text.as_bytes();
+  let mut depth = 0;
+  let mut quote = None;
+  let mut i = 0;
+  while i < bytes.len() {
+    let c = bytes[i];
+    match quote {
+      Some(_) if c == b'\\' => i += 1,
+      Some(q) if c == q => quote = None,
+      Some(_) => {}
+      None => match c {
+        b'\\' => i += 1,
+        b'"' | b'\'' => quote = Some(c),
+        c if c == target && depth == 0 => return Some(i),
+        b'(' => depth += 1,
+        b')' => depth -= 1,
+        _ => {}
+      },
+    }
+    i += 1;
+  }
+  None
+}
+
+/// The name and fallback of the arguments of a `var()`.
+fn split_args(args: &str) -> (&str, Option<&str>) {
+  match top_level(args, b',') {
+    Some(comma) => (args[..comma].trim(), Some(args[comma + 1..].trim())),
+    None => (args.trim(), None),
+  }
+}
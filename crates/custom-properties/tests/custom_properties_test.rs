@@ -0,0 +1,129 @@
+use custom_properties::{CustomProperties, Error, Options, Properties};
+use postcss::{ProcessResult, Processor};
+
+fn process(preserve: bool, css: &str) -> ProcessResult {
+  Processor::run_one(
+    CustomProperties {
+      options: Options { preserve },
+    },
+    css,
+  )
+  .unwrap()
+}
+
+#[test]
+fn substitutes_values() {
+  let mut properties = Properties::new();
+  properties.insert("--unit", " 4px ");
+  properties.insert("--gap", "calc(var(--unit) * 2)");
+  properties.insert("--font", "var(--missing, Arial), sans-serif");
+  assert_eq!(properties.resolve("--gap").unwrap(), "calc(4px * 2)");
+  assert_eq!(
+    properties.substitute("var(--gap) VAR( --unit )").unwrap(),
+    "calc(4px * 2) 4px"
+  );
+  assert_eq!(
+    properties.substitute("var(--font)").unwrap(),
+    "Arial, sans-serif"
+  );
+  assert_eq!(
+    properties
+      .substitute("var(--none, var(--other, 1px 2px))")
+      .unwrap(),
+    "1px 2px"
+  );
+  assert_eq!(properties.substitute("var(--none,)").unwrap(), "");
+  assert_eq!(
+    properties
+      .substitute("\"var(--unit)\" myvar(--unit)")
+      .unwrap(),
+    "\"var(--unit)\" myvar(--unit)"
+  );
+  assert_eq!(
+    properties.substitute("1px var(--none)"),
+    Err(Error::Undefined("--none".to_string()))
+  );
+}
+
+#[test]
+fn detects_cycles() {
+  let mut properties = Properties::new();
+  properties.insert("--a", "var(--b)");
+  properties.insert("--b", "1px var(--a)");
+  properties.insert("--c", "var(--c, 2px)");
+  let error = properties.substitute("var(--a)").unwrap_err();
+  assert_eq!(
+    error,
+    Error::Cycle(vec!["--a".into(), "--b".into(), "--a".into()])
+  );
+  assert_eq!(
+    error.to_string(),
+    "Circular custom property --a -> --b -> --a"
+  );
+  assert_eq!(properties.substitute("var(--b, 0)").unwrap(), "0");
+  assert_eq!(properties.substitute("var(--c)").unwrap(), "2px");
+  assert_eq!(
+    properties.cycles(),
+    [vec!["--a", "--b", "--a"], vec!["--c", "--c"]]
+  );
+  properties.insert("--c", "var(--d, 1px)");
+  properties.insert("--d", "var(--e, var(--c))");
+  assert_eq!(properties.cycles()[1], ["--c", "--d", "--c"]);
+}
+
+#[test]
+fn preserves_declarations() {
+  let css = ":root {\n  --color: red;\n  --border: 1px solid var(--color);\n}\na {\n  color: var(--color);\n  border: var(--border);\n  --local: var(--color);\n}\n@media print {\n  :root { --color: blue }\n  b { color: var(--color, green) }\n}";
+  let result = process(true, css);
+  assert_eq!(
+    result.css,
+    ":root {\n  --color: red;\n  --border: 1px solid var(--color);\n}\na {\n  color: red;\n  color: var(--color);\n  border: 1px solid red;\n  border: var(--border);\n  --local: var(--color);\n}\n@media print {\n  :root { --color: blue }\n  b { color: red; color: var(--color, green) }\n}"
+  );
+  assert!(result.warnings().is_empty());
+  assert_eq!(process(true, &result.css).css, result.css);
+}
+
+#[test]
+fn replaces_declarations() {
+  let css = ":root { --gap: 4px; --gap: 8px; color: black }\n:root { --size: calc(var(--gap) * 2) }\na { margin: var(--gap) var(--size) }";
+  assert_eq!(
+    process(false, css).css,
+    ":root { color: black }\na { margin: 8px calc(8px * 2) }"
+  );
+  assert_eq!(
+    process(false, ":root { --a: 1px }\na { b: var(--a) }").css,
+    "a { b: 1px }"
+  );
+}
+
+#[test]
+fn warns_about_unresolved_references() {
+  let css = ":root { --a: var(--b); --b: var(--a) }\na { width: var(--a); height: var(--missing); top: var(--a, 0) }";
+  let result = process(false, css);
+  assert_eq!(
+    result.css,
+    "a { width: var(--a); height: var(--missing); top: 0 }"
+  );
+  let warnings: Vec<_> = result.warnings().iter().map(|w| w.text.clone()).collect();
+  assert_eq!(
+    warnings,
+    [
+      "Circular custom property --a -> --b -> --a",
+      "Circular custom property --a -> --b -> --a",
+      "Undefined custom property --missing"
+    ]
+  );
+  assert_eq!(result.warnings()[0].column, 9);
+
+  let css = ":root { --gap: var(--gap, 4px) }\na { margin: var(--gap, 0) }";
+  let warnings: Vec<_> = process(true, css)
+    .warnings()
+    .iter()
+    .map(|w| w.text.clone())
+    .collect();
+  assert_eq!(warnings, ["Circular custom property --gap -> --gap"]);
+  assert_eq!(
+    result.warnings()[0].plugin.as_deref(),
+    Some("custom-properties")
+  );
+}
@@ -0,0 +1,7 @@
+mod params;
+mod plugin;
+mod resolver;
+
+pub use crate::params::ImportParams;
+pub use crate::plugin::Import;
+pub use crate::resolver::{FileResolver, Resolver};
@@ -0,0 +1,122 @@
+/// The params of an `@import` at-rule.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportParams {
+  /// The imported URL, without quotes or `url()`.
+  pub url: String,
+  /// `Some("")` for an anonymous `layer`, `Some(name)` for `layer(name)`.
+  pub layer: Option<String>,
+  /// The condition of `supports()`, as written.
+  pub supports: Option<String>,
+  /// The media query list, empty if there is none.
+  pub media: String,
+}
+
+impl ImportParams {
+  /// Parses `"file.css" layer(name) supports(condition) media`, with the URL
+  /// also written as `url(file.css)` or `url("file.css")`.
+  pub fn parse(params: &str) -> Option<ImportParams> {
+    let params = params.trim_start();
+    let (url, mut rest) =
+      if let Some(quote) = params.chars().next().filter(|c| matches!(c, '"' | '\'')) {
+        string(params, quote)?
+      } else if starts_with_function(params, "url") {
+        let (inner, rest) = parenthesized(&params[3..])?;
+        let inner = inner.trim();
+        match inner.chars().next() {
+          Some(quote @ ('"' | '\'')) => match string(inner, quote)? {
+            (url, "") => (url, rest),
+            _ => return None,
+          },
+          _ => (inner.to_string(), rest),
+        }
+      } else {
+        return None;
+      };
+
+    let mut result = ImportParams {
+      url,
+      ..Default::default()
+    };
+    rest = rest.trim_start();
+    if starts_with_function(rest, "layer") {
+      let (name, tail) = parenthesized(&rest[5..])?;
+      result.layer = Some(name.trim().to_string());
+      rest = tail.trim_start();
+    } else if starts_with_keyword(rest, "layer") {
+      result.layer = Some(String::new());
+      rest = rest[5..].trim_start();
+    }
+    if starts_with_function(rest, "supports") {
+      let (condition, tail) = parenthesized(&rest[8..])?;
+      result.supports = Some(condition.trim().to_string());
+      rest = tail.trim_start();
+    }
+    result.media = rest.trim_end().to_string();
+    Some(result)
+  }
+
+  /// `true` for URLs the browser has to load: remote and `data:` URLs.
+  pub fn is_remote(&self) -> bool {
+    let url = self.url.to_ascii_lowercase();
+    url.starts_with("//")
+      || ["http:", "https:", "data:"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+  }
+}
+
+/// Reads a quoted string at the start of `input`, returning its value and
+/// the rest.
+fn string(input: &str, quote: char) -> Option<(String, &str)> {
+  let mut value = String::new();
+  let mut chars = input.char_indices().skip(1);
+  while let Some((i, c)) = chars.next() {
+    match c {
+      '\\' => value.push(chars.next()?.1),
+      _ if c == quote => return Some((value, &input[i + 1..])),
+      _ => value.push(c),
+    }
+  }
+  None
+}
+
+fn starts_with_keyword(input: &str, keyword: &str) -> bool {
+  match (input.get(..keyword.len()), input.get(keyword.len()..)) {
+    (Some(start), Some(rest)) => {
+      start.eq_ignore_ascii_case(keyword) && rest.chars().next().is_none_or(char::is_whitespace)
+    }
+    _ => false,
+  }
+}
+
+fn starts_with_function(input: &str, name: &str) -> bool {
+  match (input.get(..name.len()), input.get(name.len()..)) {
+    (Some(start), Some(rest)) => start.eq_ignore_ascii_case(name) && rest.starts_with('('),
+    _ => false,
+  }
+}
+
+/// Splits `(inner) rest` at the matching parenthesis.
+fn parenthesized(input: &str) -> Option<(&str, &str)> {
+  let mut depth = 0;
+  let mut quote = None;
+  let mut escaped = false;
+  for (i, c) in input.char_indices() {
+    match (quote, c) {
+      _ if escaped => escaped = false,
+      (_, '\\') => escaped = true,
+      (Some(q), _) if c == q => quote = None,
+      (Some(_), _) => {}
+      (None, '"' | '\'') => quote = Some(c),
+      (None, '(') => depth += 1,
+      (None, ')') => {
+        depth -= 1;
+        if depth == 0 {
+          return Some((&input[1..i], &input[i + 1..]));
+        }
+      }
+      _ => {}
+    }
+  }
+  None
+}
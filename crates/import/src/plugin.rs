@@ -0,0 +1,253 @@
+use crate::params::ImportParams;
+use crate::resolver::{FileResolver, Resolver};
+use node::{AtRule, AtRuleRaws, Node};
+use postcss::path::absolute;
+use postcss::syntax::ParseFn;
+use postcss::{CssSyntaxError, Plugin, ProcessOptions, ProcessResult};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Inlines `@import` at-rules, like `postcss-import`.
+///
+/// Imported files are parsed and their nodes take the place of the
+/// `@import`, wrapped in `@media`, `@supports` and `@layer` at-rules for the
+/// conditions of the import. Imports of imported files are inlined too, and
+/// every imported file is added to the result as a dependency.
+///
+/// Remote and `data:` URLs are kept, as are imports following other
+/// statements, with a warning. Circular imports are removed with a warning.
+pub struct Import {
+  pub resolver: Box<dyn Resolver>,
+  /// Parses the imported files.
+  pub parser: ParseFn,
+  /// Inlines a file imported more than once under the same conditions only
+  /// the first time.
+  pub skip_duplicates: bool,
+}
+
+impl Default for Import {
+  fn default() -> Self {
+    Self::new(Box::new(FileResolver::default()))
+  }
+}
+
+/// Files being imported, with the input first, and the imports done so far.
+struct State {
+  stack: Vec<PathBuf>,
+  seen: HashSet<(PathBuf, String)>,
+}
+
+impl Import {
+  pub fn new(resolver: Box<dyn Resolver>) -> Self {
+    Self {
+      resolver,
+      parser: parser::parse::parse,
+      skip_duplicates: true,
+    }
+  }
+
+  /// Inlines the imports of `root`, a file in the `base` directory which is
+  /// `imported` unless it is the input. `conditions` describes the at-rules
+  /// the file is wrapped in.
+  fn inline<'a>(
+    &self,
+    root: &Rc<RefCell<Node<'a>>>,
+    base: &Path,
+    imported: Option<&Path>,
+    conditions: &str,
+    state: &mut State,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    let children = match root.borrow().as_shared().get_nodes() {
+      Some(children) => children,
+      None => return Ok(()),
+    };
+
+    let mut nodes = Vec::with_capacity(children.len());
+    let mut statements = false;
+    for child in children {
+      let params = match &*child.borrow() {
+        Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("import") => {
+          Some(at_rule.params.clone())
+        }
+        node => {
+          statements |= !may_precede_imports(node);
+          None
+        }
+      };
+      let params = match params {
+        Some(params) => params,
+        None => {
+          nodes.push(child);
+          continue;
+        }
+      };
+      if statements {
+        result.warn(
+          "@import must precede all other statements (besides @charset or empty @layer)",
+          Some(&child.borrow()),
+        );
+        nodes.push(child);
+        continue;
+      }
+      let params = match ImportParams::parse(&params) {
+        Some(params) => params,
+        None => {
+          let text = format!("Unable to find the URL in \"{}\"", params);
+          result.warn(&text, Some(&child.borrow()));
+          nodes.push(child);
+          continue;
+        }
+      };
+      if params.is_remote() {
+        nodes.push(child);
+        continue;
+      }
+
+      let file = self
+        .resolver
+        .resolve(&params.url, base)
+        .map_err(|message| {
+          let reason = format!("Failed to find \"{}\": {}", params.url, message);
+          child.borrow().error(&reason)
+        })?;
+      if state.stack.contains(&file) {
+        let text = format!("Circular @import of {}", file.display());
+        result.warn(&text, Some(&child.borrow()));
+        continue;
+      }
+      let conditions = format!(
+        "{}|{}|{:?}|{:?}",
+        conditions, params.media, params.supports, params.layer
+      );
+      if !state.seen.insert((file.clone(), conditions.clone())) && self.skip_duplicates {
+        continue;
+      }
+
+      let path = file.to_string_lossy().into_owned();
+      if !result.dependencies().contains(&path.as_str()) {
+        let parent = imported.map(|parent| parent.to_string_lossy().into_owned());
+        result.add_dependency(&path, parent.as_deref());
+      }
+      let css = self.resolver.load(&file).map_err(|message| {
+        let reason = format!("Failed to read {}: {}", path, message);
+        child.borrow().error(&reason)
+      })?;
+      let opts = ProcessOptions {
+        from: Some(path),
+        ..Default::default()
+      };
+      // The tree owns the CSS of the file, which is dropped with the
+      // processed tree.
+      let parsed = (self.parser)(&css, Some(opts))?.borrow().deep_clone_owned();
+      let root_of_file: Rc<RefCell<Node<'a>>> = Rc::new(RefCell::new(parsed));
+
+      let dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+      state.stack.push(file.clone());
+      self.inline(&root_of_file, &dir, Some(&file), &conditions, state, result)?;
+      state.stack.pop();
+
+      let mut imported = root_of_file
+        .borrow_mut()
+        .as_shared_mut()
+        .get_nodes_mut()
+        .map(std::mem::take)
+        .unwrap_or_default();
+      imported.retain(|node| {
+        !matches!(&*node.borrow(), Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("charset"))
+      });
+      if let Some(layer) = params.layer {
+        imported = vec![wrap(&child, "layer", layer, imported)];
+      }
+      if let Some(supports) = params.supports {
+        let supports = if supports::parse(&supports).is_ok() {
+          supports
+        } else {
+          format!("({})", supports)
+        };
+        imported = vec![wrap(&child, "supports", supports, imported)];
+      }
+      if !params.media.is_empty() {
+        imported = vec![wrap(&child, "media", params.media, imported)];
+      }
+      let before = child.borrow().as_shared().as_raws().get_raw_before();
+      if let (Some(first), Some(before)) = (imported.first(), before) {
+        first.borrow_mut().set_raw_before(before);
+      }
+      nodes.extend(imported);
+    }
+
+    if let Some(children) = root.borrow_mut().as_shared_mut().get_nodes_mut() {
+      *children = nodes;
+    }
+    Ok(())
+  }
+}
+
+impl Plugin for Import {
+  fn name(&self) -> &str {
+    "import"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    let file = result
+      .opts
+      .from
+      .as_deref()
+      .map(|from| absolute(Path::new(from)));
+    let base = file
+      .as_deref()
+      .and_then(Path::parent)
+      .map_or_else(|| absolute(Path::new(".")), Path::to_path_buf);
+    let mut state = State {
+      stack: file.into_iter().collect(),
+      seen: HashSet::new(),
+    };
+    self.inline(root, &base, None, "", &mut state, result)
+  }
+}
+
+/// `@charset`, `@layer` statements and comments may come before `@import`.
+fn may_precede_imports(node: &Node) -> bool {
+  match node {
+    Node::Comment(_) => true,
+    Node::AtRule(at_rule) => {
+      at_rule.name.eq_ignore_ascii_case("charset")
+        || (at_rule.name.eq_ignore_ascii_case("layer") && at_rule.nodes.is_none())
+    }
+    _ => false,
+  }
+}
+
+/// Wraps imported nodes in an at-rule, in place of the `@import`.
+fn wrap<'a>(
+  import: &Rc<RefCell<Node<'a>>>,
+  name: &str,
+  params: String,
+  nodes: Vec<Rc<RefCell<Node<'a>>>>,
+) -> Rc<RefCell<Node<'a>>> {
+  if let Some(first) = nodes.first() {
+    let before = first.borrow().as_shared().as_raws().get_raw_before();
+    if before.is_none_or(|before| before.is_empty()) {
+      first.borrow_mut().set_raw_before("\n".to_string());
+    }
+  }
+  Rc::new(RefCell::new(Node::AtRule(AtRule {
+    name: name.to_string(),
+    params,
+    nodes: Some(nodes),
+    source: import.borrow().as_shared().get_source(),
+    raws: AtRuleRaws {
+      between: Some(" ".to_string()),
+      after: Some("\n".to_string()),
+      ..Default::default()
+    },
+    ..Default::default()
+  })))
+}
@@ -0,0 +1,92 @@
+use postcss::path::absolute;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Finds and reads the files of `@import` at-rules.
+pub trait Resolver {
+  /// Resolves the URL of an `@import` in a file of the `base` directory to
+  /// the absolute path of the imported file.
+  fn resolve(&self, url: &str, base: &Path) -> Result<PathBuf, String>;
+
+  /// Reads a resolved file.
+  fn load(&self, path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| error.to_string())
+  }
+}
+
+/// Resolves imports on the local filesystem, like `postcss-import`.
+///
+/// A URL is looked up relative to the importing file, then in `paths`, then
+/// as a module in the `node_modules` directories of the importing file and
+/// its ancestors. URLs starting with `./` or `../` are only looked up relative
+/// to the importing file.
+///
+/// A file is found as written, with a `.css` extension added, or as a
+/// package directory: the `style` or CSS `main` file of its `package.json`,
+/// otherwise its `index.css`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileResolver {
+  /// More directories to look up imports in, relative to the current
+  /// directory.
+  pub paths: Vec<PathBuf>,
+}
+
+impl Resolver for FileResolver {
+  fn resolve(&self, url: &str, base: &Path) -> Result<PathBuf, String> {
+    let base = absolute(base);
+    if url.starts_with("./") || url.starts_with("../") || Path::new(url).is_absolute() {
+      return find(&base.join(url)).ok_or_else(|| format!("not found in {}", base.display()));
+    }
+
+    let dirs = std::iter::once(base.clone())
+      .chain(self.paths.iter().map(|path| absolute(path)))
+      .chain(base.ancestors().map(|dir| dir.join("node_modules")));
+    let mut searched = vec![];
+    for dir in dirs {
+      if let Some(file) = find(&dir.join(url)) {
+        return Ok(file);
+      }
+      searched.push(dir.display().to_string());
+    }
+    Err(format!("not found in {}", searched.join(", ")))
+  }
+}
+
+fn find(path: &Path) -> Option<PathBuf> {
+  let path = absolute(path);
+  if path.is_file() {
+    return Some(path);
+  }
+  let mut css = path.clone().into_os_string();
+  css.push(".css");
+  let css = PathBuf::from(css);
+  if css.is_file() {
+    return Some(css);
+  }
+  if path.is_dir() {
+    return package(&path);
+  }
+  None
+}
+
+fn package(dir: &Path) -> Option<PathBuf> {
+  let main = fs::read_to_string(dir.join("package.json"))
+    .ok()
+    .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+    .and_then(|package| {
+      let style = package["style"].as_str().map(str::to_string);
+      style.or_else(|| {
+        package["main"]
+          .as_str()
+          .filter(|main| main.ends_with(".css"))
+          .map(str::to_string)
+      })
+    });
+  main
+    .map(|main| absolute(&dir.join(main)))
+    .into_iter()
+    .chain(Some(dir.join("index.css")))
+    .find(|file| file.is_file())
+}
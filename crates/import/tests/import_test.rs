@@ -0,0 +1,228 @@
+use import::{FileResolver, Import, ImportParams, Resolver};
+use postcss::{MapOptions, ProcessOptions, ProcessResult, Processor};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Files by absolute path, imported relative to the importing file.
+struct Files(HashMap<PathBuf, &'static str>);
+
+impl Resolver for Files {
+  fn resolve(&self, url: &str, base: &Path) -> Result<PathBuf, String> {
+    let path = postcss::path::absolute(&base.join(url));
+    match self.0.contains_key(&path) {
+      true => Ok(path),
+      false => Err("no such file".to_string()),
+    }
+  }
+
+  fn load(&self, path: &Path) -> Result<String, String> {
+    Ok(self.0[path].to_string())
+  }
+}
+
+fn process(files: &[(&str, &'static str)], css: &str) -> ProcessResult {
+  let files = files
+    .iter()
+    .map(|(path, css)| (PathBuf::from(path), *css))
+    .collect();
+  let processor = Processor::with_plugin(Import::new(Box::new(Files(files))));
+  let opts = ProcessOptions {
+    from: Some("/styles/app.css".to_string()),
+    ..Default::default()
+  };
+  processor.process(css, opts).unwrap()
+}
+
+#[test]
+fn parses_params() {
+  let params = ImportParams::parse(r#"url("a.css") layer(base) supports(display: grid) screen"#);
+  assert_eq!(
+    params,
+    Some(ImportParams {
+      url: "a.css".to_string(),
+      layer: Some("base".to_string()),
+      supports: Some("display: grid".to_string()),
+      media: "screen".to_string(),
+    })
+  );
+  let params = ImportParams::parse("'b\\'.css' LAYER print, (min-width: 1px)").unwrap();
+  assert_eq!(params.url, "b'.css");
+  assert_eq!(params.layer.as_deref(), Some(""));
+  assert_eq!(params.media, "print, (min-width: 1px)");
+  let params = ImportParams::parse("url(//cdn.example/a.css) layers").unwrap();
+  assert!(params.is_remote());
+  assert_eq!((params.layer, params.media.as_str()), (None, "layers"));
+  assert_eq!(ImportParams::parse("a.css"), None);
+}
+
+#[test]
+fn inlines_imports() {
+  let result = process(
+    &[
+      (
+        "/styles/a.css",
+        "@charset \"utf-8\";\n@import './nested/b.css';\na { b: c }",
+      ),
+      ("/styles/nested/b.css", "b { c: d }"),
+    ],
+    "@charset \"utf-8\";\n@import \"a.css\";\nc { d: e }",
+  );
+  assert_eq!(
+    result.css,
+    "@charset \"utf-8\";\nb { c: d }\na { b: c }\nc { d: e }"
+  );
+  assert_eq!(
+    result.dependencies(),
+    vec!["/styles/a.css", "/styles/nested/b.css"]
+  );
+  assert_eq!(
+    result.messages[1],
+    postcss::Message::Dependency {
+      plugin: "import".to_string(),
+      file: "/styles/nested/b.css".to_string(),
+      parent: Some("/styles/a.css".to_string()),
+    }
+  );
+}
+
+#[test]
+fn maps_imported_files() {
+  let files = [(PathBuf::from("/styles/a.css"), "a { b: c }")].into();
+  let processor = Processor::with_plugin(Import::new(Box::new(Files(files))));
+  let opts = ProcessOptions {
+    from: Some("/styles/app.css".to_string()),
+    to: Some("/styles/app.out.css".to_string()),
+    map: Some(MapOptions {
+      inline: false,
+      ..Default::default()
+    }),
+  };
+  let result = processor
+    .process("@import 'a.css';\nb { c: d }", opts)
+    .unwrap();
+  let map = result.map.unwrap();
+  assert!(map.contains(r#""sources":["a.css","app.css"]"#), "{}", map);
+  assert!(map.contains(r#""a { b: c }""#), "{}", map);
+}
+
+#[test]
+fn wraps_imports_in_conditions() {
+  let files = [("/styles/a.css", "a { b: c }")];
+  let result = process(
+    &files,
+    "@import url(a.css) layer(base) supports(display: grid) screen;",
+  );
+  assert_eq!(
+    result.css,
+    "@media screen {\n@supports (display: grid) {\n@layer base {\na { b: c }\n}\n}\n}"
+  );
+  let result = process(&files, "@import 'a.css' layer supports(not (a: b));");
+  assert_eq!(
+    result.css,
+    "@supports not (a: b) {\n@layer {\na { b: c }\n}\n}"
+  );
+}
+
+#[test]
+fn skips_duplicates_and_cycles() {
+  let result = process(
+    &[
+      ("/styles/a.css", "@import 'b.css';\na { b: c }"),
+      ("/styles/b.css", "@import 'a.css';\nb { c: d }"),
+    ],
+    "@import 'a.css';\n@import 'a.css';\n@import 'a.css' print;",
+  );
+  assert_eq!(
+    result.css,
+    "b { c: d }\na { b: c }\n@media print {\nb { c: d }\na { b: c }\n}"
+  );
+  let warnings = result.warnings();
+  assert_eq!(warnings.len(), 2);
+  assert_eq!(warnings[0].text, "Circular @import of /styles/a.css");
+  assert_eq!(warnings[0].plugin.as_deref(), Some("import"));
+}
+
+#[test]
+fn keeps_remote_and_late_imports() {
+  let result = process(
+    &[("/styles/a.css", "a { b: c }")],
+    "@import url(https://example.com/a.css);\nb {}\n@import 'a.css';",
+  );
+  assert_eq!(
+    result.css,
+    "@import url(https://example.com/a.css);\nb {}\n@import 'a.css';"
+  );
+  assert_eq!(
+    result.warnings()[0].text,
+    "@import must precede all other statements (besides @charset or empty @layer)"
+  );
+}
+
+#[test]
+fn reports_missing_files_and_syntax_errors() {
+  let processor = Processor::with_plugin(Import::new(Box::new(Files(
+    [(PathBuf::from("/styles/a.css"), "a {")].into(),
+  ))));
+  let opts = ProcessOptions {
+    from: Some("/styles/app.css".to_string()),
+    ..Default::default()
+  };
+  let error = processor
+    .process("\n@import 'b.css';", opts.clone())
+    .unwrap_err();
+  assert_eq!(error.reason, "Failed to find \"b.css\": no such file");
+  assert_eq!((error.line, error.column), (2, 1));
+  assert_eq!(error.plugin.as_deref(), Some("import"));
+
+  let error = processor.process("@import 'a.css';", opts).unwrap_err();
+  assert_eq!(error.reason, "Unclosed block");
+  assert_eq!(error.file.as_deref(), Some("/styles/a.css"));
+}
+
+#[test]
+fn resolves_files_and_node_modules() {
+  let dir = TempDir::new().unwrap();
+  let root = dir.path();
+  let write = |path: &str, css: &str| {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, css).unwrap();
+  };
+  write("src/app.css", "");
+  write("src/partials/a.css", "");
+  write("vendor/grid.css", "");
+  write("node_modules/normalize.css/normalize.css", "");
+  write(
+    "node_modules/normalize.css/package.json",
+    r#"{ "main": "index.js", "style": "normalize.css" }"#,
+  );
+  write("node_modules/theme/index.css", "");
+  write("node_modules/theme/dark.css", "");
+
+  let resolver = FileResolver {
+    paths: vec![root.join("vendor")],
+  };
+  let base = root.join("src");
+  let resolve = |url: &str| {
+    resolver
+      .resolve(url, &base)
+      .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+  };
+  assert_eq!(resolve("partials/a"), Ok("src/partials/a.css".into()));
+  assert_eq!(resolve("./partials/../app.css"), Ok("src/app.css".into()));
+  assert_eq!(resolve("grid.css"), Ok("vendor/grid.css".into()));
+  assert_eq!(
+    resolve("normalize.css"),
+    Ok("node_modules/normalize.css/normalize.css".into())
+  );
+  assert_eq!(resolve("theme"), Ok("node_modules/theme/index.css".into()));
+  assert_eq!(
+    resolve("theme/dark"),
+    Ok("node_modules/theme/dark.css".into())
+  );
+  assert!(resolve("./grid.css")
+    .unwrap_err()
+    .starts_with("not found in"));
+}
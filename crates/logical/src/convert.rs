@@ -0,0 +1,183 @@
+use once_cell::sync::Lazy;
+
+/// The inline direction of a horizontal writing mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+  Ltr,
+  Rtl,
+}
+
+/// A logical longhand with its physical properties in `ltr` and `rtl`.
+struct Longhand {
+  logical: String,
+  ltr: String,
+  rtl: String,
+}
+
+impl Longhand {
+  fn physical(&self, direction: Direction) -> &str {
+    match direction {
+      Direction::Ltr => &self.ltr,
+      Direction::Rtl => &self.rtl,
+    }
+  }
+}
+
+/// The families of box properties, with the prefix of their physical
+/// properties and the suffixes of their longhands.
+const FAMILIES: &[(&str, &str, &[&str])] = &[
+  ("margin", "margin-", &[""]),
+  ("padding", "padding-", &[""]),
+  ("scroll-margin", "scroll-margin-", &[""]),
+  ("scroll-padding", "scroll-padding-", &[""]),
+  ("inset", "", &[""]),
+  ("border", "border-", &["", "-width", "-style", "-color"]),
+];
+
+static LONGHANDS: Lazy<Vec<Longhand>> = Lazy::new(|| {
+  let mut longhands = vec![];
+  let mut push =
+    |logical: String, ltr: String, rtl: String| longhands.push(Longhand { logical, ltr, rtl });
+  for prefix in ["", "min-", "max-"] {
+    for (logical, physical) in [("inline-size", "width"), ("block-size", "height")] {
+      let physical = format!("{}{}", prefix, physical);
+      push(format!("{}{}", prefix, logical), physical.clone(), physical);
+    }
+  }
+  for (family, physical, suffixes) in FAMILIES {
+    for suffix in *suffixes {
+      let side = |side: &str| format!("{}{}{}", physical, side, suffix);
+      let logical = |side: &str| format!("{}-{}{}", family, side, suffix);
+      push(logical("inline-start"), side("left"), side("right"));
+      push(logical("inline-end"), side("right"), side("left"));
+      push(logical("block-start"), side("top"), side("top"));
+      push(logical("block-end"), side("bottom"), side("bottom"));
+    }
+  }
+  for (block, vertical) in [("start", "top"), ("end", "bottom")] {
+    for (inline, ltr, rtl) in [("start", "left", "right"), ("end", "right", "left")] {
+      push(
+        format!("border-{}-{}-radius", block, inline),
+        format!("border-{}-{}-radius", vertical, ltr),
+        format!("border-{}-{}-radius", vertical, rtl),
+      );
+    }
+  }
+  longhands
+});
+
+/// Converts a logical declaration to the physical ones for a horizontal
+/// writing mode, or returns `None` if it is not logical.
+///
+/// Shorthands like `margin-inline: 1px 2px` become two declarations, and
+/// `inline-start` and `inline-end` values of `float` and `clear`, `start`
+/// and `end` of `text-align` and `block` and `inline` of `resize` become
+/// physical values.
+pub fn to_physical(prop: &str, value: &str, direction: Direction) -> Option<Vec<(String, String)>> {
+  let prop = prop.to_ascii_lowercase();
+  if let Some(longhand) = LONGHANDS.iter().find(|longhand| longhand.logical == prop) {
+    return Some(vec![(
+      longhand.physical(direction).to_string(),
+      value.to_string(),
+    )]);
+  }
+  if let Some((start, end)) = shorthand(&prop) {
+    let values = if prop == "border-inline" || prop == "border-block" {
+      (value.to_string(), value.to_string())
+    } else {
+      match split(value)[..] {
+        [both] => (both.to_string(), both.to_string()),
+        [start, end] => (start.to_string(), end.to_string()),
+        _ => return None,
+      }
+    };
+    return Some(vec![
+      (to_physical(&start, "", direction)?.remove(0).0, values.0),
+      (to_physical(&end, "", direction)?.remove(0).0, values.1),
+    ]);
+  }
+
+  let lower = value.trim().to_ascii_lowercase();
+  let (start, end) = match direction {
+    Direction::Ltr => ("left", "right"),
+    Direction::Rtl => ("right", "left"),
+  };
+  let physical = match (prop.as_str(), lower.as_str()) {
+    ("float" | "clear", "inline-start") | ("text-align", "start") => start,
+    ("float" | "clear", "inline-end") | ("text-align", "end") => end,
+    ("resize", "block") => "vertical",
+    ("resize", "inline") => "horizontal",
+    _ => return None,
+  };
+  Some(vec![(prop, physical.to_string())])
+}
+
+/// Converts a physical declaration to the logical one, or returns `None` if
+/// it has no logical equivalent.
+pub fn to_logical(prop: &str, value: &str, direction: Direction) -> Option<(String, String)> {
+  let prop = prop.to_ascii_lowercase();
+  if let Some(longhand) = LONGHANDS
+    .iter()
+    .find(|longhand| longhand.physical(direction) == prop)
+  {
+    return Some((longhand.logical.clone(), value.to_string()));
+  }
+
+  let lower = value.trim().to_ascii_lowercase();
+  let (left, right) = match direction {
+    Direction::Ltr => ("start", "end"),
+    Direction::Rtl => ("end", "start"),
+  };
+  let logical = match (prop.as_str(), lower.as_str()) {
+    ("float" | "clear", "left") => format!("inline-{}", left),
+    ("float" | "clear", "right") => format!("inline-{}", right),
+    ("text-align", "left") => left.to_string(),
+    ("text-align", "right") => right.to_string(),
+    ("resize", "vertical") => "block".to_string(),
+    ("resize", "horizontal") => "inline".to_string(),
+    _ => return None,
+  };
+  Some((prop, logical))
+}
+
+/// The start and end longhands of an `inline` or `block` shorthand.
+fn shorthand(prop: &str) -> Option<(String, String)> {
+  for (family, _, suffixes) in FAMILIES {
+    for suffix in *suffixes {
+      for axis in ["inline", "block"] {
+        if prop == format!("{}-{}{}", family, axis, suffix) {
+          return Some((
+            format!("{}-{}-start{}", family, axis, suffix),
+            format!("{}-{}-end{}", family, axis, suffix),
+          ));
+        }
+      }
+    }
+  }
+  None
+}
+
+/// Splits a value at the whitespace outside of parentheses.
+fn split(value: &str) -> Vec<&str> {
+  let mut parts = vec![];
+  let mut depth = 0;
+  let mut start = None;
+  for (i, c) in value.char_indices() {
+    match c {
+      '(' => depth += 1,
+      ')' => depth -= 1,
+      _ if c.is_whitespace() && depth == 0 => {
+        if let Some(start) = start.take() {
+          parts.push(&value[start..i]);
+        }
+        continue;
+      }
+      _ => {}
+    }
+    start.get_or_insert(i);
+  }
+  if let Some(start) = start {
+    parts.push(&value[start..]);
+  }
+  parts
+}
@@ -0,0 +1,5 @@
+mod convert;
+mod plugin;
+
+pub use crate::convert::{to_logical, to_physical, Direction};
+pub use crate::plugin::{Dir, Logical, Options};
@@ -0,0 +1,184 @@
+use crate::convert::{to_logical, to_physical, Direction};
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use selector::{
+  AttributeOperator, AttributeSelector, Combinator, CombinatorKind, CompoundSelector,
+  SimpleSelector,
+};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The directions to convert logical properties for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dir {
+  #[default]
+  Ltr,
+  Rtl,
+  /// `ltr`, with `[dir="rtl"]` rules for the declarations that differ.
+  Both,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  pub dir: Dir,
+  /// Converts physical properties to logical ones instead, for `ltr` unless
+  /// `dir` is `rtl`.
+  pub reverse: bool,
+}
+
+/// Converts logical properties and values to physical ones, see
+/// [`to_physical`](crate::to_physical).
+///
+/// With `dir: both`, declarations that depend on the direction are
+/// converted for `ltr`, so that documents without `dir` get them, and
+/// rules after theirs override them for `[dir="rtl"]` ancestors. The
+/// overrides set the `ltr` properties that `rtl` does not set to `initial`,
+/// and win over the physical declarations of their rule, whatever the order.
+/// Outside of rules the `ltr` properties are used.
+#[derive(Debug, Clone, Default)]
+pub struct Logical {
+  pub options: Options,
+}
+
+impl Logical {
+  /// Converts the declarations of `parent`, and returns the `[dir]` rules
+  /// for it if it is a rule.
+  fn convert<'a>(
+    &self,
+    parent: &Rc<RefCell<Node<'a>>>,
+  ) -> Result<Vec<Rc<RefCell<Node<'a>>>>, CssSyntaxError> {
+    let children = match parent.borrow().as_shared().get_nodes() {
+      Some(children) => children,
+      None => return Ok(vec![]),
+    };
+    let is_rule = matches!(&*parent.borrow(), Node::Rule(_));
+    let mut nodes = Vec::with_capacity(children.len());
+    let mut rtl = vec![];
+    for child in children {
+      let converted =
+        match &*child.borrow() {
+          Node::Decl(decl) if self.options.reverse => {
+            let direction = match self.options.dir {
+              Dir::Rtl => Direction::Rtl,
+              _ => Direction::Ltr,
+            };
+            to_logical(&decl.prop, &decl.value, direction)
+              .map(|decl| (vec![decl.clone()], vec![decl]))
+          }
+          Node::Decl(decl) => match self.options.dir {
+            Dir::Ltr => to_physical(&decl.prop, &decl.value, Direction::Ltr)
+              .map(|decls| (decls.clone(), decls)),
+            Dir::Rtl => to_physical(&decl.prop, &decl.value, Direction::Rtl)
+              .map(|decls| (decls.clone(), decls)),
+            Dir::Both => to_physical(&decl.prop, &decl.value, Direction::Ltr).zip(to_physical(
+              &decl.prop,
+              &decl.value,
+              Direction::Rtl,
+            )),
+          },
+          _ => None,
+        };
+      match converted {
+        Some((left, right)) if left == right || !is_rule => nodes.extend(replace(&child, left)),
+        Some((left, right)) => {
+          let resets = left
+            .iter()
+            .filter(|(prop, _)| !right.iter().any(|(other, _)| other == prop))
+            .map(|(prop, _)| (prop.clone(), "initial".to_string()));
+          rtl.extend(replace(&child, resets.chain(right.clone()).collect()));
+          nodes.extend(replace(&child, left));
+        }
+        None => {
+          let fallbacks = self.convert(&child)?;
+          nodes.push(child);
+          nodes.extend(fallbacks);
+        }
+      }
+    }
+
+    let mut fallbacks = vec![];
+    if !rtl.is_empty() {
+      fallbacks.push(dir_rule(parent, "rtl", rtl)?);
+    }
+    if let Some(children) = parent.borrow_mut().as_shared_mut().get_nodes_mut() {
+      *children = nodes;
+    }
+    Ok(fallbacks)
+  }
+}
+
+impl Plugin for Logical {
+  fn name(&self) -> &str {
+    "logical"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    self.convert(root)?;
+    Ok(())
+  }
+}
+
+/// Copies of the declaration with the properties and values of `decls`.
+fn replace<'a>(
+  decl: &Rc<RefCell<Node<'a>>>,
+  decls: Vec<(String, String)>,
+) -> Vec<Rc<RefCell<Node<'a>>>> {
+  decls
+    .into_iter()
+    .map(|(prop, value)| {
+      let node = decl.borrow().deep_clone();
+      let node = Rc::new(RefCell::new(node));
+      if let Some(decl) = node.borrow_mut().as_decl_mut() {
+        decl.prop = prop;
+        if value != decl.value {
+          decl.value = value;
+          decl.raws.value = None;
+        }
+      }
+      node
+    })
+    .collect()
+}
+
+/// A copy of `rule` with the `decls`, for elements in a `[dir]` element.
+fn dir_rule<'a>(
+  rule: &Rc<RefCell<Node<'a>>>,
+  dir: &str,
+  decls: Vec<Rc<RefCell<Node<'a>>>>,
+) -> Result<Rc<RefCell<Node<'a>>>, CssSyntaxError> {
+  let mut node = rule.borrow().deep_clone();
+  let mut list = match selector::parse(&node.as_rule().unwrap().selector) {
+    Ok(list) => list,
+    Err(error) => return Err(node.error(&error.to_string())),
+  };
+  for selector in &mut list.selectors {
+    let attribute = SimpleSelector::Attribute(AttributeSelector {
+      namespace: None,
+      name: "dir".to_string(),
+      operator: Some(AttributeOperator::Equal),
+      value: Some(dir.to_string()),
+      quote: Some('"'),
+      modifier: None,
+    });
+    if let Some(first) = selector.compounds.first_mut() {
+      first
+        .combinator
+        .get_or_insert_with(|| Combinator::new(CombinatorKind::Descendant));
+    }
+    selector
+      .compounds
+      .insert(0, CompoundSelector::new(vec![attribute]));
+  }
+  let rule = node.as_rule_mut().unwrap();
+  rule.selector = list.to_string();
+  rule.raws.selector = None;
+  rule.nodes = Some(decls);
+  if rule.raws.before.as_deref() == Some("") {
+    // The rule was the first of the stylesheet, its copies are not.
+    rule.raws.before = None;
+  }
+  Ok(Rc::new(RefCell::new(node)))
+}
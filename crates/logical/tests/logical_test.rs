@@ -0,0 +1,136 @@
+use logical::{to_logical, to_physical, Dir, Direction, Logical, Options};
+use postcss::Processor;
+
+fn process(options: Options, css: &str) -> String {
+  Processor::run_one(Logical { options }, css).unwrap().css
+}
+
+fn decls(decls: &[(&str, &str)]) -> Option<Vec<(String, String)>> {
+  Some(
+    decls
+      .iter()
+      .map(|(prop, value)| (prop.to_string(), value.to_string()))
+      .collect(),
+  )
+}
+
+#[test]
+fn converts_to_physical() {
+  let rtl = Direction::Rtl;
+  assert_eq!(
+    to_physical("margin-inline-start", "1px", Direction::Ltr),
+    decls(&[("margin-left", "1px")])
+  );
+  assert_eq!(
+    to_physical("margin-inline-start", "1px", rtl),
+    decls(&[("margin-right", "1px")])
+  );
+  assert_eq!(
+    to_physical("padding-inline", "1px calc(2px + 1em)", rtl),
+    decls(&[
+      ("padding-right", "1px"),
+      ("padding-left", "calc(2px + 1em)")
+    ])
+  );
+  assert_eq!(
+    to_physical("inset-block", "0", rtl),
+    decls(&[("top", "0"), ("bottom", "0")])
+  );
+  assert_eq!(
+    to_physical("border-inline", "1px solid red", rtl),
+    decls(&[
+      ("border-right", "1px solid red"),
+      ("border-left", "1px solid red")
+    ])
+  );
+  assert_eq!(
+    to_physical("border-start-end-radius", "4px", rtl),
+    decls(&[("border-top-left-radius", "4px")])
+  );
+  assert_eq!(
+    to_physical("max-inline-size", "1px", rtl),
+    decls(&[("max-width", "1px")])
+  );
+  assert_eq!(
+    to_physical("float", "inline-start", rtl),
+    decls(&[("float", "right")])
+  );
+  assert_eq!(
+    to_physical("text-align", "end", Direction::Ltr),
+    decls(&[("text-align", "right")])
+  );
+  assert_eq!(to_physical("margin-left", "1px", rtl), None);
+  assert_eq!(to_physical("margin-inline", "1px 2px 3px", rtl), None);
+}
+
+#[test]
+fn converts_to_logical() {
+  let pair = |prop: &str, value: &str| Some((prop.to_string(), value.to_string()));
+  assert_eq!(
+    to_logical("margin-left", "1px", Direction::Ltr),
+    pair("margin-inline-start", "1px")
+  );
+  assert_eq!(
+    to_logical("left", "0", Direction::Rtl),
+    pair("inset-inline-end", "0")
+  );
+  assert_eq!(
+    to_logical("border-top-width", "1px", Direction::Rtl),
+    pair("border-block-start-width", "1px")
+  );
+  assert_eq!(
+    to_logical("float", "right", Direction::Ltr),
+    pair("float", "inline-end")
+  );
+  assert_eq!(to_logical("margin", "0", Direction::Ltr), None);
+}
+
+#[test]
+fn converts_declarations() {
+  let css = "a { margin-inline: 1px 2px !important; color: red }";
+  let options = Options {
+    dir: Dir::Rtl,
+    ..Default::default()
+  };
+  assert_eq!(
+    process(options, css),
+    "a { margin-right: 1px !important; margin-left: 2px !important; color: red }"
+  );
+  let options = Options {
+    reverse: true,
+    ..Default::default()
+  };
+  assert_eq!(
+    process(options, "a { padding-left: 1px; text-align: left }"),
+    "a { padding-inline-start: 1px; text-align: start }"
+  );
+}
+
+#[test]
+fn adds_dir_rules_for_both() {
+  let options = Options {
+    dir: Dir::Both,
+    ..Default::default()
+  };
+  let css = "a, .b > c {\n  inset-block-start: 0;\n  float: inline-end;\n}\n\n.d {\n  margin-inline-start: 1px;\n}\n\n.e {}";
+  assert_eq!(
+    process(options, css),
+    "a, .b > c {\n  top: 0;\n  float: right;\n}\n\n[dir=\"rtl\"] a, [dir=\"rtl\"] .b > c {\n  float: left;\n}\n\n.d {\n  margin-left: 1px;\n}\n\n[dir=\"rtl\"] .d {\n  margin-left: initial;\n  margin-right: 1px;\n}\n\n.e {}"
+  );
+}
+
+#[test]
+fn keeps_ltr_without_dir() {
+  let options = Options {
+    dir: Dir::Both,
+    ..Default::default()
+  };
+  // a document without `dir` only matches the rules without `[dir]`
+  let css = process(options, ".a { padding-inline: 1px 2px; text-align: end }");
+  let rules: Vec<_> = css.lines().filter(|rule| !rule.contains("[dir")).collect();
+  assert_eq!(
+    rules,
+    vec![".a { padding-left: 1px; padding-right: 2px; text-align: right }"]
+  );
+  assert!(!css.contains("[dir=\"ltr\"]"), "{}", css);
+}
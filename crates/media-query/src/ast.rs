@@ -0,0 +1,241 @@
+use std::fmt;
+
+/// A comma separated list of media queries, the params of `@media`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MediaQueryList {
+  pub queries: Vec<MediaQuery>,
+}
+
+/// A single media query, e.g. `only screen and (min-width: 768px)`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MediaQuery {
+  pub qualifier: Option<Qualifier>,
+
+  /// The media type, like `screen` or `print`, in lowercase.
+  pub media_type: Option<String>,
+
+  pub condition: Option<MediaCondition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Qualifier {
+  Not,
+  Only,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaCondition {
+  /// `(min-width: 768px)`
+  Feature(MediaFeature),
+  /// `not (color)`
+  Not(Box<MediaCondition>),
+  /// `(color) and (hover)`
+  And(Vec<MediaCondition>),
+  /// `(color) or (hover)`
+  Or(Vec<MediaCondition>),
+  /// A function or parenthesized block this parser does not understand, kept
+  /// verbatim, e.g. `(foo: bar baz)` or `style(--theme: dark)`.
+  GeneralEnclosed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaFeature {
+  /// `(min-width: 768px)`
+  Plain { name: String, value: MediaValue },
+  /// `(color)`
+  Boolean { name: String },
+  /// `(width >= 768px)`
+  ///
+  /// The value-first form `(768px <= width)` is normalized to this form.
+  Range {
+    name: String,
+    operator: RangeOperator,
+    value: MediaValue,
+  },
+  /// `(400px <= width < 800px)`
+  Interval {
+    name: String,
+    start: MediaValue,
+    start_operator: RangeOperator,
+    end_operator: RangeOperator,
+    end: MediaValue,
+  },
+}
+
+impl MediaFeature {
+  /// The feature name in lowercase, including any `min-`/`max-` prefix.
+  pub fn name(&self) -> &str {
+    match self {
+      MediaFeature::Plain { name, .. }
+      | MediaFeature::Boolean { name }
+      | MediaFeature::Range { name, .. }
+      | MediaFeature::Interval { name, .. } => name,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RangeOperator {
+  /// `<`
+  Lt,
+  /// `<=`
+  Le,
+  /// `>`
+  Gt,
+  /// `>=`
+  Ge,
+  /// `=`
+  Eq,
+}
+
+impl RangeOperator {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      RangeOperator::Lt => "<",
+      RangeOperator::Le => "<=",
+      RangeOperator::Gt => ">",
+      RangeOperator::Ge => ">=",
+      RangeOperator::Eq => "=",
+    }
+  }
+
+  /// The operator with its operands swapped, `a < b` is `b > a`.
+  pub fn flip(&self) -> Self {
+    match self {
+      RangeOperator::Lt => RangeOperator::Gt,
+      RangeOperator::Le => RangeOperator::Ge,
+      RangeOperator::Gt => RangeOperator::Lt,
+      RangeOperator::Ge => RangeOperator::Le,
+      RangeOperator::Eq => RangeOperator::Eq,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaValue {
+  /// `2`
+  Number(f64),
+  /// `768px`, `2dppx`, `50%`
+  Dimension(f64, String),
+  /// `16/9`
+  Ratio(f64, f64),
+  /// `landscape`
+  Ident(String),
+  /// Anything else, like `calc(100px + 2em)`, kept verbatim.
+  Raw(String),
+}
+
+impl fmt::Display for MediaQueryList {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, query) in self.queries.iter().enumerate() {
+      if i > 0 {
+        f.write_str(", ")?;
+      }
+      write!(f, "{}", query)?;
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for MediaQuery {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.qualifier {
+      Some(Qualifier::Not) => f.write_str("not ")?,
+      Some(Qualifier::Only) => f.write_str("only ")?,
+      None => {}
+    }
+    if let Some(media_type) = &self.media_type {
+      f.write_str(media_type)?;
+      if let Some(condition) = &self.condition {
+        f.write_str(" and ")?;
+        // `or` can not follow a media type without parentheses.
+        match condition {
+          MediaCondition::Or(_) => write_in_parens(f, condition)?,
+          _ => write!(f, "{}", condition)?,
+        }
+      }
+      return Ok(());
+    }
+    match &self.condition {
+      Some(condition) => write!(f, "{}", condition),
+      None => Ok(()),
+    }
+  }
+}
+
+impl fmt::Display for MediaCondition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      MediaCondition::Not(condition) => {
+        f.write_str("not ")?;
+        write_in_parens(f, condition)
+      }
+      MediaCondition::And(conditions) => write_joined(f, conditions, " and "),
+      MediaCondition::Or(conditions) => write_joined(f, conditions, " or "),
+      MediaCondition::Feature(_) | MediaCondition::GeneralEnclosed(_) => write_in_parens(f, self),
+    }
+  }
+}
+
+fn write_joined(
+  f: &mut fmt::Formatter,
+  conditions: &[MediaCondition],
+  separator: &str,
+) -> fmt::Result {
+  for (i, condition) in conditions.iter().enumerate() {
+    if i > 0 {
+      f.write_str(separator)?;
+    }
+    write_in_parens(f, condition)?;
+  }
+  Ok(())
+}
+
+/// Writes `<media-in-parens>`.
+fn write_in_parens(f: &mut fmt::Formatter, condition: &MediaCondition) -> fmt::Result {
+  match condition {
+    MediaCondition::Feature(feature) => write!(f, "({})", feature),
+    MediaCondition::GeneralEnclosed(raw) => f.write_str(raw),
+    _ => write!(f, "({})", condition),
+  }
+}
+
+impl fmt::Display for MediaFeature {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      MediaFeature::Plain { name, value } => write!(f, "{}: {}", name, value),
+      MediaFeature::Boolean { name } => f.write_str(name),
+      MediaFeature::Range {
+        name,
+        operator,
+        value,
+      } => write!(f, "{} {} {}", name, operator.as_str(), value),
+      MediaFeature::Interval {
+        name,
+        start,
+        start_operator,
+        end_operator,
+        end,
+      } => write!(
+        f,
+        "{} {} {} {} {}",
+        start,
+        start_operator.as_str(),
+        name,
+        end_operator.as_str(),
+        end
+      ),
+    }
+  }
+}
+
+impl fmt::Display for MediaValue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      MediaValue::Number(number) => write!(f, "{}", number),
+      MediaValue::Dimension(number, unit) => write!(f, "{}{}", number, unit),
+      MediaValue::Ratio(numerator, denominator) => write!(f, "{}/{}", numerator, denominator),
+      MediaValue::Ident(ident) | MediaValue::Raw(ident) => f.write_str(ident),
+    }
+  }
+}
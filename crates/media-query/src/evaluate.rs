@@ -0,0 +1,389 @@
+use crate::ast::*;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+/// The result of evaluating a media query, using three-valued logic.
+///
+/// A query is `Unknown` when it depends on something the [`Environment`] does
+/// not know, like the viewport width of an environment that has none, or on a
+/// feature this crate does not understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchResult {
+  True,
+  False,
+  Unknown,
+}
+
+impl MatchResult {
+  pub fn and(self, other: MatchResult) -> MatchResult {
+    match (self, other) {
+      (MatchResult::False, _) | (_, MatchResult::False) => MatchResult::False,
+      (MatchResult::True, MatchResult::True) => MatchResult::True,
+      _ => MatchResult::Unknown,
+    }
+  }
+
+  pub fn or(self, other: MatchResult) -> MatchResult {
+    match (self, other) {
+      (MatchResult::True, _) | (_, MatchResult::True) => MatchResult::True,
+      (MatchResult::False, MatchResult::False) => MatchResult::False,
+      _ => MatchResult::Unknown,
+    }
+  }
+
+  #[allow(clippy::should_implement_trait)]
+  pub fn not(self) -> MatchResult {
+    match self {
+      MatchResult::True => MatchResult::False,
+      MatchResult::False => MatchResult::True,
+      MatchResult::Unknown => MatchResult::Unknown,
+    }
+  }
+}
+
+impl From<bool> for MatchResult {
+  fn from(value: bool) -> Self {
+    if value {
+      MatchResult::True
+    } else {
+      MatchResult::False
+    }
+  }
+}
+
+/// The environment media queries are evaluated against.
+///
+/// Every feature that is `None` is unknown, and queries depending on it
+/// evaluate to [`MatchResult::Unknown`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Environment {
+  /// The media type, like `screen` or `print`.
+  pub media_type: Option<String>,
+
+  /// The viewport width in `px`.
+  pub width: Option<f64>,
+  /// The viewport height in `px`.
+  pub height: Option<f64>,
+  pub device_width: Option<f64>,
+  pub device_height: Option<f64>,
+
+  /// The resolution in `dppx`.
+  pub resolution: Option<f64>,
+
+  /// Bits per color component, `0` for monochrome devices.
+  pub color: Option<f64>,
+  pub color_index: Option<f64>,
+  pub monochrome: Option<f64>,
+
+  /// `portrait` or `landscape`, derived from `width` and `height` when not set.
+  pub orientation: Option<String>,
+  pub prefers_color_scheme: Option<String>,
+  pub prefers_reduced_motion: Option<String>,
+  pub prefers_contrast: Option<String>,
+  pub forced_colors: Option<String>,
+  pub hover: Option<String>,
+  pub any_hover: Option<String>,
+  pub pointer: Option<String>,
+  pub any_pointer: Option<String>,
+  pub scripting: Option<String>,
+
+  /// The root font size in `px`, used to resolve `em` and `rem`.
+  pub font_size: f64,
+}
+
+impl Default for Environment {
+  fn default() -> Self {
+    Self {
+      media_type: None,
+      width: None,
+      height: None,
+      device_width: None,
+      device_height: None,
+      resolution: None,
+      color: None,
+      color_index: None,
+      monochrome: None,
+      orientation: None,
+      prefers_color_scheme: None,
+      prefers_reduced_motion: None,
+      prefers_contrast: None,
+      forced_colors: None,
+      hover: None,
+      any_hover: None,
+      pointer: None,
+      any_pointer: None,
+      scripting: None,
+      font_size: 16.0,
+    }
+  }
+}
+
+/// The value of a feature in the environment.
+enum EnvValue<'e> {
+  Length(Option<f64>),
+  Resolution(Option<f64>),
+  Integer(Option<f64>),
+  Ratio(Option<f64>),
+  Ident {
+    value: Option<&'e str>,
+    /// The value that makes the feature false in a boolean context.
+    none: Option<&'static str>,
+  },
+}
+
+fn ident<'e>(value: &'e Option<String>, none: Option<&'static str>) -> EnvValue<'e> {
+  EnvValue::Ident {
+    value: value.as_deref(),
+    none,
+  }
+}
+
+impl Environment {
+  fn orientation(&self) -> Option<&str> {
+    match (&self.orientation, self.width, self.height) {
+      (Some(orientation), _, _) => Some(orientation),
+      (None, Some(width), Some(height)) if width > height => Some("landscape"),
+      (None, Some(_), Some(_)) => Some("portrait"),
+      _ => None,
+    }
+  }
+
+  fn feature(&self, name: &str) -> Option<EnvValue<'_>> {
+    let aspect_ratio = |width: Option<f64>, height: Option<f64>| match (width, height) {
+      (Some(width), Some(height)) if height != 0.0 => Some(width / height),
+      _ => None,
+    };
+    let value = match name {
+      "width" => EnvValue::Length(self.width),
+      "height" => EnvValue::Length(self.height),
+      "device-width" => EnvValue::Length(self.device_width),
+      "device-height" => EnvValue::Length(self.device_height),
+      "aspect-ratio" => EnvValue::Ratio(aspect_ratio(self.width, self.height)),
+      "device-aspect-ratio" => EnvValue::Ratio(aspect_ratio(self.device_width, self.device_height)),
+      "resolution" => EnvValue::Resolution(self.resolution),
+      "color" => EnvValue::Integer(self.color),
+      "color-index" => EnvValue::Integer(self.color_index),
+      "monochrome" => EnvValue::Integer(self.monochrome),
+      "orientation" => EnvValue::Ident {
+        value: self.orientation(),
+        none: None,
+      },
+      "prefers-color-scheme" => ident(&self.prefers_color_scheme, None),
+      "prefers-reduced-motion" => ident(&self.prefers_reduced_motion, Some("no-preference")),
+      "prefers-contrast" => ident(&self.prefers_contrast, Some("no-preference")),
+      "forced-colors" => ident(&self.forced_colors, Some("none")),
+      "hover" => ident(&self.hover, Some("none")),
+      "any-hover" => ident(&self.any_hover, Some("none")),
+      "pointer" => ident(&self.pointer, Some("none")),
+      "any-pointer" => ident(&self.any_pointer, Some("none")),
+      "scripting" => ident(&self.scripting, Some("none")),
+      _ => return None,
+    };
+    Some(value)
+  }
+
+  /// Converts a length to `px`, `None` if the unit is not a length or is
+  /// relative to an unknown viewport size.
+  pub(crate) fn length(&self, value: &MediaValue) -> Option<f64> {
+    let (number, unit) = match value {
+      MediaValue::Number(number) if *number == 0.0 => return Some(0.0),
+      MediaValue::Dimension(number, unit) => (*number, unit.as_str()),
+      _ => return None,
+    };
+    let factor = match unit {
+      "px" => 1.0,
+      "em" | "rem" => self.font_size,
+      "in" => 96.0,
+      "cm" => 96.0 / 2.54,
+      "mm" => 96.0 / 25.4,
+      "q" => 96.0 / 101.6,
+      "pt" => 96.0 / 72.0,
+      "pc" => 16.0,
+      "vw" => self.width? / 100.0,
+      "vh" => self.height? / 100.0,
+      "vmin" => self.width?.min(self.height?) / 100.0,
+      "vmax" => self.width?.max(self.height?) / 100.0,
+      _ => return None,
+    };
+    Some(number * factor)
+  }
+}
+
+/// Converts a resolution to `dppx`.
+fn resolution(value: &MediaValue) -> Option<f64> {
+  match value {
+    MediaValue::Dimension(number, unit) => match unit.as_str() {
+      "dppx" | "x" => Some(*number),
+      "dpi" => Some(number / 96.0),
+      "dpcm" => Some(number * 2.54 / 96.0),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn ratio(value: &MediaValue) -> Option<f64> {
+  match value {
+    MediaValue::Ratio(numerator, denominator) if *denominator != 0.0 => {
+      Some(numerator / denominator)
+    }
+    MediaValue::Number(number) => Some(*number),
+    _ => None,
+  }
+}
+
+fn compare(left: f64, operator: RangeOperator, right: f64) -> bool {
+  // Tolerate rounding errors of unit conversions.
+  let ordering = if (left - right).abs() < 1e-9 {
+    Ordering::Equal
+  } else if left < right {
+    Ordering::Less
+  } else {
+    Ordering::Greater
+  };
+  match operator {
+    RangeOperator::Lt => ordering == Ordering::Less,
+    RangeOperator::Le => ordering != Ordering::Greater,
+    RangeOperator::Gt => ordering == Ordering::Greater,
+    RangeOperator::Ge => ordering != Ordering::Less,
+    RangeOperator::Eq => ordering == Ordering::Equal,
+  }
+}
+
+impl MediaQueryList {
+  /// Evaluates the list, it matches if any of its queries matches.
+  ///
+  /// An empty list, like `@media {}`, always matches.
+  pub fn matches(&self, env: &Environment) -> MatchResult {
+    if self.queries.is_empty() {
+      return MatchResult::True;
+    }
+    self
+      .queries
+      .iter()
+      .fold(MatchResult::False, |result, query| {
+        result.or(query.matches(env))
+      })
+  }
+}
+
+impl MediaQuery {
+  pub fn matches(&self, env: &Environment) -> MatchResult {
+    let media_type = match (self.media_type.as_deref(), &env.media_type) {
+      (None, _) | (Some("all"), _) => MatchResult::True,
+      (Some(media_type), Some(env_type)) => media_type.eq_ignore_ascii_case(env_type).into(),
+      (Some(_), None) => MatchResult::Unknown,
+    };
+    let result = match &self.condition {
+      Some(condition) => media_type.and(condition.matches(env)),
+      None => media_type,
+    };
+    match self.qualifier {
+      Some(Qualifier::Not) => result.not(),
+      _ => result,
+    }
+  }
+}
+
+impl MediaCondition {
+  pub fn matches(&self, env: &Environment) -> MatchResult {
+    match self {
+      MediaCondition::Feature(feature) => feature.matches(env),
+      MediaCondition::Not(condition) => condition.matches(env).not(),
+      MediaCondition::And(conditions) => conditions
+        .iter()
+        .fold(MatchResult::True, |result, condition| {
+          result.and(condition.matches(env))
+        }),
+      MediaCondition::Or(conditions) => conditions
+        .iter()
+        .fold(MatchResult::False, |result, condition| {
+          result.or(condition.matches(env))
+        }),
+      MediaCondition::GeneralEnclosed(_) => MatchResult::Unknown,
+    }
+  }
+}
+
+impl MediaFeature {
+  pub fn matches(&self, env: &Environment) -> MatchResult {
+    match self {
+      MediaFeature::Boolean { name } => match env.feature(name) {
+        Some(EnvValue::Length(Some(value)))
+        | Some(EnvValue::Resolution(Some(value)))
+        | Some(EnvValue::Integer(Some(value)))
+        | Some(EnvValue::Ratio(Some(value))) => (value != 0.0).into(),
+        Some(EnvValue::Ident {
+          value: Some(value),
+          none,
+        }) => (none != Some(value)).into(),
+        _ => MatchResult::Unknown,
+      },
+      MediaFeature::Plain { name, value } => {
+        let (name, operator) = if let Some(name) = name.strip_prefix("min-") {
+          (name, RangeOperator::Ge)
+        } else if let Some(name) = name.strip_prefix("max-") {
+          (name, RangeOperator::Le)
+        } else {
+          (name.as_str(), RangeOperator::Eq)
+        };
+        match env.feature(name) {
+          Some(EnvValue::Ident {
+            value: env_value, ..
+          }) if operator == RangeOperator::Eq => match (env_value, value) {
+            (Some(env_value), MediaValue::Ident(value)) => {
+              env_value.eq_ignore_ascii_case(value).into()
+            }
+            _ => MatchResult::Unknown,
+          },
+          Some(env_value) => compare_feature(env, &env_value, operator, value),
+          None => MatchResult::Unknown,
+        }
+      }
+      MediaFeature::Range {
+        name,
+        operator,
+        value,
+      } => match env.feature(name) {
+        Some(env_value) => compare_feature(env, &env_value, *operator, value),
+        None => MatchResult::Unknown,
+      },
+      MediaFeature::Interval {
+        name,
+        start,
+        start_operator,
+        end_operator,
+        end,
+      } => match env.feature(name) {
+        // `start < width` is `width > start`
+        Some(env_value) => compare_feature(env, &env_value, start_operator.flip(), start)
+          .and(compare_feature(env, &env_value, *end_operator, end)),
+        None => MatchResult::Unknown,
+      },
+    }
+  }
+}
+
+/// Evaluates `<feature> <operator> <value>`.
+fn compare_feature(
+  env: &Environment,
+  env_value: &EnvValue,
+  operator: RangeOperator,
+  value: &MediaValue,
+) -> MatchResult {
+  let (env_value, value) = match env_value {
+    EnvValue::Length(env_value) => (*env_value, env.length(value)),
+    EnvValue::Resolution(env_value) => (*env_value, resolution(value)),
+    EnvValue::Ratio(env_value) => (*env_value, ratio(value)),
+    EnvValue::Integer(env_value) => match value {
+      MediaValue::Number(number) => (*env_value, Some(*number)),
+      _ => (*env_value, None),
+    },
+    EnvValue::Ident { .. } => return MatchResult::Unknown,
+  };
+  match (env_value, value) {
+    (Some(env_value), Some(value)) => compare(env_value, operator, value).into(),
+    _ => MatchResult::Unknown,
+  }
+}
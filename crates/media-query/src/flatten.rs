@@ -0,0 +1,61 @@
+use crate::ast::MediaQueryList;
+use crate::evaluate::{Environment, MatchResult};
+use node::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Resolves every `@media` at-rule of the tree for a fixed environment.
+///
+/// The children of matching at-rules are inlined into their parent and
+/// at-rules that can never match are removed. At-rules whose result is
+/// unknown are kept, without the queries that are known not to match.
+/// At-rules with params that can not be parsed are left untouched.
+pub fn flatten(root: &Rc<RefCell<Node>>, env: &Environment) {
+  let children = match root.borrow().as_shared().get_nodes() {
+    Some(children) => children,
+    None => return,
+  };
+
+  let mut nodes = Vec::with_capacity(children.len());
+  for child in children {
+    flatten(&child, env);
+
+    let list = match &*child.borrow() {
+      Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("media") => {
+        crate::parse(&at_rule.params).ok()
+      }
+      _ => None,
+    };
+    let list = match list {
+      Some(list) => list,
+      None => {
+        nodes.push(child);
+        continue;
+      }
+    };
+
+    match list.matches(env) {
+      MatchResult::True => nodes.extend(child.borrow_mut().take_children()),
+      MatchResult::False => {}
+      MatchResult::Unknown => {
+        let queries: Vec<_> = list
+          .queries
+          .into_iter()
+          .filter(|query| query.matches(env) != MatchResult::False)
+          .collect();
+        if let Some(at_rule) = child.borrow_mut().as_at_rule_mut() {
+          let params = MediaQueryList { queries }.to_string();
+          if params != at_rule.params {
+            at_rule.params = params;
+            at_rule.raws.params = None;
+          }
+        }
+        nodes.push(child);
+      }
+    }
+  }
+
+  if let Some(children) = root.borrow_mut().as_shared_mut().get_nodes_mut() {
+    *children = nodes;
+  }
+}
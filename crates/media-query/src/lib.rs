@@ -0,0 +1,30 @@
+mod ast;
+mod evaluate;
+mod flatten;
+mod parser;
+mod plugin;
+mod sort;
+
+pub use crate::ast::*;
+pub use crate::evaluate::{Environment, MatchResult};
+pub use crate::flatten::flatten;
+pub use crate::parser::ParseError;
+pub use crate::plugin::MediaFlatten;
+pub use crate::sort::{Options, Order, SortMediaQueries};
+
+use crate::parser::Parser;
+
+/// Parses the params of `@media` into a [`MediaQueryList`].
+pub fn parse(params: &str) -> Result<MediaQueryList, ParseError> {
+  Parser::new(params).parse_query_list()
+}
+
+/// Parses a standalone `<media-condition>`, like `(width >= 600px) or (hover)`.
+pub fn parse_condition(input: &str) -> Result<MediaCondition, ParseError> {
+  let mut parser = Parser::new(input);
+  let condition = parser.parse_condition(true)?;
+  match parser.peek() {
+    Some(_) => Err(parser.error("Unexpected token")),
+    None => Ok(condition),
+  }
+}
@@ -0,0 +1,512 @@
+use crate::ast::*;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+  pub reason: String,
+  /// Byte offset in the parsed params.
+  pub offset: usize,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} at {}", self.reason, self.offset)
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind<'a> {
+  Ident(&'a str),
+  Number(f64),
+  Dimension(f64, &'a str),
+  /// `name(`
+  Function(&'a str),
+  OpenParen,
+  CloseParen,
+  Comma,
+  Colon,
+  Slash,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  Eq,
+  /// Any other character, or a string.
+  Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Token<'a> {
+  pub kind: TokenKind<'a>,
+  pub start: usize,
+  pub end: usize,
+}
+
+/// Splits params into tokens, dropping whitespace and comments.
+pub(crate) fn tokenize(input: &str) -> Vec<Token<'_>> {
+  let bytes = input.as_bytes();
+  let mut tokens = vec![];
+  let mut pos = 0;
+  while pos < bytes.len() {
+    let start = pos;
+    let byte = bytes[pos];
+    let kind = match byte {
+      b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' => {
+        pos += 1;
+        continue;
+      }
+      b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+        pos = match input[pos + 2..].find("*/") {
+          Some(end) => pos + end + 4,
+          None => bytes.len(),
+        };
+        continue;
+      }
+      b'(' => {
+        pos += 1;
+        TokenKind::OpenParen
+      }
+      b')' => {
+        pos += 1;
+        TokenKind::CloseParen
+      }
+      b',' => {
+        pos += 1;
+        TokenKind::Comma
+      }
+      b':' => {
+        pos += 1;
+        TokenKind::Colon
+      }
+      b'/' => {
+        pos += 1;
+        TokenKind::Slash
+      }
+      b'<' | b'>' => {
+        pos += 1;
+        let equal = bytes.get(pos) == Some(&b'=');
+        if equal {
+          pos += 1;
+        }
+        match (byte, equal) {
+          (b'<', false) => TokenKind::Lt,
+          (b'<', true) => TokenKind::Le,
+          (_, false) => TokenKind::Gt,
+          (_, true) => TokenKind::Ge,
+        }
+      }
+      b'=' => {
+        pos += 1;
+        TokenKind::Eq
+      }
+      b'"' | b'\'' => {
+        pos += 1;
+        while pos < bytes.len() && bytes[pos] != byte {
+          pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+        }
+        pos = (pos + 1).min(bytes.len());
+        TokenKind::Other
+      }
+      _ if starts_number(bytes, pos) => {
+        pos = consume_number(bytes, pos);
+        let number = input[start..pos].parse::<f64>().unwrap_or(0.0);
+        if bytes.get(pos) == Some(&b'%') {
+          pos += 1;
+          TokenKind::Dimension(number, "%")
+        } else if starts_ident(bytes, pos) {
+          let unit_start = pos;
+          pos = consume_ident(bytes, pos);
+          TokenKind::Dimension(number, &input[unit_start..pos])
+        } else {
+          TokenKind::Number(number)
+        }
+      }
+      _ if starts_ident(bytes, pos) => {
+        pos = consume_ident(bytes, pos);
+        if bytes.get(pos) == Some(&b'(') {
+          pos += 1;
+          TokenKind::Function(&input[start..pos - 1])
+        } else {
+          TokenKind::Ident(&input[start..pos])
+        }
+      }
+      _ => {
+        pos += input[pos..].chars().next().map_or(1, char::len_utf8);
+        TokenKind::Other
+      }
+    };
+    tokens.push(Token {
+      kind,
+      start,
+      end: pos,
+    });
+  }
+  tokens
+}
+
+fn starts_number(bytes: &[u8], pos: usize) -> bool {
+  let digit_at = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+  match bytes[pos] {
+    b'0'..=b'9' => true,
+    b'.' => digit_at(pos + 1),
+    b'+' | b'-' => digit_at(pos + 1) || (bytes.get(pos + 1) == Some(&b'.') && digit_at(pos + 2)),
+    _ => false,
+  }
+}
+
+fn consume_number(bytes: &[u8], mut pos: usize) -> usize {
+  if matches!(bytes[pos], b'+' | b'-') {
+    pos += 1;
+  }
+  while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+    pos += 1;
+  }
+  if bytes.get(pos) == Some(&b'.') && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit) {
+    pos += 1;
+    while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+      pos += 1;
+    }
+  }
+  if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+    let mut exponent = pos + 1;
+    if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+      exponent += 1;
+    }
+    if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+      pos = exponent;
+      while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+        pos += 1;
+      }
+    }
+  }
+  pos
+}
+
+fn is_name_start(byte: u8) -> bool {
+  byte.is_ascii_alphabetic() || byte == b'_' || byte >= 0x80
+}
+
+fn starts_ident(bytes: &[u8], pos: usize) -> bool {
+  match bytes.get(pos) {
+    Some(b'-') => match bytes.get(pos + 1) {
+      Some(b'-') | Some(b'\\') => true,
+      Some(&byte) => is_name_start(byte),
+      None => false,
+    },
+    Some(b'\\') => true,
+    Some(&byte) => is_name_start(byte),
+    None => false,
+  }
+}
+
+fn consume_ident(bytes: &[u8], mut pos: usize) -> usize {
+  while pos < bytes.len() {
+    match bytes[pos] {
+      b'\\' => pos += 2,
+      byte if is_name_start(byte) || byte.is_ascii_digit() || byte == b'-' => pos += 1,
+      _ => break,
+    }
+  }
+  pos.min(bytes.len())
+}
+
+pub(crate) struct Parser<'a> {
+  input: &'a str,
+  tokens: Vec<Token<'a>>,
+  pos: usize,
+}
+
+impl<'a> Parser<'a> {
+  pub(crate) fn new(input: &'a str) -> Self {
+    Self {
+      input,
+      tokens: tokenize(input),
+      pos: 0,
+    }
+  }
+
+  pub(crate) fn parse_query_list(&mut self) -> Result<MediaQueryList, ParseError> {
+    let mut queries = vec![];
+    if self.tokens.is_empty() {
+      return Ok(MediaQueryList { queries });
+    }
+    loop {
+      queries.push(self.parse_query()?);
+      match self.peek() {
+        None => break,
+        Some(TokenKind::Comma) => self.pos += 1,
+        Some(_) => return Err(self.error("Expected a comma")),
+      }
+    }
+    Ok(MediaQueryList { queries })
+  }
+
+  fn parse_query(&mut self) -> Result<MediaQuery, ParseError> {
+    let mut query = MediaQuery::default();
+    match self.peek() {
+      Some(TokenKind::Ident(ident)) => {
+        let keyword = ident.to_ascii_lowercase();
+        if keyword == "not" || keyword == "only" {
+          // `not (color)` is a condition, `not screen` qualifies a media type.
+          if let Some(TokenKind::Ident(_)) = self.peek_at(1) {
+            query.qualifier = Some(if keyword == "not" {
+              Qualifier::Not
+            } else {
+              Qualifier::Only
+            });
+            self.pos += 1;
+          } else if keyword == "only" {
+            return Err(self.error("Expected a media type"));
+          } else {
+            query.condition = Some(self.parse_condition(true)?);
+            return Ok(query);
+          }
+        }
+        let media_type = self.expect_ident()?.to_ascii_lowercase();
+        if matches!(media_type.as_str(), "not" | "only" | "and" | "or" | "layer") {
+          return Err(self.error(&format!("Invalid media type {}", media_type)));
+        }
+        query.media_type = Some(media_type);
+        if self.eat_keyword("and") {
+          query.condition = Some(self.parse_condition(false)?);
+        }
+      }
+      _ => query.condition = Some(self.parse_condition(true)?),
+    }
+    Ok(query)
+  }
+
+  /// Parses `<media-condition>`, or `<media-condition-without-or>` if `allow_or` is `false`.
+  pub(crate) fn parse_condition(&mut self, allow_or: bool) -> Result<MediaCondition, ParseError> {
+    if self.eat_keyword("not") {
+      return Ok(MediaCondition::Not(Box::new(self.parse_in_parens()?)));
+    }
+    let first = self.parse_in_parens()?;
+    let keyword = match self.peek() {
+      Some(TokenKind::Ident(ident)) if ident.eq_ignore_ascii_case("and") => "and",
+      Some(TokenKind::Ident(ident)) if allow_or && ident.eq_ignore_ascii_case("or") => "or",
+      _ => return Ok(first),
+    };
+    let mut conditions = vec![first];
+    while self.eat_keyword(keyword) {
+      conditions.push(self.parse_in_parens()?);
+    }
+    Ok(if keyword == "and" {
+      MediaCondition::And(conditions)
+    } else {
+      MediaCondition::Or(conditions)
+    })
+  }
+
+  /// Parses `<media-in-parens>`.
+  fn parse_in_parens(&mut self) -> Result<MediaCondition, ParseError> {
+    let start = self.pos;
+    match self.peek() {
+      Some(TokenKind::OpenParen) => {
+        self.pos += 1;
+        let parsed = match self.peek() {
+          Some(TokenKind::OpenParen) => self.parse_condition(true),
+          Some(TokenKind::Ident(ident)) if ident.eq_ignore_ascii_case("not") => {
+            self.parse_condition(true)
+          }
+          _ => self.parse_feature().map(MediaCondition::Feature),
+        };
+        match parsed {
+          Ok(condition) if self.peek() == Some(&TokenKind::CloseParen) => {
+            self.pos += 1;
+            Ok(condition)
+          }
+          _ => {
+            self.pos = start;
+            self.parse_general_enclosed()
+          }
+        }
+      }
+      Some(TokenKind::Function(_)) => self.parse_general_enclosed(),
+      _ => Err(self.error("Expected (")),
+    }
+  }
+
+  /// Parses `<general-enclosed>`, a balanced block kept verbatim.
+  fn parse_general_enclosed(&mut self) -> Result<MediaCondition, ParseError> {
+    let start = self.tokens[self.pos].start;
+    let mut depth = 0;
+    while let Some(token) = self.tokens.get(self.pos) {
+      self.pos += 1;
+      match token.kind {
+        TokenKind::OpenParen | TokenKind::Function(_) => depth += 1,
+        TokenKind::CloseParen => {
+          depth -= 1;
+          if depth == 0 {
+            return Ok(MediaCondition::GeneralEnclosed(
+              self.input[start..token.end].to_string(),
+            ));
+          }
+        }
+        _ => {}
+      }
+    }
+    Err(ParseError {
+      reason: "Unclosed bracket".to_string(),
+      offset: start,
+    })
+  }
+
+  /// Parses `<media-feature>`, without the parentheses.
+  fn parse_feature(&mut self) -> Result<MediaFeature, ParseError> {
+    if let Some(TokenKind::Ident(name)) = self.peek() {
+      let name = name.to_ascii_lowercase();
+      match self.peek_at(1) {
+        Some(TokenKind::CloseParen) => {
+          self.pos += 1;
+          return Ok(MediaFeature::Boolean { name });
+        }
+        Some(TokenKind::Colon) => {
+          self.pos += 2;
+          let value = self.parse_value()?;
+          return Ok(MediaFeature::Plain { name, value });
+        }
+        Some(kind) if range_operator(kind).is_some() => {
+          let operator = range_operator(kind).unwrap();
+          self.pos += 2;
+          let value = self.parse_value()?;
+          return Ok(MediaFeature::Range {
+            name,
+            operator,
+            value,
+          });
+        }
+        _ => {}
+      }
+    }
+
+    // `<mf-value> <mf-comparison> <mf-name> [<mf-comparison> <mf-value>]?`
+    let start = self.parse_value()?;
+    let start_operator = self.expect_range_operator()?;
+    let name = self.expect_ident()?.to_ascii_lowercase();
+    match self.peek().and_then(range_operator) {
+      Some(end_operator) => {
+        self.pos += 1;
+        let end = self.parse_value()?;
+        let ascending = matches!(start_operator, RangeOperator::Lt | RangeOperator::Le)
+          && matches!(end_operator, RangeOperator::Lt | RangeOperator::Le);
+        let descending = matches!(start_operator, RangeOperator::Gt | RangeOperator::Ge)
+          && matches!(end_operator, RangeOperator::Gt | RangeOperator::Ge);
+        if !ascending && !descending {
+          return Err(self.error("Range operators must point in the same direction"));
+        }
+        Ok(MediaFeature::Interval {
+          name,
+          start,
+          start_operator,
+          end_operator,
+          end,
+        })
+      }
+      None => Ok(MediaFeature::Range {
+        name,
+        operator: start_operator.flip(),
+        value: start,
+      }),
+    }
+  }
+
+  fn parse_value(&mut self) -> Result<MediaValue, ParseError> {
+    let token = match self.tokens.get(self.pos) {
+      Some(token) => token.clone(),
+      None => return Err(self.error("Expected a value")),
+    };
+    self.pos += 1;
+    match token.kind {
+      TokenKind::Number(numerator) => {
+        if self.peek() == Some(&TokenKind::Slash) {
+          if let Some(TokenKind::Number(denominator)) = self.peek_at(1) {
+            let denominator = *denominator;
+            self.pos += 2;
+            return Ok(MediaValue::Ratio(numerator, denominator));
+          }
+        }
+        Ok(MediaValue::Number(numerator))
+      }
+      TokenKind::Dimension(number, unit) => {
+        Ok(MediaValue::Dimension(number, unit.to_ascii_lowercase()))
+      }
+      TokenKind::Ident(ident) => Ok(MediaValue::Ident(ident.to_string())),
+      TokenKind::Function(_) => {
+        self.pos -= 1;
+        match self.parse_general_enclosed()? {
+          MediaCondition::GeneralEnclosed(raw) => Ok(MediaValue::Raw(raw)),
+          _ => unreachable!(),
+        }
+      }
+      _ => {
+        self.pos -= 1;
+        Err(self.error("Expected a value"))
+      }
+    }
+  }
+
+  fn expect_range_operator(&mut self) -> Result<RangeOperator, ParseError> {
+    match self.peek().and_then(range_operator) {
+      Some(operator) => {
+        self.pos += 1;
+        Ok(operator)
+      }
+      None => Err(self.error("Expected a comparison")),
+    }
+  }
+
+  fn expect_ident(&mut self) -> Result<&'a str, ParseError> {
+    match self.tokens.get(self.pos).map(|token| &token.kind) {
+      Some(TokenKind::Ident(ident)) => {
+        let ident = *ident;
+        self.pos += 1;
+        Ok(ident)
+      }
+      _ => Err(self.error("Expected an identifier")),
+    }
+  }
+
+  fn eat_keyword(&mut self, keyword: &str) -> bool {
+    match self.peek() {
+      Some(TokenKind::Ident(ident)) if ident.eq_ignore_ascii_case(keyword) => {
+        self.pos += 1;
+        true
+      }
+      _ => false,
+    }
+  }
+
+  #[inline]
+  pub(crate) fn peek(&self) -> Option<&TokenKind<'a>> {
+    self.peek_at(0)
+  }
+
+  #[inline]
+  fn peek_at(&self, n: usize) -> Option<&TokenKind<'a>> {
+    self.tokens.get(self.pos + n).map(|token| &token.kind)
+  }
+
+  pub(crate) fn error(&self, reason: &str) -> ParseError {
+    ParseError {
+      reason: reason.to_string(),
+      offset: self
+        .tokens
+        .get(self.pos)
+        .map_or(self.input.len(), |token| token.start),
+    }
+  }
+}
+
+fn range_operator(kind: &TokenKind) -> Option<RangeOperator> {
+  match kind {
+    TokenKind::Lt => Some(RangeOperator::Lt),
+    TokenKind::Le => Some(RangeOperator::Le),
+    TokenKind::Gt => Some(RangeOperator::Gt),
+    TokenKind::Ge => Some(RangeOperator::Ge),
+    TokenKind::Eq => Some(RangeOperator::Eq),
+    _ => None,
+  }
+}
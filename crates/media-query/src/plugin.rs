@@ -0,0 +1,22 @@
+use crate::evaluate::Environment;
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Runs [`flatten`](crate::flatten) for a fixed environment.
+#[derive(Debug, Clone, Default)]
+pub struct MediaFlatten {
+  pub env: Environment,
+}
+
+impl Plugin for MediaFlatten {
+  fn name(&self) -> &str {
+    "media-flatten"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    crate::flatten(root, &self.env);
+    Ok(())
+  }
+}
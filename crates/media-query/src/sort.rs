@@ -0,0 +1,336 @@
+use crate::ast::*;
+use crate::evaluate::Environment;
+use node::tree::replace_children;
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/// The order of the `@media` blocks at the end of the stylesheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Order {
+  /// `min-width` queries ascending, then `max-width` queries descending.
+  #[default]
+  MobileFirst,
+  /// `max-width` queries descending, then `min-width` queries ascending.
+  DesktopFirst,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  pub order: Order,
+}
+
+/// Gathers the `@media` blocks of the root with the same query into one,
+/// moves them to the end of the stylesheet and sorts them by
+/// [`Options::order`]. Queries without a width, like `print`, come last.
+///
+/// Moving a block after the rules it came before changes the cascade when
+/// both set a property of the same selector for the same widths, which is
+/// reported as a warning.
+#[derive(Debug, Clone, Default)]
+pub struct SortMediaQueries {
+  pub options: Options,
+}
+
+/// The viewport widths in `px` a media query applies to, unbounded when
+/// `None`.
+#[derive(Debug, Clone, Copy, Default)]
+struct Widths {
+  min: Option<f64>,
+  max: Option<f64>,
+}
+
+impl Widths {
+  fn of_query(query: &MediaQuery) -> Self {
+    let mut widths = Widths::default();
+    if query.qualifier != Some(Qualifier::Not) {
+      if let Some(condition) = &query.condition {
+        widths.restrict(condition);
+      }
+    }
+    widths
+  }
+
+  /// Narrows the widths to the ones `condition` applies to, as far as it is
+  /// known.
+  fn restrict(&mut self, condition: &MediaCondition) {
+    let env = Environment::default();
+    let length = |value: &MediaValue| env.length(value);
+    match condition {
+      MediaCondition::And(conditions) => {
+        for condition in conditions {
+          self.restrict(condition);
+        }
+      }
+      MediaCondition::Feature(feature) => match feature {
+        MediaFeature::Plain { name, value } => match (name.as_str(), length(value)) {
+          ("min-width", Some(value)) => self.raise(value),
+          ("max-width", Some(value)) => self.lower(value),
+          ("width", Some(value)) => {
+            self.raise(value);
+            self.lower(value);
+          }
+          _ => {}
+        },
+        MediaFeature::Range {
+          name,
+          operator,
+          value,
+        } if name == "width" => {
+          if let Some(value) = length(value) {
+            match operator {
+              RangeOperator::Gt | RangeOperator::Ge => self.raise(value),
+              RangeOperator::Lt | RangeOperator::Le => self.lower(value),
+              RangeOperator::Eq => {
+                self.raise(value);
+                self.lower(value);
+              }
+            }
+          }
+        }
+        MediaFeature::Interval {
+          name,
+          start,
+          start_operator,
+          end,
+          ..
+        } if name == "width" => {
+          let (min, max) = match start_operator {
+            RangeOperator::Lt | RangeOperator::Le => (start, end),
+            _ => (end, start),
+          };
+          if let Some(min) = length(min) {
+            self.raise(min);
+          }
+          if let Some(max) = length(max) {
+            self.lower(max);
+          }
+        }
+        _ => {}
+      },
+      _ => {}
+    }
+  }
+
+  fn raise(&mut self, min: f64) {
+    self.min = Some(self.min.map_or(min, |value| value.max(min)));
+  }
+
+  fn lower(&mut self, max: f64) {
+    self.max = Some(self.max.map_or(max, |value| value.min(max)));
+  }
+
+  fn overlaps(&self, other: &Widths) -> bool {
+    let below = |max: Option<f64>, min: Option<f64>| matches!((max, min), (Some(max), Some(min)) if max < min);
+    !below(self.max, other.min) && !below(other.max, self.min)
+  }
+}
+
+/// A top-level node of the stylesheet, for the cascade warnings.
+struct Unit<'a> {
+  node: Rc<RefCell<Node<'a>>>,
+  /// The widths of the queries of an `@media` block, a single unbounded one
+  /// for the other nodes.
+  widths: Vec<Widths>,
+  /// The selectors of the rules in the node, with the properties they set.
+  rules: Vec<(String, String)>,
+}
+
+/// The selector with the spaces around its commas removed.
+fn normalize(selector: &str) -> String {
+  selector
+    .split(',')
+    .map(str::trim)
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Collects the properties set by the rules of `node` and its descendants.
+fn collect_rules(node: &Node, rules: &mut Vec<(String, String)>) {
+  if let Node::Rule(rule) = node {
+    let selector = normalize(&rule.selector);
+    for child in node.as_shared().get_nodes().unwrap_or_default() {
+      if let Some(decl) = child.borrow().as_decl() {
+        rules.push((selector.clone(), decl.prop.to_ascii_lowercase()));
+      }
+    }
+  }
+  for child in node.as_shared().get_nodes().unwrap_or_default() {
+    collect_rules(&child.borrow(), rules);
+  }
+}
+
+impl<'a> Unit<'a> {
+  fn new(node: &Rc<RefCell<Node<'a>>>, list: Option<&MediaQueryList>) -> Self {
+    let mut rules = Vec::new();
+    collect_rules(&node.borrow(), &mut rules);
+    let widths = match list {
+      Some(list) => list.queries.iter().map(Widths::of_query).collect(),
+      None => vec![Widths::default()],
+    };
+    Unit {
+      node: node.clone(),
+      widths,
+      rules,
+    }
+  }
+
+  /// The selector and property both units set, for overlapping widths.
+  fn conflict(&self, other: &Unit) -> Option<&(String, String)> {
+    let overlaps = self
+      .widths
+      .iter()
+      .any(|a| other.widths.iter().any(|b| a.overlaps(b)));
+    if !overlaps {
+      return None;
+    }
+    self.rules.iter().find(|rule| other.rules.contains(rule))
+  }
+}
+
+impl SortMediaQueries {
+  /// The sort key of the queries, by their first query.
+  fn key(&self, list: Option<&MediaQueryList>) -> (u8, f64, f64) {
+    let widths = match list.and_then(|list| list.queries.first()) {
+      Some(query) => Widths::of_query(query),
+      None => return (2, 0.0, 0.0),
+    };
+    match (self.options.order, widths.min, widths.max) {
+      (Order::MobileFirst, Some(min), max) => (0, min, -max.unwrap_or(f64::INFINITY)),
+      (Order::MobileFirst, None, Some(max)) => (1, -max, 0.0),
+      (Order::DesktopFirst, None, Some(max)) => (0, -max, 0.0),
+      (Order::DesktopFirst, Some(min), max) => (1, min, -max.unwrap_or(f64::INFINITY)),
+      (_, None, None) => (2, 0.0, 0.0),
+    }
+  }
+}
+
+fn compare(a: (u8, f64, f64), b: (u8, f64, f64)) -> Ordering {
+  a.0
+    .cmp(&b.0)
+    .then(a.1.total_cmp(&b.1))
+    .then(a.2.total_cmp(&b.2))
+}
+
+impl Plugin for SortMediaQueries {
+  fn name(&self) -> &str {
+    "sort-media-queries"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    let children = match root.borrow().as_shared().get_nodes() {
+      Some(children) => children,
+      None => return Ok(()),
+    };
+
+    // The `@media` blocks by query, in the order of their first block.
+    let mut groups: Vec<(String, Option<MediaQueryList>, Vec<usize>)> = Vec::new();
+    let mut others = Vec::new();
+    for (i, child) in children.iter().enumerate() {
+      let params = match &*child.borrow() {
+        Node::AtRule(at_rule)
+          if at_rule.name.eq_ignore_ascii_case("media") && at_rule.nodes.is_some() =>
+        {
+          Some(at_rule.params.clone())
+        }
+        _ => None,
+      };
+      let params = match params {
+        Some(params) => params,
+        None => {
+          others.push(i);
+          continue;
+        }
+      };
+      let list = crate::parse(&params).ok();
+      let query = match &list {
+        Some(list) => list.to_string(),
+        None => params.trim().to_string(),
+      };
+      match groups.iter_mut().find(|(other, ..)| *other == query) {
+        Some((.., members)) => members.push(i),
+        None => groups.push((query, list, vec![i])),
+      }
+    }
+    if groups.is_empty() {
+      return Ok(());
+    }
+    groups.sort_by(|(_, a, _), (_, b, _)| compare(self.key(a.as_ref()), self.key(b.as_ref())));
+
+    // Warns about the nodes that swapped places with a block setting the same
+    // property of the same selector.
+    let mut units: Vec<Option<Unit>> = children.iter().map(|_| None).collect();
+    let mut positions = vec![0; children.len()];
+    for (position, &i) in others.iter().enumerate() {
+      units[i] = Some(Unit::new(&children[i], None));
+      positions[i] = position;
+    }
+    for (position, (_, list, members)) in groups.iter().enumerate() {
+      for &i in members {
+        units[i] = Some(Unit::new(&children[i], list.as_ref()));
+        positions[i] = others.len() + position;
+      }
+    }
+    let units: Vec<_> = units.into_iter().flatten().collect();
+    for x in 0..units.len() {
+      for y in x + 1..units.len() {
+        if positions[x] <= positions[y] {
+          continue;
+        }
+        if let Some((selector, prop)) = units[x].conflict(&units[y]) {
+          let media = match groups.iter().any(|(.., members)| members.contains(&x)) {
+            true => &units[x].node,
+            false => &units[y].node,
+          };
+          let params = media
+            .borrow()
+            .as_at_rule()
+            .map(|at_rule| at_rule.params.clone())
+            .unwrap_or_default();
+          result.warn(
+            &format!(
+              "Moving `@media {}` changes the cascade of `{}` in `{}`",
+              params, prop, selector
+            ),
+            Some(&media.borrow()),
+          );
+        }
+      }
+    }
+
+    let mut nodes: Vec<_> = others.iter().map(|&i| children[i].clone()).collect();
+    for (.., members) in &groups {
+      let head = children[members[0]].clone();
+      for &i in &members[1..] {
+        let moved = children[i]
+          .borrow()
+          .as_shared()
+          .get_nodes()
+          .unwrap_or_default();
+        let semicolon = children[i]
+          .borrow()
+          .as_at_rule()
+          .and_then(|at_rule| at_rule.raws.semicolon);
+        if let Some(at_rule) = head.borrow_mut().as_at_rule_mut() {
+          at_rule.raws.semicolon = semicolon;
+        }
+        if let Some(children) = head.borrow_mut().as_shared_mut().get_nodes_mut() {
+          children.extend(moved);
+        }
+      }
+      nodes.push(head);
+    }
+
+    replace_children(root, nodes);
+    Ok(())
+  }
+}
@@ -0,0 +1,88 @@
+use media_query::*;
+
+fn screen(width: f64, height: f64) -> Environment {
+  Environment {
+    media_type: Some("screen".into()),
+    width: Some(width),
+    height: Some(height),
+    ..Default::default()
+  }
+}
+
+fn matches(params: &str, env: &Environment) -> MatchResult {
+  parse(params).unwrap().matches(env)
+}
+
+#[test]
+fn matches_media_types() {
+  let env = screen(1024.0, 768.0);
+  assert_eq!(matches("screen", &env), MatchResult::True);
+  assert_eq!(matches("all", &env), MatchResult::True);
+  assert_eq!(matches("print", &env), MatchResult::False);
+  assert_eq!(matches("not print", &env), MatchResult::True);
+  assert_eq!(matches("only screen", &env), MatchResult::True);
+  assert_eq!(matches("print, screen", &env), MatchResult::True);
+  assert_eq!(
+    matches("print", &Environment::default()),
+    MatchResult::Unknown
+  );
+}
+
+#[test]
+fn matches_ranges_in_both_syntaxes() {
+  let env = screen(1024.0, 768.0);
+  assert_eq!(matches("(min-width: 768px)", &env), MatchResult::True);
+  assert_eq!(matches("(max-width: 767.98px)", &env), MatchResult::False);
+  assert_eq!(matches("(width >= 64em)", &env), MatchResult::True);
+  assert_eq!(matches("(width > 64em)", &env), MatchResult::False);
+  assert_eq!(
+    matches("(400px <= width < 1200px)", &env),
+    MatchResult::True
+  );
+  assert_eq!(matches("(1024px < width)", &env), MatchResult::False);
+  assert_eq!(matches("(min-aspect-ratio: 4/3)", &env), MatchResult::True);
+  assert_eq!(matches("(orientation: landscape)", &env), MatchResult::True);
+  assert_eq!(matches("(width)", &env), MatchResult::True);
+}
+
+#[test]
+fn uses_three_valued_logic() {
+  let env = screen(1024.0, 768.0);
+  assert_eq!(matches("(hover: hover)", &env), MatchResult::Unknown);
+  assert_eq!(
+    matches("print and (hover: hover)", &env),
+    MatchResult::False
+  );
+  assert_eq!(matches("(width > 0) or (hover)", &env), MatchResult::True);
+  assert_eq!(
+    matches("(width < 0) or (hover)", &env),
+    MatchResult::Unknown
+  );
+  assert_eq!(matches("not (hover)", &env), MatchResult::Unknown);
+  assert_eq!(matches("(unknown-feature: 1)", &env), MatchResult::Unknown);
+  assert_eq!(matches("not (width < 500px)", &env), MatchResult::True);
+}
+
+#[test]
+fn matches_discrete_features() {
+  let env = Environment {
+    prefers_reduced_motion: Some("no-preference".into()),
+    prefers_color_scheme: Some("dark".into()),
+    resolution: Some(2.0),
+    ..Default::default()
+  };
+  assert_eq!(
+    matches("(prefers-reduced-motion: reduce)", &env),
+    MatchResult::False
+  );
+  assert_eq!(
+    matches("(prefers-reduced-motion)", &env),
+    MatchResult::False
+  );
+  assert_eq!(
+    matches("(prefers-color-scheme: dark)", &env),
+    MatchResult::True
+  );
+  assert_eq!(matches("(min-resolution: 192dpi)", &env), MatchResult::True);
+  assert_eq!(matches("(resolution > 2x)", &env), MatchResult::False);
+}
@@ -0,0 +1,73 @@
+use media_query::{flatten, Environment};
+use parser::parse::parse;
+use stringify::to_string;
+
+fn run(css: &str, env: &Environment) -> String {
+  let root = parse(css, None).unwrap();
+  flatten(&root, env);
+  let root = root.borrow();
+  to_string(&root)
+}
+
+fn desktop() -> Environment {
+  Environment {
+    media_type: Some("screen".into()),
+    width: Some(1280.0),
+    height: Some(800.0),
+    ..Default::default()
+  }
+}
+
+#[test]
+fn inlines_matching_blocks() {
+  assert_eq!(
+    run(
+      "a{}\n@media (min-width: 768px) {\n  b {}\n  c {}\n}",
+      &desktop()
+    ),
+    "a{}\nb {}\n  c {}"
+  );
+}
+
+#[test]
+fn removes_blocks_that_never_match() {
+  assert_eq!(
+    run("a{}\n@media print { b {} }\nc{}", &desktop()),
+    "a{}\nc{}"
+  );
+}
+
+#[test]
+fn keeps_unknown_blocks_without_false_queries() {
+  assert_eq!(
+    run("@media print, (hover: hover) { a {} }", &desktop()),
+    "@media (hover: hover) { a {} }"
+  );
+  assert_eq!(
+    run("@media (hover: hover) { a {} }", &desktop()),
+    "@media (hover: hover) { a {} }"
+  );
+}
+
+#[test]
+fn flattens_nested_blocks() {
+  let css =
+    "@supports (display: grid) { @media screen { @media (max-width: 600px) { a {} } b {} } }";
+  assert_eq!(run(css, &desktop()), "@supports (display: grid) { b {} }");
+}
+
+#[test]
+fn flattens_bootstrap_for_a_phone() {
+  let css = include_str!("../../../assets/bootstrap.css");
+  let env = Environment {
+    media_type: Some("screen".into()),
+    width: Some(375.0),
+    height: Some(667.0),
+    prefers_reduced_motion: Some("no-preference".into()),
+    ..Default::default()
+  };
+  let result = run(css, &env);
+  assert!(!result.contains("@media"));
+  assert!(!result.contains(".col-md-6"));
+  assert!(result.contains(".col-6"));
+}
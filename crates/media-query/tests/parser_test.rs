@@ -0,0 +1,132 @@
+use media_query::*;
+
+fn roundtrip(params: &str) {
+  assert_eq!(parse(params).unwrap().to_string(), params);
+}
+
+fn px(value: f64) -> MediaValue {
+  MediaValue::Dimension(value, "px".into())
+}
+
+#[test]
+fn parses_media_types() {
+  let list = parse("screen, only print, not tv").unwrap();
+  let types: Vec<_> = list
+    .queries
+    .iter()
+    .map(|query| (query.qualifier, query.media_type.as_deref()))
+    .collect();
+  assert_eq!(
+    types,
+    vec![
+      (None, Some("screen")),
+      (Some(Qualifier::Only), Some("print")),
+      (Some(Qualifier::Not), Some("tv")),
+    ]
+  );
+}
+
+#[test]
+fn parses_plain_and_boolean_features() {
+  let list = parse("screen and (min-width: 768px) and (color)").unwrap();
+  assert_eq!(
+    list.queries[0].condition,
+    Some(MediaCondition::And(vec![
+      MediaCondition::Feature(MediaFeature::Plain {
+        name: "min-width".into(),
+        value: px(768.0),
+      }),
+      MediaCondition::Feature(MediaFeature::Boolean {
+        name: "color".into()
+      }),
+    ]))
+  );
+}
+
+#[test]
+fn parses_range_features() {
+  let list = parse("(400px <= width < 800px), (width >= 600px), (600px < height)").unwrap();
+  let conditions: Vec<_> = list
+    .queries
+    .into_iter()
+    .map(|query| query.condition.unwrap())
+    .collect();
+  assert_eq!(
+    conditions,
+    vec![
+      MediaCondition::Feature(MediaFeature::Interval {
+        name: "width".into(),
+        start: px(400.0),
+        start_operator: RangeOperator::Le,
+        end_operator: RangeOperator::Lt,
+        end: px(800.0),
+      }),
+      MediaCondition::Feature(MediaFeature::Range {
+        name: "width".into(),
+        operator: RangeOperator::Ge,
+        value: px(600.0),
+      }),
+      MediaCondition::Feature(MediaFeature::Range {
+        name: "height".into(),
+        operator: RangeOperator::Gt,
+        value: px(600.0),
+      }),
+    ]
+  );
+}
+
+#[test]
+fn parses_not_and_or() {
+  let list = parse("not ((color) or (hover)), (a: 1) and (not (b))").unwrap();
+  assert!(matches!(
+    &list.queries[0].condition,
+    Some(MediaCondition::Not(inner)) if matches!(**inner, MediaCondition::Or(_))
+  ));
+  assert!(matches!(
+    &list.queries[1].condition,
+    Some(MediaCondition::And(conditions)) if matches!(conditions[1], MediaCondition::Not(_))
+  ));
+}
+
+#[test]
+fn parses_general_enclosed() {
+  let list = parse("(foo: bar baz) or custom(1, 2)").unwrap();
+  assert_eq!(
+    list.queries[0].condition,
+    Some(MediaCondition::Or(vec![
+      MediaCondition::GeneralEnclosed("(foo: bar baz)".into()),
+      MediaCondition::GeneralEnclosed("custom(1, 2)".into()),
+    ]))
+  );
+}
+
+#[test]
+fn serializes_queries() {
+  roundtrip("screen and (min-width: 768px)");
+  roundtrip("only screen and (-webkit-min-device-pixel-ratio: 2), (min-resolution: 192dpi)");
+  roundtrip("(aspect-ratio: 16/9)");
+  roundtrip("not all and (monochrome)");
+  roundtrip("(400px <= width < 800px)");
+  roundtrip("print and ((color) or (hover))");
+  roundtrip("(prefers-reduced-motion: reduce)");
+  roundtrip("(min-width: calc(100px + 2em))");
+  assert_eq!(
+    parse("SCREEN AND (MIN-WIDTH:768PX)").unwrap().to_string(),
+    "screen and (min-width: 768px)"
+  );
+  assert_eq!(
+    parse("(600px > width)").unwrap().to_string(),
+    "(width < 600px)"
+  );
+}
+
+#[test]
+fn reports_errors() {
+  assert!(parse("screen and").is_err());
+  assert!(parse("screen or (color)").is_err());
+  assert!(parse("(color) and (hover) or (pointer)").is_err());
+  assert!(parse("only (color)").is_err());
+  assert!(parse("(min-width: 1px").is_err());
+  assert!(parse("and").is_err());
+  assert!(parse("").unwrap().queries.is_empty());
+}
@@ -0,0 +1,68 @@
+use media_query::{Options, Order, SortMediaQueries};
+use postcss::Processor;
+
+fn sort(order: Order, css: &str) -> (String, Vec<String>) {
+  let result = Processor::run_one(
+    SortMediaQueries {
+      options: Options { order },
+    },
+    css,
+  )
+  .unwrap();
+  let warnings = result.warnings().iter().map(|w| w.text.clone()).collect();
+  (result.css.clone(), warnings)
+}
+
+#[test]
+fn gathers_identical_queries_at_the_end() {
+  assert_eq!(
+    sort(
+      Order::MobileFirst,
+      "@media (min-width: 768px) { a { top: 0 } }\nb { top: 0 }\n@media (min-width:768px) { c { top: 0 } }\nd { top: 0 }"
+    ),
+    (
+      "b { top: 0 }\nd { top: 0 }\n@media (min-width: 768px) { a { top: 0 } c { top: 0 } }".to_string(),
+      vec![]
+    )
+  );
+}
+
+#[test]
+fn sorts_mobile_first() {
+  let css = "@media print { a { top: 0 } }\n@media (max-width: 500px) { a { top: 0 } }\n@media (min-width: 1024px) { b { top: 0 } }\n@media (max-width: 1000px) { a { top: 0 } }\n@media (min-width: 40em) { b { top: 0 } }";
+  assert_eq!(
+    sort(Order::MobileFirst, css).0,
+    "@media (min-width: 40em) { b { top: 0 } }\n@media (min-width: 1024px) { b { top: 0 } }\n@media (max-width: 1000px) { a { top: 0 } }\n@media (max-width: 500px) { a { top: 0 } }\n@media print { a { top: 0 } }"
+  );
+  assert_eq!(
+    sort(Order::DesktopFirst, css).0,
+    "@media (max-width: 1000px) { a { top: 0 } }\n@media (max-width: 500px) { a { top: 0 } }\n@media (min-width: 40em) { b { top: 0 } }\n@media (min-width: 1024px) { b { top: 0 } }\n@media print { a { top: 0 } }"
+  );
+}
+
+#[test]
+fn warns_when_the_cascade_changes() {
+  let (css, warnings) = sort(
+    Order::MobileFirst,
+    "@media (min-width: 768px) { a { color: red } }\na { color: blue; top: 0 }",
+  );
+  assert_eq!(
+    css,
+    "a { color: blue; top: 0 }\n@media (min-width: 768px) { a { color: red } }"
+  );
+  assert_eq!(
+    warnings,
+    ["Moving `@media (min-width: 768px)` changes the cascade of `color` in `a`"]
+  );
+
+  let (_, warnings) = sort(
+    Order::MobileFirst,
+    "@media (max-width: 500px) { a { color: red } }\n@media (min-width: 768px) { a { color: blue } }",
+  );
+  assert!(warnings.is_empty());
+  let (_, warnings) = sort(
+    Order::MobileFirst,
+    "@media (max-width: 800px) { a { color: red } }\n@media (min-width: 768px) { a { color: blue } }",
+  );
+  assert_eq!(warnings.len(), 1);
+}
@@ -0,0 +1,139 @@
+use node::tree::replace_children;
+use node::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+use value_parser::{dimension, Node as ValueNode};
+
+/// The units every browser knows.
+const UNITS: &[&str] = &[
+  "", "%", "px", "em", "ex", "pt", "pc", "in", "cm", "mm", "s", "ms", "deg",
+];
+
+/// The keywords every browser knows.
+const KEYWORDS: &[&str] = &[
+  "absolute",
+  "auto",
+  "black",
+  "block",
+  "blue",
+  "bold",
+  "bolder",
+  "both",
+  "bottom",
+  "center",
+  "dashed",
+  "dotted",
+  "double",
+  "fixed",
+  "gray",
+  "green",
+  "hidden",
+  "inherit",
+  "inline",
+  "inline-block",
+  "italic",
+  "justify",
+  "left",
+  "lighter",
+  "lowercase",
+  "middle",
+  "no-repeat",
+  "none",
+  "normal",
+  "nowrap",
+  "pointer",
+  "red",
+  "relative",
+  "repeat",
+  "repeat-x",
+  "repeat-y",
+  "right",
+  "scroll",
+  "serif",
+  "sans-serif",
+  "monospace",
+  "solid",
+  "static",
+  "table",
+  "table-cell",
+  "top",
+  "transparent",
+  "underline",
+  "uppercase",
+  "visible",
+  "white",
+];
+
+/// Returns `true` if every browser understands the value, so that it always
+/// overrides the declarations of its property before it.
+fn is_plain(value: &str) -> bool {
+  value_parser::parse(value)
+    .nodes
+    .iter()
+    .all(|node| match node {
+      ValueNode::Space(_) | ValueNode::String { .. } => true,
+      ValueNode::Div(div) => *div == ',' || *div == '/',
+      ValueNode::Word(word) => {
+        let lower = word.to_ascii_lowercase();
+        let hex = lower.strip_prefix('#').is_some_and(|hex| {
+          (hex.len() == 3 || hex.len() == 6) && hex.bytes().all(|b| b.is_ascii_hexdigit())
+        });
+        hex
+          || KEYWORDS.contains(&lower.as_str())
+          || dimension(&lower).is_some_and(|d| UNITS.contains(&d.unit))
+      }
+      _ => false,
+    })
+}
+
+/// Removes the declarations of a block that are overridden by a later
+/// declaration of the same property, or by an earlier `!important` one.
+///
+/// A declaration is only removed if the one overriding it has the same value
+/// or is understood by every browser, so fallbacks like
+/// `display: flex; display: grid` are kept.
+pub fn dedupe(parent: &Rc<RefCell<Node>>) {
+  let children = match parent.borrow().as_shared().get_nodes() {
+    Some(children) => children,
+    None => return,
+  };
+  let decls: Vec<_> = children
+    .iter()
+    .enumerate()
+    .filter_map(|(i, child)| match &*child.borrow() {
+      Node::Decl(decl) => Some((i, decl.prop.clone(), decl.value.clone(), decl.important)),
+      _ => None,
+    })
+    .collect();
+  let mut removed = vec![false; children.len()];
+  for (a, (i, prop, value, important)) in decls.iter().enumerate().rev() {
+    // The next declarations of the same property decide.
+    for (j, other, other_value, other_important) in &decls[a + 1..] {
+      if removed[*i] {
+        break;
+      }
+      if removed[*j] || other != prop {
+        continue;
+      }
+      if *important && !other_important {
+        if other_value == value || is_plain(value) {
+          removed[*j] = true;
+          continue;
+        }
+      } else if value == other_value || is_plain(other_value) {
+        removed[*i] = true;
+      }
+      break;
+    }
+  }
+  if !removed.contains(&true) {
+    return;
+  }
+  let nodes = children
+    .into_iter()
+    .zip(removed)
+    .filter(|(_, removed)| !removed)
+    .map(|(child, _)| child)
+    .collect();
+  replace_children(parent, nodes);
+}
@@ -0,0 +1,5 @@
+mod dedupe;
+mod plugin;
+
+pub use crate::dedupe::dedupe;
+pub use crate::plugin::MergeRules;
@@ -0,0 +1,226 @@
+use crate::dedupe::dedupe;
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use selector::{CombinatorKind, PseudoArgument, SimpleSelector};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Merges rules and at-rules like cssnano: adjacent rules with the same
+/// selector, adjacent rules with the same declarations into a selector list
+/// and adjacent `@media` or `@supports` blocks with the same params. The
+/// overridden declarations of each rule are removed, see
+/// [`dedupe`](crate::dedupe).
+///
+/// Only adjacent nodes are merged, so that no declaration moves across
+/// another rule. Rules only join a selector list if every browser knows
+/// their selectors, as a browser drops a list with a selector it does not
+/// know.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeRules;
+
+/// How a node merges into the node before it.
+enum Merge {
+  Children,
+  Selector(String),
+  AtRule,
+}
+
+/// The selector with the spaces around its commas removed.
+fn normalize(selector: &str) -> String {
+  selector
+    .split(',')
+    .map(str::trim)
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Returns `true` if the rules have the same declarations, and nothing else.
+fn same_declarations(a: &Node, b: &Node) -> bool {
+  let decls = |node: &Node| {
+    node.as_shared().get_nodes().and_then(|children| {
+      children
+        .iter()
+        .map(|child| match &*child.borrow() {
+          Node::Decl(decl) => Some((decl.prop.clone(), decl.value.clone(), decl.important)),
+          _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+    })
+  };
+  matches!((decls(a), decls(b)), (Some(a), Some(b)) if !a.is_empty() && a == b)
+}
+
+/// The pseudo-classes of CSS 2 and Selectors Level 3, which every browser
+/// knows.
+const KNOWN_PSEUDO_CLASSES: &[&str] = &[
+  "active",
+  "checked",
+  "disabled",
+  "empty",
+  "enabled",
+  "first-child",
+  "first-of-type",
+  "focus",
+  "hover",
+  "lang",
+  "last-child",
+  "last-of-type",
+  "link",
+  "not",
+  "nth-child",
+  "nth-last-child",
+  "nth-last-of-type",
+  "nth-of-type",
+  "only-child",
+  "only-of-type",
+  "root",
+  "target",
+  "visited",
+];
+
+/// The pseudo-elements of CSS 2.
+const KNOWN_PSEUDO_ELEMENTS: &[&str] = &["after", "before", "first-letter", "first-line"];
+
+/// Returns `true` if every browser knows the selector: it only uses the
+/// pseudo-classes and pseudo-elements above, `:not()` with a simple
+/// selector, and no Selectors Level 4 syntax like `:nth-child(2n of .a)`,
+/// `[a=b i]`, `||` or `&`.
+fn is_known(selector: &str) -> bool {
+  let list = match selector::parse(selector) {
+    Ok(list) => list,
+    Err(_) => return false,
+  };
+  let mut known = true;
+  for complex in &list.selectors {
+    known &= complex.compounds.iter().all(|compound| {
+      compound
+        .combinator
+        .as_ref()
+        .is_none_or(|combinator| combinator.kind != CombinatorKind::Column)
+    });
+    complex.walk(&mut |simple| {
+      known &= match simple {
+        SimpleSelector::Nesting => false,
+        SimpleSelector::Attribute(attribute) => attribute.modifier.is_none(),
+        SimpleSelector::PseudoClass(pseudo) => {
+          let name = pseudo.lower_name();
+          let argument = match &pseudo.argument {
+            Some(PseudoArgument::Selectors(list)) => {
+              name == "not"
+                && list.selectors.len() == 1
+                && list.selectors[0].compounds.len() == 1
+                && list.selectors[0].compounds[0].selectors.len() == 1
+            }
+            Some(PseudoArgument::Nth { of, .. }) => of.is_none(),
+            _ => true,
+          };
+          argument && KNOWN_PSEUDO_CLASSES.contains(&name.as_str())
+        }
+        SimpleSelector::PseudoElement(pseudo) => {
+          pseudo.argument.is_none() && KNOWN_PSEUDO_ELEMENTS.contains(&pseudo.lower_name().as_str())
+        }
+        _ => true,
+      };
+    });
+  }
+  known
+}
+
+fn merge(prev: &Node, node: &Node) -> Option<Merge> {
+  match (prev, node) {
+    (Node::Rule(a), Node::Rule(b)) => {
+      if normalize(&a.selector) == normalize(&b.selector) {
+        Some(Merge::Children)
+      } else if same_declarations(prev, node) && is_known(&a.selector) && is_known(&b.selector) {
+        let comma = match a.selector.contains(", ") || b.selector.contains(", ") {
+          true => ", ",
+          false => ",",
+        };
+        Some(Merge::Selector(format!(
+          "{}{}{}",
+          a.selector, comma, b.selector
+        )))
+      } else {
+        None
+      }
+    }
+    (Node::AtRule(a), Node::AtRule(b))
+      if ["media", "supports"].contains(&a.name.to_ascii_lowercase().as_str())
+        && a.name.eq_ignore_ascii_case(&b.name)
+        && a.params.trim() == b.params.trim()
+        && a.nodes.is_some()
+        && b.nodes.is_some() =>
+    {
+      Some(Merge::AtRule)
+    }
+    _ => None,
+  }
+}
+
+/// Merges the children of `parent` and its descendants.
+fn optimize(parent: &Rc<RefCell<Node>>) {
+  let children = match parent.borrow().as_shared().get_nodes() {
+    Some(children) => children,
+    None => return,
+  };
+  for child in &children {
+    optimize(child);
+    if child.borrow().as_rule().is_some() {
+      dedupe(child);
+    }
+  }
+  let mut nodes: Vec<Rc<RefCell<Node>>> = Vec::with_capacity(children.len());
+  for child in children {
+    let prev = match nodes.last() {
+      Some(prev) => prev,
+      None => {
+        nodes.push(child);
+        continue;
+      }
+    };
+    let merged = merge(&prev.borrow(), &child.borrow());
+    match merged {
+      Some(Merge::Selector(selector)) => {
+        if let Node::Rule(rule) = &mut *prev.borrow_mut() {
+          rule.selector = selector;
+          rule.raws.selector = None;
+        }
+      }
+      Some(merge) => {
+        let moved = child.borrow().as_shared().get_nodes().unwrap_or_default();
+        let semicolon = match &*child.borrow() {
+          Node::Rule(rule) => rule.raws.semicolon,
+          Node::AtRule(at_rule) => at_rule.raws.semicolon,
+          _ => None,
+        };
+        match &mut *prev.borrow_mut() {
+          Node::Rule(rule) => rule.raws.semicolon = semicolon,
+          Node::AtRule(at_rule) => at_rule.raws.semicolon = semicolon,
+          _ => {}
+        }
+        if let Some(children) = prev.borrow_mut().as_shared_mut().get_nodes_mut() {
+          children.extend(moved);
+        }
+        match merge {
+          Merge::AtRule => optimize(prev),
+          _ => dedupe(prev),
+        }
+      }
+      None => nodes.push(child),
+    }
+  }
+  if let Some(children) = parent.borrow_mut().as_shared_mut().get_nodes_mut() {
+    *children = nodes;
+  }
+}
+
+impl Plugin for MergeRules {
+  fn name(&self) -> &str {
+    "merge-rules"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    optimize(root);
+    Ok(())
+  }
+}
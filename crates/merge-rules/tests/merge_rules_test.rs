@@ -0,0 +1,98 @@
+use merge_rules::MergeRules;
+use postcss::Processor;
+
+fn merge(css: &str) -> String {
+  Processor::run_one(MergeRules, css).unwrap().css
+}
+
+#[test]
+fn merges_adjacent_rules_with_the_same_selector() {
+  assert_eq!(
+    merge("a { color: red } a { margin: 0; }"),
+    "a { color: red; margin: 0; }"
+  );
+  assert_eq!(
+    merge("a, b { color: red } a,b { color: blue }"),
+    "a, b { color: blue }"
+  );
+  assert_eq!(
+    merge("a { color: red } b { top: 0 } a { margin: 0 }"),
+    "a { color: red } b { top: 0 } a { margin: 0 }"
+  );
+}
+
+#[test]
+fn merges_rules_with_the_same_declarations() {
+  assert_eq!(
+    merge("a { color: red } b { color: red } c { color: red }"),
+    "a,b,c { color: red }"
+  );
+  assert_eq!(
+    merge("a { color: red } b { color: red; top: 0 }"),
+    "a { color: red } b { color: red; top: 0 }"
+  );
+  assert_eq!(
+    merge("a::-moz-selection { color: red } a::selection { color: red }"),
+    "a::-moz-selection { color: red } a::selection { color: red }"
+  );
+  assert_eq!(
+    merge("a:hover { color: red } b:first-child::before { color: red } c:not(.d) { color: red }"),
+    "a:hover,b:first-child::before,c:not(.d) { color: red }"
+  );
+  assert_eq!(
+    merge(".a:focus-visible { color: red } .a:focus { color: red }"),
+    ".a:focus-visible { color: red } .a:focus { color: red }"
+  );
+  assert_eq!(
+    merge(".a:not(.b, .c) { color: red } .d { color: red } .e:is(.f) { color: red }"),
+    ".a:not(.b, .c) { color: red } .d { color: red } .e:is(.f) { color: red }"
+  );
+}
+
+#[test]
+fn drops_overridden_declarations() {
+  assert_eq!(
+    merge("a { color: red; margin: 0; color: #fff }"),
+    "a { margin: 0; color: #fff }"
+  );
+  assert_eq!(
+    merge("a { display: flex; display: grid }"),
+    "a { display: flex; display: grid }"
+  );
+  assert_eq!(
+    merge("a { width: 1px; width: calc(100% - 1px); width: 1px }"),
+    "a { width: 1px }"
+  );
+  assert_eq!(
+    merge("a { color: red !important; color: blue; color: green }"),
+    "a { color: red !important }"
+  );
+  assert_eq!(
+    merge("a { color: red !important; color: lab(0 0 0) }"),
+    "a { color: red !important }"
+  );
+  assert_eq!(
+    merge("a { color: lab(0 0 0) !important; color: red }"),
+    "a { color: lab(0 0 0) !important; color: red }"
+  );
+}
+
+#[test]
+fn combines_identical_at_rules() {
+  assert_eq!(
+    merge("@media print { a { color: red } } @media print { a { top: 0 } b { top: 0 } }"),
+    "@media print { a { color: red } a,b { top: 0 } }"
+  );
+  assert_eq!(
+    merge("@media print { a { color: red } } @media print { a { top: 0 } }"),
+    "@media print { a { color: red; top: 0 } }"
+  );
+  assert_eq!(
+    merge("@media print { a { color: red } } a { top: 0 } @media print { b { top: 0 } }"),
+    "@media print { a { color: red } } a { top: 0 } @media print { b { top: 0 } }"
+  );
+  assert_eq!(
+    merge("@media print { a { color: red } } @media screen { a { color: red } }"),
+    "@media print { a { color: red } } @media screen { a { color: red } }"
+  );
+}
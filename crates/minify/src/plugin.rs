@@ -0,0 +1,300 @@
+use browserslist::{Target, Version};
+use node::shorthand::{find, Shorthand, SHORTHANDS};
+use node::tree::replace_children;
+use node::{Declaration, Node};
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// Removes the whitespace and the comments, except the `/*! */` ones.
+  pub whitespace: bool,
+  /// The browserslist query of the browsers to support, see
+  /// [`targets_or_defaults`](browserslist::targets_or_defaults).
+  pub browsers: Option<String>,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      whitespace: true,
+      browsers: None,
+    }
+  }
+}
+
+/// Minifies the stylesheet: collapses the complete longhand sets of a block
+/// into their shorthand, shortens the values of shorthands and, with
+/// [`Options::whitespace`], removes the whitespace and the comments.
+///
+/// Longhands are only collapsed into shorthands setting nothing else, so
+/// never into `font`, and that all the targets know, so only into `inset`
+/// without Internet Explorer.
+#[derive(Debug, Clone, Default)]
+pub struct Minify {
+  pub options: Options,
+  /// The targets of `options.browsers`, if set.
+  pub targets: Option<Vec<Target>>,
+}
+
+impl Minify {
+  /// Creates a plugin for the browsers of `options.browsers`.
+  pub fn new(options: Options) -> Result<Self, browserslist::Error> {
+    let targets = options
+      .browsers
+      .as_deref()
+      .map(browserslist::resolve)
+      .transpose()?;
+    Ok(Self { options, targets })
+  }
+}
+
+/// The first versions supporting the shorthands that some of the browsers
+/// do not know.
+const SUPPORT: &[(&str, &[(&str, &str)])] = &[(
+  "inset",
+  &[
+    ("chrome", "87"),
+    ("edge", "87"),
+    ("firefox", "66"),
+    ("safari", "14.1"),
+    ("opera", "73"),
+    ("ios_saf", "14.5"),
+    ("samsung", "14.0"),
+    ("android", "87"),
+  ],
+)];
+
+/// Returns `true` if all the targets know the shorthand. Browsers missing
+/// from the support table, like Internet Explorer, know none of its
+/// shorthands.
+fn supported(shorthand: &Shorthand, targets: &[Target]) -> bool {
+  let versions = match SUPPORT.iter().find(|(name, _)| *name == shorthand.name) {
+    Some((_, versions)) => versions,
+    None => return true,
+  };
+  targets.iter().all(|target| {
+    let browser = target.table_browser();
+    versions.iter().any(|(name, first)| {
+      *name == browser
+        && target
+          .numeric_version()
+          .zip(Version::parse(first))
+          .is_some_and(|(version, first)| version >= first)
+    })
+  })
+}
+
+/// Returns `true` if `prop` may set one of the longhands of `shorthand`, so
+/// that longhands can not be collapsed across it.
+fn related(shorthand: &Shorthand, prop: &str) -> bool {
+  let prop = prop.to_ascii_lowercase();
+  let family = |name: &str| name.split('-').next().unwrap_or_default().to_string();
+  if shorthand.name == "inset" {
+    return ["top", "right", "bottom", "left"].contains(&prop.as_str()) || family(&prop) == "inset";
+  }
+  family(&prop) == family(shorthand.name)
+}
+
+/// The declaration of `node`, if it is one.
+fn decl<'a>(node: &Rc<RefCell<Node<'a>>>) -> Option<Declaration<'a>> {
+  node.borrow().as_decl().cloned()
+}
+
+/// Collapses the complete longhand sets in the children of `parent` into
+/// their shorthand, at the position of the last longhand.
+fn collapse_longhands(parent: &Rc<RefCell<Node>>, targets: &[Target]) {
+  let mut children = match parent.borrow().as_shared().get_nodes() {
+    Some(children) => children,
+    None => return,
+  };
+  let mut changed = false;
+  let shorthands = SHORTHANDS
+    .iter()
+    .filter(|shorthand| !shorthand.resets && supported(shorthand, targets));
+  for shorthand in shorthands {
+    let indices: Option<Vec<usize>> = shorthand
+      .longhands
+      .iter()
+      .map(|longhand| {
+        let mut found = children
+          .iter()
+          .enumerate()
+          .filter(|(_, child)| {
+            child
+              .borrow()
+              .as_decl()
+              .is_some_and(|decl| decl.prop.eq_ignore_ascii_case(longhand))
+          })
+          .map(|(i, _)| i);
+        match (found.next(), found.next()) {
+          (Some(i), None) => Some(i),
+          _ => None,
+        }
+      })
+      .collect();
+    let indices = match indices {
+      Some(indices) => indices,
+      None => continue,
+    };
+    let first = indices.iter().copied().min().unwrap_or_default();
+    let last = indices.iter().copied().max().unwrap_or_default();
+    let interleaved = (first..=last).any(|i| {
+      !indices.contains(&i)
+        && children[i]
+          .borrow()
+          .as_decl()
+          .is_some_and(|decl| related(shorthand, &decl.prop))
+    });
+    if interleaved {
+      continue;
+    }
+    let decls: Vec<_> = indices.iter().filter_map(|&i| decl(&children[i])).collect();
+    let refs: Vec<_> = decls.iter().collect();
+    let mut collapsed = match Declaration::collapse(&refs) {
+      Some(collapsed) => collapsed,
+      None => continue,
+    };
+    if let Some(last) = decl(&children[last]) {
+      collapsed.raws = last.raws;
+      collapsed.raws.value = None;
+    }
+    children[last] = Rc::new(RefCell::new(Node::Decl(collapsed)));
+    children = children
+      .into_iter()
+      .enumerate()
+      .filter(|(i, _)| *i == last || !indices.contains(i))
+      .map(|(_, child)| child)
+      .collect();
+    changed = true;
+  }
+  if changed {
+    replace_children(parent, children);
+  }
+}
+
+/// Replaces the value of a shorthand declaration by the shortest equivalent
+/// one, like `margin: 0 0 0 0` by `margin: 0`.
+fn shorten(decl: &mut Declaration) {
+  if find(&decl.prop).is_none() {
+    return;
+  }
+  let shortest = decl.expand().and_then(|longhands| {
+    let refs: Vec<_> = longhands.iter().collect();
+    Declaration::collapse(&refs)
+  });
+  if let Some(shortest) = shortest {
+    if shortest.value.len() < decl.value.len() {
+      decl.value = shortest.value;
+      decl.raws.value = None;
+    }
+  }
+}
+
+/// The selector with the spaces around its commas removed, unless a comma
+/// may be inside a string or a function.
+fn compact_selector(selector: &str) -> String {
+  if selector.contains(['"', '\'', '(', '[']) {
+    return selector.trim().to_string();
+  }
+  selector
+    .split(',')
+    .map(str::trim)
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Removes the whitespace around the node, and inside it for rules and
+/// at-rules.
+fn compact(node: &mut Node) {
+  match node {
+    Node::Root(root) => {
+      root.raws.after = Some(String::new());
+      root.raws.semicolon = Some(false);
+    }
+    Node::Comment(comment) => comment.raws.before = Some(String::new()),
+    Node::Decl(decl) => {
+      decl.raws.before = Some(String::new());
+      decl.raws.between = Some(":".to_string());
+      if decl.important {
+        decl.raws.important = Some("!important".to_string());
+      }
+      if decl.raws.value.is_some() {
+        decl.value = decl.value.trim().to_string();
+        decl.raws.value = None;
+      }
+    }
+    Node::Rule(rule) => {
+      rule.selector = compact_selector(&rule.selector);
+      rule.raws.selector = None;
+      rule.raws.before = Some(String::new());
+      rule.raws.between = Some(String::new());
+      rule.raws.after = Some(String::new());
+      rule.raws.semicolon = Some(false);
+    }
+    Node::AtRule(at_rule) => {
+      at_rule.params = at_rule.params.trim().to_string();
+      at_rule.raws.params = None;
+      at_rule.raws.before = Some(String::new());
+      at_rule.raws.after_name = Some(
+        match at_rule.params.is_empty() {
+          true => "",
+          false => " ",
+        }
+        .to_string(),
+      );
+      at_rule.raws.between = Some(String::new());
+      at_rule.raws.after = Some(String::new());
+      at_rule.raws.semicolon = Some(false);
+    }
+    Node::Document(_) => {}
+  }
+}
+
+impl Minify {
+  /// Minifies `node` and its descendants.
+  fn minify(&self, node: &Rc<RefCell<Node>>, targets: &[Target]) {
+    if let Node::Decl(decl) = &mut *node.borrow_mut() {
+      shorten(decl);
+    }
+    let children = node.borrow().as_shared().get_nodes();
+    if let Some(children) = children {
+      let children: Vec<_> = children
+        .into_iter()
+        .filter(|child| {
+          !self.options.whitespace
+            || !matches!(&*child.borrow(), Node::Comment(comment) if !comment.text.starts_with('!'))
+        })
+        .collect();
+      for child in &children {
+        self.minify(child, targets);
+      }
+      if let Some(nodes) = node.borrow_mut().as_shared_mut().get_nodes_mut() {
+        *nodes = children;
+      }
+      collapse_longhands(node, targets);
+    }
+    if self.options.whitespace {
+      compact(&mut node.borrow_mut());
+    }
+  }
+}
+
+impl Plugin for Minify {
+  fn name(&self) -> &str {
+    "minify"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    let targets = self.targets.as_deref().or(result.targets.as_deref());
+    self.minify(root, &browserslist::targets_or_defaults(targets));
+    Ok(())
+  }
+}
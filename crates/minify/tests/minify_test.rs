@@ -0,0 +1,113 @@
+use minify::{Minify, Options};
+use postcss::Processor;
+
+fn minify(whitespace: bool, css: &str) -> String {
+  let options = Options {
+    whitespace,
+    browsers: Some("chrome 100".to_string()),
+  };
+  Processor::run_one(Minify::new(options).unwrap(), css)
+    .unwrap()
+    .css
+}
+
+#[test]
+fn removes_whitespace_and_comments() {
+  assert_eq!(
+    minify(
+      true,
+      "/*! license */\n/* a */\na ,\nb {\n  color : red ;\n  top: 0 !important;\n}\n\n@media print {\n  a { top: 0 }\n}\n@import \"a.css\";\n"
+    ),
+    "/*! license */a,b{color:red;top:0!important}@media print{a{top:0}}@import \"a.css\""
+  );
+  assert_eq!(
+    minify(true, "a[title=\"a, b\"] { color: red }"),
+    "a[title=\"a, b\"]{color:red}"
+  );
+}
+
+#[test]
+fn collapses_longhands() {
+  assert_eq!(
+    minify(
+      false,
+      "a { margin-top: 0; margin-right: 1px; color: red; margin-bottom: 0; margin-left: 1px }"
+    ),
+    "a { color: red; margin: 0 1px }"
+  );
+  assert_eq!(
+    minify(
+      false,
+      "a { margin-top: 0; margin-right: 0; margin: 1px; margin-bottom: 0; margin-left: 0 }"
+    ),
+    "a { margin-top: 0; margin-right: 0; margin: 1px; margin-bottom: 0; margin-left: 0 }"
+  );
+  assert_eq!(
+    minify(false, "a { flex-grow: 1; flex-shrink: 1; flex-basis: 0% }"),
+    "a { flex: 1 }"
+  );
+  assert_eq!(
+    minify(
+      false,
+      "a { font-style: normal; font-variant: normal; font-weight: 400; font-stretch: normal; font-size: 1em; line-height: normal; font-family: serif }"
+    ),
+    "a { font-style: normal; font-variant: normal; font-weight: 400; font-stretch: normal; font-size: 1em; line-height: normal; font-family: serif }"
+  );
+  assert_eq!(
+    minify(
+      false,
+      "a { border-width: 1px; border-style: solid; border-color: red; border-image: none }"
+    ),
+    "a { border: 1px solid red }"
+  );
+  assert_eq!(
+    minify(
+      false,
+      "a { border-width: 1px; border-style: solid; border-color: red; border-image: url(a.png) 30 }"
+    ),
+    "a { border-width: 1px; border-style: solid; border-color: red; border-image: url(a.png) 30 }"
+  );
+}
+
+#[test]
+fn collapses_into_shorthands_the_targets_know() {
+  let css = "a { top: 0; right: 0; bottom: 0; left: 0 }";
+  assert_eq!(minify(false, css), "a { inset: 0 }");
+  for browsers in [None, Some("ie 11"), Some("safari 14")] {
+    let options = Options {
+      whitespace: false,
+      browsers: browsers.map(str::to_string),
+    };
+    let result = Processor::run_one(Minify::new(options).unwrap(), css).unwrap();
+    assert_eq!(result.css, css);
+  }
+}
+
+#[test]
+fn keeps_important_apart() {
+  assert_eq!(
+    minify(
+      true,
+      "a { top: 0 !important; right: 0 !important; bottom: 0 !important; left: 0 !important }"
+    ),
+    "a{inset:0!important}"
+  );
+  assert_eq!(
+    minify(
+      true,
+      "a { top: 0 !important; right: 0; bottom: 0; left: 0 }"
+    ),
+    "a{top:0!important;right:0;bottom:0;left:0}"
+  );
+}
+
+#[test]
+fn shortens_shorthands() {
+  assert_eq!(
+    minify(
+      true,
+      "a { padding: 1px 2px 1px 2px; flex: 0 0 auto; border: solid medium; margin: var(--a) var(--a) }"
+    ),
+    "a{padding:1px 2px;flex:none;border:solid;margin:var(--a) var(--a)}"
+  );
+}
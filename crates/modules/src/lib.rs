@@ -0,0 +1,7 @@
+mod name;
+mod plugin;
+mod scope;
+
+pub use crate::name::scoped_name;
+pub use crate::plugin::{Modules, Options};
+pub use crate::scope::{Mode, STRIPPED_PSEUDO_CLASSES};
@@ -0,0 +1,60 @@
+/// Expands a scoped name pattern.
+///
+/// - `[name]` is the file name without its extension and a `.module`
+///   suffix, e.g. `button` for `button.module.css`;
+/// - `[local]` is the name in the source;
+/// - `[hash]` and `[hash:<length>]` are a base-36 hash of `path` and the
+///   local name, 8 characters long by default.
+///
+/// A name starting with a digit gets a `_` prefix to stay a valid
+/// identifier.
+pub fn scoped_name(pattern: &str, name: &str, path: &str, local: &str) -> String {
+  let mut result = String::with_capacity(pattern.len() + local.len());
+  let mut rest = pattern;
+  while let Some(start) = rest.find('[') {
+    result.push_str(&rest[..start]);
+    rest = &rest[start..];
+    let end = match rest.find(']') {
+      Some(end) => end,
+      None => break,
+    };
+    let placeholder = &rest[1..end];
+    match placeholder.split_once(':') {
+      _ if placeholder == "name" => result.push_str(name),
+      _ if placeholder == "local" => result.push_str(local),
+      _ if placeholder == "hash" => result.push_str(&hash(path, local, 8)),
+      Some(("hash", length)) if length.parse::<usize>().is_ok() => {
+        result.push_str(&hash(path, local, length.parse().unwrap()))
+      }
+      _ => result.push_str(&rest[..=end]),
+    }
+    rest = &rest[end + 1..];
+  }
+  result.push_str(rest);
+  if result.starts_with(|c: char| c.is_ascii_digit()) {
+    result.insert(0, '_');
+  }
+  result
+}
+
+/// The `[name]` of a file path.
+pub(crate) fn file_name(path: &str) -> String {
+  let name = path.rsplit(&['/', '\\'][..]).next().unwrap_or_default();
+  let name = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+  name.strip_suffix(".module").unwrap_or(name).to_string()
+}
+
+/// FNV-1a of `path` and `local`, in base 36 and cut to `length`.
+fn hash(path: &str, local: &str, length: usize) -> String {
+  let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+  for byte in path.bytes().chain(Some(0)).chain(local.bytes()) {
+    hash ^= byte as u64;
+    hash = hash.wrapping_mul(0x0100_0000_01b3);
+  }
+  let mut digits = vec![];
+  while hash > 0 {
+    digits.push(std::char::from_digit((hash % 36) as u32, 36).unwrap());
+    hash /= 36;
+  }
+  digits.into_iter().take(length).collect()
+}
@@ -0,0 +1,365 @@
+use crate::name::{file_name, scoped_name};
+use crate::scope::{localize, Mode};
+use import::{FileResolver, Resolver};
+use node::rename::{is_keyframes, rename_idents};
+use node::tree::{replace_children, walk};
+use node::Node;
+use postcss::path::{absolute, relative};
+use postcss::{CssSyntaxError, Plugin, ProcessOptions, ProcessResult};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// The scoped names, see [`scoped_name`](crate::scoped_name).
+  pub pattern: String,
+  pub mode: Mode,
+  /// Adds a `dts` message with a TypeScript declaration of the exports.
+  pub dts: bool,
+  /// The directory hashed paths are relative to, the current directory by
+  /// default.
+  pub context: Option<PathBuf>,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      pattern: "[name]__[local]___[hash:5]".to_string(),
+      mode: Mode::Local,
+      dts: false,
+      context: None,
+    }
+  }
+}
+
+/// Scopes class, id and `@keyframes` names like CSS Modules.
+///
+/// Local names are renamed with the `pattern` of the options, and the
+/// `animation` declarations use the renamed keyframes. A `composes`
+/// declaration adds the classes of the same file, of `global` or of another
+/// file (`composes: a b from "./other.css"`) to the export of the rule's
+/// class, and the other files are added as dependencies.
+///
+/// The exports go to an `export` message, an object of the local names and
+/// the space separated classes to use. With the `dts` option a `dts`
+/// message holds a TypeScript declaration of them.
+pub struct Modules {
+  pub options: Options,
+  /// Finds the files of `composes`.
+  pub resolver: Box<dyn Resolver>,
+}
+
+impl Default for Modules {
+  fn default() -> Self {
+    Self::new(Options::default())
+  }
+}
+
+/// Local names and the classes they export, in order of appearance.
+type Exports = Vec<(String, Vec<String>)>;
+
+/// Files being transformed, and the exports of the ones done.
+#[derive(Default)]
+struct State {
+  stack: Vec<PathBuf>,
+  cache: HashMap<PathBuf, Exports>,
+}
+
+/// A `composes` declaration.
+struct Composes<'a> {
+  decl: Rc<RefCell<Node<'a>>>,
+  class: String,
+  names: Vec<String>,
+  from: Option<String>,
+}
+
+impl Modules {
+  pub fn new(options: Options) -> Self {
+    Self {
+      options,
+      resolver: Box::new(FileResolver::default()),
+    }
+  }
+
+  /// Scopes the names of `root`, from `file` if it has one, and returns its
+  /// exports.
+  fn transform(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    file: Option<&Path>,
+    state: &mut State,
+    result: &mut ProcessResult,
+  ) -> Result<Exports, CssSyntaxError> {
+    let path = file.map(|file| {
+      let context = absolute(self.options.context.as_deref().unwrap_or(Path::new(".")));
+      relative(&context, file)
+    });
+    let name = path.as_deref().map(file_name).unwrap_or_default();
+    let path = path.unwrap_or_default();
+    let mut exports: Exports = vec![];
+    let mut scope = |local: &str| {
+      let scoped = scoped_name(&self.options.pattern, &name, &path, local);
+      if !exports.iter().any(|(name, _)| name == local) {
+        exports.push((local.to_string(), vec![scoped.clone()]));
+      }
+      scoped
+    };
+
+    let mut keyframes = HashMap::new();
+    let mut composes = vec![];
+    walk(root, &mut |node| {
+      let mut node = node.borrow_mut();
+      match &mut *node {
+        Node::AtRule(at_rule) if is_keyframes(&at_rule.name) => {
+          let params = at_rule.params.trim();
+          let (local, mode) = match unwrap_switch(params) {
+            Some((local, mode)) => (local.to_string(), mode),
+            None => (params.to_string(), self.options.mode),
+          };
+          at_rule.params = match mode {
+            Mode::Local => {
+              let scoped = scope(&local);
+              keyframes.insert(local, scoped.clone());
+              scoped
+            }
+            Mode::Global => local,
+          };
+          at_rule.raws.params = None;
+          Ok(false)
+        }
+        Node::Rule(rule) => {
+          let mut list = match selector::parse(&rule.selector) {
+            Ok(list) => list,
+            Err(error) => return Err(node.error(&error.to_string())),
+          };
+          let mut classes = vec![];
+          let localized = localize(&mut list, self.options.mode, &mut |local| {
+            classes.push(local.to_string());
+            scope(local)
+          });
+          if let Err(reason) = localized {
+            return Err(node.error(&reason));
+          }
+          let rule = node.as_rule_mut().unwrap();
+          rule.selector = list.to_string();
+          rule.raws.selector = None;
+          let decls = rule.nodes.clone().unwrap_or_default();
+          drop(node);
+          for decl in decls {
+            let value = match &*decl.borrow() {
+              Node::Decl(decl) if decl.prop.eq_ignore_ascii_case("composes") => decl.value.clone(),
+              _ => continue,
+            };
+            let single = list.selectors.len() == 1
+              && list.selectors[0].compounds.len() == 1
+              && classes.len() == 1;
+            if !single {
+              let reason = "composes is only allowed in rules of a single local class";
+              return Err(decl.borrow().error(reason));
+            }
+            let (names, from) = parse_composes(&value);
+            composes.push(Composes {
+              decl: decl.clone(),
+              class: classes[0].clone(),
+              names,
+              from,
+            });
+          }
+          Ok(true)
+        }
+        _ => Ok(true),
+      }
+    })?;
+
+    walk(root, &mut |node| {
+      if let Node::Decl(decl) = &mut *node.borrow_mut() {
+        let prop = decl.prop.to_ascii_lowercase();
+        let prop = prop.trim_start_matches("-webkit-");
+        if prop == "animation" || prop == "animation-name" {
+          let value = rename_idents(&decl.value, &keyframes);
+          if value != decl.value {
+            decl.value = value;
+            decl.raws.value = None;
+          }
+        }
+      }
+      Ok(true)
+    })?;
+
+    for composes in composes {
+      let classes = match &composes.from {
+        None => {
+          let mut classes = vec![];
+          for name in &composes.names {
+            match exports.iter().find(|(local, _)| local == name) {
+              Some((_, scoped)) => classes.extend(scoped.iter().cloned()),
+              None => {
+                let reason = format!("Class \"{}\" is not defined in this file", name);
+                return Err(composes.decl.borrow().error(&reason));
+              }
+            }
+          }
+          classes
+        }
+        Some(from) if from == "global" => composes.names.clone(),
+        Some(from) => {
+          let other = self.exports_of(from, file, &composes.decl, state, result)?;
+          let mut classes = vec![];
+          for name in &composes.names {
+            match other.iter().find(|(local, _)| local == name) {
+              Some((_, scoped)) => classes.extend(scoped.iter().cloned()),
+              None => {
+                let reason = format!("Class \"{}\" is not exported by {}", name, from);
+                return Err(composes.decl.borrow().error(&reason));
+              }
+            }
+          }
+          classes
+        }
+      };
+      if let Some((_, export)) = exports
+        .iter_mut()
+        .find(|(local, _)| *local == composes.class)
+      {
+        for class in classes {
+          if !export.contains(&class) {
+            export.push(class);
+          }
+        }
+      }
+      remove(&composes.decl, root);
+    }
+    Ok(exports)
+  }
+
+  /// Transforms the file composed `from` the `file` for its exports.
+  fn exports_of(
+    &self,
+    from: &str,
+    file: Option<&Path>,
+    decl: &Rc<RefCell<Node>>,
+    state: &mut State,
+    result: &mut ProcessResult,
+  ) -> Result<Exports, CssSyntaxError> {
+    let base = file
+      .and_then(Path::parent)
+      .map_or_else(|| absolute(Path::new(".")), Path::to_path_buf);
+    let other = self.resolver.resolve(from, &base).map_err(|message| {
+      let reason = format!("Failed to find \"{}\": {}", from, message);
+      decl.borrow().error(&reason)
+    })?;
+    if let Some(exports) = state.cache.get(&other) {
+      return Ok(exports.clone());
+    }
+    if state.stack.contains(&other) {
+      let reason = format!("Circular composes from {}", other.display());
+      return Err(decl.borrow().error(&reason));
+    }
+
+    let path = other.to_string_lossy().into_owned();
+    let parent = file.map(|file| file.to_string_lossy().into_owned());
+    result.add_dependency(&path, parent.as_deref());
+    let css = self.resolver.load(&other).map_err(|message| {
+      let reason = format!("Failed to read {}: {}", path, message);
+      decl.borrow().error(&reason)
+    })?;
+    let opts = ProcessOptions {
+      from: Some(path),
+      ..Default::default()
+    };
+    let root = parser::parse::parse(&css, Some(opts))?;
+    state.stack.push(other.clone());
+    let exports = self.transform(&root, Some(&other), state, result)?;
+    state.stack.pop();
+    state.cache.insert(other, exports.clone());
+    Ok(exports)
+  }
+}
+
+impl Plugin for Modules {
+  fn name(&self) -> &str {
+    "modules"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    let file = result
+      .opts
+      .from
+      .as_deref()
+      .map(|from| absolute(Path::new(from)));
+    let mut state = State {
+      stack: file.iter().cloned().collect(),
+      ..Default::default()
+    };
+    let exports = self.transform(root, file.as_deref(), &mut state, result)?;
+
+    let mut map = Map::new();
+    for (local, classes) in &exports {
+      map.insert(local.clone(), Value::String(classes.join(" ")));
+    }
+    if self.options.dts {
+      let mut dts = "declare const styles: {\n".to_string();
+      for local in map.keys() {
+        dts.push_str(&format!(
+          "  readonly {}: string;\n",
+          Value::String(local.clone())
+        ));
+      }
+      dts.push_str("};\nexport default styles;\n");
+      result.add_message("dts", Value::String(dts));
+    }
+    result.add_message("export", Value::Object(map));
+    Ok(())
+  }
+}
+
+/// Splits `:global(name)` and `:local(name)`.
+fn unwrap_switch(params: &str) -> Option<(&str, Mode)> {
+  let (name, mode) = if let Some(rest) = params.strip_prefix(":global(") {
+    (rest, Mode::Global)
+  } else {
+    (params.strip_prefix(":local(")?, Mode::Local)
+  };
+  Some((name.strip_suffix(')')?.trim(), mode))
+}
+
+/// Splits `a b from "./file.css"` into the names and the source.
+fn parse_composes(value: &str) -> (Vec<String>, Option<String>) {
+  let (names, from) = match value.rsplit_once(" from ") {
+    Some((names, from)) => {
+      let from = from.trim().trim_matches(|c| c == '"' || c == '\'');
+      (names, Some(from.to_string()))
+    }
+    None => (value, None),
+  };
+  let names = names
+    .split(|c: char| c.is_whitespace() || c == ',')
+    .filter(|name| !name.is_empty())
+    .map(str::to_string)
+    .collect();
+  (names, from)
+}
+
+/// Removes `node` from its parent in the tree of `root`.
+fn remove<'a>(node: &Rc<RefCell<Node<'a>>>, root: &Rc<RefCell<Node<'a>>>) {
+  let _ = walk::<(), _>(root, &mut |parent| {
+    let children = parent.borrow().as_shared().get_nodes().unwrap_or_default();
+    if children.iter().any(|child| Rc::ptr_eq(child, node)) {
+      let nodes = children
+        .into_iter()
+        .filter(|child| !Rc::ptr_eq(child, node))
+        .collect();
+      replace_children(parent, nodes);
+    }
+    Ok(true)
+  });
+}
@@ -0,0 +1,120 @@
+use selector::{ComplexSelector, CompoundSelector, PseudoArgument, SelectorList, SimpleSelector};
+
+/// Whether names are scoped unless marked otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+  /// Names are scoped, `:global` opts out.
+  #[default]
+  Local,
+  /// Names are kept, `:local` opts in.
+  Global,
+}
+
+/// The pseudo-classes CSS Modules strip from selectors, for
+/// [`ComplexSelector::specificity_without`].
+pub const STRIPPED_PSEUDO_CLASSES: &[&str] = &["global", "local"];
+
+/// Replaces the local class and id names of the selectors with `rename`
+/// and removes `:global` and `:local`.
+pub(crate) fn localize(
+  list: &mut SelectorList,
+  mode: Mode,
+  rename: &mut dyn FnMut(&str) -> String,
+) -> Result<(), String> {
+  for selector in &mut list.selectors {
+    localize_complex(selector, mode, rename)?;
+  }
+  Ok(())
+}
+
+fn localize_complex(
+  selector: &mut ComplexSelector,
+  mut mode: Mode,
+  rename: &mut dyn FnMut(&str) -> String,
+) -> Result<(), String> {
+  let mut compounds: Vec<CompoundSelector> = vec![];
+  // The combinator of a removed compound, for the next one.
+  let mut pending = None;
+  for mut compound in std::mem::take(&mut selector.compounds) {
+    if let Some(combinator) = pending.take() {
+      compound.combinator = combinator;
+    }
+    let combinator = compound.combinator.clone();
+    let alone = compound.selectors.len() == 1;
+    let mut simples = vec![];
+    for simple in compound.selectors {
+      match simple {
+        SimpleSelector::PseudoClass(pseudo) if is_switch(&pseudo.lower_name()) => {
+          let inner_mode = switch(&pseudo.lower_name());
+          let mut list = match pseudo.argument {
+            None => {
+              mode = inner_mode;
+              continue;
+            }
+            Some(PseudoArgument::Selectors(list)) => list,
+            Some(_) => return Err(format!("Invalid argument of :{}", pseudo.name)),
+          };
+          if list.selectors.len() != 1 {
+            return Err(format!(":{}() takes a single selector", pseudo.name));
+          }
+          let mut inner = list.selectors.remove(0);
+          localize_complex(&mut inner, inner_mode, rename)?;
+          if inner.compounds.len() == 1 {
+            simples.extend(inner.compounds.remove(0).selectors);
+          } else if alone {
+            inner.compounds[0].combinator = combinator.clone();
+            let last = inner.compounds.pop().unwrap();
+            compounds.extend(inner.compounds);
+            simples.extend(last.selectors);
+            compound.combinator = last.combinator;
+          } else {
+            return Err(format!(
+              ":{}() with combinators must be a compound on its own",
+              pseudo.name
+            ));
+          }
+        }
+        SimpleSelector::Class(name) if mode == Mode::Local => {
+          simples.push(SimpleSelector::Class(rename(&name)))
+        }
+        SimpleSelector::Id(name) if mode == Mode::Local => {
+          simples.push(SimpleSelector::Id(rename(&name)))
+        }
+        SimpleSelector::PseudoClass(mut pseudo) => {
+          if let Some(list) = pseudo.selectors_mut() {
+            localize(list, mode, rename)?;
+          }
+          simples.push(SimpleSelector::PseudoClass(pseudo));
+        }
+        simple => simples.push(simple),
+      }
+    }
+    if simples.is_empty() {
+      pending = Some(if compounds.is_empty() {
+        None
+      } else {
+        combinator
+      });
+      continue;
+    }
+    compound.selectors = simples;
+    compounds.push(compound);
+  }
+  if compounds.is_empty() {
+    return Err("Selector is empty without :global or :local".to_string());
+  }
+  selector.compounds = compounds;
+  Ok(())
+}
+
+fn is_switch(name: &str) -> bool {
+  STRIPPED_PSEUDO_CLASSES.contains(&name)
+}
+
+fn switch(name: &str) -> Mode {
+  match name {
+    "global" => Mode::Global,
+    _ => Mode::Local,
+  }
+}
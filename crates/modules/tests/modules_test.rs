@@ -0,0 +1,152 @@
+use import::Resolver;
+use modules::{scoped_name, Mode, Modules, Options, STRIPPED_PSEUDO_CLASSES};
+use postcss::{ProcessOptions, ProcessResult, Processor};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Files by absolute path, composed relative to the composing file.
+struct Files(HashMap<PathBuf, &'static str>);
+
+impl Resolver for Files {
+  fn resolve(&self, url: &str, base: &Path) -> Result<PathBuf, String> {
+    let path = postcss::path::absolute(&base.join(url));
+    match self.0.contains_key(&path) {
+      true => Ok(path),
+      false => Err("no such file".to_string()),
+    }
+  }
+
+  fn load(&self, path: &Path) -> Result<String, String> {
+    Ok(self.0[path].to_string())
+  }
+}
+
+fn process_with(options: Options, files: &[(&str, &'static str)], css: &str) -> ProcessResult {
+  let files = files
+    .iter()
+    .map(|(path, css)| (PathBuf::from(path), *css))
+    .collect();
+  let processor = Processor::with_plugin(Modules {
+    options,
+    resolver: Box::new(Files(files)),
+  });
+  let opts = ProcessOptions {
+    from: Some("/styles/app.module.css".to_string()),
+    ..Default::default()
+  };
+  processor.process(css, opts).unwrap()
+}
+
+fn process(css: &str) -> ProcessResult {
+  let options = Options {
+    pattern: "[name]_[local]".to_string(),
+    context: Some(PathBuf::from("/")),
+    ..Default::default()
+  };
+  process_with(options, &[], css)
+}
+
+#[test]
+fn scopes_names() {
+  let path = "styles/app.css";
+  let name = scoped_name("[name]__[local]___[hash:5]", "app", path, "title");
+  assert!(name.starts_with("app__title___"));
+  assert_eq!(name.len(), "app__title___".len() + 5);
+  assert_eq!(
+    name,
+    scoped_name("[name]__[local]___[hash:5]", "app", path, "title")
+  );
+  assert_ne!(
+    name,
+    scoped_name("[name]__[local]___[hash:5]", "app", path, "text")
+  );
+  assert_eq!(scoped_name("[hash]", "app", path, "title").len(), 8);
+  assert!(scoped_name("[hash:3]", "app", path, "title")
+    .chars()
+    .all(char::is_alphanumeric));
+  assert!(scoped_name("[local]", "app", path, "9lives").starts_with("_"));
+}
+
+#[test]
+fn honours_global_and_local() {
+  let result = process(".a :global(.b) .c {}\n:global .d .e {}\n:global(#f) :local(.g) {}");
+  assert_eq!(result.css, ".app_a .b .app_c {}\n.d .e {}\n#f .app_g {}");
+  let list = selector::parse(":global(#f) :local(.g)").unwrap();
+  assert_eq!(
+    list.selectors[0].specificity_without(STRIPPED_PSEUDO_CLASSES),
+    selector::Specificity::new(1, 1, 0)
+  );
+  let options = Options {
+    pattern: "[name]_[local]".to_string(),
+    mode: Mode::Global,
+    context: Some(PathBuf::from("/")),
+    ..Default::default()
+  };
+  let result = process_with(options, &[], ".a :local(.b), .c:not(:local(.d)) {}");
+  assert_eq!(result.css, ".a .app_b, .c:not(.app_d) {}");
+}
+
+#[test]
+fn renames_keyframes() {
+  let result = process(
+    "@keyframes spin { from { color: red } }\n@keyframes :global(fade) {}\n.a { animation: spin 1s, fade 2s; animation-name: spin }",
+  );
+  assert_eq!(
+    result.css,
+    "@keyframes app_spin { from { color: red } }\n@keyframes fade {}\n.app_a { animation: app_spin 1s, fade 2s; animation-name: app_spin }"
+  );
+  assert_eq!(
+    result.messages_of("export"),
+    vec![&json!({ "spin": "app_spin", "a": "app_a" })]
+  );
+  // the names are exported in order of appearance
+  assert_eq!(
+    result.messages_of("export")[0].to_string(),
+    r#"{"spin":"app_spin","a":"app_a"}"#
+  );
+}
+
+#[test]
+fn composes_classes() {
+  let files = [(
+    "/styles/base.css",
+    ".button { color: red }\n.shadow { box-shadow: none }",
+  )];
+  let options = Options {
+    pattern: "[name]_[local]".to_string(),
+    context: Some(PathBuf::from("/")),
+    dts: true,
+    ..Default::default()
+  };
+  let result = process_with(
+    options,
+    &files,
+    ".a { color: blue }\n.b { composes: a; composes: button shadow from \"./base.css\"; composes: util from global; margin: 0 }",
+  );
+  assert_eq!(result.css, ".app_a { color: blue }\n.app_b { margin: 0 }");
+  assert_eq!(
+    result.messages_of("export"),
+    vec![&json!({ "a": "app_a", "b": "app_b app_a base_button base_shadow util" })]
+  );
+  assert_eq!(
+    result.messages_of("dts"),
+    vec![&json!(
+      "declare const styles: {\n  readonly \"a\": string;\n  readonly \"b\": string;\n};\nexport default styles;\n"
+    )]
+  );
+  assert_eq!(result.dependencies(), vec!["/styles/base.css"]);
+}
+
+#[test]
+fn rejects_invalid_composes() {
+  let processor = Processor::with_plugin(Modules::default());
+  let error = processor
+    .process(".a .b { composes: c }", ProcessOptions::default())
+    .unwrap_err();
+  assert!(error.to_string().contains("single local class"));
+  let error = processor
+    .process(".a { composes: missing }", ProcessOptions::default())
+    .unwrap_err();
+  assert!(error.to_string().contains("\"missing\" is not defined"));
+}
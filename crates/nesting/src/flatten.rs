@@ -0,0 +1,295 @@
+use crate::resolve::resolve;
+use node::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// At-rules whose body may contain declarations of the rule they are
+/// nested in.
+const CONDITIONAL: &[&str] = &[
+  "media",
+  "supports",
+  "container",
+  "layer",
+  "scope",
+  "starting-style",
+  "document",
+];
+
+/// Unwraps the rules nested in rules, following CSS Nesting.
+///
+/// Nested rules follow their parent with a resolved selector (see
+/// [`resolve`]). Conditional at-rules like `@media` nested in a rule follow
+/// it too, with their declarations wrapped in a rule with the parent
+/// selector. Declarations after a nested rule go into a copy of the parent,
+/// which keeps their order in the cascade, and a rule with nothing but
+/// nested rules is removed.
+pub fn flatten(root: &Rc<RefCell<Node>>) {
+  let children = match root.borrow().as_shared().get_nodes() {
+    Some(children) => children,
+    None => return,
+  };
+
+  let mut nodes = Vec::with_capacity(children.len());
+  for child in children {
+    if child.borrow().is_rule() {
+      nodes.extend(flatten_rule(&child, None));
+    } else {
+      flatten(&child);
+      nodes.push(child);
+    }
+  }
+
+  if let Some(children) = root.borrow_mut().as_shared_mut().get_nodes_mut() {
+    *children = nodes;
+  }
+}
+
+#[derive(PartialEq)]
+enum Kind {
+  Rule,
+  AtRule,
+  Other,
+}
+
+fn kind(node: &Node) -> Kind {
+  match node {
+    Node::Rule(_) => Kind::Rule,
+    Node::AtRule(at_rule)
+      if at_rule.nodes.is_some()
+        && CONDITIONAL
+          .iter()
+          .any(|name| at_rule.name.eq_ignore_ascii_case(name)) =>
+    {
+      Kind::AtRule
+    }
+    _ => Kind::Other,
+  }
+}
+
+/// Resolves the selector of `rule` nested in a rule with the `parent`
+/// selector, and returns it followed by its unwrapped nested nodes.
+fn flatten_rule<'a>(
+  rule: &Rc<RefCell<Node<'a>>>,
+  parent: Option<&str>,
+) -> Vec<Rc<RefCell<Node<'a>>>> {
+  let selector = match rule.borrow_mut().as_rule_mut() {
+    Some(rule) => {
+      if let Some(parent) = parent {
+        rule.selector = resolve(&rule.selector, parent);
+        rule.raws.selector = None;
+      }
+      rule.selector.clone()
+    }
+    None => return vec![rule.clone()],
+  };
+  let children = rule.borrow().as_shared().get_nodes().unwrap_or_default();
+  if children
+    .iter()
+    .all(|child| kind(&child.borrow()) == Kind::Other)
+  {
+    return vec![rule.clone()];
+  }
+
+  let template = rule.borrow().clone();
+  let indent = indent(&rule.borrow());
+  let mut first = Some(rule.clone());
+  let semicolon = rule.borrow().as_rule().and_then(|rule| rule.raws.semicolon);
+  let nodes = unnest(
+    &template, &selector, &mut first, children, &indent, None, semicolon,
+  );
+  if first.is_some() {
+    // The rule had no declarations before its nested nodes and goes away,
+    // the first of them takes its place.
+    let before = rule.borrow().as_shared().as_raws().get_raw_before();
+    if let (Some(node), Some(before)) = (nodes.first(), before) {
+      node.borrow_mut().set_raw_before(before);
+    }
+  }
+  nodes
+}
+
+/// Splits the children of a rule with the `selector` into rules of
+/// declarations and unwrapped nested nodes. The first rule of declarations
+/// is `first` if they come before any nested node, the others are copies of
+/// `template` at `indent`.
+///
+/// `wrapped` is the indentation unit when the children are the body of an
+/// at-rule: declarations get wrapped in a rule one level deeper. Otherwise
+/// nested nodes move one level up, next to the rule.
+///
+/// Declarations followed by a nested node end with a semicolon, the last
+/// ones have the `semicolon` raw of the block.
+fn unnest<'a>(
+  template: &Node<'a>,
+  selector: &str,
+  first: &mut Option<Rc<RefCell<Node<'a>>>>,
+  children: Vec<Rc<RefCell<Node<'a>>>>,
+  indent: &str,
+  wrapped: Option<&str>,
+  semicolon: Option<bool>,
+) -> Vec<Rc<RefCell<Node<'a>>>> {
+  let mut nodes: Vec<Rc<RefCell<Node<'a>>>> = vec![];
+  let mut chunk: Option<Rc<RefCell<Node<'a>>>> = None;
+  for child in children {
+    let kind = kind(&child.borrow());
+    if kind != Kind::Other {
+      if let Some(chunk) = chunk.take() {
+        set_semicolon(&chunk, Some(true));
+      }
+    }
+    match kind {
+      Kind::Rule => {
+        for node in flatten_rule(&child, Some(selector)) {
+          if wrapped.is_none() {
+            outdent(&node, indent);
+          }
+          nodes.push(node);
+        }
+      }
+      Kind::AtRule => {
+        let own = self::indent(&child.borrow());
+        let inner = child
+          .borrow_mut()
+          .as_shared_mut()
+          .get_nodes_mut()
+          .map(std::mem::take)
+          .unwrap_or_default();
+        let inner_indent = inner
+          .first()
+          .map(|node| self::indent(&node.borrow()))
+          .unwrap_or_default();
+        let unit = inner_indent.strip_prefix(&own).unwrap_or_default();
+        let semicolon = child
+          .borrow()
+          .as_at_rule()
+          .and_then(|at_rule| at_rule.raws.semicolon);
+        let inner = unnest(
+          template,
+          selector,
+          &mut None,
+          inner,
+          &inner_indent,
+          Some(unit),
+          semicolon,
+        );
+        if let Some(children) = child.borrow_mut().as_shared_mut().get_nodes_mut() {
+          *children = inner;
+        }
+        if wrapped.is_none() {
+          outdent(&child, indent);
+        }
+        nodes.push(child);
+      }
+      Kind::Other => {
+        let chunk = chunk.get_or_insert_with(|| {
+          let rule = match first.take().filter(|_| nodes.is_empty()) {
+            Some(rule) => rule,
+            None => {
+              let before = match wrapped {
+                Some(_) => child.borrow().as_shared().as_raws().get_raw_before(),
+                None => None,
+              };
+              let before = before.unwrap_or_else(|| format!("\n{}", indent));
+              Rc::new(RefCell::new(copy(template, selector, before, wrapped)))
+            }
+          };
+          if let Some(children) = rule.borrow_mut().as_shared_mut().get_nodes_mut() {
+            children.clear();
+          }
+          nodes.push(rule.clone());
+          rule
+        });
+        if let Some(unit) = wrapped.filter(|unit| !unit.is_empty()) {
+          replace_raws(&mut child.borrow_mut(), "\n", &format!("\n{}", unit), true);
+        }
+        chunk.borrow_mut().push_child(child);
+      }
+    }
+  }
+  if let Some(chunk) = chunk {
+    set_semicolon(&chunk, semicolon);
+  }
+  nodes
+}
+
+/// A rule like `template` for declarations, a copy after a nested node or a
+/// wrapper in an at-rule which closes on the line it opens.
+fn copy<'a>(
+  template: &Node<'a>,
+  selector: &str,
+  before: String,
+  wrapped: Option<&str>,
+) -> Node<'a> {
+  let mut copy = template.clone();
+  if let Node::Rule(rule) = &mut copy {
+    rule.selector = selector.to_string();
+    rule.raws.selector = None;
+    if wrapped.is_some() {
+      rule.raws.after = Some(before.clone());
+    }
+    rule.raws.before = Some(before);
+  }
+  copy
+}
+
+fn set_semicolon(rule: &Rc<RefCell<Node>>, semicolon: Option<bool>) {
+  if let Some(rule) = rule.borrow_mut().as_rule_mut() {
+    rule.raws.semicolon = semicolon;
+  }
+}
+
+/// The indentation of a node, the last line of its `before` raw.
+fn indent(node: &Node) -> String {
+  let before = node
+    .as_shared()
+    .as_raws()
+    .get_raw_before()
+    .unwrap_or_default();
+  match before.rfind('\n') {
+    Some(newline) => before[newline + 1..].to_string(),
+    None => String::new(),
+  }
+}
+
+/// Moves a node nested in a rule at `indent` next to the rule.
+fn outdent(node: &Rc<RefCell<Node>>, indent: &str) {
+  let own = self::indent(&node.borrow());
+  if own.len() <= indent.len() || !own.starts_with(indent) {
+    return;
+  }
+  let mut node = node.borrow_mut();
+  let before = node
+    .as_shared()
+    .as_raws()
+    .get_raw_before()
+    .unwrap_or_default();
+  let lines = &before[..before.len() - own.len()];
+  node.set_raw_before(format!("{}{}", lines, indent));
+  replace_raws(
+    &mut node,
+    &format!("\n{}", own),
+    &format!("\n{}", indent),
+    false,
+  );
+}
+
+/// Replaces `from` with `to` in the `before` and `after` raws of the node
+/// and its descendants, skipping the node's own `before` unless `before`.
+fn replace_raws(node: &mut Node, from: &str, to: &str, before: bool) {
+  if before {
+    if let Some(raw) = node.as_shared().as_raws().get_raw_before() {
+      node.set_raw_before(raw.replace(from, to));
+    }
+  }
+  let after = match node {
+    Node::Rule(rule) => rule.raws.after.as_mut(),
+    Node::AtRule(at_rule) => at_rule.raws.after.as_mut(),
+    _ => None,
+  };
+  if let Some(after) = after {
+    *after = after.replace(from, to);
+  }
+  for child in node.as_shared().get_nodes().unwrap_or_default() {
+    replace_raws(&mut child.borrow_mut(), from, to, true);
+  }
+}
@@ -0,0 +1,7 @@
+mod flatten;
+mod plugin;
+mod resolve;
+
+pub use crate::flatten::flatten;
+pub use crate::plugin::Nesting;
+pub use crate::resolve::resolve;
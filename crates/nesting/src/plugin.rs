@@ -0,0 +1,19 @@
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Runs [`flatten`](crate::flatten) to unwrap nested rules.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Nesting;
+
+impl Plugin for Nesting {
+  fn name(&self) -> &str {
+    "nesting"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    crate::flatten(root);
+    Ok(())
+  }
+}
@@ -0,0 +1,193 @@
+use selector::{
+  Combinator, CombinatorKind, ComplexSelector, CompoundSelector, PseudoArgument, PseudoSelector,
+  SelectorList, SimpleSelector,
+};
+use tokenizer::list;
+
+/// Resolves the selector of a rule nested in a rule with the `parent`
+/// selector, following CSS Nesting.
+///
+/// Selectors without `&` are relative to the parent: `b` is `& b` and
+/// `> b` is `& > b`. The `&` of a single parent selector is replaced by it.
+/// A parent list expands into one selector per item if all of them have the
+/// same specificity, otherwise `&` becomes `:is(<parent>)`, which keeps the
+/// specificity of the nested rule.
+///
+/// Selectors that can not be parsed are resolved as text, with a parent
+/// list wrapped in `:is()`.
+pub fn resolve(selector: &str, parent: &str) -> String {
+  let (list, parents) = match (selector::parse(selector), selector::parse(parent)) {
+    (Ok(list), Ok(parents)) => (list, parents),
+    _ => {
+      let parent = match list::comma(parent).len() {
+        1 => parent.to_string(),
+        _ => format!(":is({})", parent),
+      };
+      return match selector.contains('&') {
+        true => selector.replace('&', &parent),
+        false => format!("{} {}", parent, selector),
+      };
+    }
+  };
+
+  let specificity = parents.selectors[0].specificity();
+  let expand = parents.selectors.len() == 1
+    || parents
+      .selectors
+      .iter()
+      .all(|parent| parent.specificity() == specificity && !has_pseudo_element(parent));
+
+  let mut selectors = vec![];
+  for selector in list.selectors {
+    let selector = absolute(selector);
+    if expand {
+      for parent in &parents.selectors {
+        selectors.push(substitute(&selector, parent));
+      }
+    } else {
+      let is = ComplexSelector::new(vec![CompoundSelector::new(vec![is(&parents)])]);
+      selectors.push(substitute(&selector, &is));
+    }
+  }
+  if parents.selectors.len() > 1 {
+    for (i, selector) in selectors.iter_mut().enumerate() {
+      selector.before = if i == 0 {
+        String::new()
+      } else {
+        " ".to_string()
+      };
+      selector.after.clear();
+    }
+  }
+  SelectorList::new(selectors).to_string()
+}
+
+/// Makes a selector without `&` relative to `&`.
+fn absolute(mut selector: ComplexSelector) -> ComplexSelector {
+  if contains_nesting(&selector) {
+    return selector;
+  }
+  let combinator = selector.compounds[0]
+    .combinator
+    .get_or_insert_with(|| Combinator::new(CombinatorKind::Descendant));
+  if !combinator.raw.starts_with(char::is_whitespace) {
+    combinator.raw.insert(0, ' ');
+  }
+  selector
+    .compounds
+    .insert(0, CompoundSelector::new(vec![SimpleSelector::Nesting]));
+  selector
+}
+
+fn contains_nesting(selector: &ComplexSelector) -> bool {
+  let mut found = false;
+  selector.walk(&mut |simple| found |= *simple == SimpleSelector::Nesting);
+  found
+}
+
+fn has_pseudo_element(selector: &ComplexSelector) -> bool {
+  selector
+    .compounds
+    .iter()
+    .flat_map(|compound| &compound.selectors)
+    .any(|simple| matches!(simple, SimpleSelector::PseudoElement(_)))
+}
+
+fn is(list: &SelectorList) -> SimpleSelector {
+  let mut list = list.clone();
+  for (i, selector) in list.selectors.iter_mut().enumerate() {
+    selector.before = if i == 0 {
+      String::new()
+    } else {
+      " ".to_string()
+    };
+    selector.after.clear();
+  }
+  SimpleSelector::PseudoClass(PseudoSelector::new(
+    "is",
+    Some(PseudoArgument::Selectors(list)),
+  ))
+}
+
+/// Replaces every `&` of `selector` with `parent`.
+fn substitute(selector: &ComplexSelector, parent: &ComplexSelector) -> ComplexSelector {
+  let mut compounds = vec![];
+  for compound in &selector.compounds {
+    let mut compound = compound.clone();
+    for simple in &mut compound.selectors {
+      if let SimpleSelector::PseudoClass(pseudo) | SimpleSelector::PseudoElement(pseudo) = simple {
+        if let Some(list) = pseudo.selectors_mut() {
+          for inner in &mut list.selectors {
+            if contains_nesting(inner) {
+              *inner = substitute(inner, parent);
+            }
+          }
+        }
+      }
+    }
+
+    if !compound.selectors.contains(&SimpleSelector::Nesting) {
+      compounds.push(compound);
+      continue;
+    }
+    let others: Vec<_> = compound
+      .selectors
+      .into_iter()
+      .filter(|simple| *simple != SimpleSelector::Nesting)
+      .collect();
+    let mut inserted = parent.compounds.clone();
+    inserted[0].combinator = compound.combinator;
+    let last = inserted.last_mut().unwrap();
+    if others.is_empty() {
+      compounds.extend(inserted);
+    } else if let Some(merged) = merge(&last.selectors, &others) {
+      last.selectors = merged;
+      compounds.extend(inserted);
+    } else {
+      let mut selectors = others;
+      let position = selectors
+        .iter()
+        .take_while(|simple| is_type(simple))
+        .count();
+      let parent = SelectorList::new(vec![parent.clone()]);
+      selectors.insert(position, is(&parent));
+      compounds.push(CompoundSelector {
+        combinator: inserted.swap_remove(0).combinator,
+        selectors,
+      });
+    }
+  }
+  ComplexSelector {
+    compounds,
+    before: selector.before.clone(),
+    after: selector.after.clone(),
+  }
+}
+
+fn is_type(simple: &SimpleSelector) -> bool {
+  matches!(
+    simple,
+    SimpleSelector::Type { .. } | SimpleSelector::Universal { .. }
+  )
+}
+
+/// Joins the simple selectors of the parent's last compound with the others
+/// of the compound with `&`, `None` if they do not make a valid compound.
+fn merge(parent: &[SimpleSelector], others: &[SimpleSelector]) -> Option<Vec<SimpleSelector>> {
+  let parent_type = parent.iter().any(is_type);
+  let other_type = others.iter().any(is_type);
+  let parent_element = parent
+    .iter()
+    .any(|simple| matches!(simple, SimpleSelector::PseudoElement(_)));
+  if (parent_type && other_type) || parent_element {
+    return None;
+  }
+  let mut merged: Vec<_> = others
+    .iter()
+    .filter(|simple| is_type(simple))
+    .cloned()
+    .collect();
+  merged.extend(parent.iter().cloned());
+  merged.extend(others.iter().filter(|simple| !is_type(simple)).cloned());
+  Some(merged)
+}
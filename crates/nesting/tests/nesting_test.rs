@@ -0,0 +1,95 @@
+use nesting::{resolve, Nesting};
+use postcss::Processor;
+
+fn process(css: &str) -> String {
+  Processor::run_one(Nesting, css).unwrap().css
+}
+
+#[test]
+fn resolves_selectors() {
+  assert_eq!(resolve("b", "a"), "a b");
+  assert_eq!(resolve("> b, + c", "a"), "a > b, a + c");
+  assert_eq!(resolve("&:hover", ".a .b"), ".a .b:hover");
+  assert_eq!(resolve(".x &", ".a .b"), ".x .a .b");
+  assert_eq!(resolve("div&", ".a"), "div.a");
+  assert_eq!(resolve("div&", "a span"), "div:is(a span)");
+  assert_eq!(resolve("& + &", ".a"), ".a + .a");
+  assert_eq!(resolve(":not(&) b", ".a"), ":not(.a) b");
+  assert_eq!(resolve("&:hover", ".a, .b"), ".a:hover, .b:hover");
+  assert_eq!(resolve("& b", ".a, #b"), ":is(.a, #b) b");
+  assert_eq!(resolve("&::before", "a::after"), ":is(a::after)::before");
+  assert_eq!(resolve("&div", ".a"), "div.a");
+  // selectors that can not be parsed
+  assert_eq!(resolve("& %x", ".a"), ".a %x");
+  assert_eq!(resolve("& %x", ".a, .b"), ":is(.a, .b) %x");
+  assert_eq!(resolve("%x", ".a, .b"), ":is(.a, .b) %x");
+  assert_eq!(resolve("& %x", ":is(.a, .b)"), ":is(.a, .b) %x");
+}
+
+#[test]
+fn unwraps_nested_rules() {
+  let css = "\
+.card {
+  color: red;
+  &:hover {
+    color: blue;
+    .icon { fill: blue }
+  }
+  > .title { font-weight: bold }
+  margin: 0;
+}
+";
+  assert_eq!(
+    process(css),
+    "\
+.card {
+  color: red;
+}
+.card:hover {
+  color: blue;
+}
+.card:hover .icon { fill: blue }
+.card > .title { font-weight: bold }
+.card {
+  margin: 0;
+}
+"
+  );
+}
+
+#[test]
+fn unwraps_nested_at_rules() {
+  let css = "\
+a {
+  color: red;
+  @media (min-width: 1px) {
+    color: blue;
+    b { c: d }
+    @supports (display: grid) { display: grid }
+  }
+}
+@media print { .x { .y { z: 1 } } }
+";
+  assert_eq!(
+    process(css),
+    "\
+a {
+  color: red;
+}
+@media (min-width: 1px) {
+  a {
+    color: blue;
+  }
+  a b { c: d }
+  @supports (display: grid) { a { display: grid } }
+}
+@media print { .x .y { z: 1 } }
+"
+  );
+}
+
+#[test]
+fn keeps_plain_rules() {
+  let css = "a { b: c }\n@font-face { font-family: x }\n@keyframes k { from { d: e } }";
+  assert_eq!(process(css), css);
+}
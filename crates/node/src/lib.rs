@@ -1,3 +1,7 @@
 mod node;
+pub mod rename;
+pub mod rewrite;
+pub mod shorthand;
+pub mod tree;
 pub use crate::node::*;
 pub mod macros;
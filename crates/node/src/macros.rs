@@ -16,10 +16,18 @@ macro_rules! impl_node_traits {
         self.nodes.clone()
       }
 
+      fn get_nodes_mut(&mut self) -> Option<&mut Vec<Rc<RefCell<Node<'a>>>>> {
+        self.nodes.as_mut()
+      }
+
       fn get_source(&self) -> Option<Source<'a>> {
         self.source.clone()
       }
 
+      fn get_source_mut(&mut self) -> Option<&mut Source<'a>> {
+        self.source.as_mut()
+      }
+
       fn set_source(&mut self, source: Source<'a>) {
         self.source = Some(source);
       }
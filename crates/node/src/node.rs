@@ -1,8 +1,11 @@
 use crate::{enum_mapping, impl_node_traits};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::{Rc, Weak};
+use tokenizer::error::CssSyntaxError;
 use tokenizer::input::Input;
+use tokenizer::warning::Warning;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Position {
@@ -86,7 +89,7 @@ impl<'a> Node<'a> {
   }
 
   pub fn set_source_end(&mut self, end: Option<Position>) {
-    if let Some(source) = self.as_shared_mut().get_source().as_mut() {
+    if let Some(source) = self.as_shared_mut().get_source_mut() {
       source.end = end
     }
   }
@@ -95,8 +98,118 @@ impl<'a> Node<'a> {
     self.as_shared_mut().as_raws_mut().set_raw_before(before);
   }
 
+  /// Takes the children out of the node, to put them in its place in the
+  /// parent. The first one takes over the formatting before the node.
+  pub fn take_children(&mut self) -> Vec<Rc<RefCell<Node<'a>>>> {
+    let before = self.as_shared().as_raws().get_raw_before();
+    let nodes = self
+      .as_shared_mut()
+      .get_nodes_mut()
+      .map(std::mem::take)
+      .unwrap_or_default();
+    if let (Some(first), Some(before)) = (nodes.first(), before) {
+      first.borrow_mut().set_raw_before(before);
+    }
+    nodes
+  }
+
+  /// Clones the node with all its children, unlike `clone` which shares the
+  /// children with the original.
+  pub fn deep_clone(&self) -> Node<'a> {
+    let mut node = self.clone();
+    if let Some(children) = node.as_shared_mut().get_nodes_mut() {
+      for child in children.iter_mut() {
+        let clone = child.borrow().deep_clone();
+        *child = Rc::new(RefCell::new(clone));
+      }
+    }
+    node
+  }
+
+  /// Clones the node with all its children into a tree owning the CSS it was
+  /// parsed from, so that it can outlive that text. The nodes parsed from the
+  /// same input keep sharing one copy of it.
+  pub fn deep_clone_owned<'b>(&self) -> Node<'b> {
+    self.clone_owned(&mut HashMap::new())
+  }
+
+  fn clone_owned<'b>(&self, inputs: &mut Inputs<'a, 'b>) -> Node<'b> {
+    match self {
+      Node::Decl(decl) => Node::Decl(Declaration {
+        prop: decl.prop.clone(),
+        value: decl.value.clone(),
+        important: decl.important,
+        variable: decl.variable,
+        nodes: owned_nodes(&decl.nodes, inputs),
+        parent: None,
+        source: owned_source(&decl.source, inputs),
+        raws: decl.raws.clone(),
+      }),
+      Node::Rule(rule) => Node::Rule(Rule {
+        selector: rule.selector.clone(),
+        selectors: rule.selectors.clone(),
+        nodes: owned_nodes(&rule.nodes, inputs),
+        parent: None,
+        source: owned_source(&rule.source, inputs),
+        raws: rule.raws.clone(),
+      }),
+      Node::AtRule(at_rule) => Node::AtRule(AtRule {
+        nodes: owned_nodes(&at_rule.nodes, inputs),
+        parent: None,
+        source: owned_source(&at_rule.source, inputs),
+        name: at_rule.name.clone(),
+        params: at_rule.params.clone(),
+        raws: at_rule.raws.clone(),
+      }),
+      Node::Comment(comment) => Node::Comment(Comment {
+        nodes: owned_nodes(&comment.nodes, inputs),
+        parent: None,
+        source: owned_source(&comment.source, inputs),
+        text: comment.text.clone(),
+        raws: comment.raws.clone(),
+      }),
+      Node::Document(document) => Node::Document(Document {
+        nodes: owned_nodes(&document.nodes, inputs),
+        parent: None,
+        source: owned_source(&document.source, inputs),
+        name: document.name.clone(),
+        raws: document.raws.clone(),
+      }),
+      Node::Root(root) => Node::Root(Root {
+        nodes: owned_nodes(&root.nodes, inputs),
+        parent: None,
+        source: owned_source(&root.source, inputs),
+        raws: root.raws.clone(),
+      }),
+    }
+  }
+
+  /// Returns a `CssSyntaxError` pointing to the start of this node.
+  pub fn error(&self, reason: &str) -> CssSyntaxError {
+    match self.as_shared().get_source() {
+      Some(Source {
+        input,
+        start: Some(start),
+        ..
+      }) => input.borrow().error(reason, start.line, start.column),
+      _ => CssSyntaxError::new(reason, 1, 1),
+    }
+  }
+
+  /// Returns a `Warning` pointing to the start of this node.
+  pub fn warning(&self, text: &str) -> Warning {
+    let source = self.as_shared().get_source();
+    let (line, column) = source
+      .as_ref()
+      .and_then(|source| source.start.as_ref())
+      .map_or((1, 1), |start| (start.line as u32, start.column as u32));
+    let mut warning = Warning::new(text, line, column);
+    warning.file = source.and_then(|source| source.input.borrow().file().map(str::to_string));
+    warning
+  }
+
   pub fn push_child(&mut self, node: Rc<RefCell<Node<'a>>>) {
-    if let Some(children) = self.as_shared().get_nodes().as_mut() {
+    if let Some(children) = self.as_shared_mut().get_nodes_mut() {
       children.push(node)
     }
   }
@@ -124,7 +237,8 @@ impl<'a> Node<'a> {
   pub fn as_document(&self) -> Option<&Document<'a>> {
     enum_mapping!(self, Self::Document(v) => v)
   }
-  pub fn as_root_mut(&self) -> Option<&Root<'a>> {
+
+  pub fn as_root_mut(&mut self) -> Option<&mut Root<'a>> {
     enum_mapping!(self, Self::Root(v) => v)
   }
 
@@ -198,14 +312,16 @@ fn is_false(boolean: &bool) -> bool {
 /// CommonBehaviors
 pub trait NodeTrait<'a> {
   fn get_nodes(&self) -> Option<Vec<Rc<RefCell<Node<'a>>>>>;
+  fn get_nodes_mut(&mut self) -> Option<&mut Vec<Rc<RefCell<Node<'a>>>>>;
   fn get_source(&self) -> Option<Source<'a>>;
+  fn get_source_mut(&mut self) -> Option<&mut Source<'a>>;
   fn set_source(&mut self, source: Source<'a>);
   fn as_raws(&self) -> &dyn RawBefore;
   fn as_raws_mut(&mut self) -> &mut dyn RawBefore;
   fn as_trait(&'a self) -> &dyn NodeTrait<'a>;
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Declaration<'a> {
   /// The declaration's property name.
@@ -274,7 +390,7 @@ pub struct Rule<'a> {
 }
 impl_node_traits!(Rule);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AtRule<'a> {
   /// An array containing the node’s children.
@@ -324,7 +440,7 @@ pub struct Comment<'a> {
 }
 impl_node_traits!(Comment);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Document<'a> {
   /// An array containing the node’s children.
@@ -352,7 +468,7 @@ pub struct Document<'a> {
 }
 impl_node_traits!(Document);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Root<'a> {
   /// An array containing the node’s children.
@@ -372,6 +488,35 @@ pub struct Root<'a> {
 }
 impl_node_traits!(Root);
 
+/// The owned copies of the inputs made by `Node::deep_clone_owned`.
+type Inputs<'a, 'b> = HashMap<*const RefCell<Input<'a>>, Rc<RefCell<Input<'b>>>>;
+
+fn owned_nodes<'a, 'b>(
+  nodes: &Option<Vec<Rc<RefCell<Node<'a>>>>>,
+  inputs: &mut Inputs<'a, 'b>,
+) -> Option<Vec<Rc<RefCell<Node<'b>>>>> {
+  nodes.as_ref().map(|nodes| {
+    nodes
+      .iter()
+      .map(|node| Rc::new(RefCell::new(node.borrow().clone_owned(inputs))))
+      .collect()
+  })
+}
+
+fn owned_source<'a, 'b>(
+  source: &Option<Source<'a>>,
+  inputs: &mut Inputs<'a, 'b>,
+) -> Option<Source<'b>> {
+  source.as_ref().map(|source| Source {
+    input: inputs
+      .entry(Rc::as_ptr(&source.input))
+      .or_insert_with(|| Rc::new(RefCell::new(source.input.borrow().to_owned_input())))
+      .clone(),
+    start: source.start.clone(),
+    end: source.end.clone(),
+  })
+}
+
 pub trait RawBefore {
   fn get_raw_before(&self) -> Option<String>;
   fn set_raw_before(&mut self, value: String);
@@ -470,7 +615,7 @@ pub struct RuleRaws {
   #[serde(skip_serializing_if = "Option::is_none")]
   pub semicolon: Option<bool>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub own_semicolon: Option<bool>,
+  pub own_semicolon: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub selector: Option<RawValue>,
 }
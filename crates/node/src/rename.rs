@@ -0,0 +1,32 @@
+//! Renaming the identifiers of values, for the plugins that rename classes
+//! and keyframes.
+
+use std::collections::HashMap;
+
+/// Returns `true` for the name of `@keyframes`, with or without a vendor
+/// prefix.
+pub fn is_keyframes(name: &str) -> bool {
+  let name = name.to_ascii_lowercase();
+  name == "keyframes" || (name.starts_with('-') && name.ends_with("-keyframes"))
+}
+
+/// Replaces the identifiers of a value found in `names`.
+pub fn rename_idents(value: &str, names: &HashMap<String, String>) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut ident = String::new();
+  for c in value.chars().chain(Some('\0')) {
+    if c.is_alphanumeric() || c == '-' || c == '_' {
+      ident.push(c);
+      continue;
+    }
+    match names.get(&ident) {
+      Some(name) => result.push_str(name),
+      None => result.push_str(&ident),
+    }
+    ident.clear();
+    if c != '\0' {
+      result.push(c);
+    }
+  }
+  result
+}
@@ -0,0 +1,93 @@
+//! Rewriting the values of declarations, keeping the original ones or not,
+//! for the plugins that add fallbacks or convert values.
+
+use crate::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Where a rewritten declaration goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preserve {
+  /// The value of the declaration is replaced.
+  No,
+  /// The declaration is kept after a copy with the new value, its fallback.
+  /// No copy is added if the previous declaration is the same already.
+  Before,
+  /// The declaration is kept before a copy with the new value, which wins
+  /// in the browsers knowing it.
+  After,
+}
+
+/// What to do with a child of the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Visit {
+  /// Rewrites the value of the declaration.
+  Rewrite(String),
+  /// Visits the children of the node.
+  Descend,
+  /// Leaves the node and its descendants alone.
+  Skip,
+}
+
+/// Calls `callback` on every descendant of `parent`, and rewrites the
+/// declarations it returns a new value for, see [`Preserve`].
+pub fn rewrite_decls<'a, F>(parent: &Rc<RefCell<Node<'a>>>, preserve: Preserve, callback: &mut F)
+where
+  F: FnMut(&Rc<RefCell<Node<'a>>>) -> Visit,
+{
+  let children = match parent.borrow().as_shared().get_nodes() {
+    Some(children) => children,
+    None => return,
+  };
+  let mut nodes: Vec<Rc<RefCell<Node<'a>>>> = Vec::with_capacity(children.len());
+  for child in children {
+    let value = match callback(&child) {
+      Visit::Rewrite(value) if child.borrow().as_decl().is_some() => value,
+      Visit::Descend => {
+        rewrite_decls(&child, preserve, callback);
+        nodes.push(child);
+        continue;
+      }
+      _ => {
+        nodes.push(child);
+        continue;
+      }
+    };
+    if preserve == Preserve::No {
+      set_value(&mut child.borrow_mut(), value);
+      nodes.push(child);
+      continue;
+    }
+    let mut copy = child.borrow().deep_clone();
+    set_value(&mut copy, value);
+    if preserve == Preserve::After {
+      nodes.push(child);
+      nodes.push(Rc::new(RefCell::new(copy)));
+      continue;
+    }
+    let exists = nodes
+      .last()
+      .is_some_and(|last| match (&*last.borrow(), &copy) {
+        (Node::Decl(last), Node::Decl(copy)) => last.prop == copy.prop && last.value == copy.value,
+        _ => false,
+      });
+    if !exists {
+      if nodes.is_empty() && parent.borrow().is_root() {
+        // The copy is the first node of the stylesheet now.
+        child.borrow_mut().set_raw_before("\n".to_string());
+      }
+      nodes.push(Rc::new(RefCell::new(copy)));
+    }
+    nodes.push(child);
+  }
+  if let Some(children) = parent.borrow_mut().as_shared_mut().get_nodes_mut() {
+    *children = nodes;
+  }
+}
+
+fn set_value(node: &mut Node, value: String) {
+  if let Some(decl) = node.as_decl_mut() {
+    decl.value = value;
+    decl.raws.value = None;
+  }
+}
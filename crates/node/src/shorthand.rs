@@ -0,0 +1,820 @@
+//! Shorthand properties, their longhands, and the expansion of shorthand
+//! values into longhand values and back.
+
+use crate::Declaration;
+use value_parser::{dimension, split, trim, Node as ValueNode};
+
+/// A shorthand property and the longhands its value sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shorthand {
+  pub name: &'static str,
+  /// The longhands, in the order of [`expand`] and [`collapse`]. They may be
+  /// shorthands themselves, like `border-width` for `border`.
+  pub longhands: &'static [&'static str],
+  /// `true` if the shorthand also resets properties it can not set, like
+  /// `font-kerning` for `font`, so that collapsing longhands into it changes
+  /// more than them.
+  pub resets: bool,
+}
+
+const fn shorthand(
+  name: &'static str,
+  longhands: &'static [&'static str],
+  resets: bool,
+) -> Shorthand {
+  Shorthand {
+    name,
+    longhands,
+    resets,
+  }
+}
+
+pub const SHORTHANDS: &[Shorthand] = &[
+  shorthand(
+    "background",
+    &[
+      "background-image",
+      "background-position",
+      "background-size",
+      "background-repeat",
+      "background-attachment",
+      "background-origin",
+      "background-clip",
+      "background-color",
+    ],
+    false,
+  ),
+  // `border` resets `border-image`, which it can only set to `none`.
+  shorthand(
+    "border",
+    &[
+      "border-width",
+      "border-style",
+      "border-color",
+      "border-image",
+    ],
+    false,
+  ),
+  shorthand(
+    "border-color",
+    &[
+      "border-top-color",
+      "border-right-color",
+      "border-bottom-color",
+      "border-left-color",
+    ],
+    false,
+  ),
+  shorthand(
+    "border-style",
+    &[
+      "border-top-style",
+      "border-right-style",
+      "border-bottom-style",
+      "border-left-style",
+    ],
+    false,
+  ),
+  shorthand(
+    "border-width",
+    &[
+      "border-top-width",
+      "border-right-width",
+      "border-bottom-width",
+      "border-left-width",
+    ],
+    false,
+  ),
+  shorthand("flex", &["flex-grow", "flex-shrink", "flex-basis"], false),
+  shorthand(
+    "font",
+    &[
+      "font-style",
+      "font-variant",
+      "font-weight",
+      "font-stretch",
+      "font-size",
+      "line-height",
+      "font-family",
+    ],
+    true,
+  ),
+  shorthand(
+    "grid-template",
+    &[
+      "grid-template-rows",
+      "grid-template-columns",
+      "grid-template-areas",
+    ],
+    false,
+  ),
+  shorthand("inset", &["top", "right", "bottom", "left"], false),
+  shorthand(
+    "margin",
+    &["margin-top", "margin-right", "margin-bottom", "margin-left"],
+    false,
+  ),
+  shorthand(
+    "padding",
+    &[
+      "padding-top",
+      "padding-right",
+      "padding-bottom",
+      "padding-left",
+    ],
+    false,
+  ),
+];
+
+/// The shorthand named `name`, in any case.
+pub fn find(name: &str) -> Option<&'static Shorthand> {
+  SHORTHANDS
+    .iter()
+    .find(|shorthand| shorthand.name.eq_ignore_ascii_case(name))
+}
+
+const CSS_WIDE: &[&str] = &["inherit", "initial", "unset", "revert", "revert-layer"];
+
+/// The top-level components of a value, or `None` if it has comments or
+/// substitutions like `var()`, whose values are only known at computed
+/// time.
+fn components(value: &str) -> Option<Vec<ValueNode>> {
+  let mut parsed = value_parser::parse(value);
+  let mut substituted = false;
+  parsed.walk(|node| {
+    match node {
+      ValueNode::Function(function) => {
+        substituted |= matches!(function.lower_name().as_str(), "var" | "env" | "attr")
+      }
+      ValueNode::Comment { .. } => substituted = true,
+      _ => {}
+    }
+    !substituted
+  });
+  if substituted {
+    return None;
+  }
+  Some(
+    parsed
+      .nodes
+      .into_iter()
+      .filter(|node| !node.is_space())
+      .collect(),
+  )
+}
+
+/// The nodes as a value, with spaces between them and commas after the
+/// node before them.
+fn join(nodes: &[ValueNode]) -> String {
+  let mut value = String::new();
+  for node in nodes {
+    if !value.is_empty() && !matches!(node, ValueNode::Div(',')) {
+      value.push(' ');
+    }
+    value.push_str(&node.to_string());
+  }
+  value
+}
+
+fn lower_word(node: &ValueNode) -> Option<String> {
+  node.as_word().map(str::to_ascii_lowercase)
+}
+
+fn is_number(node: &ValueNode) -> bool {
+  node
+    .as_word()
+    .and_then(dimension)
+    .is_some_and(|d| d.unit.is_empty())
+}
+
+/// Returns `true` for lengths and percentages, including a unitless `0`.
+fn is_length(node: &ValueNode) -> bool {
+  node
+    .as_word()
+    .and_then(dimension)
+    .is_some_and(|d| !d.unit.is_empty() || d.value == 0.0)
+    || node.as_function().is_some_and(|function| {
+      matches!(
+        function.lower_name().as_str(),
+        "calc" | "min" | "max" | "clamp"
+      )
+    })
+}
+
+/// The longhand values of a shorthand value, in the order of the
+/// shorthand's `longhands`, or `None` if the value can not be expanded.
+pub fn expand(shorthand: &Shorthand, value: &str) -> Option<Vec<String>> {
+  let nodes = components(value)?;
+  if let [node] = nodes.as_slice() {
+    if let Some(keyword) = lower_word(node).filter(|word| CSS_WIDE.contains(&word.as_str())) {
+      return Some(vec![keyword; shorthand.longhands.len()]);
+    }
+  }
+  match shorthand.name {
+    "background" => expand_background(&nodes),
+    "border" => expand_border(&nodes),
+    "flex" => expand_flex(&nodes),
+    "font" => expand_font(&nodes),
+    "grid-template" => expand_grid_template(&nodes),
+    _ => expand_box(&nodes),
+  }
+}
+
+/// The shortest value of a shorthand for longhand values in the order of
+/// its `longhands`, or `None` if the shorthand can not express them.
+pub fn collapse(shorthand: &Shorthand, values: &[String]) -> Option<String> {
+  if values.len() != shorthand.longhands.len() {
+    return None;
+  }
+  let values = values
+    .iter()
+    .map(|value| components(value).map(|nodes| join(&nodes)))
+    .collect::<Option<Vec<_>>>()?;
+  let keywords = values
+    .iter()
+    .filter(|value| CSS_WIDE.contains(&value.to_ascii_lowercase().as_str()))
+    .count();
+  if keywords > 0 {
+    let first = values[0].to_ascii_lowercase();
+    let same = values
+      .iter()
+      .all(|value| value.eq_ignore_ascii_case(&first));
+    return (keywords == values.len() && same).then_some(first);
+  }
+  match shorthand.name {
+    "background" => collapse_background(&values),
+    "border" => collapse_border(&values),
+    "flex" => collapse_flex(&values),
+    "font" => collapse_font(&values),
+    "grid-template" => collapse_grid_template(&values),
+    _ => collapse_box(&values),
+  }
+}
+
+fn expand_box(nodes: &[ValueNode]) -> Option<Vec<String>> {
+  if nodes.is_empty()
+    || nodes.len() > 4
+    || nodes.iter().any(|node| matches!(node, ValueNode::Div(_)))
+  {
+    return None;
+  }
+  let side = |i: usize| nodes[i].to_string();
+  let top = side(0);
+  let right = if nodes.len() > 1 {
+    side(1)
+  } else {
+    top.clone()
+  };
+  let bottom = if nodes.len() > 2 {
+    side(2)
+  } else {
+    top.clone()
+  };
+  let left = if nodes.len() > 3 {
+    side(3)
+  } else {
+    right.clone()
+  };
+  Some(vec![top, right, bottom, left])
+}
+
+fn collapse_box(values: &[String]) -> Option<String> {
+  if values.iter().any(|value| value.contains(' ')) {
+    return None;
+  }
+  let (top, right, bottom, left) = (&values[0], &values[1], &values[2], &values[3]);
+  let sides: &[&String] = if left != right {
+    &[top, right, bottom, left]
+  } else if bottom != top {
+    &[top, right, bottom]
+  } else if right != top {
+    &[top, right]
+  } else {
+    &[top]
+  };
+  Some(
+    sides
+      .iter()
+      .map(|side| side.as_str())
+      .collect::<Vec<_>>()
+      .join(" "),
+  )
+}
+
+const BORDER_STYLES: &[&str] = &[
+  "none", "hidden", "dotted", "dashed", "solid", "double", "groove", "ridge", "inset", "outset",
+];
+
+fn expand_border(nodes: &[ValueNode]) -> Option<Vec<String>> {
+  let (mut width, mut style, mut color) = (None, None, None);
+  for node in nodes {
+    let word = lower_word(node).unwrap_or_default();
+    let slot = if BORDER_STYLES.contains(&word.as_str()) {
+      &mut style
+    } else if is_length(node) || ["thin", "medium", "thick"].contains(&word.as_str()) {
+      &mut width
+    } else if matches!(node, ValueNode::Div(_)) {
+      return None;
+    } else {
+      &mut color
+    };
+    if slot.replace(node.to_string()).is_some() {
+      return None;
+    }
+  }
+  Some(vec![
+    width.unwrap_or_else(|| "medium".to_string()),
+    style.unwrap_or_else(|| "none".to_string()),
+    color.unwrap_or_else(|| "currentcolor".to_string()),
+    "none".to_string(),
+  ])
+}
+
+fn collapse_border(values: &[String]) -> Option<String> {
+  let (values, image) = values.split_at(3);
+  if values.iter().any(|value| value.contains(' ')) || !image[0].eq_ignore_ascii_case("none") {
+    return None;
+  }
+  let initial = ["medium", "none", "currentcolor"];
+  let parts: Vec<_> = values
+    .iter()
+    .zip(initial)
+    .filter(|(value, initial)| !value.eq_ignore_ascii_case(initial))
+    .map(|(value, _)| value.as_str())
+    .collect();
+  Some(match parts.is_empty() {
+    true => "none".to_string(),
+    false => parts.join(" "),
+  })
+}
+
+fn expand_flex(nodes: &[ValueNode]) -> Option<Vec<String>> {
+  let values = |grow: &str, shrink: &str, basis: &str| {
+    Some(vec![
+      grow.to_string(),
+      shrink.to_string(),
+      basis.to_string(),
+    ])
+  };
+  if let [node] = nodes {
+    match lower_word(node).as_deref() {
+      Some("none") => return values("0", "0", "auto"),
+      Some("auto") => return values("1", "1", "auto"),
+      _ => {}
+    }
+  }
+  if nodes.is_empty() || nodes.len() > 3 {
+    return None;
+  }
+  // The basis comes first or last, the grow and shrink factors together.
+  let basis_at = nodes.iter().position(|node| !is_number(node));
+  let basis = match basis_at {
+    Some(i) if i == 0 || i == nodes.len() - 1 => {
+      if nodes.iter().filter(|node| !is_number(node)).count() > 1 {
+        return None;
+      }
+      nodes[i].to_string()
+    }
+    Some(_) => return None,
+    None if nodes.len() == 3 => nodes[2].to_string(),
+    None => "0%".to_string(),
+  };
+  let numbers: Vec<_> = nodes
+    .iter()
+    .enumerate()
+    .filter(|(i, _)| Some(*i) != basis_at && !(basis_at.is_none() && *i == 2))
+    .map(|(_, node)| node.to_string())
+    .collect();
+  let grow = numbers.first().map_or("1", String::as_str);
+  let shrink = numbers.get(1).map_or("1", String::as_str);
+  values(grow, shrink, &basis)
+}
+
+fn collapse_flex(values: &[String]) -> Option<String> {
+  let (grow, shrink, basis) = (&values[0], &values[1], &values[2]);
+  let number = |value: &str| dimension(value).is_some_and(|d| d.unit.is_empty());
+  if !number(grow) || !number(shrink) || basis.contains(' ') {
+    return None;
+  }
+  Some(match (grow.as_str(), shrink.as_str(), basis.as_str()) {
+    ("0", "0", "auto") => "none".to_string(),
+    ("1", "1", "auto") => "auto".to_string(),
+    (grow, "1", "0%") => grow.to_string(),
+    (grow, shrink, "0%") => format!("{} {}", grow, shrink),
+    (grow, "1", basis) if !number(basis) => format!("{} {}", grow, basis),
+    (grow, shrink, basis) => format!("{} {} {}", grow, shrink, basis),
+  })
+}
+
+const FONT_STRETCHES: &[&str] = &[
+  "ultra-condensed",
+  "extra-condensed",
+  "condensed",
+  "semi-condensed",
+  "semi-expanded",
+  "expanded",
+  "extra-expanded",
+  "ultra-expanded",
+];
+
+const FONT_SIZES: &[&str] = &[
+  "xx-small",
+  "x-small",
+  "small",
+  "medium",
+  "large",
+  "x-large",
+  "xx-large",
+  "xxx-large",
+  "larger",
+  "smaller",
+];
+
+fn expand_font(nodes: &[ValueNode]) -> Option<Vec<String>> {
+  // style, variant, weight and stretch
+  let mut prefix: [Option<String>; 4] = Default::default();
+  let mut normals = 0;
+  let mut i = 0;
+  let size = loop {
+    let node = nodes.get(i)?;
+    i += 1;
+    let word = lower_word(node).unwrap_or_default();
+    let slot = match word.as_str() {
+      "normal" => {
+        normals += 1;
+        continue;
+      }
+      "italic" | "oblique" => 0,
+      "small-caps" => 1,
+      "bold" | "bolder" | "lighter" => 2,
+      _ if is_number(node) => 2,
+      word if FONT_STRETCHES.contains(&word) => 3,
+      word if FONT_SIZES.contains(&word) || is_length(node) => break node.to_string(),
+      _ => return None,
+    };
+    if prefix[slot].replace(node.to_string()).is_some() {
+      return None;
+    }
+  };
+  if normals + prefix.iter().flatten().count() > 4 {
+    return None;
+  }
+  let mut line_height = "normal".to_string();
+  if let Some(ValueNode::Div('/')) = nodes.get(i) {
+    line_height = nodes.get(i + 1)?.to_string();
+    i += 2;
+  }
+  let family = &nodes[i..];
+  if family.is_empty()
+    || family
+      .iter()
+      .any(|node| matches!(node, ValueNode::Div('/')))
+  {
+    return None;
+  }
+  let [style, variant, weight, stretch] =
+    prefix.map(|value| value.unwrap_or_else(|| "normal".to_string()));
+  Some(vec![
+    style,
+    variant,
+    weight,
+    stretch,
+    size,
+    line_height,
+    join(family),
+  ])
+}
+
+fn collapse_font(values: &[String]) -> Option<String> {
+  let (style, variant, weight, stretch) = (&values[0], &values[1], &values[2], &values[3]);
+  let (size, line_height, family) = (&values[4], &values[5], &values[6]);
+  let normal = |value: &str| value.eq_ignore_ascii_case("normal");
+  if !(normal(variant) || variant.eq_ignore_ascii_case("small-caps"))
+    || !(normal(stretch) || FONT_STRETCHES.contains(&stretch.to_ascii_lowercase().as_str()))
+    || [style, weight, size, line_height]
+      .iter()
+      .any(|value| value.contains(' '))
+    || family.is_empty()
+  {
+    return None;
+  }
+  let mut parts: Vec<String> = [style, variant, weight, stretch]
+    .into_iter()
+    .filter(|value| !normal(value) && value.as_str() != "400")
+    .cloned()
+    .collect();
+  parts.push(match normal(line_height) {
+    true => size.clone(),
+    false => format!("{}/{}", size, line_height),
+  });
+  parts.push(family.clone());
+  Some(parts.join(" "))
+}
+
+const IMAGE_FUNCTIONS: &[&str] = &[
+  "url",
+  "image",
+  "image-set",
+  "cross-fade",
+  "element",
+  "paint",
+];
+const REPEATS: &[&str] = &[
+  "repeat",
+  "repeat-x",
+  "repeat-y",
+  "no-repeat",
+  "space",
+  "round",
+];
+const BOXES: &[&str] = &["border-box", "padding-box", "content-box"];
+const POSITIONS: &[&str] = &["left", "right", "top", "bottom", "center"];
+
+/// The initial values of the longhands of `background`.
+const BACKGROUND: [&str; 8] = [
+  "none",
+  "0% 0%",
+  "auto",
+  "repeat",
+  "scroll",
+  "padding-box",
+  "border-box",
+  "transparent",
+];
+
+fn expand_background(nodes: &[ValueNode]) -> Option<Vec<String>> {
+  // image, position, size, repeat, attachment, origin, clip and color
+  let mut values: [Option<String>; 8] = Default::default();
+  let mut i = 0;
+  let take = |i: &mut usize, matches: &dyn Fn(&ValueNode) -> bool, max: usize| {
+    let start = *i;
+    while *i < nodes.len() && *i - start < max && matches(&nodes[*i]) {
+      *i += 1;
+    }
+    join(&nodes[start..*i])
+  };
+  let is_position = |node: &ValueNode| {
+    is_length(node) || lower_word(node).is_some_and(|word| POSITIONS.contains(&word.as_str()))
+  };
+  let is_size = |node: &ValueNode| {
+    is_length(node)
+      || lower_word(node).is_some_and(|word| ["auto", "cover", "contain"].contains(&word.as_str()))
+  };
+  while i < nodes.len() {
+    let node = &nodes[i];
+    let word = lower_word(node).unwrap_or_default();
+    let (slot, value) = if let Some(function) = node.as_function() {
+      let name = function.lower_name();
+      if IMAGE_FUNCTIONS.contains(&name.as_str()) || name.ends_with("gradient") {
+        i += 1;
+        (0, node.to_string())
+      } else if is_length(node) {
+        (1, take(&mut i, &is_position, 4))
+      } else {
+        i += 1;
+        (7, node.to_string())
+      }
+    } else if word == "none" {
+      i += 1;
+      (0, node.to_string())
+    } else if is_position(node) {
+      let position = take(&mut i, &is_position, 4);
+      if let Some(ValueNode::Div('/')) = nodes.get(i) {
+        i += 1;
+        let size = take(&mut i, &is_size, 2);
+        if size.is_empty() || values[2].replace(size).is_some() {
+          return None;
+        }
+      }
+      (1, position)
+    } else if REPEATS.contains(&word.as_str()) {
+      (
+        3,
+        take(
+          &mut i,
+          &|node| lower_word(node).is_some_and(|word| REPEATS.contains(&word.as_str())),
+          2,
+        ),
+      )
+    } else if ["scroll", "fixed", "local"].contains(&word.as_str()) {
+      i += 1;
+      (4, node.to_string())
+    } else if BOXES.contains(&word.as_str()) || word == "text" {
+      i += 1;
+      match values[5] {
+        Some(_) => (6, node.to_string()),
+        None => (5, node.to_string()),
+      }
+    } else if matches!(node, ValueNode::Div(_)) {
+      // More than one layer.
+      return None;
+    } else {
+      i += 1;
+      (7, node.to_string())
+    };
+    if values[slot].replace(value).is_some() {
+      return None;
+    }
+  }
+  if values[6].is_none() {
+    values[6] = values[5].clone();
+  }
+  let mut longhands = Vec::with_capacity(8);
+  for (value, initial) in values.into_iter().zip(BACKGROUND) {
+    longhands.push(value.unwrap_or_else(|| initial.to_string()));
+  }
+  Some(longhands)
+}
+
+fn collapse_background(values: &[String]) -> Option<String> {
+  if values.iter().any(|value| value.contains(',')) {
+    return None;
+  }
+  let initial =
+    |i: usize| values[i].eq_ignore_ascii_case(BACKGROUND[i]) || (i == 1 && values[1] == "0 0");
+  let mut parts = Vec::new();
+  if !initial(0) {
+    parts.push(values[0].clone());
+  }
+  if !initial(2) {
+    parts.push(format!(
+      "{} / {}",
+      if initial(1) { "0 0" } else { &values[1] },
+      values[2]
+    ));
+  } else if !initial(1) {
+    parts.push(values[1].clone());
+  }
+  for i in [3, 4] {
+    if !initial(i) {
+      parts.push(values[i].clone());
+    }
+  }
+  if values[5].eq_ignore_ascii_case(&values[6]) {
+    if !initial(5) || !initial(6) {
+      parts.push(values[5].clone());
+    }
+  } else if !initial(5) || !initial(6) {
+    parts.push(format!("{} {}", values[5], values[6]));
+  }
+  if !initial(7) {
+    parts.push(values[7].clone());
+  }
+  Some(match parts.is_empty() {
+    true => "none".to_string(),
+    false => parts.join(" "),
+  })
+}
+
+fn expand_grid_template(nodes: &[ValueNode]) -> Option<Vec<String>> {
+  if nodes.iter().any(|node| node.to_string().contains('[')) {
+    return None;
+  }
+  if let [node] = nodes {
+    if lower_word(node).as_deref() == Some("none") {
+      return Some(vec!["none".to_string(); 3]);
+    }
+  }
+  let parts = split(nodes, '/');
+  let (rows, columns) = match parts.as_slice() {
+    [rows] => (trim(rows), None),
+    [rows, columns] => (trim(rows), Some(join(trim(columns)))),
+    _ => return None,
+  };
+  if !rows
+    .iter()
+    .any(|node| matches!(node, ValueNode::String { .. }))
+  {
+    return Some(vec![join(rows), columns?, "none".to_string()]);
+  }
+  let mut areas = Vec::new();
+  let mut sizes: Vec<Option<String>> = Vec::new();
+  for node in rows {
+    match node {
+      ValueNode::String { .. } => {
+        areas.push(node.to_string());
+        sizes.push(None);
+      }
+      node => {
+        if sizes.last_mut()?.replace(node.to_string()).is_some() {
+          return None;
+        }
+      }
+    }
+  }
+  let sizes: Vec<_> = sizes
+    .into_iter()
+    .map(|size| size.unwrap_or_else(|| "auto".to_string()))
+    .collect();
+  Some(vec![
+    sizes.join(" "),
+    columns.unwrap_or_else(|| "none".to_string()),
+    areas.join(" "),
+  ])
+}
+
+fn collapse_grid_template(values: &[String]) -> Option<String> {
+  let (rows, columns, areas) = (&values[0], &values[1], &values[2]);
+  let none = |value: &str| value.eq_ignore_ascii_case("none");
+  if none(areas) {
+    return Some(match none(rows) && none(columns) {
+      true => "none".to_string(),
+      false => format!("{} / {}", rows, columns),
+    });
+  }
+  let areas = components(areas)?;
+  let sizes = components(rows)?;
+  if areas.len() != sizes.len()
+    || !areas
+      .iter()
+      .all(|area| matches!(area, ValueNode::String { .. }))
+    || sizes.iter().any(|size| {
+      size
+        .as_function()
+        .is_some_and(|f| f.lower_name() == "repeat")
+    })
+    || columns.contains("repeat(")
+    || rows.contains('[')
+  {
+    return None;
+  }
+  let mut parts = Vec::new();
+  for (area, size) in areas.iter().zip(&sizes) {
+    parts.push(area.to_string());
+    if !size
+      .as_word()
+      .is_some_and(|size| size.eq_ignore_ascii_case("auto"))
+    {
+      parts.push(size.to_string());
+    }
+  }
+  let mut value = parts.join(" ");
+  if !none(columns) {
+    value = format!("{} / {}", value, columns);
+  }
+  Some(value)
+}
+
+impl<'a> Declaration<'a> {
+  /// The longhand declarations of a shorthand declaration, with its
+  /// importance, or `None` if it is not a shorthand or its value can not be
+  /// expanded, like values with `var()`.
+  pub fn expand(&self) -> Option<Vec<Declaration<'a>>> {
+    let shorthand = find(&self.prop)?;
+    let values = expand(shorthand, &self.value)?;
+    Some(
+      shorthand
+        .longhands
+        .iter()
+        .zip(values)
+        .map(|(longhand, value)| {
+          let mut decl = self.clone();
+          decl.prop = longhand.to_string();
+          decl.value = value;
+          decl.raws.value = None;
+          decl
+        })
+        .collect(),
+    )
+  }
+
+  /// The shorthand declaration setting exactly the longhands of `decls`, in
+  /// any order, or `None` if they are not the complete longhands of a
+  /// shorthand, mix `!important` with normal declarations, or can not be
+  /// expressed by the shorthand. The shorthand has the raws of the first
+  /// declaration.
+  pub fn collapse(decls: &[&Declaration<'a>]) -> Option<Declaration<'a>> {
+    let first = decls.first()?;
+    if decls
+      .iter()
+      .any(|decl| decl.important != first.important || decl.variable)
+    {
+      return None;
+    }
+    let shorthand = SHORTHANDS.iter().find(|shorthand| {
+      shorthand.longhands.len() == decls.len()
+        && shorthand.longhands.iter().all(|longhand| {
+          decls
+            .iter()
+            .filter(|decl| decl.prop.eq_ignore_ascii_case(longhand))
+            .count()
+            == 1
+        })
+    })?;
+    let values: Vec<_> = shorthand
+      .longhands
+      .iter()
+      .filter_map(|longhand| {
+        decls
+          .iter()
+          .find(|decl| decl.prop.eq_ignore_ascii_case(longhand))
+          .map(|decl| decl.value.clone())
+      })
+      .collect();
+    let mut decl = (*first).clone();
+    decl.prop = shorthand.name.to_string();
+    decl.value = collapse(shorthand, &values)?;
+    decl.raws.value = None;
+    Some(decl)
+  }
+}
@@ -0,0 +1,49 @@
+//! Walking a tree and replacing the children of its nodes, for the plugins
+//! that visit or reorder nodes.
+
+use crate::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Calls `callback` on every descendant of `node`, and on the children of
+/// the ones it returns `true` for.
+pub fn walk<'a, E, F>(node: &Rc<RefCell<Node<'a>>>, callback: &mut F) -> Result<(), E>
+where
+  F: FnMut(&Rc<RefCell<Node<'a>>>) -> Result<bool, E>,
+{
+  for child in node.borrow().as_shared().get_nodes().unwrap_or_default() {
+    if callback(&child)? {
+      walk(&child, callback)?;
+    }
+  }
+  Ok(())
+}
+
+/// Replaces the children of `parent` by `nodes`. The first of them keeps
+/// the spacing before the first child, like the one after a selector or at
+/// the start of a stylesheet, and trades it with that child if it is one of
+/// the nodes still.
+pub fn replace_children<'a>(parent: &Rc<RefCell<Node<'a>>>, nodes: Vec<Rc<RefCell<Node<'a>>>>) {
+  let first = parent
+    .borrow()
+    .as_shared()
+    .get_nodes()
+    .and_then(|children| children.first().cloned());
+  if let (Some(first), Some(new_first)) = (first, nodes.first()) {
+    if !Rc::ptr_eq(&first, new_first) {
+      let before = first.borrow().as_shared().as_raws().get_raw_before();
+      let new_before = new_first.borrow().as_shared().as_raws().get_raw_before();
+      if let Some(before) = before {
+        new_first.borrow_mut().set_raw_before(before);
+      }
+      if let Some(new_before) = new_before {
+        if nodes.iter().any(|node| Rc::ptr_eq(node, &first)) {
+          first.borrow_mut().set_raw_before(new_before);
+        }
+      }
+    }
+  }
+  if let Some(children) = parent.borrow_mut().as_shared_mut().get_nodes_mut() {
+    *children = nodes;
+  }
+}
@@ -0,0 +1,288 @@
+use node::shorthand::{collapse, expand, find};
+use node::Declaration;
+
+fn expanded(prop: &str, value: &str) -> Option<Vec<String>> {
+  expand(find(prop).unwrap(), value)
+}
+
+fn collapsed(prop: &str, values: &[&str]) -> Option<String> {
+  let values: Vec<_> = values.iter().map(|value| value.to_string()).collect();
+  collapse(find(prop).unwrap(), &values)
+}
+
+fn decl(prop: &str, value: &str, important: bool) -> Declaration<'static> {
+  Declaration {
+    prop: prop.to_string(),
+    value: value.to_string(),
+    important,
+    ..Declaration::default()
+  }
+}
+
+#[test]
+fn expands_and_collapses_boxes() {
+  assert_eq!(
+    expanded("margin", "1px 2px").unwrap(),
+    ["1px", "2px", "1px", "2px"]
+  );
+  assert_eq!(
+    expanded("inset", "0 auto 3px").unwrap(),
+    ["0", "auto", "3px", "auto"]
+  );
+  assert_eq!(expanded("padding", "1px 2px 3px 4px 5px"), None);
+  assert_eq!(expanded("margin", "var(--gap) 0"), None);
+  assert_eq!(expanded("border-width", "inherit").unwrap(), ["inherit"; 4]);
+  assert_eq!(
+    collapsed("margin", &["1px", "1px", "1px", "1px"]).unwrap(),
+    "1px"
+  );
+  assert_eq!(
+    collapsed("margin", &["1px", "2px", "1px", "2px"]).unwrap(),
+    "1px 2px"
+  );
+  assert_eq!(
+    collapsed("margin", &["1px", "2px", "3px", "2px"]).unwrap(),
+    "1px 2px 3px"
+  );
+  assert_eq!(
+    collapsed("padding", &["1px", "2px", "3px", "4px"]).unwrap(),
+    "1px 2px 3px 4px"
+  );
+  assert_eq!(collapsed("margin", &["inherit", "1px", "1px", "1px"]), None);
+}
+
+#[test]
+fn expands_and_collapses_border_and_flex() {
+  assert_eq!(
+    expanded("border", "solid 1px red").unwrap(),
+    ["1px", "solid", "red", "none"]
+  );
+  assert_eq!(
+    expanded("border", "dashed").unwrap(),
+    ["medium", "dashed", "currentcolor", "none"]
+  );
+  assert_eq!(expanded("border", "1px 2px solid"), None);
+  assert_eq!(
+    collapsed("border", &["medium", "none", "currentcolor", "none"]).unwrap(),
+    "none"
+  );
+  assert_eq!(
+    collapsed("border", &["2px", "solid", "red", "none"]).unwrap(),
+    "2px solid red"
+  );
+  // `border` can not set `border-image`, only reset it.
+  assert_eq!(
+    collapsed("border", &["2px", "solid", "red", "url(a.png) 30"]),
+    None
+  );
+
+  assert_eq!(expanded("flex", "none").unwrap(), ["0", "0", "auto"]);
+  assert_eq!(expanded("flex", "2").unwrap(), ["2", "1", "0%"]);
+  assert_eq!(expanded("flex", "1 30px").unwrap(), ["1", "1", "30px"]);
+  assert_eq!(expanded("flex", "10px 2 3").unwrap(), ["2", "3", "10px"]);
+  assert_eq!(expanded("flex", "1 2 3").unwrap(), ["1", "2", "3"]);
+  assert_eq!(collapsed("flex", &["1", "1", "0%"]).unwrap(), "1");
+  assert_eq!(collapsed("flex", &["0", "0", "auto"]).unwrap(), "none");
+  assert_eq!(collapsed("flex", &["2", "1", "10px"]).unwrap(), "2 10px");
+  assert_eq!(collapsed("flex", &["2", "3", "0"]).unwrap(), "2 3 0");
+}
+
+#[test]
+fn expands_and_collapses_font() {
+  assert_eq!(
+    expanded(
+      "font",
+      "italic bold 12px/1.5 \"Helvetica Neue\", Arial, sans-serif"
+    )
+    .unwrap(),
+    [
+      "italic",
+      "normal",
+      "bold",
+      "normal",
+      "12px",
+      "1.5",
+      "\"Helvetica Neue\", Arial, sans-serif"
+    ]
+  );
+  assert_eq!(
+    expanded("font", "normal small-caps 1em serif").unwrap(),
+    [
+      "normal",
+      "small-caps",
+      "normal",
+      "normal",
+      "1em",
+      "normal",
+      "serif"
+    ]
+  );
+  assert_eq!(expanded("font", "caption"), None);
+  assert_eq!(expanded("font", "bold 12px"), None);
+  assert_eq!(
+    collapsed(
+      "font",
+      &["normal", "normal", "400", "normal", "12px", "normal", "serif"]
+    )
+    .unwrap(),
+    "12px serif"
+  );
+  assert_eq!(
+    collapsed(
+      "font",
+      &[
+        "italic",
+        "normal",
+        "bold",
+        "condensed",
+        "12px",
+        "1.5",
+        "a, b"
+      ]
+    )
+    .unwrap(),
+    "italic bold condensed 12px/1.5 a, b"
+  );
+  assert_eq!(
+    collapsed(
+      "font",
+      &[
+        "normal",
+        "all-small-caps",
+        "normal",
+        "normal",
+        "12px",
+        "normal",
+        "serif"
+      ]
+    ),
+    None
+  );
+}
+
+#[test]
+fn expands_and_collapses_background() {
+  assert_eq!(
+    expanded("background", "url(a.png) no-repeat center / cover #fff").unwrap(),
+    [
+      "url(a.png)",
+      "center",
+      "cover",
+      "no-repeat",
+      "scroll",
+      "padding-box",
+      "border-box",
+      "#fff"
+    ]
+  );
+  assert_eq!(
+    expanded("background", "red content-box").unwrap(),
+    [
+      "none",
+      "0% 0%",
+      "auto",
+      "repeat",
+      "scroll",
+      "content-box",
+      "content-box",
+      "red"
+    ]
+  );
+  assert_eq!(expanded("background", "url(a.png), url(b.png)"), None);
+  assert_eq!(
+    collapsed(
+      "background",
+      &[
+        "none",
+        "0% 0%",
+        "auto",
+        "repeat",
+        "scroll",
+        "padding-box",
+        "border-box",
+        "red"
+      ]
+    )
+    .unwrap(),
+    "red"
+  );
+  assert_eq!(
+    collapsed(
+      "background",
+      &[
+        "url(a.png)",
+        "0% 0%",
+        "cover",
+        "no-repeat",
+        "fixed",
+        "padding-box",
+        "content-box",
+        "red"
+      ]
+    )
+    .unwrap(),
+    "url(a.png) 0 0 / cover no-repeat fixed padding-box content-box red"
+  );
+}
+
+#[test]
+fn expands_and_collapses_grid_template() {
+  assert_eq!(
+    expanded("grid-template", "auto 1fr / 100px 1fr").unwrap(),
+    ["auto 1fr", "100px 1fr", "none"]
+  );
+  assert_eq!(
+    expanded("grid-template", "\"a a\" 40px \"b c\" / 1fr 2fr").unwrap(),
+    ["40px auto", "1fr 2fr", "\"a a\" \"b c\""]
+  );
+  assert_eq!(expanded("grid-template", "[a] 1fr / 1fr"), None);
+  assert_eq!(
+    collapsed(
+      "grid-template",
+      &["40px auto", "1fr 2fr", "\"a a\" \"b c\""]
+    )
+    .unwrap(),
+    "\"a a\" 40px \"b c\" / 1fr 2fr"
+  );
+  assert_eq!(
+    collapsed("grid-template", &["none", "none", "none"]).unwrap(),
+    "none"
+  );
+  assert_eq!(
+    collapsed("grid-template", &["repeat(2, 1fr)", "1fr", "\"a\" \"b\""]),
+    None
+  );
+}
+
+#[test]
+fn expands_and_collapses_declarations() {
+  let margin = decl("margin", "0 auto", true);
+  let longhands = margin.expand().unwrap();
+  let props: Vec<_> = longhands.iter().map(|decl| decl.prop.as_str()).collect();
+  assert_eq!(
+    props,
+    ["margin-top", "margin-right", "margin-bottom", "margin-left"]
+  );
+  assert!(longhands.iter().all(|decl| decl.important));
+  assert!(decl("color", "red", false).expand().is_none());
+
+  let refs: Vec<_> = longhands.iter().rev().collect();
+  let collapsed = Declaration::collapse(&refs).unwrap();
+  assert_eq!(
+    (
+      collapsed.prop.as_str(),
+      collapsed.value.as_str(),
+      collapsed.important
+    ),
+    ("margin", "0 auto", true)
+  );
+
+  let top = decl("margin-top", "0", false);
+  let rest: Vec<_> = ["margin-right", "margin-bottom", "margin-left"]
+    .iter()
+    .map(|prop| decl(prop, "0", true))
+    .collect();
+  let mixed: Vec<_> = std::iter::once(&top).chain(&rest).collect();
+  assert!(Declaration::collapse(&mixed).is_none());
+  assert!(Declaration::collapse(&mixed[1..]).is_none());
+}
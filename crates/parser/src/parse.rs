@@ -1,13 +1,25 @@
-use node::Node;
-use tokenizer::input::{Input, ProcessOptions};
-// use node::Node;
 use crate::parser::Parser;
+use node::Node;
 use std::cell::RefCell;
 use std::rc::Rc;
+use tokenizer::error::CssSyntaxError;
+use tokenizer::input::ProcessOptions;
+
+pub fn parse(
+  css: &str,
+  opts: Option<ProcessOptions>,
+) -> Result<Rc<RefCell<Node<'_>>>, CssSyntaxError> {
+  let mut parser = Parser::new(css, opts);
+  parser.parse()?;
+  Ok(parser.root)
+}
 
-pub fn parse(css: &str, opts: Option<ProcessOptions>) -> Rc<RefCell<Node>> {
-  let input = Input::new(css, opts);
-  let mut parser = Parser::new(input);
-  parser.parse();
-  parser.root
+/// Parses broken CSS without errors, fixing what can be fixed.
+pub fn safe_parse(
+  css: &str,
+  opts: Option<ProcessOptions>,
+) -> Result<Rc<RefCell<Node<'_>>>, CssSyntaxError> {
+  let mut parser = Parser::new_safe(css, opts);
+  parser.parse()?;
+  Ok(parser.root)
 }
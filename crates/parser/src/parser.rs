@@ -1,121 +1,260 @@
-#![allow(unused_variables)]
-#![allow(dead_code)]
-
 use crate::regex;
-use node::{Comment, Node, Position, Root, RootRaws, Rule, RuleRaws};
+use node::{
+  AtRule, Comment, Declaration, Node, Position, RawValue, Root, RootRaws, Rule, RuleRaws, Source,
+};
 use std::cell::RefCell;
 use std::rc::Rc;
-use tokenizer::input::Input;
+use tokenizer::error::CssSyntaxError;
+use tokenizer::input::{Input, ProcessOptions};
 use tokenizer::{Token, TokenType, Tokenizer};
 
 pub struct Parser<'a> {
   pub root: Rc<RefCell<Node<'a>>>,
   current: Rc<RefCell<Node<'a>>>,
+  /// Ancestors of `current`, the innermost last.
+  parents: Vec<Rc<RefCell<Node<'a>>>>,
   tokenizer: Tokenizer<'a>,
   spaces: String,
   semicolon: bool,
-  custom_property: bool,
   input: Rc<RefCell<Input<'a>>>,
+  /// Recovers from syntax errors instead of failing, like `postcss-safe-parser`.
+  safe: bool,
 }
 
+type ParseResult<T> = Result<T, CssSyntaxError>;
+
 impl<'a> Parser<'a> {
-  pub fn new(input: Input<'a>) -> Self {
+  pub fn new(css: &'a str, opts: Option<ProcessOptions>) -> Self {
+    let input = Rc::new(RefCell::new(Input::new(css, opts)));
     let root = Rc::new(RefCell::new(Node::Root(Root {
       nodes: Some(vec![]),
       parent: None,
-      source: None,
+      source: Some(Source {
+        input: input.clone(),
+        start: Some(Position::new(0, 1, 1)),
+        end: None,
+      }),
       raws: RootRaws::default(),
     })));
     Self {
       root: root.clone(),
       current: root,
+      parents: vec![],
       spaces: "".to_string(),
       semicolon: false,
-      custom_property: false,
-      tokenizer: Tokenizer::new(input.css, true),
-      input: Rc::new(RefCell::new(input)),
+      tokenizer: Tokenizer::new(css, true),
+      input,
+      safe: false,
+    }
+  }
+
+  /// A parser that fixes broken CSS: unclosed blocks are closed, unknown
+  /// words and stray `}` are kept as spaces and unclosed tokens are accepted.
+  pub fn new_safe(css: &'a str, opts: Option<ProcessOptions>) -> Self {
+    Self {
+      safe: true,
+      ..Self::new(css, opts)
     }
   }
 
-  pub fn parse(&mut self) {
+  pub fn parse(&mut self) -> ParseResult<()> {
     use TokenType::*;
     while !self.tokenizer.end_of_file() {
-      let token = self.tokenizer.next_token(true);
+      let token = self.next_token()?;
       match token.0 {
         Space => self.spaces += token.1,
         Semicolon => self.free_semicolon(&token),
-        CloseCurly => self.end(&token),
+        CloseCurly => self.end(&token)?,
         Comment => self.comment(&token),
-        AtWord => self.atrule(&token),
+        AtWord => self.atrule(token)?,
         OpenCurly => self.empty_rule(&token),
-        _ => self.other(token),
+        _ => self.other(token)?,
       }
     }
-    self.end_file();
+    self.end_file()
+  }
+
+  /// The tokenizer runs in `ignore_errors` mode so it never panics, unclosed
+  /// strings, comments and `url(` are reported here instead.
+  fn next_token(&mut self) -> ParseResult<Token<'a>> {
+    let token = self.tokenizer.next_token(false);
+    let unclosed = match token.0 {
+      TokenType::String => {
+        let quote = &token.1[..1];
+        token.1.len() < 2 || !token.1.ends_with(quote) || is_escaped(&token.1[..token.1.len() - 1])
+      }
+      TokenType::Comment => token.1.len() < 4 || !token.1.ends_with("*/"),
+      TokenType::Brackets => !token.1.ends_with(')'),
+      _ => false,
+    };
+    if unclosed && !self.safe {
+      let what = match token.0 {
+        TokenType::String => "string",
+        TokenType::Comment => "comment",
+        _ => "bracket",
+      };
+      return Err(self.error(&format!("Unclosed {}", what), token.2));
+    }
+    Ok(token)
   }
 
   #[inline]
   fn free_semicolon(&mut self, token: &Token) {
     self.spaces += token.1;
-    if let Some(rule) = self.current.borrow_mut().as_rule_mut() {
-      if rule.raws.own_semicolon.unwrap_or(false) {
-        rule.raws.own_semicolon = Some(!self.spaces.is_empty());
-        self.spaces = "".to_owned();
+    let prev = self
+      .current
+      .borrow()
+      .as_shared()
+      .get_nodes()
+      .and_then(|nodes| nodes.last().cloned());
+    if let Some(prev) = prev {
+      if let Some(rule) = prev.borrow_mut().as_rule_mut() {
+        if rule.raws.own_semicolon.is_none() {
+          rule.raws.own_semicolon = Some(std::mem::take(&mut self.spaces));
+        }
       }
     }
   }
 
-  //   if let Some(node) = self
-  //   .current
-  //   .nodes_mut()
-  //   .and_then(|nodes| nodes.last_mut())
-  //   .and_then(|prev| prev.as_any_mut().downcast_mut::<&mut Rule>())
-  // {
-
   #[inline]
-  fn end(&self, token: &Token) {
-    todo!()
+  fn end(&mut self, token: &Token) -> ParseResult<()> {
+    if self.safe && self.parents.is_empty() {
+      self.spaces += token.1;
+      return Ok(());
+    }
+    self.finish_current();
+    match self.parents.pop() {
+      Some(parent) => {
+        let end = self.get_end_position(token.2 + 1);
+        self.current.borrow_mut().set_source_end(Some(end));
+        self.current = parent;
+        Ok(())
+      }
+      None => Err(self.error("Unexpected }", token.2)),
+    }
   }
 
   #[inline]
   fn comment(&mut self, token: &Token) {
-    let node = Node::Comment(Comment {
+    let mut node = Comment::default();
+    // an unclosed comment is only accepted by the safe parser
+    let text = &token.1[2..];
+    let text = text.strip_suffix("*/").unwrap_or(text);
+    if is_all_white_space(text) {
+      node.text = "".into();
+      node.raws.left = Some(text.to_string());
+      node.raws.right = Some("".to_string());
+    } else {
+      // text.match(/^(\s*)([^]*\S)(\s*)$/) these two reg is equivalent, but is not valid in rust regexp crate
+      let comment_reg = regex!(r#"^(\s*)([\s\S]*\S)(\s*)$"#);
+      let capture = comment_reg.captures(text).unwrap();
+      node.text = capture.get(2).unwrap().as_str().to_string();
+      node.raws.left = Some(capture.get(1).unwrap().as_str().to_string());
+      node.raws.right = Some(capture.get(3).unwrap().as_str().to_string());
+    }
+    let node = Rc::new(RefCell::new(Node::Comment(node)));
+    self.init(node.clone(), token.2);
+    let end = self.get_end_position(token.3);
+    node.borrow_mut().set_source_end(Some(end));
+  }
+
+  #[inline]
+  fn atrule(&mut self, token: Token<'a>) -> ParseResult<()> {
+    use TokenType::*;
+    let name = &token.1[1..];
+    if name.is_empty() && !self.safe {
+      return Err(self.error("At-rule without name", token.2));
+    }
+    let node = Rc::new(RefCell::new(Node::AtRule(AtRule {
+      name: name.to_string(),
       ..Default::default()
-    });
-    let node = Rc::new(RefCell::new(node));
+    })));
     self.init(node.clone(), token.2);
-    let offset = token.3;
-    let (line, column) = self.tokenizer.from_offset(offset);
-    node
-      .borrow_mut()
-      .set_source_end(Some(Position::new(offset, line, column)));
-
-    let text = &token.1[2..token.1.len() - 2];
-    if let Some(comment) = node.borrow_mut().as_comment_mut() {
-      if is_all_white_space(text) {
-        comment.text = "".into();
-        comment.raws.left = Some(text.to_string());
-        comment.raws.right = Some("".to_string());
+
+    let mut last = false;
+    let mut open = false;
+    let mut params: Vec<Token<'a>> = vec![];
+    let mut brackets: Vec<TokenType> = vec![];
+    while !self.tokenizer.end_of_file() {
+      let token = self.next_token()?;
+      let kind = token.0;
+      if kind == OpenParentheses || kind == OpenSquare {
+        brackets.push(if kind == OpenParentheses {
+          CloseParentheses
+        } else {
+          CloseSquare
+        });
+      } else if kind == OpenCurly && !brackets.is_empty() {
+        brackets.push(CloseCurly);
+      } else if brackets.last() == Some(&kind) {
+        brackets.pop();
+      }
+
+      if brackets.is_empty() {
+        match kind {
+          Semicolon => {
+            let end = self.get_end_position(token.3);
+            node.borrow_mut().set_source_end(Some(end));
+            self.semicolon = true;
+            break;
+          }
+          OpenCurly => {
+            open = true;
+            break;
+          }
+          CloseCurly => {
+            if let Some(prev) = params.iter().rev().find(|token| token.0 != Space) {
+              let end = self.get_end_position(prev.3);
+              node.borrow_mut().set_source_end(Some(end));
+            }
+            self.end(&token)?;
+            break;
+          }
+          _ => params.push(token),
+        }
       } else {
-        // text.match(/^(\s*)([^]*\S)(\s*)$/) these two reg is equivalent, but is not valid in rust regexp crate
-        let comment_reg = regex!(r#"^(\s*)([\s\S]*\S)(\s*)$"#);
-        let capture = comment_reg.captures(text).unwrap();
-        comment.text = capture.get(1).unwrap().as_str().to_string();
-        comment.raws.left = Some(capture.get(0).unwrap().as_str().to_string());
-        comment.raws.right = Some(capture.get(2).unwrap().as_str().to_string());
+        params.push(token);
       }
-    };
-  }
 
-  #[inline]
-  fn atrule(&self, token: &Token) {
-    todo!()
+      if self.tokenizer.end_of_file() {
+        last = true;
+        break;
+      }
+    }
+
+    let mut between = spaces_and_comments_from_end(&mut params);
+    let mut after_name = "".to_string();
+    let mut params_value = ("".to_string(), None);
+    if !params.is_empty() {
+      after_name = spaces_and_comments_from_start(&mut params);
+      params_value = raw(&params, false);
+      if last {
+        let end = self.get_end_position(params.last().unwrap().3);
+        node.borrow_mut().set_source_end(Some(end));
+        self.spaces = std::mem::take(&mut between);
+      }
+    }
+
+    if let Some(at_rule) = node.borrow_mut().as_at_rule_mut() {
+      at_rule.raws.between = Some(between);
+      at_rule.raws.after_name = Some(after_name);
+      at_rule.params = params_value.0;
+      at_rule.raws.params = params_value.1;
+      if open {
+        at_rule.nodes = Some(vec![]);
+      }
+    }
+    if open {
+      self.enter(node);
+    }
+    Ok(())
   }
 
   #[inline]
   fn empty_rule(&mut self, token: &Token) {
     let node = Node::Rule(Rule {
       selector: "".to_string(),
+      nodes: Some(vec![]),
       raws: RuleRaws {
         between: Some("".to_string()),
         ..Default::default()
@@ -124,36 +263,452 @@ impl<'a> Parser<'a> {
     });
     let node = Rc::new(RefCell::new(node));
     self.init(node.clone(), token.2);
-    self.current = node;
+    self.enter(node);
   }
 
   #[inline]
-  fn other(&self, token: Token) {
-    todo!()
+  fn other(&mut self, start: Token<'a>) -> ParseResult<()> {
+    use TokenType::*;
+    let mut end = false;
+    let mut colon = false;
+    let mut bracket: Option<usize> = None;
+    let mut brackets: Vec<TokenType> = vec![];
+    let custom_property = start.1.starts_with("--");
+    let mut tokens: Vec<Token<'a>> = vec![];
+    let mut token = start;
+    loop {
+      let kind = token.0;
+      let offset = token.2;
+      tokens.push(token);
+
+      if kind == OpenParentheses || kind == OpenSquare {
+        bracket.get_or_insert(offset);
+        brackets.push(if kind == OpenParentheses {
+          CloseParentheses
+        } else {
+          CloseSquare
+        });
+      } else if custom_property && colon && kind == OpenCurly {
+        bracket.get_or_insert(offset);
+        brackets.push(CloseCurly);
+      } else if brackets.is_empty() {
+        match kind {
+          Semicolon => {
+            if colon {
+              return self.decl(tokens, custom_property);
+            } else {
+              break;
+            }
+          }
+          OpenCurly => {
+            self.rule(tokens);
+            return Ok(());
+          }
+          CloseCurly => {
+            self.tokenizer.back(tokens.pop().unwrap());
+            end = true;
+            break;
+          }
+          Colon => colon = true,
+          _ => {}
+        }
+      } else if brackets.last() == Some(&kind) {
+        brackets.pop();
+        if brackets.is_empty() {
+          bracket = None;
+        }
+      }
+
+      if self.tokenizer.end_of_file() {
+        break;
+      }
+      token = self.next_token()?;
+    }
+
+    if self.tokenizer.end_of_file() {
+      end = true;
+    }
+    if let Some(offset) = bracket.filter(|_| !brackets.is_empty() && !self.safe) {
+      return Err(self.error("Unclosed bracket", offset));
+    }
+
+    if end && colon {
+      if !custom_property {
+        while let Some(last) = tokens.last() {
+          if last.0 != Space && last.0 != Comment {
+            break;
+          }
+          self.tokenizer.back(tokens.pop().unwrap());
+        }
+      }
+      self.decl(tokens, custom_property)
+    } else {
+      self.unknown_word(&tokens, tokens[0].2)
+    }
+  }
+
+  fn rule(&mut self, mut tokens: Vec<Token<'a>>) {
+    tokens.pop();
+    let node = Rc::new(RefCell::new(Node::Rule(Rule {
+      nodes: Some(vec![]),
+      ..Default::default()
+    })));
+    self.init(node.clone(), tokens[0].2);
+    let between = spaces_and_comments_from_end(&mut tokens);
+    let (selector, raw_selector) = raw(&tokens, false);
+    if let Some(rule) = node.borrow_mut().as_rule_mut() {
+      rule.raws.between = Some(between);
+      rule.selector = selector;
+      rule.raws.selector = raw_selector;
+    }
+    self.enter(node);
+  }
+
+  fn decl(&mut self, mut tokens: Vec<Token<'a>>, custom_property: bool) -> ParseResult<()> {
+    use TokenType::*;
+    let words_end = tokens.len() - (tokens.last().unwrap().0 == Semicolon) as usize;
+    if !tokens[..words_end].iter().any(|token| token.0 == Word) {
+      return self.unknown_word(&tokens, tokens[words_end - 1].2);
+    }
+    let node = Rc::new(RefCell::new(Node::Decl(Declaration::default())));
+    self.init(node.clone(), tokens[0].2);
+    let mut decl = Declaration::default();
+
+    let last = tokens.last().unwrap();
+    let end = self.get_end_position(last.3);
+    if last.0 == Semicolon {
+      self.semicolon = true;
+      tokens.pop();
+    }
+
+    let mut before = "".to_string();
+    while tokens[0].0 != Word {
+      before += tokens.remove(0).1;
+    }
+    let start = self.get_position(tokens[0].2);
+
+    let mut prop = "".to_string();
+    while let Some(token) = tokens.first() {
+      if token.0 == Colon || token.0 == Space || token.0 == Comment {
+        break;
+      }
+      prop += tokens.remove(0).1;
+    }
+
+    let word = regex!(r"\w");
+    let mut between = "".to_string();
+    while !tokens.is_empty() {
+      let token = tokens.remove(0);
+      if token.0 == Colon {
+        between += token.1;
+        break;
+      } else {
+        if token.0 == Word && word.is_match(token.1) && !self.safe {
+          return Err(self.error("Unknown word", token.2));
+        }
+        between += token.1;
+      }
+    }
+
+    if prop.starts_with('_') || prop.starts_with('*') {
+      before.push_str(&prop[..1]);
+      prop.remove(0);
+    }
+
+    let mut first_spaces = vec![];
+    while let Some(token) = tokens.first() {
+      if token.0 != Space && token.0 != Comment {
+        break;
+      }
+      first_spaces.push(tokens.remove(0));
+    }
+
+    for i in (0..tokens.len()).rev() {
+      let token = &tokens[i];
+      let kind = token.0;
+      if token.1.eq_ignore_ascii_case("!important") {
+        decl.important = true;
+        let string = string_from(&mut tokens, i);
+        let string = spaces_from_end(&mut tokens) + &string;
+        if string != " !important" {
+          decl.raws.important = Some(string);
+        }
+        break;
+      } else if token.1.eq_ignore_ascii_case("important") {
+        let mut cache = tokens.clone();
+        let mut string = "".to_string();
+        for j in (1..=i).rev() {
+          if string.trim().starts_with('!') && cache[j].0 != Space {
+            break;
+          }
+          string = cache.pop().unwrap().1.to_string() + &string;
+        }
+        if string.trim().starts_with('!') {
+          decl.important = true;
+          decl.raws.important = Some(string);
+          tokens = cache;
+        }
+      }
+      if kind != Space && kind != Comment {
+        break;
+      }
+    }
+
+    let has_word = tokens
+      .iter()
+      .any(|token| token.0 != Space && token.0 != Comment);
+    if has_word {
+      between.extend(first_spaces.drain(..).map(|token| token.1));
+    }
+    first_spaces.extend(tokens.iter().cloned());
+    let (value, raw_value) = raw(&first_spaces, custom_property);
+
+    if value.contains(':') && !custom_property && !self.safe {
+      self.check_missed_semicolon(&tokens)?;
+    }
+
+    decl.variable = prop.starts_with("--") || prop.starts_with('$');
+    decl.prop = prop;
+    decl.value = value;
+    decl.raws.before = Some(before);
+    decl.raws.between = Some(between);
+    decl.raws.value = raw_value;
+
+    let mut node = node.borrow_mut();
+    if let Some(source) = node.as_shared_mut().get_source_mut() {
+      decl.source = Some(Source {
+        input: source.input.clone(),
+        start: Some(start),
+        end: Some(end),
+      });
+    }
+    // keep the `before` collected by `init`, the declaration may start with spaces
+    let init_before = node.as_shared().as_raws().get_raw_before();
+    decl.raws.before = Some(init_before.unwrap_or_default() + &decl.raws.before.unwrap());
+    *node = Node::Decl(decl);
+    Ok(())
+  }
+
+  fn check_missed_semicolon(&mut self, tokens: &[Token<'a>]) -> ParseResult<()> {
+    let colon = match self.colon(tokens)? {
+      Some(colon) => colon,
+      None => return Ok(()),
+    };
+    let mut founded = 0;
+    let mut token = &tokens[colon];
+    for j in (0..colon).rev() {
+      token = &tokens[j];
+      if token.0 != TokenType::Space {
+        founded += 1;
+        if founded == 2 {
+          break;
+        }
+      }
+    }
+    let offset = if token.0 == TokenType::Word {
+      token.3
+    } else {
+      token.2
+    };
+    Err(self.error("Missed semicolon", offset))
+  }
+
+  fn colon(&mut self, tokens: &[Token<'a>]) -> ParseResult<Option<usize>> {
+    let mut brackets = 0;
+    let mut prev: Option<&Token> = None;
+    for (i, token) in tokens.iter().enumerate() {
+      match token.0 {
+        TokenType::OpenParentheses => brackets += 1,
+        TokenType::CloseParentheses => brackets -= 1,
+        TokenType::Colon if brackets == 0 => match prev {
+          None => return Err(self.error("Double colon", token.2)),
+          Some(prev) if prev.0 == TokenType::Word && prev.1 == "progid" => {}
+          Some(_) => return Ok(Some(i)),
+        },
+        _ => {}
+      }
+      prev = Some(token);
+    }
+    Ok(None)
+  }
+
+  /// Fails, or keeps the tokens as spaces in the safe parser.
+  fn unknown_word(&mut self, tokens: &[Token<'a>], offset: usize) -> ParseResult<()> {
+    if !self.safe {
+      return Err(self.error("Unknown word", offset));
+    }
+    self.spaces.extend(tokens.iter().map(|token| token.1));
+    Ok(())
   }
 
   #[inline]
-  fn end_file(&self) {
-    todo!()
+  fn end_file(&mut self) -> ParseResult<()> {
+    if self.safe {
+      while let Some(parent) = self.parents.pop() {
+        self.finish_current();
+        self.current = parent;
+      }
+    }
+    if !self.parents.is_empty() {
+      let start = self
+        .current
+        .borrow()
+        .as_shared()
+        .get_source()
+        .and_then(|source| source.start)
+        .unwrap();
+      return Err(
+        self
+          .input
+          .borrow()
+          .error("Unclosed block", start.line, start.column),
+      );
+    }
+    self.finish_current();
+    let end = self.get_position(self.tokenizer.position());
+    self.root.borrow_mut().set_source_end(Some(end));
+    Ok(())
   }
+
+  /// Stores the trailing `semicolon` and `after` raws of the current container.
+  fn finish_current(&mut self) {
+    let spaces = std::mem::take(&mut self.spaces);
+    let semicolon = std::mem::replace(&mut self.semicolon, false);
+    let mut current = self.current.borrow_mut();
+    let has_nodes = matches!(current.as_shared().get_nodes(), Some(nodes) if !nodes.is_empty());
+    let semicolon = if has_nodes { Some(semicolon) } else { None };
+    match &mut *current {
+      Node::Root(root) => {
+        root.raws.semicolon = semicolon.or(root.raws.semicolon);
+        root.raws.after = Some(root.raws.after.take().unwrap_or_default() + &spaces);
+      }
+      Node::Rule(rule) => {
+        rule.raws.semicolon = semicolon.or(rule.raws.semicolon);
+        rule.raws.after = Some(rule.raws.after.take().unwrap_or_default() + &spaces);
+      }
+      Node::AtRule(at_rule) => {
+        at_rule.raws.semicolon = semicolon.or(at_rule.raws.semicolon);
+        at_rule.raws.after = Some(at_rule.raws.after.take().unwrap_or_default() + &spaces);
+      }
+      _ => {}
+    }
+  }
+
   fn get_position(&mut self, offset: usize) -> Position {
     let (line, column) = self.tokenizer.from_offset(offset);
     Position::new(offset, line, column)
   }
 
+  /// The position of the last character before `next`, with an exclusive offset.
+  fn get_end_position(&mut self, next: usize) -> Position {
+    let mut position = self.get_position(next.saturating_sub(1));
+    position.offset = next;
+    position
+  }
+
+  fn error(&mut self, reason: &str, offset: usize) -> CssSyntaxError {
+    let (line, column) = self.tokenizer.from_offset(offset);
+    self.input.borrow().error(reason, line, column)
+  }
+
+  /// Makes `node` the container new nodes are appended to.
+  fn enter(&mut self, node: Rc<RefCell<Node<'a>>>) {
+    let parent = std::mem::replace(&mut self.current, node);
+    self.parents.push(parent);
+  }
+
   fn init(&mut self, node: Rc<RefCell<Node<'a>>>, offset: usize) {
     let pos = self.get_position(offset);
-    let mut cur_node = self.current.borrow_mut();
-    cur_node.set_source(self.input.clone(), Some(pos), None);
-    cur_node.push_child(node);
+    self.current.borrow_mut().push_child(node.clone());
+    let mut node = node.borrow_mut();
+    node.set_source(self.input.clone(), Some(pos), None);
     let old_spaces = std::mem::replace(&mut self.spaces, "".to_string());
-    cur_node.set_raw_before(old_spaces);
-    if !cur_node.is_comment() {
+    node.set_raw_before(old_spaces);
+    if !node.is_comment() {
       self.semicolon = false;
     }
   }
 }
 
+/// Joins tokens into a value. Comments are dropped from the value, the
+/// original string is kept in the returned [`RawValue`] if it differs.
+fn raw(tokens: &[Token], custom_property: bool) -> (String, Option<RawValue>) {
+  let length = tokens.len();
+  let mut value = String::new();
+  let mut clean = true;
+  let is_safe_neighbor = |token: Option<&Token>| match token {
+    None => true,
+    Some(token) => token.0 == TokenType::Space,
+  };
+  for (i, token) in tokens.iter().enumerate() {
+    match token.0 {
+      TokenType::Space if i == length - 1 && !custom_property => clean = false,
+      TokenType::Comment => {
+        let prev = if i > 0 { tokens.get(i - 1) } else { None };
+        let next = tokens.get(i + 1);
+        if !is_safe_neighbor(prev) && !is_safe_neighbor(next) {
+          if value.ends_with(',') {
+            clean = false;
+          } else {
+            value += token.1;
+          }
+        } else {
+          clean = false;
+        }
+      }
+      _ => value += token.1,
+    }
+  }
+  if clean {
+    (value, None)
+  } else {
+    let raw = tokens.iter().map(|token| token.1).collect();
+    (value.clone(), Some(RawValue { value, raw }))
+  }
+}
+
+fn spaces_and_comments_from_end(tokens: &mut Vec<Token>) -> String {
+  let mut spaces = String::new();
+  while let Some(last) = tokens.last() {
+    if last.0 != TokenType::Space && last.0 != TokenType::Comment {
+      break;
+    }
+    spaces = tokens.pop().unwrap().1.to_string() + &spaces;
+  }
+  spaces
+}
+
+fn spaces_and_comments_from_start(tokens: &mut Vec<Token>) -> String {
+  let mut spaces = String::new();
+  while let Some(first) = tokens.first() {
+    if first.0 != TokenType::Space && first.0 != TokenType::Comment {
+      break;
+    }
+    spaces += tokens.remove(0).1;
+  }
+  spaces
+}
+
+fn spaces_from_end(tokens: &mut Vec<Token>) -> String {
+  let mut spaces = String::new();
+  while let Some(last) = tokens.last() {
+    if last.0 != TokenType::Space {
+      break;
+    }
+    spaces = tokens.pop().unwrap().1.to_string() + &spaces;
+  }
+  spaces
+}
+
+fn string_from(tokens: &mut Vec<Token>, from: usize) -> String {
+  tokens.drain(from..).map(|token| token.1).collect()
+}
+
+/// Returns `true` if `s` ends with an odd number of backslashes.
+fn is_escaped(s: &str) -> bool {
+  s.bytes().rev().take_while(|&byte| byte == b'\\').count() % 2 == 1
+}
+
 #[inline]
 fn is_all_white_space(s: &str) -> bool {
   s.chars().all(is_whitespace)
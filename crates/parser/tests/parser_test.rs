@@ -0,0 +1,97 @@
+use parser::parse::parse;
+
+fn error(css: &str) -> String {
+  parse(css, None).expect_err("should fail").to_string()
+}
+
+#[test]
+fn parses_nodes_and_raws() {
+  let root = parse("a { color : red !important ; }\n@media print{}", None).unwrap();
+  let root = root.borrow();
+  let nodes = root.as_shared().get_nodes().unwrap();
+  assert_eq!(nodes.len(), 2);
+
+  let rule = nodes[0].borrow();
+  let rule = rule.as_rule().unwrap();
+  assert_eq!(rule.selector, "a");
+  assert_eq!(rule.raws.between.as_deref(), Some(" "));
+  let decl = rule.nodes.as_ref().unwrap()[0].borrow();
+  let decl = decl.as_decl().unwrap();
+  assert_eq!(decl.prop, "color");
+  assert_eq!(decl.value, "red");
+  assert!(decl.important);
+  assert_eq!(decl.raws.between.as_deref(), Some(" : "));
+  assert_eq!(decl.raws.important.as_deref(), Some(" !important "));
+
+  let at_rule = nodes[1].borrow();
+  let at_rule = at_rule.as_at_rule().unwrap();
+  assert_eq!(at_rule.name, "media");
+  assert_eq!(at_rule.params, "print");
+  assert_eq!(at_rule.nodes.as_ref().map(Vec::len), Some(0));
+}
+
+#[test]
+fn sets_source_positions() {
+  let root = parse("a {\n  b: c;\n}", None).unwrap();
+  let root = root.borrow();
+  let rule = root.as_shared().get_nodes().unwrap()[0].clone();
+  let source = rule.borrow().as_shared().get_source().unwrap();
+  let start = source.start.unwrap();
+  let end = source.end.unwrap();
+  assert_eq!((start.line, start.column, start.offset), (1, 1, 0));
+  assert_eq!((end.line, end.column, end.offset), (3, 1, 13));
+}
+
+#[test]
+fn marks_variables() {
+  let root = parse(":root { --a: 1px; $b: 2 }", None).unwrap();
+  let root = root.borrow();
+  let rule = root.as_shared().get_nodes().unwrap()[0].clone();
+  let rule = rule.borrow();
+  for decl in rule.as_shared().get_nodes().unwrap() {
+    assert!(decl.borrow().as_decl().unwrap().variable);
+  }
+}
+
+#[test]
+fn reports_syntax_errors() {
+  assert_eq!(error("a {"), "<css input>:1:1: Unclosed block");
+  assert_eq!(error("}"), "<css input>:1:1: Unexpected }");
+  assert_eq!(error("a { b: \"c }"), "<css input>:1:8: Unclosed string");
+  assert_eq!(error("/* a"), "<css input>:1:1: Unclosed comment");
+  assert_eq!(
+    error("a { b: c\n d: e }"),
+    "<css input>:1:9: Missed semicolon"
+  );
+  assert_eq!(error("a b"), "<css input>:1:1: Unknown word");
+  assert_eq!(error("@ {}"), "<css input>:1:1: At-rule without name");
+  assert_eq!(error("a { b: (c }"), "<css input>:1:8: Unclosed bracket");
+}
+
+#[test]
+fn safe_parser_fixes_broken_css() {
+  use parser::parse::safe_parse;
+  let root = safe_parse("a { color: red; } } b { c d; e: f", None).unwrap();
+  let root = root.borrow();
+  let nodes = root.as_shared().get_nodes().unwrap();
+  assert_eq!(nodes.len(), 2);
+  let rule = nodes[1].borrow();
+  let rule = rule.as_rule().unwrap();
+  assert_eq!(rule.selector, "b");
+  assert_eq!(rule.raws.before.as_deref(), Some(" } "));
+  let decls = rule.nodes.as_ref().unwrap();
+  assert_eq!(decls.len(), 1);
+  assert_eq!(decls[0].borrow().as_decl().unwrap().prop, "e");
+
+  assert!(safe_parse("a { b: url(c", None).is_ok());
+  assert!(safe_parse("/* open", None).is_ok());
+}
+
+#[test]
+fn shows_source_code_of_errors() {
+  let error = parse("a {\n  b c;\n}\n", None).expect_err("should fail");
+  assert_eq!(
+    error.show_source_code().unwrap(),
+    "  1 | a {\n> 2 |   b c;\n    |   ^\n  3 | }\n  4 | "
+  );
+}
@@ -0,0 +1,280 @@
+//! `postcss.toml` and `postcss.json` configuration files.
+//!
+//! ```toml
+//! parser = "safe"
+//! browsers = "> 0.5%, last 2 versions, not dead"
+//! map = { inline = false }
+//! plugins = ["supports-flatten", { name = "media-flatten", options = { width = 1024.0 } }]
+//!
+//! [[overrides]]
+//! files = "legacy/**/*.css"
+//! plugins = [{ name = "supports-flatten", enabled = false }]
+//! ```
+
+use crate::path::absolute;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokenizer::input::MapOptions;
+
+/// Names of the configuration files, in lookup order.
+pub const FILE_NAMES: &[&str] = &["postcss.toml", "postcss.json"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+  /// The configuration file, `None` for a configuration built in code.
+  pub path: Option<PathBuf>,
+  pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(path) = &self.path {
+      write!(f, "{}: ", path.display())?;
+    }
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  /// The parser name, see [`syntax::parser`](crate::syntax::parser).
+  pub parser: Option<String>,
+  /// The syntax name, see [`syntax::syntax`](crate::syntax::syntax).
+  pub syntax: Option<String>,
+  /// The browserslist query of the browsers to support, see
+  /// [`Processor::targets`](crate::Processor::targets).
+  pub browsers: Option<String>,
+  pub map: Option<MapConfig>,
+  pub plugins: Vec<PluginConfig>,
+  /// Settings for the files matching a glob, applied in order.
+  pub overrides: Vec<Override>,
+  /// The file this configuration was loaded from, globs are relative to its
+  /// directory.
+  #[serde(skip)]
+  pub path: Option<PathBuf>,
+}
+
+/// `map = false`, `map = true` or a table of [`MapOptions`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum MapConfig {
+  Enabled(bool),
+  Options(MapTable),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct MapTable {
+  pub inline: bool,
+  pub annotation: bool,
+  pub sources_content: bool,
+}
+
+impl Default for MapTable {
+  fn default() -> Self {
+    let options = MapOptions::default();
+    Self {
+      inline: options.inline,
+      annotation: options.annotation,
+      sources_content: options.sources_content,
+    }
+  }
+}
+
+impl MapConfig {
+  /// The map options, `None` if maps are disabled.
+  pub fn options(&self) -> Option<MapOptions> {
+    match self {
+      MapConfig::Enabled(false) => None,
+      MapConfig::Enabled(true) => Some(MapOptions::default()),
+      MapConfig::Options(table) => Some(MapOptions {
+        inline: table.inline,
+        annotation: table.annotation,
+        sources_content: table.sources_content,
+      }),
+    }
+  }
+}
+
+/// A plugin by name, written as a string or as a table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "RawPlugin")]
+pub struct PluginConfig {
+  pub name: String,
+  pub options: Value,
+  /// Disabled plugins are skipped, overrides use it to turn plugins off.
+  pub enabled: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawPlugin {
+  Name(String),
+  Table(PluginTable),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct PluginTable {
+  name: String,
+  #[serde(default)]
+  options: Value,
+  #[serde(default = "enabled")]
+  enabled: bool,
+}
+
+fn enabled() -> bool {
+  true
+}
+
+impl From<RawPlugin> for PluginConfig {
+  fn from(raw: RawPlugin) -> Self {
+    match raw {
+      RawPlugin::Name(name) => Self {
+        name,
+        options: Value::Null,
+        enabled: true,
+      },
+      RawPlugin::Table(PluginTable {
+        name,
+        options,
+        enabled,
+      }) => Self {
+        name,
+        options,
+        enabled,
+      },
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Override {
+  /// Globs of the files, relative to the configuration file. A glob without
+  /// `/` matches file names in any directory.
+  #[serde(deserialize_with = "one_or_many")]
+  pub files: Vec<String>,
+  pub parser: Option<String>,
+  pub syntax: Option<String>,
+  pub browsers: Option<String>,
+  pub map: Option<MapConfig>,
+  /// Merged by name into the plugins: a known plugin gets the new options
+  /// and `enabled` flag in its place, other plugins are appended.
+  pub plugins: Vec<PluginConfig>,
+}
+
+fn one_or_many<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+  }
+  Ok(match OneOrMany::deserialize(deserializer)? {
+    OneOrMany::One(glob) => vec![glob],
+    OneOrMany::Many(globs) => globs,
+  })
+}
+
+impl Config {
+  /// Loads a configuration file, JSON if its extension is `.json` and TOML
+  /// otherwise.
+  pub fn load(path: &Path) -> Result<Config, ConfigError> {
+    let error = |message: String| ConfigError {
+      path: Some(path.to_path_buf()),
+      message,
+    };
+    let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let mut config: Config = if path.extension().is_some_and(|ext| ext == "json") {
+      serde_json::from_str(&text).map_err(|e| error(e.to_string()))?
+    } else {
+      toml::from_str(&text).map_err(|e| error(e.to_string()))?
+    };
+    for glob in config.overrides.iter().flat_map(|item| &item.files) {
+      glob::Pattern::new(glob).map_err(|e| error(format!("Invalid glob `{}`: {}", glob, e)))?;
+    }
+    config.path = Some(absolute(path));
+    Ok(config)
+  }
+
+  /// Looks for a configuration file in `dir` and its ancestors.
+  pub fn find(dir: &Path) -> Result<Option<Config>, ConfigError> {
+    let dir = absolute(dir);
+    for dir in dir.ancestors() {
+      for name in FILE_NAMES {
+        let path = dir.join(name);
+        if path.is_file() {
+          return Config::load(&path).map(Some);
+        }
+      }
+    }
+    Ok(None)
+  }
+
+  /// Returns the configuration of `file`, with the matching overrides
+  /// applied.
+  pub fn for_file(&self, file: &Path) -> Config {
+    let mut config = Config {
+      overrides: vec![],
+      ..self.clone()
+    };
+    let base = self
+      .path
+      .as_deref()
+      .and_then(Path::parent)
+      .map_or_else(|| absolute(Path::new(".")), Path::to_path_buf);
+    let file = absolute(file);
+    let relative = file.strip_prefix(&base).unwrap_or(&file);
+    for item in &self.overrides {
+      if !item.files.iter().any(|glob| matches(glob, relative)) {
+        continue;
+      }
+      if item.parser.is_some() {
+        config.parser = item.parser.clone();
+      }
+      if item.syntax.is_some() {
+        config.syntax = item.syntax.clone();
+      }
+      if item.browsers.is_some() {
+        config.browsers = item.browsers.clone();
+      }
+      if item.map.is_some() {
+        config.map = item.map.clone();
+      }
+      for plugin in &item.plugins {
+        match config.plugins.iter_mut().find(|p| p.name == plugin.name) {
+          Some(existing) => *existing = plugin.clone(),
+          None => config.plugins.push(plugin.clone()),
+        }
+      }
+    }
+    config
+  }
+}
+
+/// Invalid globs are rejected by [`Config::load`], and match nothing in a
+/// configuration built in code.
+fn matches(glob: &str, path: &Path) -> bool {
+  let pattern = match glob::Pattern::new(glob) {
+    Ok(pattern) => pattern,
+    Err(_) => return false,
+  };
+  let options = glob::MatchOptions {
+    require_literal_separator: true,
+    ..Default::default()
+  };
+  if glob.contains('/') {
+    pattern.matches_path_with(path, options)
+  } else {
+    path
+      .file_name()
+      .is_some_and(|name| pattern.matches_with(&name.to_string_lossy(), options))
+  }
+}
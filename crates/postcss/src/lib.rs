@@ -0,0 +1,17 @@
+pub mod config;
+mod map;
+pub mod path;
+mod processor;
+mod registry;
+mod result;
+pub mod syntax;
+
+pub use crate::config::{Config, ConfigError};
+pub use crate::processor::{Plugin, Processor};
+pub use crate::registry::{options, PluginFactory, Registry};
+pub use crate::result::{Message, ProcessResult};
+pub use crate::syntax::Syntax;
+pub use browserslist::Target;
+pub use tokenizer::error::CssSyntaxError;
+pub use tokenizer::input::{MapOptions, ProcessOptions};
+pub use tokenizer::warning::Warning;
@@ -0,0 +1,132 @@
+use crate::path::{absolute, relative};
+use crate::syntax::StringifyFn;
+use node::Node;
+use sourcemap::SourceMapBuilder;
+use std::collections::HashMap;
+use std::path::Path;
+use tokenizer::input::{MapOptions, ProcessOptions};
+
+/// Stringifies the root and builds a source map of the output, like
+/// postcss's `MapGenerator`.
+///
+/// Returns the CSS, with the annotation comment or the inlined map, and the
+/// map itself unless it was inlined.
+pub(crate) fn generate(
+  root: &Node,
+  stringify: StringifyFn,
+  opts: &ProcessOptions,
+  map_opts: &MapOptions,
+) -> (String, Option<String>) {
+  let base = match &opts.to {
+    Some(to) => absolute(Path::new(to))
+      .parent()
+      .map(Path::to_path_buf)
+      .unwrap_or_default(),
+    None => absolute(Path::new(".")),
+  };
+  let file = output_file(opts);
+  let mut map = SourceMapBuilder::new(Some(&file));
+  let mut sources: HashMap<String, u32> = HashMap::new();
+  let mut css = String::new();
+  let mut line = 1;
+  let mut column = 1;
+
+  stringify(root, &mut |str, node, kind| {
+    css.push_str(str);
+
+    let mut add_mapping = |node: &Node, end: bool, line: u32, column: u32| {
+      let source = node.as_shared().get_source();
+      let position = source.as_ref().and_then(|source| {
+        if end {
+          source.end.clone()
+        } else {
+          source.start.clone()
+        }
+      });
+      let (source, position) = match (source, position) {
+        (Some(source), Some(position)) => (source, position),
+        _ => {
+          map.add(line - 1, column, 0, 0, Some("<no source>"), None);
+          return;
+        }
+      };
+      let input = source.input.borrow();
+      let path = match input.file() {
+        Some(file) => relative(&base, Path::new(file)),
+        None => input.from(),
+      };
+      if !sources.contains_key(&path) {
+        let id = map.add_source(&path);
+        if map_opts.sources_content {
+          map.set_source_contents(id, Some(&input.css));
+        }
+        sources.insert(path.clone(), id);
+      }
+      map.add(
+        line - 1,
+        column,
+        position.line as u32 - 1,
+        position.column as u32 - 1,
+        Some(&path),
+        None,
+      );
+    };
+
+    if let Some(node) = node.filter(|_| kind != Some("end")) {
+      add_mapping(node, false, line, column - 1);
+    }
+
+    match str.rfind('\n') {
+      Some(last) => {
+        line += str.matches('\n').count() as u32;
+        column = str[last..].chars().count() as u32;
+      }
+      None => column += str.chars().count() as u32,
+    }
+
+    if let Some(node) = node.filter(|_| kind != Some("start")) {
+      add_mapping(node, true, line, column.saturating_sub(2));
+    }
+  });
+
+  let mut json = vec![];
+  map
+    .into_sourcemap()
+    .to_writer(&mut json)
+    .expect("writes to a Vec");
+  let json = String::from_utf8(json).expect("source maps are JSON");
+
+  if map_opts.inline {
+    if map_opts.annotation {
+      let url = format!(
+        "data:application/json;base64,{}",
+        base64::encode(json.as_bytes())
+      );
+      annotate(&mut css, &url);
+    }
+    return (css, None);
+  }
+  if map_opts.annotation {
+    annotate(&mut css, &format!("{}.map", file));
+  }
+  (css, Some(json))
+}
+
+fn annotate(css: &mut String, url: &str) {
+  let eol = if css.contains("\r\n") { "\r\n" } else { "\n" };
+  css.push_str(eol);
+  css.push_str(&format!("/*# sourceMappingURL={} */", url));
+}
+
+/// The name of the output file, relative to the map.
+fn output_file(opts: &ProcessOptions) -> String {
+  opts
+    .to
+    .as_deref()
+    .or(opts.from.as_deref())
+    .and_then(|file| Path::new(file).file_name())
+    .map_or_else(
+      || "to.css".to_string(),
+      |name| name.to_string_lossy().into_owned(),
+    )
+}
@@ -0,0 +1,42 @@
+//! Path helpers for plugins that read files.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Resolves `path` against the current directory.
+pub fn absolute(path: &Path) -> PathBuf {
+  let path = if path.is_absolute() {
+    path.to_path_buf()
+  } else {
+    std::env::current_dir().unwrap_or_default().join(path)
+  };
+  let mut result = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        result.pop();
+      }
+      component => result.push(component),
+    }
+  }
+  result
+}
+
+/// The path from the `from` directory to `to`, with `/` separators.
+pub fn relative(from: &Path, to: &Path) -> String {
+  let (from, to) = (absolute(from), absolute(to));
+  let from: Vec<_> = from.components().collect();
+  let to: Vec<_> = to.components().collect();
+  let common = from
+    .iter()
+    .zip(&to)
+    .take_while(|(from, to)| from == to)
+    .count();
+  let mut parts = vec!["..".to_string(); from.len() - common];
+  parts.extend(
+    to[common..]
+      .iter()
+      .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+  );
+  parts.join("/")
+}
@@ -0,0 +1,181 @@
+use crate::config::{Config, ConfigError};
+use crate::map;
+use crate::registry::Registry;
+use crate::result::ProcessResult;
+use crate::syntax::{self, ParseFn, Syntax};
+use browserslist::Target;
+use node::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tokenizer::error::CssSyntaxError;
+use tokenizer::input::ProcessOptions;
+
+/// A transformation of the CSS tree.
+pub trait Plugin {
+  /// The plugin name, used in warnings and errors.
+  fn name(&self) -> &str;
+
+  /// Transforms the tree. Warnings and other messages go to `result`.
+  fn run(&self, root: &Rc<RefCell<Node>>, result: &mut ProcessResult)
+    -> Result<(), CssSyntaxError>;
+}
+
+/// Runs plugins over CSS.
+///
+/// ```ignore
+/// let mut processor = Processor::new();
+/// processor.use_plugin(Box::new(MediaFlatten::default()));
+/// let result = processor.process("a {}", ProcessOptions::default())?;
+/// ```
+#[derive(Default)]
+pub struct Processor {
+  plugins: Vec<Box<dyn Plugin>>,
+  syntax: Syntax,
+  parser: Option<ParseFn>,
+  targets: Option<Vec<Target>>,
+}
+
+impl Processor {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates a processor with a single plugin, for the tests of plugins.
+  #[cfg(feature = "test-util")]
+  #[doc(hidden)]
+  pub fn with_plugin(plugin: impl Plugin + 'static) -> Self {
+    let mut processor = Self::new();
+    processor.use_plugin(Box::new(plugin));
+    processor
+  }
+
+  /// Processes the CSS with a single plugin and the default options, for
+  /// the tests of plugins.
+  #[cfg(feature = "test-util")]
+  #[doc(hidden)]
+  pub fn run_one(
+    plugin: impl Plugin + 'static,
+    css: &str,
+  ) -> Result<ProcessResult, CssSyntaxError> {
+    Self::with_plugin(plugin).process(css, ProcessOptions::default())
+  }
+
+  /// Creates a processor with the parser, syntax and enabled plugins of a
+  /// configuration, looking the plugins up in `registry`.
+  ///
+  /// Use [`Config::for_file`] first to apply the overrides of a file.
+  pub fn from_config(config: &Config, registry: &Registry) -> Result<Processor, ConfigError> {
+    let error = |message: String| ConfigError {
+      path: config.path.clone(),
+      message,
+    };
+    let mut processor = Processor::new();
+    if let Some(name) = &config.syntax {
+      let syntax =
+        syntax::syntax(name).ok_or_else(|| error(format!("Unknown syntax \"{}\"", name)))?;
+      processor.set_syntax(syntax);
+    }
+    if let Some(name) = &config.parser {
+      let parser =
+        syntax::parser(name).ok_or_else(|| error(format!("Unknown parser \"{}\"", name)))?;
+      processor.set_parser(parser);
+    }
+    if let Some(query) = &config.browsers {
+      let targets = browserslist::resolve(query).map_err(|e| error(e.to_string()))?;
+      processor.set_targets(targets);
+    }
+    for plugin in config.plugins.iter().filter(|plugin| plugin.enabled) {
+      processor.use_plugin(
+        registry
+          .create(&plugin.name, &plugin.options)
+          .map_err(error)?,
+      );
+    }
+    Ok(processor)
+  }
+
+  /// Adds a plugin, plugins run in the order they were added.
+  pub fn use_plugin(&mut self, plugin: Box<dyn Plugin>) -> &mut Self {
+    self.plugins.push(plugin);
+    self
+  }
+
+  pub fn plugins(&self) -> &[Box<dyn Plugin>] {
+    &self.plugins
+  }
+
+  /// Sets the syntax used to parse the input and stringify the output.
+  pub fn set_syntax(&mut self, syntax: Syntax) -> &mut Self {
+    self.syntax = syntax;
+    self
+  }
+
+  /// Sets the parser, overriding the parser of the syntax.
+  pub fn set_parser(&mut self, parser: ParseFn) -> &mut Self {
+    self.parser = Some(parser);
+    self
+  }
+
+  /// Sets the browsers to support, for target-aware plugins like
+  /// prefixing. They get them in [`ProcessResult::targets`].
+  pub fn set_targets(&mut self, targets: Vec<Target>) -> &mut Self {
+    self.targets = Some(targets);
+    self
+  }
+
+  /// The browsers to support, `None` if they were not set.
+  pub fn targets(&self) -> Option<&[Target]> {
+    self.targets.as_deref()
+  }
+
+  /// Parses the CSS with the processor's parser.
+  pub fn parse<'a>(
+    &self,
+    css: &'a str,
+    opts: &ProcessOptions,
+  ) -> Result<Rc<RefCell<Node<'a>>>, CssSyntaxError> {
+    let parse = self.parser.unwrap_or(self.syntax.parse);
+    parse(css, Some(opts.clone()))
+  }
+
+  /// Parses the CSS, runs the plugins and stringifies the result.
+  pub fn process(&self, css: &str, opts: ProcessOptions) -> Result<ProcessResult, CssSyntaxError> {
+    let root = self.parse(css, &opts)?;
+    self.process_root(&root, opts)
+  }
+
+  /// Runs the plugins on an already parsed tree and stringifies it.
+  pub fn process_root(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    opts: ProcessOptions,
+  ) -> Result<ProcessResult, CssSyntaxError> {
+    let mut result = ProcessResult::new(opts);
+    result.targets = self.targets.clone();
+    for plugin in &self.plugins {
+      result.last_plugin = Some(plugin.name().to_string());
+      if let Err(mut error) = plugin.run(root, &mut result) {
+        error
+          .plugin
+          .get_or_insert_with(|| plugin.name().to_string());
+        return Err(error);
+      }
+    }
+    result.last_plugin = None;
+
+    let root = root.borrow();
+    match result.opts.map.clone() {
+      Some(map_opts) => {
+        let (css, map) = map::generate(&root, self.syntax.stringify, &result.opts, &map_opts);
+        result.css = css;
+        result.map = map;
+      }
+      None => {
+        let mut css = String::new();
+        (self.syntax.stringify)(&root, &mut |str, _, _| css.push_str(str));
+        result.css = css;
+      }
+    }
+    Ok(result)
+  }
+}
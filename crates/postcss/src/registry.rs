@@ -0,0 +1,59 @@
+use crate::processor::Plugin;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Creates a plugin from its options, `Value::Null` if it has none.
+pub type PluginFactory = fn(&Value) -> Result<Box<dyn Plugin>, String>;
+
+/// Plugins a configuration file can refer to by name.
+#[derive(Clone, Default)]
+pub struct Registry {
+  factories: Vec<(String, PluginFactory)>,
+}
+
+impl Registry {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Registers a plugin, replacing any plugin with the same name.
+  pub fn register(&mut self, name: &str, factory: PluginFactory) -> &mut Self {
+    self.factories.retain(|(registered, _)| registered != name);
+    self.factories.push((name.to_string(), factory));
+    self
+  }
+
+  /// Creates the plugin named `name` with `options`.
+  pub fn create(&self, name: &str, options: &Value) -> Result<Box<dyn Plugin>, String> {
+    let factory = self
+      .factories
+      .iter()
+      .find(|(registered, _)| registered == name)
+      .map(|(_, factory)| factory)
+      .ok_or_else(|| {
+        format!(
+          "Unknown plugin \"{}\", known plugins are {}",
+          name,
+          self.names().join(", ")
+        )
+      })?;
+    factory(options).map_err(|error| format!("Invalid options of plugin \"{}\": {}", name, error))
+  }
+
+  /// The names of the registered plugins, in registration order.
+  pub fn names(&self) -> Vec<&str> {
+    self
+      .factories
+      .iter()
+      .map(|(name, _)| name.as_str())
+      .collect()
+  }
+}
+
+/// Deserializes plugin options, missing options are the default ones.
+pub fn options<T: DeserializeOwned + Default>(value: &Value) -> Result<T, String> {
+  match value {
+    Value::Null => Ok(T::default()),
+    value => serde_json::from_value(value.clone()).map_err(|error| error.to_string()),
+  }
+}
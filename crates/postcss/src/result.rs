@@ -0,0 +1,131 @@
+use browserslist::Target;
+use node::Node;
+use serde_json::Value;
+use tokenizer::input::ProcessOptions;
+use tokenizer::warning::Warning;
+
+/// Messages plugins pass to the runner and to each other.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+  Warning(Warning),
+  /// The output depends on a file, e.g. the target of an `@import`.
+  Dependency {
+    plugin: String,
+    /// Absolute path to the file.
+    file: String,
+    /// The file which made this dependency, if it is not the input.
+    parent: Option<String>,
+  },
+  /// The output depends on every file of a directory matching a glob.
+  DirDependency {
+    plugin: String,
+    /// Absolute path to the directory.
+    dir: String,
+    glob: Option<String>,
+    parent: Option<String>,
+  },
+  /// Any other data for the runner, like the exports of CSS Modules.
+  Other {
+    plugin: String,
+    /// What the value is, e.g. `export`.
+    kind: String,
+    value: Value,
+  },
+}
+
+/// The result of processing CSS with a [`Processor`](crate::Processor).
+#[derive(Debug, Clone, Default)]
+pub struct ProcessResult {
+  /// The output CSS.
+  pub css: String,
+  /// The source map, unless it was not requested or is inlined into `css`.
+  pub map: Option<String>,
+  /// Warnings and other messages from plugins.
+  pub messages: Vec<Message>,
+  /// The options used to process the CSS.
+  pub opts: ProcessOptions,
+  /// The browsers to support, set by the processor.
+  pub targets: Option<Vec<Target>>,
+  /// The name of the running plugin, set by the processor.
+  pub last_plugin: Option<String>,
+}
+
+impl ProcessResult {
+  pub fn new(opts: ProcessOptions) -> Self {
+    Self {
+      opts,
+      ..Default::default()
+    }
+  }
+
+  /// Adds a warning from the running plugin, positioned at `node`.
+  pub fn warn(&mut self, text: &str, node: Option<&Node>) -> &Warning {
+    let mut warning = match node {
+      Some(node) => node.warning(text),
+      None => Warning::new(text, 1, 1),
+    };
+    warning.plugin = self.last_plugin.clone();
+    self.messages.push(Message::Warning(warning));
+    match self.messages.last() {
+      Some(Message::Warning(warning)) => warning,
+      _ => unreachable!(),
+    }
+  }
+
+  /// Records that the output depends on `file`.
+  pub fn add_dependency(&mut self, file: &str, parent: Option<&str>) {
+    self.messages.push(Message::Dependency {
+      plugin: self.last_plugin.clone().unwrap_or_default(),
+      file: file.to_string(),
+      parent: parent.map(str::to_string),
+    });
+  }
+
+  /// Adds a message of another `kind` from the running plugin.
+  pub fn add_message(&mut self, kind: &str, value: Value) {
+    self.messages.push(Message::Other {
+      plugin: self.last_plugin.clone().unwrap_or_default(),
+      kind: kind.to_string(),
+      value,
+    });
+  }
+
+  /// Returns the values of the messages of `kind`, in the order they were
+  /// added.
+  pub fn messages_of(&self, kind: &str) -> Vec<&Value> {
+    self
+      .messages
+      .iter()
+      .filter_map(|message| match message {
+        Message::Other {
+          kind: other, value, ..
+        } if other == kind => Some(value),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// Returns the warnings from plugins.
+  pub fn warnings(&self) -> Vec<&Warning> {
+    self
+      .messages
+      .iter()
+      .filter_map(|message| match message {
+        Message::Warning(warning) => Some(warning),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// Returns the files the output depends on, in the order they were added.
+  pub fn dependencies(&self) -> Vec<&str> {
+    self
+      .messages
+      .iter()
+      .filter_map(|message| match message {
+        Message::Dependency { file, .. } => Some(file.as_str()),
+        _ => None,
+      })
+      .collect()
+  }
+}
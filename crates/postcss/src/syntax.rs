@@ -0,0 +1,79 @@
+//! Parsers and stringifiers the processor can use instead of plain CSS.
+
+use node::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+use stringify::Builder;
+use tokenizer::error::CssSyntaxError;
+use tokenizer::input::ProcessOptions;
+
+pub type ParseFn =
+  for<'a> fn(&'a str, Option<ProcessOptions>) -> Result<Rc<RefCell<Node<'a>>>, CssSyntaxError>;
+pub type StringifyFn = fn(&Node, Builder);
+
+/// A parser and a stringifier for one input format.
+#[derive(Clone, Copy)]
+pub struct Syntax {
+  pub parse: ParseFn,
+  pub stringify: StringifyFn,
+}
+
+impl Default for Syntax {
+  fn default() -> Self {
+    CSS
+  }
+}
+
+pub const CSS: Syntax = Syntax {
+  parse: parser::parse::parse,
+  stringify: stringify::stringify,
+};
+
+/// The AST serialized as JSON, handy to inspect what the parser produced.
+pub const JSON: Syntax = Syntax {
+  parse: parse_json,
+  stringify: stringify_json,
+};
+
+/// Finds a syntax by name: `css` (or `postcss`) and `json`.
+pub fn syntax(name: &str) -> Option<Syntax> {
+  match name {
+    "css" | "postcss" => Some(CSS),
+    "json" => Some(JSON),
+    _ => None,
+  }
+}
+
+/// Finds a parser by name: `postcss`, `safe` (see `postcss-safe-parser`) and
+/// `json`.
+pub fn parser(name: &str) -> Option<ParseFn> {
+  match name {
+    "postcss" | "css" => Some(parser::parse::parse),
+    "safe" => Some(parser::parse::safe_parse),
+    "json" => Some(parse_json),
+    _ => None,
+  }
+}
+
+fn parse_json(
+  json: &str,
+  _opts: Option<ProcessOptions>,
+) -> Result<Rc<RefCell<Node<'_>>>, CssSyntaxError> {
+  match serde_json::from_str::<Node>(json) {
+    Ok(root) => Ok(Rc::new(RefCell::new(root))),
+    Err(error) => {
+      let mut error = CssSyntaxError::new(
+        &error.to_string(),
+        error.line() as u32,
+        error.column() as u32,
+      );
+      error.source = Some(json.to_string());
+      Err(error)
+    }
+  }
+}
+
+fn stringify_json(node: &Node, builder: Builder) {
+  let json = serde_json::to_string_pretty(node).expect("nodes are serializable");
+  builder(&json, None, None);
+}
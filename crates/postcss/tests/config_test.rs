@@ -0,0 +1,217 @@
+use node::Node;
+use postcss::config::{MapConfig, PluginConfig};
+use postcss::{
+  options, Config, CssSyntaxError, Plugin, ProcessOptions, ProcessResult, Processor, Registry,
+};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use tempfile::TempDir;
+
+/// Appends `suffix` to every rule selector.
+#[derive(Default, serde::Deserialize)]
+struct Suffix {
+  suffix: String,
+}
+
+impl Plugin for Suffix {
+  fn name(&self) -> &str {
+    "suffix"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    for node in root.borrow().as_shared().get_nodes().unwrap() {
+      if let Some(rule) = node.borrow_mut().as_rule_mut() {
+        rule.selector.push_str(&self.suffix);
+      }
+    }
+    Ok(())
+  }
+}
+
+fn registry() -> Registry {
+  let mut registry = Registry::new();
+  registry.register("suffix", |value| Ok(Box::new(options::<Suffix>(value)?)));
+  registry
+}
+
+fn plugin(name: &str, options: Value, enabled: bool) -> PluginConfig {
+  PluginConfig {
+    name: name.into(),
+    options,
+    enabled,
+  }
+}
+
+const TOML: &str = r#"
+parser = "safe"
+map = { inline = false }
+plugins = ["suffix", { name = "other", options = { a = 1 } }]
+
+[[overrides]]
+files = "legacy/**/*.css"
+map = false
+plugins = [{ name = "other", enabled = false }, "third"]
+
+[[overrides]]
+files = ["*.module.css"]
+syntax = "json"
+"#;
+
+#[test]
+fn loads_toml_and_json() {
+  let dir = TempDir::new().unwrap();
+  fs::write(dir.path().join("postcss.toml"), TOML).unwrap();
+  let config = Config::load(&dir.path().join("postcss.toml")).unwrap();
+  assert_eq!(config.parser.as_deref(), Some("safe"));
+  assert!(!config.map.as_ref().unwrap().options().unwrap().inline);
+  assert_eq!(
+    config.plugins,
+    vec![
+      plugin("suffix", Value::Null, true),
+      plugin("other", json!({ "a": 1 }), true)
+    ]
+  );
+  assert_eq!(config.overrides.len(), 2);
+  assert_eq!(
+    config.path.as_deref(),
+    Some(&*dir.path().join("postcss.toml"))
+  );
+
+  let json = r#"{ "map": true, "plugins": [{ "name": "suffix", "options": { "suffix": ".x" } }] }"#;
+  fs::write(dir.path().join("postcss.json"), json).unwrap();
+  let config = Config::load(&dir.path().join("postcss.json")).unwrap();
+  assert_eq!(config.map, Some(MapConfig::Enabled(true)));
+  assert_eq!(config.plugins[0].options, json!({ "suffix": ".x" }));
+
+  fs::write(dir.path().join("bad.toml"), "parser = 1").unwrap();
+  let error = Config::load(&dir.path().join("bad.toml")).unwrap_err();
+  assert!(
+    error.to_string().contains("bad.toml: invalid type"),
+    "{}",
+    error
+  );
+
+  // a misspelled key of a plugin table is not ignored
+  let toml = "[[plugins]]\nname = \"suffix\"\noption = { suffix = \".x\" }";
+  fs::write(dir.path().join("bad.toml"), toml).unwrap();
+  assert!(Config::load(&dir.path().join("bad.toml")).is_err());
+
+  let toml = "[[overrides]]\nfiles = [\"*.css\", \"src/[a.css\"]\nsyntax = \"json\"";
+  fs::write(dir.path().join("bad.toml"), toml).unwrap();
+  let error = Config::load(&dir.path().join("bad.toml")).unwrap_err();
+  assert!(
+    error
+      .to_string()
+      .contains("bad.toml: Invalid glob `src/[a.css`"),
+    "{}",
+    error
+  );
+}
+
+#[test]
+fn applies_overrides_by_glob() {
+  let dir = TempDir::new().unwrap();
+  fs::write(dir.path().join("postcss.toml"), TOML).unwrap();
+  let config = Config::load(&dir.path().join("postcss.toml")).unwrap();
+
+  let legacy = config.for_file(&dir.path().join("legacy/old/a.css"));
+  assert_eq!(legacy.map, Some(MapConfig::Enabled(false)));
+  assert_eq!(
+    legacy.plugins,
+    vec![
+      plugin("suffix", Value::Null, true),
+      plugin("other", Value::Null, false),
+      plugin("third", Value::Null, true),
+    ]
+  );
+  assert!(legacy.overrides.is_empty());
+
+  let module = config.for_file(&dir.path().join("src/button.module.css"));
+  assert_eq!(module.syntax.as_deref(), Some("json"));
+  assert_eq!(module.plugins, config.plugins);
+
+  let plain = config.for_file(&dir.path().join("src/legacy/a.css"));
+  assert_eq!(plain.syntax, None);
+  assert_eq!(plain.plugins, config.plugins);
+}
+
+#[test]
+fn finds_configs_in_ancestors() {
+  let dir = TempDir::new().unwrap();
+  let nested = dir.path().join("packages/a/src");
+  fs::create_dir_all(&nested).unwrap();
+  fs::write(dir.path().join("postcss.json"), r#"{ "parser": "safe" }"#).unwrap();
+  let config = Config::find(&nested).unwrap().unwrap();
+  assert_eq!(config.parser.as_deref(), Some("safe"));
+
+  fs::write(dir.path().join("packages/postcss.toml"), "").unwrap();
+  let config = Config::find(&nested).unwrap().unwrap();
+  assert_eq!(config.parser, None);
+  assert_eq!(
+    config.path.as_deref(),
+    Some(Path::new(&dir.path().join("packages/postcss.toml")))
+  );
+}
+
+#[test]
+fn creates_processors_from_configs() {
+  let config: Config = serde_json::from_value(json!({
+    "parser": "safe",
+    "plugins": ["suffix", { "name": "suffix", "options": { "suffix": ".b" } }],
+  }))
+  .unwrap();
+  let processor = Processor::from_config(&config, &registry()).unwrap();
+  assert_eq!(processor.plugins().len(), 2);
+  let result = processor.process("a {", ProcessOptions::default()).unwrap();
+  assert_eq!(result.css, "a.b {}");
+
+  let config = Config {
+    plugins: vec![plugin("missing", Value::Null, true)],
+    ..Default::default()
+  };
+  let error = Processor::from_config(&config, &registry()).err().unwrap();
+  assert_eq!(
+    error.to_string(),
+    "Unknown plugin \"missing\", known plugins are suffix"
+  );
+
+  let config = Config {
+    plugins: vec![plugin("suffix", json!({ "suffix": 1 }), true)],
+    ..Default::default()
+  };
+  let error = Processor::from_config(&config, &registry()).err().unwrap();
+  assert!(error
+    .message
+    .starts_with("Invalid options of plugin \"suffix\": invalid type"));
+}
+
+#[test]
+fn resolves_browsers_into_targets() {
+  let config: Config =
+    serde_json::from_value(json!({ "browsers": "last 2 Chrome versions" })).unwrap();
+  let processor = Processor::from_config(&config, &registry()).unwrap();
+  let targets: Vec<String> = processor
+    .targets()
+    .unwrap()
+    .iter()
+    .map(ToString::to_string)
+    .collect();
+  assert_eq!(targets, ["chrome 125", "chrome 124"]);
+  let result = processor
+    .process("a {}", ProcessOptions::default())
+    .unwrap();
+  assert_eq!(result.targets.as_deref(), processor.targets());
+
+  let processor = Processor::from_config(&Config::default(), &registry()).unwrap();
+  assert_eq!(processor.targets(), None);
+
+  let config = Config {
+    browsers: Some("netscape 4".to_string()),
+    ..Default::default()
+  };
+  let error = Processor::from_config(&config, &registry()).err().unwrap();
+  assert_eq!(error.to_string(), "Unknown browser netscape");
+}
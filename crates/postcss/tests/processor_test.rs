@@ -0,0 +1,124 @@
+use node::Node;
+use postcss::syntax::{self, JSON};
+use postcss::{CssSyntaxError, MapOptions, Plugin, ProcessOptions, ProcessResult, Processor};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Uppercases the props of declarations and warns about `color`.
+struct Upper;
+
+impl Plugin for Upper {
+  fn name(&self) -> &str {
+    "upper"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    for rule in root.borrow().as_shared().get_nodes().unwrap() {
+      for decl in rule.borrow().as_shared().get_nodes().unwrap_or_default() {
+        let mut decl = decl.borrow_mut();
+        if decl.as_decl().unwrap().prop == "color" {
+          result.warn("Avoid color", Some(&decl));
+        }
+        if decl.as_decl().unwrap().prop == "error" {
+          return Err(decl.error("Bad prop"));
+        }
+        let decl = decl.as_decl_mut().unwrap();
+        decl.prop = decl.prop.to_uppercase();
+      }
+    }
+    Ok(())
+  }
+}
+
+#[test]
+fn runs_plugins_and_collects_warnings() {
+  let mut processor = Processor::new();
+  processor.use_plugin(Box::new(Upper));
+  let result = processor
+    .process("a {\n  color: red\n}", ProcessOptions::default())
+    .unwrap();
+  assert_eq!(result.css, "a {\n  COLOR: red\n}");
+  assert_eq!(result.map, None);
+  let warnings = result.warnings();
+  assert_eq!(warnings.len(), 1);
+  assert_eq!(warnings[0].to_string(), "upper: 2:3: Avoid color");
+
+  let error = processor
+    .process("a { error: 1 }", ProcessOptions::default())
+    .expect_err("should fail");
+  assert_eq!(error.plugin.as_deref(), Some("upper"));
+  assert_eq!(error.reason, "Bad prop");
+  assert_eq!((error.line, error.column), (1, 5));
+}
+
+#[test]
+fn runs_one_plugin() {
+  let result = Processor::run_one(Upper, "a { color: red }").unwrap();
+  assert_eq!(result.css, "a { COLOR: red }");
+  assert_eq!(result.warnings().len(), 1);
+  let error = Processor::run_one(Upper, "a { error: 1 }").unwrap_err();
+  assert_eq!(error.plugin.as_deref(), Some("upper"));
+}
+
+#[test]
+fn generates_external_maps() {
+  let opts = ProcessOptions {
+    from: Some("/project/src/a.css".into()),
+    to: Some("/project/dist/a.css".into()),
+    map: Some(MapOptions {
+      inline: false,
+      ..Default::default()
+    }),
+  };
+  let result = Processor::new().process("a {\n  b: c\n}", opts).unwrap();
+  assert_eq!(
+    result.css,
+    "a {\n  b: c\n}\n/*# sourceMappingURL=a.css.map */"
+  );
+  let map = result.map.unwrap();
+  assert!(map.contains(r#""file":"a.css""#), "{}", map);
+  assert!(map.contains(r#""sources":["../src/a.css"]"#), "{}", map);
+  assert!(
+    map.contains(r#""sourcesContent":["a {\n  b: c\n}"]"#),
+    "{}",
+    map
+  );
+  assert!(
+    map.contains(r#""mappings":"AAAA;EACE,GAAG;AACL"#),
+    "{}",
+    map
+  );
+}
+
+#[test]
+fn inlines_maps_by_default() {
+  let opts = ProcessOptions {
+    map: Some(MapOptions::default()),
+    ..Default::default()
+  };
+  let result = Processor::new().process("a {}", opts).unwrap();
+  assert_eq!(result.map, None);
+  assert!(result
+    .css
+    .starts_with("a {}\n/*# sourceMappingURL=data:application/json;base64,"));
+}
+
+#[test]
+fn converts_between_syntaxes() {
+  let css = "a { b: c }\n@media print { d { e: f } }\n";
+  let mut to_json = Processor::new();
+  to_json
+    .set_syntax(JSON)
+    .set_parser(syntax::parser("postcss").unwrap());
+  let json = to_json.process(css, ProcessOptions::default()).unwrap().css;
+  assert!(json.contains(r#""type": "atrule""#), "{}", json);
+
+  let mut from_json = Processor::new();
+  from_json.set_parser(syntax::parser("json").unwrap());
+  let result = from_json.process(&json, ProcessOptions::default()).unwrap();
+  assert_eq!(result.css, css);
+}
@@ -0,0 +1,129 @@
+use browserslist::{Target, Version};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A vendor prefix, in the order prefixed declarations are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Prefix {
+  Webkit,
+  Moz,
+  Ms,
+  O,
+}
+
+impl Prefix {
+  pub const ALL: [Prefix; 4] = [Prefix::Webkit, Prefix::Moz, Prefix::Ms, Prefix::O];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Prefix::Webkit => "-webkit-",
+      Prefix::Moz => "-moz-",
+      Prefix::Ms => "-ms-",
+      Prefix::O => "-o-",
+    }
+  }
+
+  /// The prefix of `name` and the name without it, case-insensitively.
+  pub fn strip(name: &str) -> Option<(Prefix, &str)> {
+    Prefix::ALL.into_iter().find_map(|prefix| {
+      let len = prefix.as_str().len();
+      match name.get(..len) {
+        Some(start) if start.eq_ignore_ascii_case(prefix.as_str()) => Some((prefix, &name[len..])),
+        _ => None,
+      }
+    })
+  }
+}
+
+/// The prefixes of some browser versions, from and to a version.
+type Prefixes = Vec<(Prefix, Option<String>, Option<String>)>;
+
+#[derive(Deserialize)]
+struct Group {
+  features: Vec<String>,
+  prefixes: HashMap<String, Prefixes>,
+}
+
+/// The versions of a browser that need a prefix for a feature.
+struct Range {
+  browser: String,
+  prefix: Prefix,
+  from: Option<Version>,
+  to: Option<Version>,
+}
+
+/// The prefix table, embedded so that no network access is needed. It is
+/// extracted from the one of lightningcss 1.0.0-alpha.58, under the MPL-2.0
+/// (see `data/README.md`), with the versions of Android, Chrome, Edge,
+/// Firefox, Internet Explorer, iOS Safari, Opera, Safari and Samsung
+/// Internet needing each prefix of each feature.
+static FEATURES: Lazy<HashMap<String, Vec<Range>>> = Lazy::new(|| {
+  let groups: Vec<Group> = serde_json::from_str(include_str!("../data/prefixes.json")).unwrap();
+  let mut features = HashMap::new();
+  for group in groups {
+    for feature in group.features {
+      let ranges = group
+        .prefixes
+        .iter()
+        .flat_map(|(browser, ranges)| {
+          ranges.iter().map(move |(prefix, from, to)| Range {
+            browser: browser.clone(),
+            prefix: *prefix,
+            from: from.as_deref().and_then(Version::parse),
+            to: to.as_deref().and_then(Version::parse),
+          })
+        })
+        .collect();
+      features.insert(feature, ranges);
+    }
+  }
+  features
+});
+
+/// The prefixes a set of targets needs for each feature of the prefix
+/// table, like `transform`, `linear-gradient` or `pseudo-element-placeholder`.
+#[derive(Debug, Clone, Default)]
+pub struct Support {
+  needed: HashMap<String, Vec<Prefix>>,
+}
+
+impl Support {
+  pub fn new(targets: &[Target]) -> Self {
+    let mut needed = HashMap::new();
+    for (feature, ranges) in FEATURES.iter() {
+      let mut prefixes: Vec<Prefix> = ranges
+        .iter()
+        .filter(|range| {
+          targets.iter().any(|target| {
+            target.table_browser() == range.browser
+              && target.numeric_version().is_some_and(|version| {
+                range.from.is_none_or(|from| version >= from)
+                  && range.to.is_none_or(|to| version <= to)
+              })
+          })
+        })
+        .map(|range| range.prefix)
+        .collect();
+      prefixes.sort();
+      prefixes.dedup();
+      needed.insert(feature.clone(), prefixes);
+    }
+    Self { needed }
+  }
+
+  /// Returns `true` if the feature is in the prefix table.
+  pub fn is_known(feature: &str) -> bool {
+    FEATURES.contains_key(feature)
+  }
+
+  /// The prefixes needed for `feature`.
+  pub fn prefixes(&self, feature: &str) -> &[Prefix] {
+    self.needed.get(feature).map_or(&[], |prefixes| prefixes)
+  }
+
+  pub fn needs(&self, feature: &str, prefix: Prefix) -> bool {
+    self.prefixes(feature).contains(&prefix)
+  }
+}
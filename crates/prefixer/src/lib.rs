@@ -0,0 +1,8 @@
+mod data;
+mod plugin;
+mod properties;
+mod selectors;
+mod values;
+
+pub use crate::data::{Prefix, Support};
+pub use crate::plugin::{Options, Prefixer};
@@ -0,0 +1,296 @@
+use crate::data::{Prefix, Support};
+use crate::properties::{
+  flex_2009, is_flexbox, is_property, ms_flex_value, prefixed_property, unprefixed_property,
+};
+use crate::selectors::{is_outdated_selector, prefix_selector};
+use crate::values::{is_outdated_value, prefix_value};
+use browserslist::Target;
+use node::{Declaration, Node};
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// The browserslist query of the browsers to support, see
+  /// [`targets_or_defaults`](browserslist::targets_or_defaults).
+  pub browsers: Option<String>,
+  /// Removes the prefixes that none of the browsers need.
+  pub remove: bool,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      browsers: None,
+      remove: true,
+    }
+  }
+}
+
+/// Adds the vendor prefixes the browsers of a browserslist query need, like
+/// autoprefixer, and removes the ones they do not.
+///
+/// The prefixes are looked up in an embedded table. Properties like
+/// `user-select`, values like `display: flex`, `position: sticky` or
+/// `linear-gradient()`, selectors like `::placeholder` and `@keyframes` are
+/// prefixed. Prefixed declarations and rules are added before the standard
+/// ones, unless they are already there, and `@keyframes` get copies with
+/// only the prefix of their name inside. Grid layout is left alone.
+///
+/// With `remove`, prefixed properties, selectors and at-rules that no browser
+/// needs are removed, and so are declarations with prefixed values that are
+/// followed by one of the same property.
+#[derive(Debug, Clone)]
+pub struct Prefixer {
+  pub options: Options,
+  /// The targets of `options.browsers`, if set.
+  pub targets: Option<Vec<Target>>,
+}
+
+impl Prefixer {
+  /// Creates a plugin for the browsers of `options.browsers`.
+  pub fn new(options: Options) -> Result<Self, browserslist::Error> {
+    let targets = options
+      .browsers
+      .as_deref()
+      .map(browserslist::resolve)
+      .transpose()?;
+    Ok(Self { options, targets })
+  }
+
+  /// Prefixes the children of `parent`, with `only` a single prefix when it
+  /// is inside a prefixed at-rule.
+  fn prefix<'a>(&self, parent: &Rc<RefCell<Node<'a>>>, support: &Support, only: Option<Prefix>) {
+    let children = match parent.borrow().as_shared().get_nodes() {
+      Some(children) => children,
+      None => return,
+    };
+    let allowed = |prefix: &Prefix| only.is_none_or(|only| only == *prefix);
+    let mut nodes = Vec::with_capacity(children.len());
+    for (i, child) in children.iter().enumerate() {
+      let count = nodes.len();
+      let added = match &*child.borrow() {
+        Node::Decl(decl) => {
+          if self.options.remove && is_outdated_decl(decl, &children[i + 1..], support) {
+            continue;
+          }
+          decl_prefixes(decl, support, only)
+            .into_iter()
+            .filter(|(prop, value)| {
+              let value = (prop == &decl.prop).then_some(value.as_str());
+              !has_decl(&children, prop, value) && !has_decl(&nodes, prop, value)
+            })
+            .map(|(prop, value)| {
+              let node = child.borrow().deep_clone();
+              let node = Rc::new(RefCell::new(node));
+              if let Some(prefixed) = node.borrow_mut().as_decl_mut() {
+                prefixed.prop = prop;
+                if value != prefixed.value {
+                  prefixed.value = value;
+                  prefixed.raws.value = None;
+                }
+              }
+              node
+            })
+            .collect()
+        }
+        Node::Rule(rule) => {
+          if self.options.remove && is_outdated_selector(&rule.selector, support) {
+            continue;
+          }
+          vec![]
+        }
+        Node::AtRule(at_rule) => {
+          let name = at_rule.name.to_ascii_lowercase();
+          if let Some((prefix, base)) = Prefix::strip(&name) {
+            let outdated = match base {
+              "keyframes" => !support.needs("at-keyframes", prefix),
+              "viewport" => !support.needs("at-viewport", prefix),
+              _ => false,
+            };
+            if self.options.remove && outdated {
+              continue;
+            }
+          }
+          let feature = match name.as_str() {
+            "keyframes" => "at-keyframes",
+            "viewport" => "at-viewport",
+            _ => "",
+          };
+          support
+            .prefixes(feature)
+            .iter()
+            .filter(|prefix| allowed(prefix))
+            .filter(|prefix| {
+              let name = format!("{}{}", prefix.as_str(), name);
+              !children.iter().chain(nodes.iter()).any(|node| {
+                matches!(&*node.borrow(), Node::AtRule(other)
+                  if other.name.eq_ignore_ascii_case(&name) && other.params == at_rule.params)
+              })
+            })
+            .map(|prefix| {
+              let node = Rc::new(RefCell::new(child.borrow().deep_clone()));
+              if let Some(prefixed) = node.borrow_mut().as_at_rule_mut() {
+                prefixed.name = format!("{}{}", prefix.as_str(), prefixed.name);
+              }
+              self.prefix(&node, support, Some(*prefix));
+              node
+            })
+            .collect()
+        }
+        _ => vec![],
+      };
+      nodes.extend(added);
+
+      let only = match &*child.borrow() {
+        Node::AtRule(at_rule) => Prefix::strip(&at_rule.name)
+          .filter(|(_, base)| base.eq_ignore_ascii_case("keyframes"))
+          .map(|(prefix, _)| prefix)
+          .or(only),
+        _ => only,
+      };
+      self.prefix(child, support, only);
+
+      let selector = child.borrow().as_rule().map(|rule| rule.selector.clone());
+      if let Some(selector) = selector {
+        for prefix in Prefix::ALL.iter().filter(|prefix| allowed(prefix)) {
+          let prefixed = match prefix_selector(&selector, *prefix, support) {
+            Some(prefixed) => prefixed,
+            None => continue,
+          };
+          let exists = children
+            .iter()
+            .chain(nodes.iter())
+            .any(|node| matches!(&*node.borrow(), Node::Rule(other) if other.selector == prefixed));
+          if exists {
+            continue;
+          }
+          let mut node = child.borrow().deep_clone();
+          if let Some(rule) = node.as_rule_mut() {
+            rule.selector = prefixed;
+            rule.raws.selector = None;
+          }
+          nodes.push(Rc::new(RefCell::new(node)));
+        }
+      }
+
+      if nodes.len() > count && is_first(parent, child) {
+        // The node was the first of the stylesheet, only its first prefixed
+        // copy is now.
+        let before = children
+          .get(i + 1)
+          .and_then(|next| next.borrow().as_shared().as_raws().get_raw_before())
+          .unwrap_or_else(|| "\n".to_string());
+        for node in nodes[count + 1..].iter().chain([child]) {
+          node.borrow_mut().set_raw_before(before.clone());
+        }
+      }
+      nodes.push(child.clone());
+    }
+    if let Some(children) = parent.borrow_mut().as_shared_mut().get_nodes_mut() {
+      *children = nodes;
+    }
+  }
+}
+
+impl Plugin for Prefixer {
+  fn name(&self) -> &str {
+    "prefixer"
+  }
+
+  fn run(
+    &self,
+    root: &Rc<RefCell<Node>>,
+    result: &mut ProcessResult,
+  ) -> Result<(), CssSyntaxError> {
+    let targets = self.targets.as_deref().or(result.targets.as_deref());
+    let support = Support::new(&browserslist::targets_or_defaults(targets));
+    self.prefix(root, &support, None);
+    Ok(())
+  }
+}
+
+/// The prefixed properties and values of a declaration, in the order they
+/// are added before it.
+fn decl_prefixes(
+  decl: &Declaration,
+  support: &Support,
+  only: Option<Prefix>,
+) -> Vec<(String, String)> {
+  let prop = decl.prop.to_ascii_lowercase();
+  if decl.variable || Prefix::strip(&prop).is_some() {
+    return vec![];
+  }
+  let allowed = |prefix: Prefix| only.is_none_or(|only| only == prefix);
+  let mut decls = vec![];
+  if allowed(Prefix::Webkit) && support.needs("flex-2009", Prefix::Webkit) {
+    decls.extend(flex_2009(&prop, &decl.value));
+  }
+  if is_property(&prop) {
+    for prefix in support.prefixes(&prop) {
+      // Firefox only had the 2009 flexbox syntax prefixed, with `-moz-box`.
+      if !allowed(*prefix) || (*prefix == Prefix::Moz && is_flexbox(&prop)) {
+        continue;
+      }
+      let value = match prefix {
+        Prefix::Ms if is_flexbox(&prop) => ms_flex_value(&prop, &decl.value),
+        prefix => prefix_value(&prop, &decl.value, *prefix, support),
+      };
+      decls.push((prefixed_property(&prop, *prefix), value));
+    }
+  }
+  for prefix in Prefix::ALL.into_iter().filter(|prefix| allowed(*prefix)) {
+    let value = prefix_value(&prop, &decl.value, prefix, support);
+    if value != decl.value {
+      decls.push((decl.prop.clone(), value));
+    }
+  }
+  let mut unique: Vec<(String, String)> = vec![];
+  for decl in decls {
+    if !unique.contains(&decl) {
+      unique.push(decl);
+    }
+  }
+  unique
+}
+
+/// Returns `true` if the declaration has a prefixed property that none of
+/// the targets need, or a prefixed value they do not need before another
+/// declaration of the property in `next`.
+fn is_outdated_decl(decl: &Declaration, next: &[Rc<RefCell<Node>>], support: &Support) -> bool {
+  if let Some((prefix, feature)) = unprefixed_property(&decl.prop) {
+    return !support.needs(&feature, prefix);
+  }
+  let prop = decl.prop.to_ascii_lowercase();
+  is_outdated_value(&prop, &decl.value, support)
+    && next.iter().any(
+      |node| matches!(&*node.borrow(), Node::Decl(other) if other.prop.eq_ignore_ascii_case(&prop)),
+    )
+}
+
+/// Returns `true` if `nodes` has a declaration of `prop`, with `value` if it
+/// is not `None`.
+fn has_decl(nodes: &[Rc<RefCell<Node>>], prop: &str, value: Option<&str>) -> bool {
+  nodes.iter().any(|node| match &*node.borrow() {
+    Node::Decl(decl) => {
+      decl.prop.eq_ignore_ascii_case(prop) && value.is_none_or(|value| decl.value == value)
+    }
+    _ => false,
+  })
+}
+
+/// Returns `true` if `child` is the first child of a root, without
+/// whitespace before it.
+fn is_first(parent: &Rc<RefCell<Node>>, child: &Rc<RefCell<Node>>) -> bool {
+  parent.borrow().is_root()
+    && child
+      .borrow()
+      .as_shared()
+      .as_raws()
+      .get_raw_before()
+      .as_deref()
+      == Some("")
+}
@@ -0,0 +1,237 @@
+use crate::data::{Prefix, Support};
+
+/// The features of the prefix table that are not properties, or that are
+/// left alone, like grid layout which needs more than prefixes for IE.
+const NOT_PROPERTIES: &[&str] = &[
+  "any-pseudo",
+  "at-keyframes",
+  "at-resolution",
+  "at-viewport",
+  "calc",
+  "color-adjust",
+  "cross-fade",
+  "display-flex",
+  "display-grid",
+  "element",
+  "fill",
+  "fill-available",
+  "filter-function",
+  "fit-content",
+  "flex-2009",
+  "grab",
+  "grabbing",
+  "image-rendering",
+  "image-set",
+  "inline-flex",
+  "inline-grid",
+  "isolate",
+  "isolate-override",
+  "linear-gradient",
+  "max-content",
+  "min-content",
+  "pixelated",
+  "place-self",
+  "plaintext",
+  "radial-gradient",
+  "repeating-linear-gradient",
+  "repeating-radial-gradient",
+  "sticky",
+  "stretch",
+  "text-spacing",
+  "zoom-in",
+  "zoom-out",
+];
+
+/// Returns `true` if `prop` is a property of the prefix table.
+pub(crate) fn is_property(prop: &str) -> bool {
+  Support::is_known(prop)
+    && !prop.starts_with("grid-")
+    && !prop.starts_with("pseudo-")
+    && !NOT_PROPERTIES.contains(&prop)
+}
+
+/// The prefixed properties that are not named after the standard one, for
+/// one prefix or for all of them.
+const NAMES: &[(&str, Option<Prefix>, &str)] = &[
+  ("order", Some(Prefix::Ms), "flex-order"),
+  ("justify-content", Some(Prefix::Ms), "flex-pack"),
+  ("align-items", Some(Prefix::Ms), "flex-align"),
+  ("align-self", Some(Prefix::Ms), "flex-item-align"),
+  ("align-content", Some(Prefix::Ms), "flex-line-pack"),
+  ("flex-grow", Some(Prefix::Ms), "flex-positive"),
+  ("flex-shrink", Some(Prefix::Ms), "flex-negative"),
+  ("flex-basis", Some(Prefix::Ms), "flex-preferred-size"),
+  ("mask-border", Some(Prefix::Webkit), "mask-box-image"),
+  (
+    "mask-border-source",
+    Some(Prefix::Webkit),
+    "mask-box-image-source",
+  ),
+  (
+    "mask-border-slice",
+    Some(Prefix::Webkit),
+    "mask-box-image-slice",
+  ),
+  (
+    "mask-border-width",
+    Some(Prefix::Webkit),
+    "mask-box-image-width",
+  ),
+  (
+    "mask-border-outset",
+    Some(Prefix::Webkit),
+    "mask-box-image-outset",
+  ),
+  (
+    "mask-border-repeat",
+    Some(Prefix::Webkit),
+    "mask-box-image-repeat",
+  ),
+  ("break-before", Some(Prefix::Webkit), "column-break-before"),
+  ("break-after", Some(Prefix::Webkit), "column-break-after"),
+  ("break-inside", Some(Prefix::Webkit), "column-break-inside"),
+  ("margin-inline-start", None, "margin-start"),
+  ("margin-inline-end", None, "margin-end"),
+  ("padding-inline-start", None, "padding-start"),
+  ("padding-inline-end", None, "padding-end"),
+  ("border-inline-start", None, "border-start"),
+  ("border-inline-end", None, "border-end"),
+  ("margin-block-start", None, "margin-before"),
+  ("margin-block-end", None, "margin-after"),
+  ("padding-block-start", None, "padding-before"),
+  ("padding-block-end", None, "padding-after"),
+  ("border-block-start", None, "border-before"),
+  ("border-block-end", None, "border-after"),
+];
+
+/// The name of `prop` with `prefix`, e.g. `-ms-flex-pack` for
+/// `justify-content`.
+pub(crate) fn prefixed_property(prop: &str, prefix: Prefix) -> String {
+  let name = NAMES
+    .iter()
+    .find(|(standard, only, _)| *standard == prop && only.is_none_or(|only| only == prefix))
+    .map_or(prop, |(_, _, name)| name);
+  format!("{}{}", prefix.as_str(), name)
+}
+
+/// The prefix and the feature of a prefixed property, e.g. `justify-content`
+/// for `-ms-flex-pack`, or `flex-2009` for `-webkit-box-flex`.
+pub(crate) fn unprefixed_property(prop: &str) -> Option<(Prefix, String)> {
+  let (prefix, name) = Prefix::strip(prop)?;
+  let name = name.to_ascii_lowercase();
+  if prefix == Prefix::Webkit && FLEX_2009.iter().any(|(_, old)| *old == name) {
+    return Some((prefix, "flex-2009".to_string()));
+  }
+  let renamed = NAMES
+    .iter()
+    .find(|(_, only, renamed)| *renamed == name && only.is_none_or(|only| only == prefix));
+  match renamed {
+    Some((standard, _, _)) => Some((prefix, standard.to_string())),
+    None if is_property(&name) => Some((prefix, name)),
+    None => None,
+  }
+}
+
+/// Returns `true` if `prop` is a property of flexbox layout.
+pub(crate) fn is_flexbox(prop: &str) -> bool {
+  matches!(
+    prop,
+    "flex"
+      | "flex-grow"
+      | "flex-shrink"
+      | "flex-basis"
+      | "flex-direction"
+      | "flex-wrap"
+      | "flex-flow"
+      | "justify-content"
+      | "order"
+      | "align-items"
+      | "align-self"
+      | "align-content"
+  )
+}
+
+/// The properties of the 2009 flexbox syntax, for the standard ones.
+const FLEX_2009: &[(&str, &str)] = &[
+  ("flex", "box-flex"),
+  ("order", "box-ordinal-group"),
+  ("flex-direction", "box-orient"),
+  ("flex-direction", "box-direction"),
+  ("justify-content", "box-pack"),
+  ("align-items", "box-align"),
+];
+
+/// The `-webkit-` declarations of the 2009 flexbox syntax for a standard
+/// one, e.g. `-webkit-box-ordinal-group: 2` for `order: 1`.
+pub(crate) fn flex_2009(prop: &str, value: &str) -> Vec<(String, String)> {
+  let value = value.trim().to_ascii_lowercase();
+  let webkit = |name: &str, value: &str| (format!("-webkit-{}", name), value.to_string());
+  match prop {
+    "display" => match value.as_str() {
+      "flex" => vec![("display".to_string(), "-webkit-box".to_string())],
+      "inline-flex" => vec![("display".to_string(), "-webkit-inline-box".to_string())],
+      _ => vec![],
+    },
+    "flex" => {
+      let grow = match value.split_whitespace().next() {
+        Some("none") => Some("0"),
+        Some("auto") => Some("1"),
+        Some(grow) if grow.parse::<f64>().is_ok() => Some(grow),
+        _ => None,
+      };
+      grow
+        .map(|grow| webkit("box-flex", grow))
+        .into_iter()
+        .collect()
+    }
+    "order" => match value.parse::<i64>() {
+      Ok(order) => vec![webkit("box-ordinal-group", &(order + 1).to_string())],
+      Err(_) => vec![],
+    },
+    "flex-direction" => {
+      let (orient, direction) = match value.as_str() {
+        "row" => ("horizontal", "normal"),
+        "row-reverse" => ("horizontal", "reverse"),
+        "column" => ("vertical", "normal"),
+        "column-reverse" => ("vertical", "reverse"),
+        _ => return vec![],
+      };
+      vec![
+        webkit("box-orient", orient),
+        webkit("box-direction", direction),
+      ]
+    }
+    "justify-content" | "align-items" => {
+      let value = match value.as_str() {
+        "flex-start" | "start" => "start",
+        "flex-end" | "end" => "end",
+        "space-between" if prop == "justify-content" => "justify",
+        "center" => "center",
+        "baseline" | "stretch" if prop == "align-items" => value.as_str(),
+        _ => return vec![],
+      };
+      let name = if prop == "justify-content" {
+        "box-pack"
+      } else {
+        "box-align"
+      };
+      vec![webkit(name, value)]
+    }
+    _ => vec![],
+  }
+}
+
+/// The value of a `-ms-` flexbox alignment property of the 2012 syntax.
+pub(crate) fn ms_flex_value(prop: &str, value: &str) -> String {
+  if !prop.starts_with("justify-") && !prop.starts_with("align-") {
+    return value.to_string();
+  }
+  match value.trim().to_ascii_lowercase().as_str() {
+    "flex-start" => "start",
+    "flex-end" => "end",
+    "space-between" => "justify",
+    "space-around" => "distribute",
+    _ => return value.to_string(),
+  }
+  .to_string()
+}
@@ -0,0 +1,91 @@
+use crate::data::{Prefix, Support};
+use selector::{PseudoSelector, SelectorList, SimpleSelector};
+
+/// The pseudo-classes and pseudo-elements of the prefix table, with their
+/// feature.
+const PSEUDOS: &[(&str, &str)] = &[
+  ("selection", "pseudo-element-selection"),
+  ("placeholder", "pseudo-element-placeholder"),
+  ("placeholder-shown", "pseudo-class-placeholder-shown"),
+  ("fullscreen", "pseudo-class-fullscreen"),
+  ("backdrop", "pseudo-element-backdrop"),
+  (
+    "file-selector-button",
+    "pseudo-element-file-selector-button",
+  ),
+  ("autofill", "pseudo-class-autofill"),
+  ("read-only", "pseudo-class-read-only"),
+  ("read-write", "pseudo-class-read-write"),
+  ("any-link", "pseudo-class-any-link"),
+];
+
+/// The name of a pseudo-class or pseudo-element with `prefix`, e.g.
+/// `-webkit-input-placeholder` for `placeholder`.
+fn prefixed_pseudo(name: &str, prefix: Prefix) -> String {
+  let name = match (name, prefix) {
+    ("placeholder", Prefix::Webkit | Prefix::Ms) => "input-placeholder",
+    ("fullscreen", Prefix::Webkit | Prefix::Moz) => "full-screen",
+    ("file-selector-button", Prefix::Webkit) => "file-upload-button",
+    ("file-selector-button", Prefix::Ms) => "browse",
+    (name, _) => name,
+  };
+  format!("{}{}", prefix.as_str(), name)
+}
+
+/// Calls `visit` with the pseudo-classes and pseudo-elements of `list`,
+/// including those in the arguments of others.
+fn walk<F: FnMut(&mut PseudoSelector)>(list: &mut SelectorList, visit: &mut F) {
+  for selector in &mut list.selectors {
+    for compound in &mut selector.compounds {
+      for simple in &mut compound.selectors {
+        if let SimpleSelector::PseudoClass(pseudo) | SimpleSelector::PseudoElement(pseudo) = simple
+        {
+          visit(pseudo);
+          if let Some(list) = pseudo.selectors_mut() {
+            walk(list, visit);
+          }
+        }
+      }
+    }
+  }
+}
+
+/// The selector with the pseudo-classes and pseudo-elements that need
+/// `prefix` prefixed, or `None` if there are none.
+pub(crate) fn prefix_selector(selector: &str, prefix: Prefix, support: &Support) -> Option<String> {
+  let mut list = selector::parse(selector).ok()?;
+  let mut prefixed = false;
+  walk(&mut list, &mut |pseudo| {
+    let name = pseudo.lower_name();
+    if let Some((_, feature)) = PSEUDOS.iter().find(|(pseudo, _)| *pseudo == name) {
+      if support.needs(feature, prefix) {
+        pseudo.name = prefixed_pseudo(&name, prefix);
+        // Internet Explorer only knows the pseudo-class.
+        pseudo.legacy |= name == "placeholder" && prefix == Prefix::Ms;
+        prefixed = true;
+      }
+    }
+  });
+  prefixed.then(|| list.to_string())
+}
+
+/// Returns `true` if the selector has a prefixed pseudo-class or
+/// pseudo-element that none of the targets need.
+pub(crate) fn is_outdated_selector(selector: &str, support: &Support) -> bool {
+  let mut list = match selector::parse(selector) {
+    Ok(list) => list,
+    Err(_) => return false,
+  };
+  let mut outdated = false;
+  walk(&mut list, &mut |pseudo| {
+    let name = pseudo.lower_name();
+    for (standard, feature) in PSEUDOS {
+      for prefix in Prefix::ALL {
+        if prefixed_pseudo(standard, prefix) == name {
+          outdated |= !support.needs(feature, prefix);
+        }
+      }
+    }
+  });
+  outdated
+}
@@ -0,0 +1,281 @@
+use crate::data::{Prefix, Support};
+use crate::properties::{is_property, prefixed_property, unprefixed_property};
+
+/// The functions of the prefix table, with their feature.
+const FUNCTIONS: &[(&str, &str)] = &[
+  ("linear-gradient", "linear-gradient"),
+  ("repeating-linear-gradient", "repeating-linear-gradient"),
+  ("radial-gradient", "radial-gradient"),
+  ("repeating-radial-gradient", "repeating-radial-gradient"),
+  ("calc", "calc"),
+  ("element", "element"),
+  ("cross-fade", "cross-fade"),
+  ("image-set", "image-set"),
+  ("filter", "filter-function"),
+];
+
+/// The properties whose values are sizes.
+const SIZES: &[&str] = &[
+  "width",
+  "height",
+  "min-width",
+  "min-height",
+  "max-width",
+  "max-height",
+  "inline-size",
+  "block-size",
+  "min-inline-size",
+  "min-block-size",
+  "max-inline-size",
+  "max-block-size",
+  "flex-basis",
+];
+
+/// The properties whose values are property names.
+const PROPERTY_LISTS: &[&str] = &["transition", "transition-property", "will-change"];
+
+/// The feature of a keyword in a value of `prop`, e.g. `sticky` for
+/// `position: sticky`.
+fn keyword_feature(prop: &str, keyword: &str) -> Option<&'static str> {
+  let feature = match (prop, keyword) {
+    ("display", "flex") => "display-flex",
+    ("display", "inline-flex") => "inline-flex",
+    ("position", "sticky") => "sticky",
+    ("cursor", "zoom-in") => "zoom-in",
+    ("cursor", "zoom-out") => "zoom-out",
+    ("cursor", "grab") => "grab",
+    ("cursor", "grabbing") => "grabbing",
+    ("unicode-bidi", "isolate") => "isolate",
+    ("unicode-bidi", "isolate-override") => "isolate-override",
+    ("unicode-bidi", "plaintext") => "plaintext",
+    (prop, keyword) if SIZES.contains(&prop) => match keyword {
+      "max-content" => "max-content",
+      "min-content" => "min-content",
+      "fit-content" => "fit-content",
+      "fill-available" => "fill-available",
+      "stretch" => "stretch",
+      _ => return None,
+    },
+    _ => return None,
+  };
+  Some(feature)
+}
+
+/// The keyword with `prefix`, e.g. `-ms-flexbox` for `flex`.
+fn prefixed_keyword(keyword: &str, prefix: Prefix) -> String {
+  let name = match (keyword, prefix) {
+    ("flex", Prefix::Ms) => "flexbox",
+    ("inline-flex", Prefix::Ms) => "inline-flexbox",
+    ("flex", Prefix::Moz) => "box",
+    ("inline-flex", Prefix::Moz) => "inline-box",
+    ("fill-available" | "stretch", Prefix::Moz) => "available",
+    ("stretch", _) => "fill-available",
+    (keyword, _) => keyword,
+  };
+  format!("{}{}", prefix.as_str(), name)
+}
+
+/// The prefix and the feature of a prefixed keyword in a value of `prop`.
+fn unprefixed_keyword(prop: &str, keyword: &str) -> Option<(Prefix, &'static str)> {
+  let (prefix, name) = Prefix::strip(keyword)?;
+  let feature = match (prop, prefix, name.to_ascii_lowercase().as_str()) {
+    ("display", Prefix::Webkit, "box" | "inline-box") => "flex-2009",
+    ("display", Prefix::Ms | Prefix::Moz, "flexbox" | "box") => "display-flex",
+    ("display", Prefix::Ms | Prefix::Moz, "inline-flexbox" | "inline-box") => "inline-flex",
+    (prop, Prefix::Moz, "available") if SIZES.contains(&prop) => "fill-available",
+    (prop, _, name) => keyword_feature(prop, name)?,
+  };
+  Some((prefix, feature))
+}
+
+/// Calls `map` with every identifier of `value` and whether it is the name
+/// of a function, and replaces the identifier with the result. Strings and
+/// URLs are left alone.
+fn map_idents<F>(value: &str, mut map: F) -> String
+where
+  F: FnMut(&str, bool) -> Option<String>,
+{
+  let bytes = value.as_bytes();
+  let is_ident =
+    |byte: u8| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' || byte >= 0x80;
+  let mut result = String::with_capacity(value.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let start = i;
+    match bytes[i] {
+      quote @ (b'"' | b'\'') => {
+        i += 1;
+        while i < bytes.len() && bytes[i] != quote {
+          i += if bytes[i] == b'\\' { 2 } else { 1 };
+        }
+        i = (i + 1).min(bytes.len());
+        result.push_str(&value[start..i]);
+      }
+      byte if is_ident(byte) && !byte.is_ascii_digit() => {
+        while i < bytes.len() && is_ident(bytes[i]) {
+          i += 1;
+        }
+        let ident = &value[start..i];
+        let function = bytes.get(i) == Some(&b'(');
+        if function && ident.eq_ignore_ascii_case("url") {
+          while i < bytes.len() && bytes[i] != b')' {
+            i += 1;
+          }
+          result.push_str(&value[start..i]);
+        } else {
+          match map(ident, function) {
+            Some(replaced) => result.push_str(&replaced),
+            None => result.push_str(ident),
+          }
+        }
+      }
+      byte if is_ident(byte) => {
+        // A number and its unit.
+        while i < bytes.len() && is_ident(bytes[i]) {
+          i += 1;
+        }
+        result.push_str(&value[start..i]);
+      }
+      _ => {
+        i += 1;
+        result.push_str(&value[start..i]);
+      }
+    }
+  }
+  result
+}
+
+/// The value of a declaration of `prop` with the functions, keywords and
+/// property names that need `prefix` prefixed, e.g.
+/// `-webkit-linear-gradient(left, red, blue)` for
+/// `linear-gradient(to right, red, blue)`.
+pub(crate) fn prefix_value(prop: &str, value: &str, prefix: Prefix, support: &Support) -> String {
+  let mut gradients = false;
+  let value = map_idents(value, |ident, function| {
+    let name = ident.to_ascii_lowercase();
+    if function {
+      let (_, feature) = FUNCTIONS.iter().find(|(function, _)| *function == name)?;
+      if !support.needs(feature, prefix) {
+        return None;
+      }
+      gradients |= feature.ends_with("gradient");
+      Some(format!("{}{}", prefix.as_str(), ident))
+    } else if let Some(feature) = keyword_feature(prop, &name) {
+      support
+        .needs(feature, prefix)
+        .then(|| prefixed_keyword(&name, prefix))
+    } else if PROPERTY_LISTS.contains(&prop) && is_property(&name) {
+      support
+        .needs(&name, prefix)
+        .then(|| prefixed_property(&name, prefix))
+    } else {
+      None
+    }
+  });
+  if gradients {
+    old_gradients(&value, prefix)
+  } else {
+    value
+  }
+}
+
+/// Returns `true` if `value` has a prefixed function, keyword or property
+/// name that none of the targets need.
+pub(crate) fn is_outdated_value(prop: &str, value: &str, support: &Support) -> bool {
+  let mut outdated = false;
+  map_idents(value, |ident, function| {
+    let found = if function {
+      Prefix::strip(ident).and_then(|(prefix, name)| {
+        let name = name.to_ascii_lowercase();
+        FUNCTIONS
+          .iter()
+          .find(|(function, _)| *function == name)
+          .map(|(_, feature)| (prefix, feature.to_string()))
+      })
+    } else if let Some((prefix, feature)) = unprefixed_keyword(prop, ident) {
+      Some((prefix, feature.to_string()))
+    } else if PROPERTY_LISTS.contains(&prop) {
+      unprefixed_property(ident)
+    } else {
+      None
+    };
+    if let Some((prefix, feature)) = found {
+      outdated |= !support.needs(&feature, prefix);
+    }
+    None
+  });
+  outdated
+}
+
+/// Converts the first arguments of the prefixed gradients of `value` to the
+/// syntax before the standard one, e.g. `left` for `to right`.
+fn old_gradients(value: &str, prefix: Prefix) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut rest = value;
+  while let Some(index) = rest.find("-gradient(") {
+    let open = index + "-gradient(".len();
+    let name_start = rest[..index]
+      .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+      .map_or(0, |i| i + 1);
+    let name = &rest[name_start..index];
+    let prefixed = name.starts_with(prefix.as_str());
+    let radial = name.ends_with("radial");
+    result.push_str(&rest[..open]);
+    rest = &rest[open..];
+    if !prefixed {
+      continue;
+    }
+    let end = first_argument_end(rest);
+    let argument = &rest[..end];
+    let converted = if radial && argument.contains(" at ") {
+      match argument.split_once(" at ") {
+        Some((shape, position)) => format!("{}, {}", position.trim(), shape.trim()),
+        None => argument.to_string(),
+      }
+    } else {
+      old_direction(argument).unwrap_or_else(|| argument.to_string())
+    };
+    result.push_str(&converted);
+    rest = &rest[end..];
+  }
+  result.push_str(rest);
+  result
+}
+
+/// The end of the first argument of a function, at the first comma or
+/// closing parenthesis that is not nested.
+fn first_argument_end(arguments: &str) -> usize {
+  let mut depth = 0;
+  for (i, c) in arguments.char_indices() {
+    match c {
+      '(' => depth += 1,
+      ')' if depth == 0 => return i,
+      ')' => depth -= 1,
+      ',' if depth == 0 => return i,
+      _ => {}
+    }
+  }
+  arguments.len()
+}
+
+/// The direction of a prefixed linear gradient for a standard one, `to`
+/// sides become the sides the gradient starts from, and angles go
+/// counterclockwise from the right instead of clockwise from the top.
+fn old_direction(direction: &str) -> Option<String> {
+  let direction = direction.trim();
+  if let Some(sides) = direction.strip_prefix("to ") {
+    let sides: Vec<&str> = sides
+      .split_whitespace()
+      .map(|side| match side {
+        "top" => Some("bottom"),
+        "bottom" => Some("top"),
+        "left" => Some("right"),
+        "right" => Some("left"),
+        _ => None,
+      })
+      .collect::<Option<_>>()?;
+    return Some(sides.join(" "));
+  }
+  let angle: f64 = direction.strip_suffix("deg")?.parse().ok()?;
+  Some(format!("{}deg", (angle - 450.0).abs() % 360.0))
+}
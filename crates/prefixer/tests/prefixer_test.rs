@@ -0,0 +1,85 @@
+use postcss::{ProcessOptions, Processor};
+use prefixer::{Options, Prefixer};
+
+fn process(browsers: &str, css: &str) -> String {
+  let options = Options {
+    browsers: Some(browsers.to_string()),
+    ..Options::default()
+  };
+  Processor::run_one(Prefixer::new(options).unwrap(), css)
+    .unwrap()
+    .css
+}
+
+#[test]
+fn prefixes_properties_and_values() {
+  let css = "a {\n  display: flex;\n  justify-content: space-between;\n  user-select: none;\n  transition: transform 1s;\n  background: linear-gradient(to right, red, blue);\n}";
+  assert_eq!(
+    process("chrome 20, ie 10", css),
+    "a {\n  display: -webkit-box;\n  display: -webkit-flex;\n  display: -ms-flexbox;\n  display: flex;\n  -webkit-box-pack: justify;\n  -webkit-justify-content: space-between;\n  -ms-flex-pack: justify;\n  justify-content: space-between;\n  -webkit-user-select: none;\n  -ms-user-select: none;\n  user-select: none;\n  -webkit-transition: -webkit-transform 1s;\n  transition: -webkit-transform 1s;\n  transition: transform 1s;\n  background: -webkit-linear-gradient(left, red, blue);\n  background: linear-gradient(to right, red, blue);\n}"
+  );
+  assert_eq!(
+    process("safari 12", "a { position: sticky; width: stretch }"),
+    "a { position: -webkit-sticky; position: sticky; width: -webkit-fill-available; width: stretch }"
+  );
+  assert_eq!(
+    process(
+      "last 2 chrome versions",
+      "a { display: flex; user-select: none }"
+    ),
+    "a { display: flex; user-select: none }"
+  );
+}
+
+#[test]
+fn prefixes_selectors_and_keyframes() {
+  assert_eq!(
+    process("chrome 50, firefox 50, ie 11", "input::placeholder { color: gray }"),
+    "input::-webkit-input-placeholder { color: gray }\ninput::-moz-placeholder { color: gray }\ninput:-ms-input-placeholder { color: gray }\ninput::placeholder { color: gray }"
+  );
+  assert_eq!(
+    process("chrome 30, firefox 15", "@keyframes spin {\n  to { transform: rotate(1turn) }\n}"),
+    "@-webkit-keyframes spin {\n  to { -webkit-transform: rotate(1turn); transform: rotate(1turn) }\n}\n@-moz-keyframes spin {\n  to { -moz-transform: rotate(1turn); transform: rotate(1turn) }\n}\n@keyframes spin {\n  to { -webkit-transform: rotate(1turn); -moz-transform: rotate(1turn); transform: rotate(1turn) }\n}"
+  );
+}
+
+#[test]
+fn avoids_duplicates() {
+  let css = "a { -webkit-user-select: none; -moz-user-select: none; user-select: none }\n::-moz-selection { color: red }\n::selection { color: red }";
+  assert_eq!(process("chrome 20, firefox 20", css), css);
+  let css = "a { display: -webkit-box; display: flex; transition: opacity 1s }";
+  let prefixed = process("chrome 20, firefox 20", css);
+  assert_eq!(
+    prefixed,
+    "a { display: -webkit-box; display: -webkit-flex; display: -moz-box; display: flex; -webkit-transition: opacity 1s; transition: opacity 1s }"
+  );
+  assert_eq!(process("chrome 20, firefox 20", &prefixed), prefixed);
+}
+
+#[test]
+fn removes_outdated_prefixes() {
+  let css = "a { -webkit-border-radius: 2px; border-radius: 2px; -webkit-tap-highlight-color: red; display: -webkit-box; display: flex; background: -webkit-linear-gradient(red, blue) }\n::-moz-selection { color: red }\n::selection { color: red }\n@-webkit-keyframes spin {}\n@keyframes spin {}";
+  assert_eq!(
+    process("last 2 chrome versions, last 2 firefox versions", css),
+    "a { border-radius: 2px; -webkit-tap-highlight-color: red; display: flex; background: -webkit-linear-gradient(red, blue) }\n::selection { color: red }\n@keyframes spin {}"
+  );
+  let options = Options {
+    browsers: Some("last 2 chrome versions".to_string()),
+    remove: false,
+  };
+  let result = Processor::run_one(Prefixer::new(options).unwrap(), css).unwrap();
+  assert_eq!(result.css, css);
+}
+
+#[test]
+fn uses_processor_targets() {
+  let mut processor = Processor::with_plugin(Prefixer::new(Options::default()).unwrap());
+  processor.set_targets(browserslist::resolve("chrome 20").unwrap());
+  let result = processor
+    .process("a { transition: opacity 1s }", ProcessOptions::default())
+    .unwrap();
+  assert_eq!(
+    result.css,
+    "a { -webkit-transition: opacity 1s; transition: opacity 1s }"
+  );
+}
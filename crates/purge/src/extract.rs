@@ -0,0 +1,35 @@
+/// Finds the candidate class, id, tag and attribute names in content files.
+pub trait Extractor {
+  /// The names in the `content` of a file with the `extension`, like
+  /// `html` or `jsx`.
+  fn extract(&self, content: &str, extension: &str) -> Vec<String>;
+}
+
+impl<F: Fn(&str, &str) -> Vec<String>> Extractor for F {
+  fn extract(&self, content: &str, extension: &str) -> Vec<String> {
+    self(content, extension)
+  }
+}
+
+/// Takes every run of letters, digits, `-` and `_` as a name, which finds
+/// the tags, attributes, classes and ids of HTML, JSX and most templates,
+/// along with many words that are no names. The runs between whitespace,
+/// quotes, `<`, `>`, `=` and backticks are names too, for the classes of
+/// Tailwind like `md:flex` or `w-1/2`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultExtractor;
+
+impl Extractor for DefaultExtractor {
+  fn extract(&self, content: &str, _: &str) -> Vec<String> {
+    let is_name = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+    let words = content
+      .split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '`' | '<' | '>' | '='))
+      .filter(|word| !word.chars().all(is_name));
+    content
+      .split(|c: char| !is_name(c))
+      .chain(words)
+      .filter(|name| !name.is_empty())
+      .map(str::to_string)
+      .collect()
+  }
+}
@@ -0,0 +1,5 @@
+mod extract;
+mod plugin;
+
+pub use crate::extract::{DefaultExtractor, Extractor};
+pub use crate::plugin::{Error, Options, Purge};
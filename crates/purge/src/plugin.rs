@@ -0,0 +1,360 @@
+use crate::extract::{DefaultExtractor, Extractor};
+use node::tree::{replace_children, walk};
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use regex::Regex;
+use selector::{unescape, ComplexSelector, SimpleSelector};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+/// The tags that are kept even if no content names them, since every page
+/// has them.
+const TAGS: &[&str] = &["html", "body"];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// The globs of the content files, relative to the current directory.
+  pub content: Vec<String>,
+  /// The class, id, tag, attribute and custom property names that are kept
+  /// even if no content names them, like classes added by scripts.
+  pub safelist: Vec<String>,
+  /// The regular expressions of the names that are kept even if no content
+  /// names them.
+  pub safelist_patterns: Vec<String>,
+  /// Removes the `@keyframes` that no `animation` uses.
+  pub keyframes: bool,
+  /// Removes the `@font-face` that no `font` or `font-family` uses.
+  pub font_face: bool,
+  /// Removes the custom properties that no `var()` uses.
+  pub variables: bool,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      content: Vec::new(),
+      safelist: Vec::new(),
+      safelist_patterns: Vec::new(),
+      keyframes: true,
+      font_face: true,
+      variables: true,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+  pub reason: String,
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(&self.reason)
+  }
+}
+
+impl std::error::Error for Error {}
+
+/// Removes the CSS no content file uses, like PurgeCSS.
+///
+/// The [`Extractor`] finds the names in the content files. A rule is removed
+/// when none of its selectors can match, that is when each of them has a
+/// class, id, tag or attribute no content names; selectors that can not
+/// match are removed from the lists of the other rules. Pseudo-classes never
+/// remove a selector, except `:is()`, `:where()` and `:has()` when none of
+/// their selectors can match. The rules of `@keyframes` are left alone, and
+/// at-rules emptied by the removals are removed too.
+pub struct Purge {
+  pub extractor: Box<dyn Extractor>,
+  /// The extension and the text of each content file.
+  contents: Vec<(String, String)>,
+  safelist: HashSet<String>,
+  patterns: Vec<Regex>,
+  keyframes: bool,
+  font_face: bool,
+  variables: bool,
+}
+
+impl Purge {
+  /// Reads the content files, which fails if there is no glob, a glob or a
+  /// safelist pattern is invalid, a file can not be read or a glob matches
+  /// no file, since purging against missing content would remove everything.
+  pub fn new(options: Options) -> Result<Self, Error> {
+    let error = |reason: String| Error { reason };
+    if options.content.is_empty() {
+      return Err(error("No content files, set `content`".to_string()));
+    }
+    let mut contents = Vec::new();
+    for pattern in &options.content {
+      let paths = glob::glob(pattern).map_err(|e| error(format!("{}: {}", pattern, e)))?;
+      let count = contents.len();
+      for path in paths {
+        let path = path.map_err(|e| error(e.to_string()))?;
+        if !path.is_file() {
+          continue;
+        }
+        let text =
+          fs::read_to_string(&path).map_err(|e| error(format!("{}: {}", path.display(), e)))?;
+        contents.push((extension(&path), text));
+      }
+      if contents.len() == count {
+        return Err(error(format!("No content file matches {}", pattern)));
+      }
+    }
+    let patterns = options
+      .safelist_patterns
+      .iter()
+      .map(|pattern| Regex::new(pattern).map_err(|e| error(e.to_string())))
+      .collect::<Result<_, _>>()?;
+    Ok(Self {
+      extractor: Box::new(DefaultExtractor),
+      contents,
+      safelist: options.safelist.into_iter().collect(),
+      patterns,
+      keyframes: options.keyframes,
+      font_face: options.font_face,
+      variables: options.variables,
+    })
+  }
+
+  /// Returns `true` if the name is in the content or the safelists.
+  fn known(&self, names: &HashSet<String>, name: &str) -> bool {
+    names.contains(name)
+      || self.safelist.contains(name)
+      || self.patterns.iter().any(|pattern| pattern.is_match(name))
+  }
+
+  /// Returns `true` if the selector may match an element of the content.
+  fn matches(&self, names: &HashSet<String>, selector: &ComplexSelector) -> bool {
+    selector.compounds.iter().all(|compound| {
+      compound.selectors.iter().all(|simple| match simple {
+        SimpleSelector::Class(name) | SimpleSelector::Id(name) => {
+          self.known(names, &unescape(name))
+        }
+        SimpleSelector::Type { name, .. } => {
+          let name = unescape(name).to_ascii_lowercase();
+          TAGS.contains(&name.as_str()) || self.known(names, &name)
+        }
+        SimpleSelector::Attribute(attribute) => self.known(names, &unescape(&attribute.name)),
+        SimpleSelector::PseudoClass(pseudo) => match pseudo.lower_name().as_str() {
+          "is" | "where" | "has" | "matches" | "-webkit-any" | "-moz-any" => {
+            pseudo.selectors().is_none_or(|list| {
+              list
+                .selectors
+                .iter()
+                .any(|selector| self.matches(names, selector))
+            })
+          }
+          _ => true,
+        },
+        _ => true,
+      })
+    })
+  }
+
+  /// Removes the selectors that can not match from the rule, `false` if
+  /// none is left.
+  fn purge_selector(&self, names: &HashSet<String>, node: &mut Node) -> bool {
+    let rule = match node {
+      Node::Rule(rule) => rule,
+      _ => return true,
+    };
+    let mut list = match selector::parse(&rule.selector) {
+      Ok(list) => list,
+      Err(_) => return true,
+    };
+    let before = match list.selectors.first() {
+      Some(selector) => selector.before.clone(),
+      None => return true,
+    };
+    let count = list.selectors.len();
+    list
+      .selectors
+      .retain(|selector| self.matches(names, selector));
+    if list.selectors.len() == count {
+      return true;
+    }
+    match list.selectors.first_mut() {
+      Some(first) => first.before = before,
+      None => return false,
+    }
+    rule.selector = list.to_string();
+    rule.raws.selector = None;
+    true
+  }
+}
+
+fn extension(path: &Path) -> String {
+  path
+    .extension()
+    .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+    .unwrap_or_default()
+}
+
+fn is_keyframes(node: &Node) -> bool {
+  node
+    .as_at_rule()
+    .is_some_and(|at_rule| at_rule.name.to_ascii_lowercase().ends_with("keyframes"))
+}
+
+/// Removes the nodes of `root` and its descendants for which `keep`
+/// returns `false`, then the rules and at-rules left empty by the removal.
+/// The rules of `@keyframes` are not visited.
+fn retain<'a>(root: &Rc<RefCell<Node<'a>>>, keep: &mut dyn FnMut(&mut Node<'a>) -> bool) {
+  // The parents come before their children, and are visited after them.
+  let mut parents = vec![root.clone()];
+  let _ = walk::<(), _>(root, &mut |node| {
+    let descend = node.borrow().as_shared().get_nodes().is_some() && !is_keyframes(&node.borrow());
+    if descend {
+      parents.push(node.clone());
+    }
+    Ok(descend)
+  });
+  let mut emptied = HashSet::new();
+  for parent in parents.iter().rev() {
+    let children = parent.borrow().as_shared().get_nodes().unwrap_or_default();
+    let nodes: Vec<_> = children
+      .iter()
+      .filter(|child| !emptied.contains(&Rc::as_ptr(child)) && keep(&mut child.borrow_mut()))
+      .cloned()
+      .collect();
+    if nodes.len() == children.len() {
+      continue;
+    }
+    if nodes.is_empty() {
+      emptied.insert(Rc::as_ptr(parent));
+    }
+    replace_children(parent, nodes);
+  }
+}
+
+/// Calls `callback` with every declaration of `root` and its descendants.
+fn walk_decls(root: &Rc<RefCell<Node>>, callback: &mut dyn FnMut(&str, &str)) {
+  let _ = walk::<(), _>(root, &mut |child| match &*child.borrow() {
+    Node::Decl(decl) => {
+      callback(&decl.prop.to_ascii_lowercase(), &decl.value);
+      Ok(false)
+    }
+    Node::AtRule(at_rule) => Ok(!at_rule.name.eq_ignore_ascii_case("font-face")),
+    _ => Ok(true),
+  });
+}
+
+/// The property without its vendor prefix.
+fn unprefixed(prop: &str) -> &str {
+  match prop.strip_prefix('-') {
+    Some(rest) => rest.split_once('-').map_or(prop, |(_, rest)| rest),
+    None => prop,
+  }
+}
+
+/// The family of a `@font-face`, in lowercase and without quotes.
+fn font_face_family(node: &Node) -> Option<String> {
+  if !node
+    .as_at_rule()
+    .is_some_and(|at_rule| at_rule.name.eq_ignore_ascii_case("font-face"))
+  {
+    return None;
+  }
+  node.as_shared().get_nodes()?.iter().find_map(|child| {
+    let child = child.borrow();
+    let decl = child
+      .as_decl()
+      .filter(|decl| decl.prop.eq_ignore_ascii_case("font-family"))?;
+    Some(
+      decl
+        .value
+        .trim()
+        .trim_matches(['"', '\''])
+        .to_ascii_lowercase(),
+    )
+  })
+}
+
+/// The names of the custom properties used by the `var()` of `value`.
+fn variables(value: &str) -> impl Iterator<Item = &str> {
+  value.split("var(").skip(1).filter_map(|rest| {
+    let rest = rest.trim_start();
+    let end = rest
+      .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+      .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|name| name.starts_with("--"))
+  })
+}
+
+impl Plugin for Purge {
+  fn name(&self) -> &str {
+    "purge"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    let names: HashSet<String> = self
+      .contents
+      .iter()
+      .flat_map(|(extension, content)| self.extractor.extract(content, extension))
+      .collect();
+    retain(root, &mut |node| self.purge_selector(&names, node));
+
+    if self.keyframes {
+      let mut animations = HashSet::new();
+      walk_decls(root, &mut |prop, value| {
+        if unprefixed(prop).starts_with("animation") {
+          animations.extend(
+            value
+              .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+              .map(str::to_string),
+          );
+        }
+      });
+      retain(root, &mut |node| {
+        !is_keyframes(node)
+          || node.as_at_rule().is_some_and(|at_rule| {
+            let name = at_rule.params.trim().trim_matches(['"', '\'']);
+            animations.contains(name) || self.known(&names, name)
+          })
+      });
+    }
+
+    if self.font_face {
+      let mut fonts = Vec::new();
+      walk_decls(root, &mut |prop, value| {
+        if prop == "font" || prop == "font-family" {
+          fonts.push(value.to_ascii_lowercase());
+        }
+      });
+      retain(root, &mut |node| match font_face_family(node) {
+        Some(family) => fonts.iter().any(|font| font.contains(&family)),
+        None => true,
+      });
+    }
+
+    if self.variables {
+      // Removing a custom property may leave the ones it used unused.
+      loop {
+        let mut used = HashSet::new();
+        walk_decls(root, &mut |_, value| {
+          used.extend(variables(value).map(str::to_string));
+        });
+        let mut removed = false;
+        retain(root, &mut |node| match node.as_decl() {
+          Some(decl) if decl.prop.starts_with("--") => {
+            let keep = used.contains(&decl.prop) || self.known(&names, &decl.prop);
+            removed |= !keep;
+            keep
+          }
+          _ => true,
+        });
+        if !removed {
+          break;
+        }
+      }
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,148 @@
+use postcss::Processor;
+use purge::{Options, Purge};
+use std::fs;
+
+fn page() -> String {
+  format!("{}/tests/fixtures/page.html", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn purge(plugin: Purge, css: &str) -> String {
+  Processor::run_one(plugin, css).unwrap().css
+}
+
+fn with_page(options: Options) -> Purge {
+  Purge::new(Options {
+    content: vec![page()],
+    ..options
+  })
+  .unwrap()
+}
+
+#[test]
+fn removes_rules_that_can_not_match() {
+  assert_eq!(
+    purge(
+      with_page(Options::default()),
+      ".btn { top: 0 }\n.card { top: 0 }\n.lead, .card-title { top: 0 }\n@media print { .card { top: 0 } }\nh1:hover, main > p { top: 0 }\n:is(.card, .row) { top: 0 }\n:not(.card) { top: 0 }\nbutton[type=button], [data-toggle] { top: 0 }"
+    ),
+    ".btn { top: 0 }\n.lead { top: 0 }\nh1:hover, main > p { top: 0 }\n:is(.card, .row) { top: 0 }\n:not(.card) { top: 0 }\nbutton[type=button] { top: 0 }"
+  );
+}
+
+#[test]
+fn keeps_safelisted_names() {
+  let plugin = with_page(Options {
+    safelist: vec!["card".to_string()],
+    safelist_patterns: vec!["^modal-".to_string()],
+    ..Options::default()
+  });
+  assert_eq!(
+    purge(
+      plugin,
+      ".card { top: 0 }\n.modal-open { top: 0 }\n.modal { top: 0 }"
+    ),
+    ".card { top: 0 }\n.modal-open { top: 0 }"
+  );
+}
+
+#[test]
+fn keeps_escaped_classes_of_tailwind() {
+  let plugin = Purge::new(Options {
+    content: vec![format!(
+      "{}/tests/fixtures/tailwind.html",
+      env!("CARGO_MANIFEST_DIR")
+    )],
+    ..Options::default()
+  })
+  .unwrap();
+  assert_eq!(
+    purge(
+      plugin,
+      ".flex { display: flex }\n.md\\:flex { display: flex }\n.w-1\\/2 { width: 50% }\n.hover\\:underline:hover { text-decoration: underline }\n.lg\\:grid { display: grid }\n.\\31 0 { top: 0 }"
+    ),
+    ".flex { display: flex }\n.md\\:flex { display: flex }\n.w-1\\/2 { width: 50% }\n.hover\\:underline:hover { text-decoration: underline }"
+  );
+}
+
+#[test]
+fn uses_the_extractor() {
+  let mut plugin = with_page(Options::default());
+  plugin.extractor = Box::new(|content: &str, extension: &str| {
+    assert_eq!(extension, "html");
+    match content.contains("btn-primary") {
+      true => vec!["card".to_string()],
+      false => vec![],
+    }
+  });
+  assert_eq!(
+    purge(plugin, ".btn { top: 0 }\n.card { top: 0 }"),
+    ".card { top: 0 }"
+  );
+}
+
+#[test]
+fn removes_unused_keyframes_fonts_and_variables() {
+  assert_eq!(
+    purge(
+      with_page(Options::default()),
+      ":root { --b: var(--c); --c: 2px; --d: 3px; --a: 1px }\n.btn { top: var(--a); animation: spin 1s; font-family: \"Used\", serif }\n@keyframes spin { from { top: 0 } }\n@keyframes fade { from { top: 0 } }\n@font-face { font-family: \"Used\"; src: url(a.woff) }\n@font-face { font-family: Unused; src: url(b.woff) }"
+    ),
+    ":root { --a: 1px }\n.btn { top: var(--a); animation: spin 1s; font-family: \"Used\", serif }\n@keyframes spin { from { top: 0 } }\n@font-face { font-family: \"Used\"; src: url(a.woff) }"
+  );
+  assert_eq!(
+    purge(
+      with_page(Options::default()),
+      ".card { --a: 1px }\n.btn { top: 0 }"
+    ),
+    ".btn { top: 0 }"
+  );
+}
+
+#[test]
+fn fails_without_content() {
+  let error = Purge::new(Options {
+    content: vec![format!(
+      "{}/tests/fixtures/*.vue",
+      env!("CARGO_MANIFEST_DIR")
+    )],
+    ..Options::default()
+  })
+  .err()
+  .unwrap();
+  assert!(error.reason.starts_with("No content file matches"));
+  let error = Purge::new(Options::default()).err().unwrap();
+  assert_eq!(error.reason, "No content files, set `content`");
+  assert!(Purge::new(Options {
+    content: vec![page()],
+    safelist_patterns: vec!["(".to_string()],
+    ..Options::default()
+  })
+  .is_err());
+}
+
+#[test]
+fn purges_bootstrap() {
+  let css = fs::read_to_string(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../assets/bootstrap.css"
+  ))
+  .unwrap();
+  let purged = purge(with_page(Options::default()), &css);
+  assert!(
+    purged.len() * 10 < css.len(),
+    "{} of {} bytes",
+    purged.len(),
+    css.len()
+  );
+  for selector in [
+    ".btn-primary {",
+    ".navbar-brand {",
+    ".col-md-6 {",
+    ".container {",
+  ] {
+    assert!(purged.contains(selector), "{}", selector);
+  }
+  for selector in [".card {", ".modal {", "@keyframes spinner-border"] {
+    assert!(!purged.contains(selector), "{}", selector);
+  }
+}
@@ -0,0 +1,78 @@
+use crate::plugin::Options;
+use serde::Deserialize;
+use std::fmt;
+use value_parser::{dimension, format_number, Node};
+
+/// The unit pixels are converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Unit {
+  Rem,
+  Em,
+}
+
+impl fmt::Display for Unit {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(match self {
+      Unit::Rem => "rem",
+      Unit::Em => "em",
+    })
+  }
+}
+
+/// Returns `true` if `prop` matches the patterns of a property list: names,
+/// `*` for any property, or names starting or ending with `*` to match a
+/// part of a property, like `*-spacing` or `*position*`. Patterns starting
+/// with `!` exclude the properties they match, over any other pattern.
+pub fn matches_prop(patterns: &[String], prop: &str) -> bool {
+  let prop = prop.to_ascii_lowercase();
+  let matches = |pattern: &str| match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+    _ if pattern == "*" => true,
+    (Some(rest), _) if rest.ends_with('*') => prop.contains(&rest[..rest.len() - 1]),
+    (Some(suffix), None) => prop.ends_with(suffix),
+    (None, Some(prefix)) => prop.starts_with(prefix),
+    _ => prop == pattern,
+  };
+  let mut allowed = false;
+  for pattern in patterns {
+    match pattern.strip_prefix('!') {
+      Some(pattern) if matches(pattern) => return false,
+      Some(_) => {}
+      None => allowed = allowed || matches(pattern),
+    }
+  }
+  allowed
+}
+
+impl Options {
+  /// The value with its pixels converted, or `None` if it has none to
+  /// convert.
+  ///
+  /// Only the lowercase `px` unit is converted, so that `PX` keeps pixels.
+  /// Strings and `url()` are left alone, as are pixels under
+  /// `min_pixel_value`.
+  pub fn convert(&self, value: &str) -> Option<String> {
+    if !value.contains("px") {
+      return None;
+    }
+    let mut parsed = value_parser::parse(value);
+    let mut converted = false;
+    parsed.walk(|node| match node {
+      Node::Function(function) => function.lower_name() != "url",
+      Node::Word(word) => {
+        if let Some(pixels) = dimension(word).filter(|d| d.unit == "px").map(|d| d.value) {
+          if pixels.abs() >= self.min_pixel_value {
+            *word = match format_number(pixels / self.root_value, self.precision) {
+              number if number == "0" => number,
+              number => format!("{}{}", number, self.unit),
+            };
+            converted = true;
+          }
+        }
+        false
+      }
+      _ => false,
+    });
+    converted.then(|| parsed.to_string())
+  }
+}
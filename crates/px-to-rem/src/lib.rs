@@ -0,0 +1,5 @@
+mod convert;
+mod plugin;
+
+pub use crate::convert::{matches_prop, Unit};
+pub use crate::plugin::{Options, PxToRem};
@@ -0,0 +1,102 @@
+use crate::convert::{matches_prop, Unit};
+use node::rewrite::{rewrite_decls, Preserve, Visit};
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// The pixels of `1rem`, or of `1em` for `em`.
+  pub root_value: f64,
+  pub unit: Unit,
+  /// The decimals converted numbers are rounded to.
+  pub precision: u32,
+  /// The properties to convert, see [`matches_prop`](crate::matches_prop).
+  pub props: Vec<String>,
+  /// The rules whose selector contains one of these are left alone, with
+  /// their descendants.
+  pub exclude_selectors: Vec<String>,
+  /// The pixels under which values are kept.
+  pub min_pixel_value: f64,
+  /// Converts the pixels of `@media` params too.
+  pub media_queries: bool,
+  /// Keeps the declarations in pixels before their converted copies, for the
+  /// browsers without `rem`.
+  pub preserve: bool,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      root_value: 16.0,
+      unit: Unit::Rem,
+      precision: 5,
+      props: vec!["*".to_string()],
+      exclude_selectors: Vec::new(),
+      min_pixel_value: 0.0,
+      media_queries: false,
+      preserve: false,
+    }
+  }
+}
+
+/// Converts the pixels of declaration values to `rem` or `em`, see
+/// [`Options::convert`].
+#[derive(Debug, Clone, Default)]
+pub struct PxToRem {
+  pub options: Options,
+}
+
+impl PxToRem {
+  /// Converts the declarations in `parent` and its descendants.
+  fn convert(&self, parent: &Rc<RefCell<Node>>) {
+    let preserve = if self.options.preserve {
+      Preserve::After
+    } else {
+      Preserve::No
+    };
+    rewrite_decls(
+      parent,
+      preserve,
+      &mut |child| match &mut *child.borrow_mut() {
+        Node::Decl(decl) if matches_prop(&self.options.props, &decl.prop) => self
+          .options
+          .convert(&decl.value)
+          .map_or(Visit::Skip, Visit::Rewrite),
+        Node::AtRule(at_rule)
+          if self.options.media_queries && at_rule.name.eq_ignore_ascii_case("media") =>
+        {
+          if let Some(params) = self.options.convert(&at_rule.params) {
+            at_rule.params = params;
+            at_rule.raws.params = None;
+          }
+          Visit::Descend
+        }
+        Node::Rule(rule)
+          if self
+            .options
+            .exclude_selectors
+            .iter()
+            .any(|selector| rule.selector.contains(selector.as_str())) =>
+        {
+          Visit::Skip
+        }
+        _ => Visit::Descend,
+      },
+    );
+  }
+}
+
+impl Plugin for PxToRem {
+  fn name(&self) -> &str {
+    "px-to-rem"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    self.convert(root);
+    Ok(())
+  }
+}
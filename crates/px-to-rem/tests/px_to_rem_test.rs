@@ -0,0 +1,110 @@
+use postcss::{ProcessResult, Processor};
+use px_to_rem::{matches_prop, Options, PxToRem, Unit};
+
+fn process(options: Options, css: &str) -> ProcessResult {
+  Processor::run_one(PxToRem { options }, css).unwrap()
+}
+
+fn patterns(patterns: &[&str]) -> Vec<String> {
+  patterns.iter().map(|pattern| pattern.to_string()).collect()
+}
+
+#[test]
+fn converts_pixels() {
+  let options = Options::default();
+  assert_eq!(options.convert("16px").as_deref(), Some("1rem"));
+  assert_eq!(
+    options.convert("0px 8px -24px 1.5px").as_deref(),
+    Some("0 0.5rem -1.5rem 0.09375rem")
+  );
+  assert_eq!(
+    options.convert("calc(100% - 10px)").as_deref(),
+    Some("calc(100% - 0.625rem)")
+  );
+  assert_eq!(options.convert("1PX solid"), None);
+  assert_eq!(options.convert("1em"), None);
+  assert_eq!(
+    options.convert("url(a-16px.png) 16px").as_deref(),
+    Some("url(a-16px.png) 1rem")
+  );
+  assert_eq!(options.convert("url(\"16px.png\")"), None);
+  assert_eq!(
+    options.convert("\"16px\" 2px").as_deref(),
+    Some("\"16px\" 0.125rem")
+  );
+  let options = Options {
+    root_value: 10.0,
+    unit: Unit::Em,
+    precision: 2,
+    min_pixel_value: 2.0,
+    ..Options::default()
+  };
+  assert_eq!(
+    options.convert("1px 5px 3.333px").as_deref(),
+    Some("1px 0.5em 0.33em")
+  );
+}
+
+#[test]
+fn matches_prop_patterns() {
+  let list = patterns(&["font*", "*-spacing", "*position*", "margin", "!font-weight"]);
+  assert!(matches_prop(&list, "font-size"));
+  assert!(matches_prop(&list, "letter-spacing"));
+  assert!(matches_prop(&list, "background-position-x"));
+  assert!(matches_prop(&list, "MARGIN"));
+  assert!(!matches_prop(&list, "font-weight"));
+  assert!(!matches_prop(&list, "margin-top"));
+  assert!(!matches_prop(&list, "padding"));
+  let list = patterns(&["*", "!border*"]);
+  assert!(matches_prop(&list, "width"));
+  assert!(!matches_prop(&list, "border-width"));
+  assert!(!matches_prop(&[], "width"));
+}
+
+#[test]
+fn converts_declarations() {
+  let css = "a { margin: 0 16px; border: 1px solid; width: 32px } .icon { width: 16px }";
+  let result = process(
+    Options {
+      props: patterns(&["*", "!border"]),
+      exclude_selectors: patterns(&[".icon"]),
+      ..Options::default()
+    },
+    css,
+  );
+  assert_eq!(
+    result.css,
+    "a { margin: 0 1rem; border: 1px solid; width: 2rem } .icon { width: 16px }"
+  );
+  let result = process(
+    Options {
+      preserve: true,
+      ..Options::default()
+    },
+    "a { font-size: 24px; color: red }",
+  );
+  assert_eq!(
+    result.css,
+    "a { font-size: 24px; font-size: 1.5rem; color: red }"
+  );
+}
+
+#[test]
+fn converts_media_queries() {
+  let css = "@media (min-width: 768px) { a { width: 8px } }";
+  assert_eq!(
+    process(Options::default(), css).css,
+    "@media (min-width: 768px) { a { width: 0.5rem } }"
+  );
+  let result = process(
+    Options {
+      media_queries: true,
+      ..Options::default()
+    },
+    css,
+  );
+  assert_eq!(
+    result.css,
+    "@media (min-width: 48rem) { a { width: 0.5rem } }"
+  );
+}
@@ -1,14 +1,13 @@
-use std::iter::Peekable;
-use tokenizer::{Token, TokenType, Tokenizer};
+use tokenizer::lookahead::Lookahead;
+use tokenizer::{Token, TokenType};
 
-use crate::Lexer;
 pub struct Root<'a> {
   children: Vec<RuleOrAtRuleOrDecl<'a>>,
   start: usize,
   end: usize,
 }
 
-enum RuleOrAtRuleOrDecl<'a> {
+pub enum RuleOrAtRuleOrDecl<'a> {
   Rule(Rule<'a>),
   AtRule(AtRule<'a>),
   Declaration(Declaration<'a>),
@@ -24,11 +23,24 @@ pub struct Rule<'a> {
   end: usize,
 }
 
+impl<'a> Rule<'a> {
+  /// The rules, at-rules and declarations of the block.
+  pub fn children(&self) -> &[RuleOrAtRuleOrDecl<'a>] {
+    &self.children
+  }
+}
+
 pub struct Declaration<'a> {
   prop: Prop<'a>,
   value: Value,
 }
 
+impl<'a> Declaration<'a> {
+  pub fn prop(&self) -> &'a str {
+    self.prop.content
+  }
+}
+
 pub struct Prop<'a> {
   content: &'a str,
   start: usize,
@@ -53,14 +65,14 @@ struct Selector<'a> {
 }
 
 pub struct Parser<'a> {
-  lexer: Peekable<Lexer<'a>>,
+  tokens: Lookahead<'a>,
   pos: usize,
 }
 
 impl<'a> Parser<'a> {
   pub fn new(input: &'a str) -> Self {
     Self {
-      lexer: Lexer::new(input).peekable(),
+      tokens: Lookahead::new(input),
       pos: 0,
     }
   }
@@ -222,6 +234,7 @@ impl<'a> Parser<'a> {
             self.bump();
           }
           AtWord => ret.push(RuleOrAtRuleOrDecl::AtRule(self.parse_at_rule())),
+          Comment => self.parse_comment(),
           Space => {
             self.bump();
           }
@@ -231,7 +244,7 @@ impl<'a> Parser<'a> {
             break;
           }
           _ => {
-            if rule {
+            if self.tokens.is_nested_rule(rule) {
               // println!("parse rule -->");
               ret.push(RuleOrAtRuleOrDecl::Rule(self.parse_rule()));
             } else {
@@ -338,11 +351,11 @@ impl<'a> Parser<'a> {
     }
   }
   pub fn peek(&mut self) -> Option<TokenType> {
-    self.lexer.peek().map(|token| token.0)
+    self.tokens.nth(0).map(|token| token.0)
   }
 
   pub fn bump(&mut self) -> Token<'a> {
-    let token = self.lexer.next().unwrap();
+    let token = self.tokens.next().unwrap();
     self.pos = token.3;
     token
     // println!("{:?}, {:?}", kind, text);
@@ -0,0 +1,40 @@
+use recursive_parser::parser::{Parser, Rule, RuleOrAtRuleOrDecl};
+
+/// The rules, at-rules and declarations of the block, by property.
+fn outline(rule: &Rule, level: usize, output: &mut Vec<String>) {
+  let indent = "  ".repeat(level);
+  for child in rule.children() {
+    match child {
+      RuleOrAtRuleOrDecl::Rule(rule) => {
+        output.push(format!("{}Rule", indent));
+        outline(rule, level + 1, output);
+      }
+      RuleOrAtRuleOrDecl::AtRule(_) => output.push(format!("{}AtRule", indent)),
+      RuleOrAtRuleOrDecl::Declaration(decl) => {
+        output.push(format!("{}Declaration {}", indent, decl.prop()))
+      }
+    }
+  }
+}
+
+#[test]
+fn parses_nested_rules() {
+  let css = "a {\n  color: red;\n  &:hover { color: blue }\n  > b { c: d }\n  --x: { e: f };\n  @media (min-width: 1px) { g: h; i:hover { j: k } }\n  /* l */\n  m:not(.n) { o: p }\n}";
+  let rule = Parser::new(css).parse_rule();
+  let mut output = vec![];
+  outline(&rule, 0, &mut output);
+  assert_eq!(
+    output,
+    vec![
+      "Declaration color",
+      "Rule",
+      "  Declaration color",
+      "Rule",
+      "  Declaration c",
+      "Declaration --x",
+      "AtRule",
+      "Rule",
+      "  Declaration o",
+    ]
+  );
+}
@@ -1,16 +1,16 @@
-use crate::syntax::{Lang, Lexer, SyntaxKind, SyntaxNode};
+use crate::syntax::{Lang, SyntaxKind, SyntaxNode};
 use rowan::{GreenNodeBuilder, Language};
-use std::iter::Peekable;
+use tokenizer::lookahead::Lookahead;
 
 pub struct Parser<'a> {
-  lexer: Peekable<Lexer<'a>>,
+  tokens: Lookahead<'a>,
   builder: GreenNodeBuilder<'static>,
 }
 
 impl<'a> Parser<'a> {
   pub fn new(input: &'a str) -> Self {
     Self {
-      lexer: Lexer::new(input).peekable(),
+      tokens: Lookahead::new(input),
       builder: GreenNodeBuilder::new(),
     }
   }
@@ -153,6 +153,7 @@ impl<'a> Parser<'a> {
         Some(kind) => match kind {
           Semicolon => self.bump(),
           AtWord => self.parse_at_rule(),
+          Comment => self.parse_comment(),
           Space => {
             self.bump();
           }
@@ -162,7 +163,7 @@ impl<'a> Parser<'a> {
             break;
           }
           _ => {
-            if rule {
+            if self.tokens.is_nested_rule(rule) {
               // println!("parse rule -->");
               self.parse_rule();
             } else {
@@ -253,11 +254,12 @@ impl<'a> Parser<'a> {
     }
   }
   pub fn peek(&mut self) -> Option<SyntaxKind> {
-    self.lexer.peek().map(|(kind, ..)| *kind)
+    self.tokens.nth(0).map(|token| token.0.into())
   }
 
   pub fn bump(&mut self) {
-    let (kind, text, _) = self.lexer.next().unwrap();
+    let token = self.tokens.next().unwrap();
+    let (kind, text) = (SyntaxKind::from(token.0), token.1);
     // println!("{:?}, {:?}", kind, text);
     self.builder.token(Lang::kind_to_raw(kind), text);
   }
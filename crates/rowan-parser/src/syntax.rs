@@ -1,4 +1,4 @@
-use tokenizer::{TokenType, Tokenizer};
+use tokenizer::TokenType;
 
 #[repr(u16)]
 #[derive(Debug, PartialEq, Clone, Copy, Ord, PartialOrd, Eq, Hash)]
@@ -46,31 +46,6 @@ impl From<SyntaxKind> for rowan::SyntaxKind {
   }
 }
 
-pub(crate) struct Lexer<'a> {
-  inner: Tokenizer<'a>,
-}
-
-impl<'a> Lexer<'a> {
-  pub(crate) fn new(input: &'a str) -> Self {
-    Self {
-      inner: Tokenizer::new(input, false),
-    }
-  }
-}
-
-impl<'a> Iterator for Lexer<'a> {
-  type Item = (SyntaxKind, &'a str, usize);
-
-  fn next(&mut self) -> Option<Self::Item> {
-    if !self.inner.end_of_file() {
-      let token = self.inner.next_token(false);
-      Some((token.0.into(), &self.inner.css[token.2..token.3], token.2))
-    } else {
-      None
-    }
-  }
-}
-
 impl From<TokenType> for SyntaxKind {
   fn from(token: TokenType) -> Self {
     match token {
@@ -0,0 +1,47 @@
+use rowan_parser::parser::Parser;
+use rowan_parser::syntax::{SyntaxKind, SyntaxNode};
+
+/// The rules, at-rules and declarations of the tree, by their first token.
+fn outline(node: &SyntaxNode, level: usize, output: &mut Vec<String>) {
+  for child in node.children() {
+    let kind = child.kind();
+    if matches!(
+      kind,
+      SyntaxKind::Rule | SyntaxKind::AtRule | SyntaxKind::Declaration
+    ) {
+      let text = child.text().to_string();
+      let head = text.split(&['{', ';', '}'][..]).next().unwrap().trim();
+      output.push(format!("{}{:?} {}", "  ".repeat(level), kind, head));
+      outline(&child, level + 1, output);
+    }
+  }
+}
+
+#[test]
+fn parses_nested_rules() {
+  let css = "a {\n  color: red;\n  &:hover { color: blue }\n  > b { c: d }\n  --x: { e: f };\n  @media (min-width: 1px) { g: h; i:hover { j: k } }\n  /* l */\n  m:not(.n) { o: p }\n}\n@font-face { font-family: x }";
+  let root = Parser::new(css).parse();
+  assert_eq!(root.to_string(), css);
+  let mut output = vec![];
+  outline(&root, 0, &mut output);
+  assert_eq!(
+    output,
+    vec![
+      "Rule a",
+      "  Declaration color: red",
+      "  Rule &:hover",
+      "    Declaration color: blue",
+      "  Rule > b",
+      "    Declaration c: d",
+      "  Declaration --x:",
+      "  AtRule @media (min-width: 1px)",
+      "    Declaration g: h",
+      "    Rule i:hover",
+      "      Declaration j: k",
+      "  Rule m:not(.n)",
+      "    Declaration o: p",
+      "AtRule @font-face",
+      "  Declaration font-family: x",
+    ]
+  );
+}
@@ -0,0 +1,83 @@
+/// A control directive comment, like `/*rtl:ignore*/`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Directive {
+  /// Leaves the next node as it is.
+  Ignore,
+  BeginIgnore,
+  EndIgnore,
+  /// Removes the next node.
+  Remove,
+  BeginRemove,
+  EndRemove,
+  /// Renames the selector of the next rule, see `auto-rename`.
+  Rename,
+  /// CSS to insert in place of the comment.
+  Raw(String),
+  /// JSON options for the nodes that follow, until `rtl:end:options`.
+  Options(String),
+  EndOptions,
+}
+
+/// A directive in a declaration value, like `ltr /*rtl:ignore*/`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ValueDirective {
+  /// Leaves the declaration as it is.
+  Ignore,
+  /// Replaces the value.
+  Replace(String),
+  Prepend(String),
+  Append(String),
+}
+
+/// The text of a directive comment, without the `rtl:` prefix.
+fn strip(text: &str) -> Option<&str> {
+  let text = text.trim_start();
+  let text = text.strip_prefix('!').unwrap_or(text).trim_start();
+  text.strip_prefix("rtl:")
+}
+
+pub(crate) fn parse(text: &str) -> Option<Directive> {
+  let rest = strip(text)?;
+  if let Some(css) = rest.strip_prefix("raw:") {
+    return Some(Directive::Raw(css.to_string()));
+  }
+  if let Some(json) = rest.strip_prefix("options:") {
+    return Some(Directive::Options(json.to_string()));
+  }
+  Some(match rest.trim() {
+    "ignore" => Directive::Ignore,
+    "begin:ignore" => Directive::BeginIgnore,
+    "end:ignore" => Directive::EndIgnore,
+    "remove" => Directive::Remove,
+    "begin:remove" => Directive::BeginRemove,
+    "end:remove" => Directive::EndRemove,
+    "rename" => Directive::Rename,
+    "end:options" => Directive::EndOptions,
+    _ => return None,
+  })
+}
+
+/// Finds the directive comment in the raw value of a declaration, and
+/// returns it with the range of the comment.
+pub(crate) fn parse_value(raw: &str) -> Option<(ValueDirective, std::ops::Range<usize>)> {
+  let mut offset = 0;
+  while let Some(start) = raw[offset..].find("/*").map(|start| offset + start) {
+    let end = raw[start..].find("*/").map(|end| start + end + 2)?;
+    offset = end;
+    let rest = match strip(&raw[start + 2..end - 2]) {
+      Some(rest) => rest,
+      None => continue,
+    };
+    let directive = if rest.trim() == "ignore" {
+      ValueDirective::Ignore
+    } else if let Some(value) = rest.strip_prefix("prepend:") {
+      ValueDirective::Prepend(value.to_string())
+    } else if let Some(value) = rest.strip_prefix("append:") {
+      ValueDirective::Append(value.to_string())
+    } else {
+      ValueDirective::Replace(rest.to_string())
+    };
+    return Some((directive, start..end));
+  }
+  None
+}
@@ -0,0 +1,407 @@
+/// Flips a declaration from left-to-right to right-to-left, returning the
+/// new property and value.
+///
+/// `left` and `right` are swapped in property names, and in the values of
+/// `float`, `clear`, `text-align`, `direction` and background positions.
+/// Four-value shorthands swap their right and left values, `border-radius`
+/// its corners, horizontal offsets, translations, rotations and gradient
+/// angles are negated and horizontal percentages are complemented.
+pub fn flip(prop: &str, value: &str) -> (String, String) {
+  let prop = map_words(prop, '-', swap_sides);
+  let lower = prop.to_ascii_lowercase();
+  let name = unprefixed(&lower);
+  let value = match name {
+    "direction" => map_idents(value, &mut |ident| match ident {
+      "ltr" => Some("rtl".to_string()),
+      "rtl" => Some("ltr".to_string()),
+      _ => None,
+    }),
+    "float" | "clear" | "text-align" | "text-align-last" | "background-position-x" => {
+      flip_positions(value)
+    }
+    "margin" | "padding" | "border-width" | "border-color" | "border-style" | "inset"
+    | "scroll-margin" | "scroll-padding" => flip_sides(value),
+    "border-radius" => flip_radius(value),
+    "box-shadow" | "text-shadow" => flip_shadows(value),
+    "transform" => flip_transform(value),
+    "transform-origin" | "perspective-origin" => flip_origin(value),
+    "background-position" | "object-position" => flip_positions(value),
+    "background" | "background-image" => flip_gradients(&map_idents(value, &mut swap_sides)),
+    "cursor" => map_idents(value, &mut swap_cursor),
+    _ => value.to_string(),
+  };
+  (prop, value)
+}
+
+fn unprefixed(prop: &str) -> &str {
+  for prefix in ["-webkit-", "-moz-", "-ms-", "-o-"] {
+    if let Some(prop) = prop.strip_prefix(prefix) {
+      return prop;
+    }
+  }
+  prop
+}
+
+fn swap_sides(word: &str) -> Option<String> {
+  match word.to_ascii_lowercase().as_str() {
+    "left" => Some("right".to_string()),
+    "right" => Some("left".to_string()),
+    _ => None,
+  }
+}
+
+fn swap_cursor(word: &str) -> Option<String> {
+  let swapped = match word.to_ascii_lowercase().as_str() {
+    "e-resize" => "w-resize",
+    "w-resize" => "e-resize",
+    "ne-resize" => "nw-resize",
+    "nw-resize" => "ne-resize",
+    "se-resize" => "sw-resize",
+    "sw-resize" => "se-resize",
+    "nesw-resize" => "nwse-resize",
+    "nwse-resize" => "nesw-resize",
+    _ => return None,
+  };
+  Some(swapped.to_string())
+}
+
+/// Replaces the `sep` separated words of `text` that `f` maps.
+fn map_words(text: &str, sep: char, f: impl Fn(&str) -> Option<String>) -> String {
+  text
+    .split(sep)
+    .map(|word| f(word).unwrap_or_else(|| word.to_string()))
+    .collect::<Vec<_>>()
+    .join(&sep.to_string())
+}
+
+/// Replaces the identifiers of a value that `f` maps, leaving strings and
+/// `url()` alone.
+fn map_idents(value: &str, f: &mut dyn FnMut(&str) -> Option<String>) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut chars = value.char_indices().peekable();
+  while let Some((start, c)) = chars.next() {
+    if c == '"' || c == '\'' {
+      let end = string_end(value, start);
+      result.push_str(&value[start..end]);
+      while chars.peek().is_some_and(|(i, _)| *i < end) {
+        chars.next();
+      }
+    } else if is_ident_char(c) {
+      let mut end = start + c.len_utf8();
+      while let Some((i, c)) = chars.peek().copied() {
+        if !is_ident_char(c) {
+          break;
+        }
+        end = i + c.len_utf8();
+        chars.next();
+      }
+      let ident = &value[start..end];
+      if ident.eq_ignore_ascii_case("url") && value[end..].starts_with('(') {
+        let close = closing(value, end);
+        result.push_str(&value[start..close]);
+        while chars.peek().is_some_and(|(i, _)| *i < close) {
+          chars.next();
+        }
+        continue;
+      }
+      match f(ident) {
+        Some(mapped) => result.push_str(&mapped),
+        None => result.push_str(ident),
+      }
+    } else {
+      result.push(c);
+    }
+  }
+  result
+}
+
+fn is_ident_char(c: char) -> bool {
+  c.is_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '%'
+}
+
+/// The end of the string starting at `start`, after its closing quote.
+fn string_end(value: &str, start: usize) -> usize {
+  let bytes = value.as_bytes();
+  let quote = bytes[start];
+  let mut i = start + 1;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'\\' => i += 1,
+      byte if byte == quote => return i + 1,
+      _ => {}
+    }
+    i += 1;
+  }
+  value.len()
+}
+
+/// The end of the parenthesized block opening at `open`, after its `)`.
+fn closing(value: &str, open: usize) -> usize {
+  let bytes = value.as_bytes();
+  let mut depth = 0;
+  let mut i = open;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'"' | b'\'' => {
+        i = string_end(value, i);
+        continue;
+      }
+      b'(' => depth += 1,
+      b')' => {
+        depth -= 1;
+        if depth == 0 {
+          return i + 1;
+        }
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+  value.len()
+}
+
+/// Splits `value` at the top-level `sep`, or whitespace if `sep` is a
+/// space, keeping the separators out of the parts.
+fn split(value: &str, sep: char) -> Vec<&str> {
+  let bytes = value.as_bytes();
+  let mut parts = vec![];
+  let mut start = 0;
+  let mut i = 0;
+  while i < bytes.len() {
+    let byte = bytes[i];
+    match byte {
+      b'"' | b'\'' => {
+        i = string_end(value, i);
+        continue;
+      }
+      b'(' => {
+        i = closing(value, i);
+        continue;
+      }
+      _ if (sep == ' ' && byte.is_ascii_whitespace()) || byte == sep as u8 => {
+        if sep != ' ' || start < i {
+          parts.push(&value[start..i]);
+        }
+        start = i + 1;
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+  if sep != ' ' || start < value.len() {
+    parts.push(&value[start..]);
+  }
+  parts
+}
+
+/// Splits a number and its unit, e.g. `-1.5rem` into `-1.5` and `rem`.
+fn number(token: &str) -> Option<(f64, &str)> {
+  let end = token
+    .char_indices()
+    .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || ((c == '-' || c == '+') && i == 0)))
+    .map_or(token.len(), |(i, _)| i);
+  let value = token[..end].parse().ok()?;
+  Some((value, &token[end..]))
+}
+
+fn format_number(value: f64) -> String {
+  let value = (value * 1e8).round() / 1e8;
+  if value == 0.0 {
+    return "0".to_string();
+  }
+  value.to_string()
+}
+
+/// Negates a length or angle, `calc()` and `var()` are multiplied by -1.
+fn negate(token: &str) -> String {
+  let trimmed = token.trim();
+  match number(trimmed) {
+    Some((0.0, _)) => token.to_string(),
+    Some(_) => {
+      let negated = match trimmed.strip_prefix('-') {
+        Some(positive) => positive.to_string(),
+        None => format!("-{}", trimmed.trim_start_matches('+')),
+      };
+      token.replacen(trimmed, &negated, 1)
+    }
+    None if trimmed.contains('(') => token.replacen(trimmed, &format!("calc(-1 * {})", trimmed), 1),
+    None => token.to_string(),
+  }
+}
+
+/// Flips a horizontal position, percentages become `100% - x` and lengths
+/// are measured from the right.
+fn flip_x(token: &str) -> String {
+  if let Some(side) = swap_sides(token) {
+    return side;
+  }
+  match number(token) {
+    Some((value, "%")) => format!("{}%", format_number(100.0 - value)),
+    Some((0.0, _)) => "100%".to_string(),
+    Some(_) => format!("calc(100% - {})", token),
+    None => token.to_string(),
+  }
+}
+
+/// Swaps the second and fourth values of `top right bottom left` shorthands.
+fn flip_sides(value: &str) -> String {
+  let mut parts = split(value, ' ');
+  if parts.len() != 4 {
+    return value.to_string();
+  }
+  parts.swap(1, 3);
+  parts.join(" ")
+}
+
+fn flip_radius(value: &str) -> String {
+  split(value, '/')
+    .into_iter()
+    .map(|radii| {
+      let parts = split(radii, ' ');
+      let flipped = match parts[..] {
+        [a, b] => vec![b, a],
+        [a, b, c] => vec![b, a, b, c],
+        [a, b, c, d] => vec![b, a, d, c],
+        _ => return radii.to_string(),
+      };
+      let (leading, trailing) = surrounding(radii);
+      format!("{}{}{}", leading, flipped.join(" "), trailing)
+    })
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// The whitespace around `text`.
+fn surrounding(text: &str) -> (&str, &str) {
+  let start = text.len() - text.trim_start().len();
+  let end = text.trim_end().len();
+  (&text[..start], &text[end.max(start)..])
+}
+
+fn flip_shadows(value: &str) -> String {
+  split(value, ',')
+    .into_iter()
+    .map(|shadow| {
+      let parts = split(shadow, ' ');
+      match parts.iter().position(|part| number(part).is_some()) {
+        Some(index) => {
+          let negated = negate(parts[index]);
+          let mut parts: Vec<_> = parts.iter().map(|part| part.to_string()).collect();
+          parts[index] = negated;
+          let (leading, trailing) = surrounding(shadow);
+          format!("{}{}{}", leading, parts.join(" "), trailing)
+        }
+        None => shadow.to_string(),
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Negates the horizontal arguments of the transform functions.
+fn flip_transform(value: &str) -> String {
+  map_functions(value, &mut |name, args| {
+    let negated: &[usize] = match name.to_ascii_lowercase().as_str() {
+      "translate" | "translatex" | "translate3d" | "rotate" | "rotatez" | "skewx" | "skewy" => &[0],
+      "skew" => &[0, 1],
+      "matrix" => &[1, 2, 4],
+      _ => return None,
+    };
+    let args = split(args, ',')
+      .into_iter()
+      .enumerate()
+      .map(|(i, arg)| match negated.contains(&i) {
+        true => negate(arg),
+        false => arg.to_string(),
+      })
+      .collect::<Vec<_>>();
+    Some(args.join(","))
+  })
+}
+
+/// Negates the angles of linear gradients and swaps their sides.
+fn flip_gradients(value: &str) -> String {
+  map_functions(value, &mut |name, args| {
+    let name = name.to_ascii_lowercase();
+    if !unprefixed(&name).ends_with("linear-gradient") {
+      return None;
+    }
+    let mut args: Vec<_> = split(args, ',').into_iter().map(str::to_string).collect();
+    let first = args[0].trim().to_ascii_lowercase();
+    if first.starts_with("to ") {
+      args[0] = map_idents(&args[0], &mut swap_sides);
+    } else if number(&first).is_some_and(|(_, unit)| !unit.is_empty()) {
+      args[0] = negate(&args[0]);
+    }
+    Some(args.join(","))
+  })
+}
+
+/// Replaces the arguments of the top-level functions that `f` maps.
+fn map_functions(value: &str, f: &mut dyn FnMut(&str, &str) -> Option<String>) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut rest = value;
+  while let Some(open) = rest.find('(') {
+    let name_start = rest[..open]
+      .rfind(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+      .map_or(0, |i| i + 1);
+    let close = closing(rest, open);
+    let name = &rest[name_start..open];
+    let end = match rest[..close].ends_with(')') {
+      true => close - 1,
+      false => close,
+    };
+    let args = &rest[open + 1..end];
+    result.push_str(&rest[..open + 1]);
+    match f(name, args) {
+      Some(args) => result.push_str(&args),
+      None => result.push_str(args),
+    }
+    result.push_str(&rest[open + 1 + args.len()..close]);
+    rest = &rest[close..];
+  }
+  result.push_str(rest);
+  result
+}
+
+fn flip_origin(value: &str) -> String {
+  let parts = split(value, ' ');
+  let mut flipped: Vec<String> = parts.iter().map(|part| part.to_string()).collect();
+  match parts.first().map(|part| part.to_ascii_lowercase()) {
+    Some(first) if first == "top" || first == "bottom" => {
+      if let Some(second) = parts.get(1) {
+        flipped[1] = flip_x(second);
+      }
+    }
+    Some(first) if first == "center" => {
+      if let Some(second) = parts.get(1) {
+        flipped[1] = swap_sides(second).unwrap_or_else(|| second.to_string());
+      }
+    }
+    Some(_) => flipped[0] = flip_x(parts[0]),
+    None => return value.to_string(),
+  }
+  flipped.join(" ")
+}
+
+/// Flips the comma separated positions, swapping `left` and `right` or
+/// complementing a horizontal percentage.
+fn flip_positions(value: &str) -> String {
+  split(value, ',')
+    .into_iter()
+    .map(|position| {
+      let parts = split(position, ' ');
+      let has_side = parts.iter().any(|part| swap_sides(part).is_some());
+      if has_side {
+        return map_idents(position, &mut swap_sides);
+      }
+      match parts.first() {
+        Some(first) if number(first).is_some_and(|(_, unit)| unit == "%") => {
+          position.replacen(first, &flip_x(first), 1)
+        }
+        _ => position.to_string(),
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(",")
+}
@@ -0,0 +1,6 @@
+mod directive;
+mod flip;
+mod plugin;
+
+pub use crate::flip::flip;
+pub use crate::plugin::{Options, Rtl, StringMap};
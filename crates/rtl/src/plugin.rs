@@ -0,0 +1,273 @@
+use crate::directive::{self, Directive, ValueDirective};
+use node::{Declaration, Node, RawValue};
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  /// Renames every selector with the `string-map`, not only the rules after
+  /// a `/*rtl:rename*/` directive.
+  pub auto_rename: bool,
+  /// Whether renaming also replaces parts of words, e.g. `prev` in `.preview`.
+  pub greedy: bool,
+  /// The words swapped by renaming, `left` and `right`, and `ltr` and `rtl`
+  /// by default.
+  pub string_map: Vec<StringMap>,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      auto_rename: false,
+      greedy: false,
+      string_map: vec![
+        StringMap::new("left-right", "left", "right"),
+        StringMap::new("ltr-rtl", "ltr", "rtl"),
+      ],
+    }
+  }
+}
+
+/// Two words swapped with each other when renaming selectors.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StringMap {
+  pub name: String,
+  pub search: String,
+  pub replace: String,
+}
+
+impl StringMap {
+  pub fn new(name: &str, search: &str, replace: &str) -> Self {
+    Self {
+      name: name.to_string(),
+      search: search.to_string(),
+      replace: replace.to_string(),
+    }
+  }
+}
+
+/// The options of a `/*rtl:options: {...}*/` directive, in the camel case
+/// of rtlcss, that change the current ones.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Overrides {
+  auto_rename: Option<bool>,
+  greedy: Option<bool>,
+  string_map: Option<Vec<StringMap>>,
+}
+
+/// Flips left-to-right stylesheets to right-to-left, like rtlcss.
+///
+/// Declarations are flipped with [`flip`](crate::flip). Comments control
+/// the result:
+///
+/// - `/*rtl:ignore*/` leaves the next node as it is, and
+///   `/*rtl:begin:ignore*/` the nodes up to `/*rtl:end:ignore*/`;
+/// - `/*rtl:remove*/` removes the next node, and `/*rtl:begin:remove*/` the
+///   nodes up to `/*rtl:end:remove*/`;
+/// - `/*rtl:raw: ...*/` is replaced with the CSS it contains;
+/// - `/*rtl:rename*/` renames the selector of the next rule;
+/// - `/*rtl:options: {...}*/` changes the options up to `/*rtl:end:options*/`.
+///
+/// In a value, `/*rtl:ignore*/` leaves the declaration as it is, and
+/// `/*rtl:...*/`, `/*rtl:prepend:...*/` and `/*rtl:append:...*/` replace the
+/// value, or add to it. Directive comments are removed.
+#[derive(Default)]
+pub struct Rtl {
+  pub options: Options,
+}
+
+/// The directives in effect while walking the tree.
+struct State {
+  /// The options, the ones of the innermost `rtl:options` last.
+  options: Vec<Options>,
+  ignore_next: bool,
+  ignoring: bool,
+  remove_next: bool,
+  removing: bool,
+  rename_next: bool,
+}
+
+impl Rtl {
+  pub fn new(options: Options) -> Self {
+    Self { options }
+  }
+
+  fn process(&self, parent: &Rc<RefCell<Node>>, state: &mut State) -> Result<(), CssSyntaxError> {
+    let children = match parent.borrow().as_shared().get_nodes() {
+      Some(children) => children,
+      None => return Ok(()),
+    };
+    let mut nodes = Vec::with_capacity(children.len());
+    for child in children {
+      let directive = match &*child.borrow() {
+        Node::Comment(comment) => directive::parse(&comment.text),
+        _ => None,
+      };
+      if let Some(directive) = directive {
+        match directive {
+          Directive::Ignore => state.ignore_next = true,
+          Directive::BeginIgnore => state.ignoring = true,
+          Directive::EndIgnore => state.ignoring = false,
+          Directive::Remove => state.remove_next = true,
+          Directive::BeginRemove => state.removing = true,
+          Directive::EndRemove => state.removing = false,
+          Directive::Rename => state.rename_next = true,
+          Directive::Raw(css) if !state.removing => {
+            // The nodes own the CSS of the directive, which is dropped with
+            // the processed tree.
+            let mut root = parser::parse::parse(&css, None)?
+              .borrow()
+              .deep_clone_owned();
+            let raw = root
+              .as_shared_mut()
+              .get_nodes_mut()
+              .map(std::mem::take)
+              .unwrap_or_default();
+            let before = child.borrow().as_shared().as_raws().get_raw_before();
+            if let (Some(first), Some(before)) = (raw.first(), before) {
+              first.borrow_mut().set_raw_before(before);
+            }
+            nodes.extend(raw);
+          }
+          Directive::Raw(_) => {}
+          Directive::Options(json) => {
+            let overrides: Overrides = serde_json::from_str(&json).map_err(|error| {
+              let reason = format!("Invalid rtl:options: {}", error);
+              child.borrow().error(&reason)
+            })?;
+            let mut options = state.options.last().unwrap().clone();
+            options.auto_rename = overrides.auto_rename.unwrap_or(options.auto_rename);
+            options.greedy = overrides.greedy.unwrap_or(options.greedy);
+            for map in overrides.string_map.unwrap_or_default() {
+              options.string_map.retain(|other| other.name != map.name);
+              options.string_map.push(map);
+            }
+            state.options.push(options);
+          }
+          Directive::EndOptions => {
+            if state.options.len() > 1 {
+              state.options.pop();
+            }
+          }
+        }
+        continue;
+      }
+
+      if state.removing || std::mem::take(&mut state.remove_next) {
+        continue;
+      }
+      if state.ignoring || std::mem::take(&mut state.ignore_next) {
+        nodes.push(child);
+        continue;
+      }
+      match &mut *child.borrow_mut() {
+        Node::Decl(decl) => flip_declaration(decl),
+        Node::Rule(rule) => {
+          let options = state.options.last().unwrap();
+          if std::mem::take(&mut state.rename_next) || options.auto_rename {
+            let selector = rename(&rule.selector, options);
+            if selector != rule.selector {
+              rule.selector = selector;
+              rule.raws.selector = None;
+            }
+          }
+        }
+        _ => {}
+      }
+      self.process(&child, state)?;
+      nodes.push(child);
+    }
+
+    if let Some(children) = parent.borrow_mut().as_shared_mut().get_nodes_mut() {
+      *children = nodes;
+    }
+    Ok(())
+  }
+}
+
+impl Plugin for Rtl {
+  fn name(&self) -> &str {
+    "rtl"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    let mut state = State {
+      options: vec![self.options.clone()],
+      ignore_next: false,
+      ignoring: false,
+      remove_next: false,
+      removing: false,
+      rename_next: false,
+    };
+    self.process(root, &mut state)
+  }
+}
+
+fn flip_declaration(decl: &mut Declaration) {
+  let raw = decl.raws.value.as_ref().map(|value| value.raw.as_str());
+  let directive = raw.and_then(directive::parse_value);
+  let directed = directive.is_some();
+  let value = match directive {
+    Some((ValueDirective::Ignore, range)) => {
+      let mut raw = raw.unwrap().to_string();
+      raw.replace_range(range, "");
+      set_value(decl, raw);
+      return;
+    }
+    Some((ValueDirective::Replace(value), _)) => value,
+    Some((ValueDirective::Prepend(value), _)) => format!("{}{}", value, decl.value),
+    Some((ValueDirective::Append(value), _)) => format!("{}{}", decl.value, value),
+    None => crate::flip(&decl.prop, &decl.value).1,
+  };
+  decl.prop = crate::flip(&decl.prop, "").0;
+  if value != decl.value || directed {
+    decl.value = value;
+    decl.raws.value = None;
+  }
+}
+
+/// Sets a value that may still contain comments.
+fn set_value(decl: &mut Declaration, raw: String) {
+  if raw.contains("/*") {
+    decl.raws.value = Some(RawValue {
+      value: decl.value.clone(),
+      raw,
+    });
+  } else {
+    decl.value = raw;
+    decl.raws.value = None;
+  }
+}
+
+/// Swaps the words of the string map in a selector.
+fn rename(selector: &str, options: &Options) -> String {
+  let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+  let mut result = String::with_capacity(selector.len());
+  let mut i = 0;
+  'outer: while i < selector.len() {
+    let rest = &selector[i..];
+    for map in &options.string_map {
+      for (search, replace) in [(&map.search, &map.replace), (&map.replace, &map.search)] {
+        if search.is_empty() || !rest.starts_with(search.as_str()) {
+          continue;
+        }
+        let bounded = options.greedy
+          || (!is_word(selector[..i].chars().next_back())
+            && !is_word(rest[search.len()..].chars().next()));
+        if bounded {
+          result.push_str(replace);
+          i += search.len();
+          continue 'outer;
+        }
+      }
+    }
+    let c = rest.chars().next().unwrap();
+    result.push(c);
+    i += c.len_utf8();
+  }
+  result
+}
@@ -0,0 +1,129 @@
+use postcss::Processor;
+use rtl::{flip, Options, Rtl};
+
+fn process(css: &str) -> String {
+  Processor::run_one(Rtl::default(), css).unwrap().css
+}
+
+#[test]
+fn flips_declarations() {
+  let flipped = |prop, value| flip(prop, value);
+  let pair = |prop: &str, value: &str| (prop.to_string(), value.to_string());
+  assert_eq!(flipped("margin-left", "1px"), pair("margin-right", "1px"));
+  assert_eq!(
+    flipped("border-top-right-radius", "0"),
+    pair("border-top-left-radius", "0")
+  );
+  assert_eq!(flipped("float", "left"), pair("float", "right"));
+  assert_eq!(
+    flipped("padding", "1px 2px 3px 4px"),
+    pair("padding", "1px 4px 3px 2px")
+  );
+  assert_eq!(
+    flipped("border-radius", "1px 2px 3px"),
+    pair("border-radius", "2px 1px 2px 3px")
+  );
+  assert_eq!(
+    flipped("border-radius", "1px 2px 3px 4px / 5px 6px"),
+    pair("border-radius", "2px 1px 4px 3px / 6px 5px")
+  );
+  assert_eq!(
+    flipped("background-position", "right 1rem center, 25% 0"),
+    pair("background-position", "left 1rem center, 75% 0")
+  );
+  assert_eq!(
+    flipped("transform", "translate(-50%, 10px) rotate(45deg) scale(2)"),
+    pair("transform", "translate(50%, 10px) rotate(-45deg) scale(2)")
+  );
+  assert_eq!(
+    flipped("transform-origin", "0 0"),
+    pair("transform-origin", "100% 0")
+  );
+  assert_eq!(
+    flipped("box-shadow", "inset 2px 0 red"),
+    pair("box-shadow", "inset -2px 0 red")
+  );
+  assert_eq!(
+    flipped(
+      "background-image",
+      "linear-gradient(45deg, red, blue), url(left.png)"
+    ),
+    pair(
+      "background-image",
+      "linear-gradient(-45deg, red, blue), url(left.png)"
+    )
+  );
+  assert_eq!(flipped("cursor", "e-resize"), pair("cursor", "w-resize"));
+}
+
+#[test]
+fn follows_directives() {
+  assert_eq!(
+    process("a { float: left /*rtl:ignore*/; content: \"<\" /*rtl:\">\"*/; }\n/*rtl:ignore*/\nb { left: 0 }"),
+    "a { float: left ; content: \">\"; }\nb { left: 0 }"
+  );
+  assert_eq!(
+    process("/*rtl:begin:remove*/\na {}\nb {}\n/*rtl:end:remove*/\nc { right: 0 }"),
+    "\nc { left: 0 }"
+  );
+  assert_eq!(
+    process("a {}\n\n/*rtl:raw:\nb { left: 0 }\n*/\nc {}"),
+    "a {}\n\nb { left: 0 }\nc {}"
+  );
+}
+
+#[test]
+fn renames_selectors() {
+  assert_eq!(
+    process(".left {}\n/*rtl:rename*/\n.left, .ltr .right-side, .bright {}"),
+    ".left {}\n.right, .rtl .left-side, .bright {}"
+  );
+  assert_eq!(
+    process(".prev {}\n/*rtl:options: {\"autoRename\": true, \"stringMap\": [{\"name\": \"prev-next\", \"search\": \"prev\", \"replace\": \"next\"}]}*/\n.prev {}\n.next {}\n/*rtl:end:options*/\n.prev {}"),
+    ".prev {}\n.next {}\n.prev {}\n.prev {}"
+  );
+  let result = Processor::run_one(
+    Rtl::new(Options {
+      auto_rename: true,
+      greedy: true,
+      ..Default::default()
+    }),
+    ".brightness {}",
+  )
+  .unwrap();
+  assert_eq!(result.css, ".bleftness {}");
+}
+
+#[test]
+fn flips_bootstrap() {
+  // The source map annotation is added after rtlcss runs.
+  let without_map = |css: &str| {
+    css
+      .split("/*# sourceMappingURL")
+      .next()
+      .unwrap()
+      .trim_end()
+      .to_string()
+  };
+  let assets = [
+    (
+      include_str!("../../../assets/bootstrap.css"),
+      include_str!("../../../assets/bootstrap.rtl.css"),
+    ),
+    (
+      include_str!("../../../assets/bootstrap-grid.css"),
+      include_str!("../../../assets/bootstrap-grid.rtl.css"),
+    ),
+    (
+      include_str!("../../../assets/bootstrap-reboot.css"),
+      include_str!("../../../assets/bootstrap-reboot.rtl.css"),
+    ),
+    (
+      include_str!("../../../assets/bootstrap-utilities.css"),
+      include_str!("../../../assets/bootstrap-utilities.rtl.css"),
+    ),
+  ];
+  for (css, expected) in assets {
+    assert!(without_map(&process(css)) == without_map(expected));
+  }
+}
@@ -0,0 +1,5 @@
+mod plugin;
+mod scope;
+
+pub use crate::plugin::Scoped;
+pub use crate::scope::{scope, STRIPPED_PSEUDO_CLASSES};
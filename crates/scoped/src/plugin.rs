@@ -0,0 +1,84 @@
+use node::rename::{is_keyframes, rename_idents};
+use node::tree::walk;
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Scopes the styles of a component like Vue's `<style scoped>`.
+///
+/// Every selector gets a `[data-v-<id>]` attribute, see
+/// [`scope`](crate::scope), and `@keyframes` are renamed to `<name>-<id>`
+/// with the `animation` declarations that use them. The selectors of the
+/// keyframes themselves are left alone.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scoped {
+  /// The id of the component, e.g. `1a2b3c` or `data-v-1a2b3c`.
+  pub id: String,
+}
+
+impl Scoped {
+  pub fn new(id: &str) -> Self {
+    Self { id: id.to_string() }
+  }
+}
+
+impl Plugin for Scoped {
+  fn name(&self) -> &str {
+    "scoped"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    let id = crate::scope::bare_id(&self.id);
+    let mut keyframes = HashMap::new();
+    walk(root, &mut |node| {
+      let mut node = node.borrow_mut();
+      match &mut *node {
+        Node::AtRule(at_rule) if is_keyframes(&at_rule.name) => {
+          let name = at_rule.params.trim().to_string();
+          let scoped = format!("{}-{}", name, id);
+          at_rule.params = scoped.clone();
+          at_rule.raws.params = None;
+          keyframes.insert(name, scoped);
+          Ok(false)
+        }
+        Node::Rule(rule) => {
+          let mut list = match selector::parse(&rule.selector) {
+            Ok(list) => list,
+            Err(error) => return Err(node.error(&error.to_string())),
+          };
+          if let Err(reason) = crate::scope(&mut list, id) {
+            return Err(node.error(&reason));
+          }
+          let rule = node.as_rule_mut().unwrap();
+          rule.selector = list.to_string();
+          rule.raws.selector = None;
+          Ok(true)
+        }
+        _ => Ok(true),
+      }
+    })?;
+
+    if keyframes.is_empty() {
+      return Ok(());
+    }
+    let _ = walk::<(), _>(root, &mut |node| {
+      if let Node::Decl(decl) = &mut *node.borrow_mut() {
+        let prop = decl.prop.to_ascii_lowercase();
+        let prop = prop.trim_start_matches("-webkit-");
+        if prop == "animation" || prop == "animation-name" {
+          let value = rename_idents(&decl.value, &keyframes);
+          if value != decl.value {
+            decl.value = value;
+            decl.raws.value = None;
+          }
+        }
+      }
+      Ok(true)
+    });
+    Ok(())
+  }
+}
@@ -0,0 +1,189 @@
+use selector::{
+  AttributeSelector, Combinator, CombinatorKind, ComplexSelector, CompoundSelector, PseudoArgument,
+  SelectorList, SimpleSelector,
+};
+
+/// What a pseudo-class or pseudo-element does to the scoping of its selector.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Escape {
+  /// `:deep()` and `::v-deep`, what follows is not scoped.
+  Deep,
+  /// `:slotted()`, the argument gets the slot attribute.
+  Slotted,
+  /// `:global()`, the selector is not scoped.
+  Global,
+}
+
+/// The pseudo-classes and pseudo-elements scoping strips from selectors,
+/// for [`ComplexSelector::specificity_without`]. `:slotted()` is replaced by
+/// an attribute instead.
+pub const STRIPPED_PSEUDO_CLASSES: &[&str] = &["deep", "v-deep", "global", "v-global"];
+
+const PREFIX: &str = "data-v-";
+
+/// The hash of a component id, which may have the `data-v-` prefix of the
+/// attribute already.
+pub(crate) fn bare_id(id: &str) -> &str {
+  id.strip_prefix(PREFIX).unwrap_or(id)
+}
+
+/// Adds the `data-v-<id>` attribute to the last compound of every selector.
+///
+/// - `.a :deep(.b)` and `.a ::v-deep .b` become `.a[data-v-<id>] .b`;
+/// - `:slotted(.a)` becomes `.a[data-v-<id>-s]`;
+/// - `:global(.a) .b` becomes `.a .b`, without attribute.
+///
+/// The attribute goes before the pseudo-classes and pseudo-elements of the
+/// compound, e.g. `.a[data-v-<id>]:hover`. Both `1a2b3c` and
+/// `data-v-1a2b3c` are accepted as `id`.
+pub fn scope(list: &mut SelectorList, id: &str) -> Result<(), String> {
+  let attribute = format!("{}{}", PREFIX, bare_id(id));
+  for selector in &mut list.selectors {
+    scope_complex(selector, &attribute)?;
+  }
+  Ok(())
+}
+
+fn scope_complex(selector: &mut ComplexSelector, attribute: &str) -> Result<(), String> {
+  let found = selector
+    .compounds
+    .iter()
+    .enumerate()
+    .find_map(|(i, compound)| {
+      compound
+        .selectors
+        .iter()
+        .enumerate()
+        .find_map(|(j, simple)| escape(simple).map(|escape| (i, j, escape)))
+    });
+  let (i, j, escape) = match found {
+    Some(found) => found,
+    None => {
+      if let Some(last) = selector.compounds.last_mut() {
+        inject(last, attribute);
+      }
+      return Ok(());
+    }
+  };
+
+  // The selector is split around the escape: `compounds prefix:deep(inner)rest tail`.
+  let mut compounds = std::mem::take(&mut selector.compounds);
+  let tail = compounds.split_off(i + 1);
+  let mut compound = compounds.pop().unwrap();
+  let rest = compound.selectors.split_off(j + 1);
+  let pseudo = match compound.selectors.pop() {
+    Some(SimpleSelector::PseudoClass(pseudo) | SimpleSelector::PseudoElement(pseudo)) => pseudo,
+    _ => unreachable!(),
+  };
+  let prefix = compound.selectors;
+  let combinator = compound.combinator;
+  let mut inner = match pseudo.argument {
+    Some(PseudoArgument::Selectors(list)) => single(list, &pseudo.name)?,
+    Some(PseudoArgument::Raw(raw)) => {
+      let list = selector::parse(&raw).map_err(|error| error.to_string())?;
+      single(list, &pseudo.name)?
+    }
+    Some(PseudoArgument::Nth { .. }) => unreachable!(),
+    None if escape == Escape::Deep => vec![],
+    None => return Err(format!(":{}() needs a selector", pseudo.name)),
+  };
+
+  if escape == Escape::Deep {
+    let inner_combinator = if prefix.is_empty() && !compounds.is_empty() {
+      combinator.clone()
+    } else {
+      None
+    };
+    if !prefix.is_empty() {
+      compounds.push(CompoundSelector {
+        combinator: combinator.clone(),
+        selectors: prefix,
+      });
+    }
+    match compounds.last_mut() {
+      Some(last) => inject(last, attribute),
+      None => compounds.push(CompoundSelector {
+        combinator,
+        selectors: vec![attribute_selector(attribute)],
+      }),
+    }
+    if !rest.is_empty() {
+      match inner.last_mut() {
+        Some(last) => last.selectors.extend(rest),
+        None => inner.push(CompoundSelector::new(rest)),
+      }
+    }
+    if let Some(first) = inner.first_mut() {
+      if first.combinator.is_none() {
+        first.combinator =
+          Some(inner_combinator.unwrap_or_else(|| Combinator::new(CombinatorKind::Descendant)));
+      }
+    }
+  } else {
+    let first = &mut inner[0];
+    first.selectors.splice(0..0, prefix);
+    first.combinator = combinator;
+    let last = inner.last_mut().unwrap();
+    last.selectors.extend(rest);
+    if escape == Escape::Slotted {
+      inject(last, &format!("{}-s", attribute));
+    }
+  }
+  compounds.extend(inner);
+  compounds.extend(tail);
+  selector.compounds = compounds;
+  Ok(())
+}
+
+fn escape(simple: &SimpleSelector) -> Option<Escape> {
+  let (pseudo, element) = match simple {
+    SimpleSelector::PseudoClass(pseudo) => (pseudo, false),
+    SimpleSelector::PseudoElement(pseudo) => (pseudo, true),
+    _ => return None,
+  };
+  match pseudo.lower_name().as_str() {
+    "deep" if !element => Some(Escape::Deep),
+    "v-deep" => Some(Escape::Deep),
+    "slotted" if !element => Some(Escape::Slotted),
+    "v-slotted" => Some(Escape::Slotted),
+    "global" if !element => Some(Escape::Global),
+    "v-global" => Some(Escape::Global),
+    _ => None,
+  }
+}
+
+/// The compounds of the only selector of an escape's argument.
+fn single(mut list: SelectorList, name: &str) -> Result<Vec<CompoundSelector>, String> {
+  if list.selectors.len() != 1 {
+    return Err(format!(":{}() takes a single selector", name));
+  }
+  Ok(list.selectors.remove(0).compounds)
+}
+
+/// Adds the attribute after the last simple selector that is not a pseudo.
+fn inject(compound: &mut CompoundSelector, attribute: &str) {
+  let index = compound
+    .selectors
+    .iter()
+    .rposition(|simple| {
+      !matches!(
+        simple,
+        SimpleSelector::PseudoClass(_) | SimpleSelector::PseudoElement(_)
+      )
+    })
+    .map_or(0, |index| index + 1);
+  compound
+    .selectors
+    .insert(index, attribute_selector(attribute));
+}
+
+fn attribute_selector(name: &str) -> SimpleSelector {
+  SimpleSelector::Attribute(AttributeSelector {
+    namespace: None,
+    name: name.to_string(),
+    operator: None,
+    value: None,
+    quote: None,
+    modifier: None,
+  })
+}
@@ -0,0 +1,80 @@
+use postcss::Processor;
+use scoped::{scope, Scoped, STRIPPED_PSEUDO_CLASSES};
+use selector::Specificity;
+
+fn scoped(selector: &str) -> String {
+  let mut list = selector::parse(selector).unwrap();
+  scope(&mut list, "1a2b3c").unwrap();
+  list.to_string()
+}
+
+#[test]
+fn scopes_last_compound() {
+  assert_eq!(scoped(".a .b"), ".a .b[data-v-1a2b3c]");
+  assert_eq!(
+    scoped("a > b, .c"),
+    "a > b[data-v-1a2b3c], .c[data-v-1a2b3c]"
+  );
+  assert_eq!(
+    scoped(".a:hover::before"),
+    ".a[data-v-1a2b3c]:hover::before"
+  );
+  assert_eq!(scoped(":hover"), "[data-v-1a2b3c]:hover");
+  assert_eq!(scoped(".a:not(.b)"), ".a[data-v-1a2b3c]:not(.b)");
+}
+
+#[test]
+fn escapes_deep() {
+  assert_eq!(scoped(".a :deep(.b .c)"), ".a[data-v-1a2b3c] .b .c");
+  assert_eq!(scoped(".a > :deep(.b)"), ".a[data-v-1a2b3c] > .b");
+  assert_eq!(scoped(".a:deep(.b):hover"), ".a[data-v-1a2b3c] .b:hover");
+  assert_eq!(scoped(":deep(.b)"), "[data-v-1a2b3c] .b");
+  assert_eq!(scoped(".a ::v-deep .b"), ".a[data-v-1a2b3c] .b");
+  assert_eq!(scoped(".a::v-deep(.b)"), ".a[data-v-1a2b3c] .b");
+  assert_eq!(scoped("::v-deep .b"), "[data-v-1a2b3c] .b");
+}
+
+#[test]
+fn escapes_slotted_and_global() {
+  assert_eq!(scoped(":slotted(.a)"), ".a[data-v-1a2b3c-s]");
+  assert_eq!(
+    scoped(".b :slotted(.a)::before"),
+    ".b .a[data-v-1a2b3c-s]::before"
+  );
+  assert_eq!(scoped(":global(.a) .b"), ".a .b");
+  assert_eq!(scoped("html:global(.dark) .b"), "html.dark .b");
+  let mut list = selector::parse(":global(.a, .b)").unwrap();
+  assert_eq!(
+    scope(&mut list, "1a2b3c"),
+    Err(":global() takes a single selector".to_string())
+  );
+}
+
+#[test]
+fn renames_keyframes() {
+  let css = ".a { animation: spin 1s }\n@media print { .b { color: red } }\n@keyframes spin { from { opacity: 0 } 50% { opacity: 1 } }\n@-webkit-keyframes spin {}";
+  let result = Processor::run_one(Scoped::new("1a2b3c"), css).unwrap();
+  assert_eq!(
+    result.css,
+    ".a[data-v-1a2b3c] { animation: spin-1a2b3c 1s }\n@media print { .b[data-v-1a2b3c] { color: red } }\n@keyframes spin-1a2b3c { from { opacity: 0 } 50% { opacity: 1 } }\n@-webkit-keyframes spin-1a2b3c {}"
+  );
+}
+
+#[test]
+fn accepts_prefixed_ids() {
+  let css = ".a { animation: spin 1s }\n@keyframes spin {}";
+  let result = Processor::run_one(Scoped::new("data-v-1a2b3c"), css).unwrap();
+  assert_eq!(
+    result.css,
+    ".a[data-v-1a2b3c] { animation: spin-1a2b3c 1s }\n@keyframes spin-1a2b3c {}"
+  );
+}
+
+#[test]
+fn strips_escapes_from_specificity() {
+  let list = selector::parse(".a :deep(.b) ::v-deep .c").unwrap();
+  assert_eq!(
+    list.selectors[0].specificity_without(STRIPPED_PSEUDO_CLASSES),
+    Specificity::new(0, 3, 0)
+  );
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+
+/// A comma separated list of complex selectors, e.g. `a > b, .c`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SelectorList {
+  pub selectors: Vec<ComplexSelector>,
+}
+
+/// A sequence of compound selectors joined by combinators, e.g. `ul > li.active`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ComplexSelector {
+  pub compounds: Vec<CompoundSelector>,
+
+  /// The whitespace before the selector, after the preceding comma.
+  pub before: String,
+
+  /// The whitespace after the selector, before the following comma.
+  pub after: String,
+}
+
+/// A sequence of simple selectors that are not separated by a combinator,
+/// e.g. `a.button:hover`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompoundSelector {
+  /// The combinator between this compound and the previous one.
+  ///
+  /// It is `None` for the first compound, unless the selector is relative
+  /// (e.g. `> img` inside `:has()`).
+  pub combinator: Option<Combinator>,
+
+  pub selectors: Vec<SimpleSelector>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CombinatorKind {
+  /// ` `
+  Descendant,
+  /// `>`
+  Child,
+  /// `+`
+  NextSibling,
+  /// `~`
+  SubsequentSibling,
+  /// `||`
+  Column,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Combinator {
+  pub kind: CombinatorKind,
+
+  /// The combinator as written in the source, including surrounding whitespace.
+  pub raw: String,
+}
+
+impl Combinator {
+  pub fn new(kind: CombinatorKind) -> Self {
+    let raw = match kind {
+      CombinatorKind::Descendant => " ",
+      CombinatorKind::Child => " > ",
+      CombinatorKind::NextSibling => " + ",
+      CombinatorKind::SubsequentSibling => " ~ ",
+      CombinatorKind::Column => " || ",
+    };
+    Self {
+      kind,
+      raw: raw.to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimpleSelector {
+  /// `*`, `ns|*`
+  Universal { namespace: Option<String> },
+  /// `div`, `svg|rect`
+  Type {
+    namespace: Option<String>,
+    name: String,
+  },
+  /// `#id`
+  Id(String),
+  /// `.class`
+  Class(String),
+  /// `[attr]`, `[attr="value" i]`
+  Attribute(AttributeSelector),
+  /// `:hover`, `:not(.a)`
+  PseudoClass(PseudoSelector),
+  /// `::before`, `:after`, `::slotted(span)`
+  PseudoElement(PseudoSelector),
+  /// `&`
+  Nesting,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeSelector {
+  pub namespace: Option<String>,
+  pub name: String,
+  pub operator: Option<AttributeOperator>,
+
+  /// The unquoted value.
+  pub value: Option<String>,
+
+  /// The quote character the value was written with, if any.
+  pub quote: Option<char>,
+
+  /// `i` or `s`.
+  pub modifier: Option<char>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttributeOperator {
+  /// `=`
+  Equal,
+  /// `~=`
+  Includes,
+  /// `|=`
+  DashMatch,
+  /// `^=`
+  Prefix,
+  /// `$=`
+  Suffix,
+  /// `*=`
+  Substring,
+}
+
+impl AttributeOperator {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      AttributeOperator::Equal => "=",
+      AttributeOperator::Includes => "~=",
+      AttributeOperator::DashMatch => "|=",
+      AttributeOperator::Prefix => "^=",
+      AttributeOperator::Suffix => "$=",
+      AttributeOperator::Substring => "*=",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PseudoSelector {
+  /// The name without colons, as written in the source.
+  pub name: String,
+
+  pub argument: Option<PseudoArgument>,
+
+  /// `true` for pseudo-elements written with a single colon, like `:before`.
+  pub legacy: bool,
+}
+
+impl PseudoSelector {
+  pub fn new(name: &str, argument: Option<PseudoArgument>) -> Self {
+    Self {
+      name: name.to_string(),
+      argument,
+      legacy: false,
+    }
+  }
+
+  /// The lowercased name, for matching against known pseudo-classes.
+  pub fn lower_name(&self) -> String {
+    self.name.to_ascii_lowercase()
+  }
+
+  /// The selector list argument of functional pseudos like `:is()` or `:nth-child(2n of .a)`.
+  pub fn selectors(&self) -> Option<&SelectorList> {
+    match &self.argument {
+      Some(PseudoArgument::Selectors(list)) => Some(list),
+      Some(PseudoArgument::Nth { of, .. }) => of.as_ref(),
+      _ => None,
+    }
+  }
+
+  pub fn selectors_mut(&mut self) -> Option<&mut SelectorList> {
+    match &mut self.argument {
+      Some(PseudoArgument::Selectors(list)) => Some(list),
+      Some(PseudoArgument::Nth { of, .. }) => of.as_mut(),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PseudoArgument {
+  /// The argument of `:is()`, `:not()`, `:where()`, `:has()` and friends.
+  Selectors(SelectorList),
+  /// The argument of `:nth-child()` and friends, e.g. `2n + 1 of .item`.
+  Nth {
+    expr: String,
+    of: Option<SelectorList>,
+  },
+  /// Any other argument, kept verbatim, e.g. `:lang(en)`.
+  Raw(String),
+}
+
+impl SelectorList {
+  pub fn new(selectors: Vec<ComplexSelector>) -> Self {
+    Self { selectors }
+  }
+
+  /// Every complex selector serialized on its own, without surrounding whitespace.
+  pub fn to_strings(&self) -> Vec<String> {
+    self
+      .selectors
+      .iter()
+      .map(|selector| {
+        let mut selector = selector.clone();
+        selector.before.clear();
+        selector.after.clear();
+        selector.to_string()
+      })
+      .collect()
+  }
+}
+
+impl ComplexSelector {
+  pub fn new(compounds: Vec<CompoundSelector>) -> Self {
+    Self {
+      compounds,
+      before: String::new(),
+      after: String::new(),
+    }
+  }
+
+  /// Returns `true` if the selector starts with a combinator, like `> img`.
+  pub fn is_relative(&self) -> bool {
+    matches!(self.compounds.first(), Some(compound) if compound.combinator.is_some())
+  }
+
+  /// Visits every simple selector, including those nested in pseudo-class arguments.
+  pub fn walk<F: FnMut(&SimpleSelector)>(&self, callback: &mut F) {
+    for compound in &self.compounds {
+      for simple in &compound.selectors {
+        callback(simple);
+        if let SimpleSelector::PseudoClass(pseudo) | SimpleSelector::PseudoElement(pseudo) = simple
+        {
+          if let Some(list) = pseudo.selectors() {
+            for selector in &list.selectors {
+              selector.walk(callback);
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+impl CompoundSelector {
+  pub fn new(selectors: Vec<SimpleSelector>) -> Self {
+    Self {
+      combinator: None,
+      selectors,
+    }
+  }
+}
+
+impl fmt::Display for SelectorList {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, selector) in self.selectors.iter().enumerate() {
+      if i > 0 {
+        f.write_str(",")?;
+      }
+      write!(f, "{}", selector)?;
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for ComplexSelector {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(&self.before)?;
+    for (i, compound) in self.compounds.iter().enumerate() {
+      if let Some(combinator) = &compound.combinator {
+        if i == 0 {
+          f.write_str(combinator.raw.trim_start())?;
+        } else {
+          f.write_str(&combinator.raw)?;
+        }
+      }
+      write!(f, "{}", compound)?;
+    }
+    f.write_str(&self.after)
+  }
+}
+
+impl fmt::Display for CompoundSelector {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for selector in &self.selectors {
+      write!(f, "{}", selector)?;
+    }
+    Ok(())
+  }
+}
+
+fn write_namespace(f: &mut fmt::Formatter, namespace: &Option<String>) -> fmt::Result {
+  match namespace {
+    Some(namespace) => write!(f, "{}|", namespace),
+    None => Ok(()),
+  }
+}
+
+impl fmt::Display for SimpleSelector {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SimpleSelector::Universal { namespace } => {
+        write_namespace(f, namespace)?;
+        f.write_str("*")
+      }
+      SimpleSelector::Type { namespace, name } => {
+        write_namespace(f, namespace)?;
+        f.write_str(name)
+      }
+      SimpleSelector::Id(id) => write!(f, "#{}", id),
+      SimpleSelector::Class(class) => write!(f, ".{}", class),
+      SimpleSelector::Attribute(attribute) => write!(f, "{}", attribute),
+      SimpleSelector::PseudoClass(pseudo) => write!(f, ":{}", pseudo),
+      SimpleSelector::PseudoElement(pseudo) => {
+        if pseudo.legacy {
+          write!(f, ":{}", pseudo)
+        } else {
+          write!(f, "::{}", pseudo)
+        }
+      }
+      SimpleSelector::Nesting => f.write_str("&"),
+    }
+  }
+}
+
+impl fmt::Display for AttributeSelector {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("[")?;
+    write_namespace(f, &self.namespace)?;
+    f.write_str(&self.name)?;
+    if let (Some(operator), Some(value)) = (&self.operator, &self.value) {
+      f.write_str(operator.as_str())?;
+      match self.quote {
+        Some(quote) => write!(f, "{}{}{}", quote, value, quote)?,
+        None => f.write_str(value)?,
+      }
+      if let Some(modifier) = self.modifier {
+        write!(f, " {}", modifier)?;
+      }
+    }
+    f.write_str("]")
+  }
+}
+
+impl fmt::Display for PseudoSelector {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(&self.name)?;
+    match &self.argument {
+      Some(PseudoArgument::Selectors(list)) => write!(f, "({})", list),
+      Some(PseudoArgument::Nth { expr, of }) => match of {
+        Some(of) => write!(f, "({} of {})", expr, of.to_string().trim_start()),
+        None => write!(f, "({})", expr),
+      },
+      Some(PseudoArgument::Raw(raw)) => write!(f, "({})", raw),
+      None => Ok(()),
+    }
+  }
+}
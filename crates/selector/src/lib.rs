@@ -0,0 +1,14 @@
+mod ast;
+mod parser;
+mod specificity;
+
+pub use crate::ast::*;
+pub use crate::parser::{unescape, ParseError};
+pub use crate::specificity::Specificity;
+
+use crate::parser::Parser;
+
+/// Parses a selector list, such as the `selector` of a `Rule`.
+pub fn parse(selector: &str) -> Result<SelectorList, ParseError> {
+  Parser::new(selector).parse_selector_list()
+}
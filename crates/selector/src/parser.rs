@@ -0,0 +1,531 @@
+use crate::ast::*;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+  pub reason: String,
+  /// Byte offset in the parsed selector.
+  pub offset: usize,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} at {}", self.reason, self.offset)
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Pseudo-classes whose argument is a selector list.
+const SELECTOR_LIST_PSEUDOS: &[&str] = &[
+  "is",
+  "not",
+  "where",
+  "has",
+  "matches",
+  "-webkit-any",
+  "-moz-any",
+  "host",
+  "host-context",
+  "global",
+  "local",
+  "deep",
+  "v-deep",
+  "slotted",
+  "v-slotted",
+  "v-global",
+];
+
+/// Pseudo-elements whose argument is a selector list.
+const SELECTOR_LIST_PSEUDO_ELEMENTS: &[&str] = &["slotted", "cue", "v-deep"];
+
+const NTH_PSEUDOS: &[&str] = &[
+  "nth-child",
+  "nth-last-child",
+  "nth-of-type",
+  "nth-last-of-type",
+  "nth-col",
+  "nth-last-col",
+];
+
+/// Pseudo-elements that may be written with a single colon for compatibility with CSS2.
+const LEGACY_PSEUDO_ELEMENTS: &[&str] = &["before", "after", "first-line", "first-letter"];
+
+pub(crate) struct Parser<'a> {
+  input: &'a str,
+  pos: usize,
+  /// Offset of `input` in the selector the user passed in, for error reporting.
+  base: usize,
+}
+
+impl<'a> Parser<'a> {
+  pub(crate) fn new(input: &'a str) -> Self {
+    Self::with_base(input, 0)
+  }
+
+  fn with_base(input: &'a str, base: usize) -> Self {
+    Self {
+      input,
+      pos: 0,
+      base,
+    }
+  }
+
+  pub(crate) fn parse_selector_list(&mut self) -> Result<SelectorList, ParseError> {
+    let mut selectors = vec![];
+    loop {
+      selectors.push(self.parse_complex_selector()?);
+      match self.peek() {
+        Some(b',') => self.pos += 1,
+        None => break,
+        Some(_) => return Err(self.error("Unexpected character")),
+      }
+    }
+    Ok(SelectorList { selectors })
+  }
+
+  fn parse_complex_selector(&mut self) -> Result<ComplexSelector, ParseError> {
+    let before = self.consume_whitespace();
+    let mut compounds: Vec<CompoundSelector> = vec![];
+    loop {
+      let start = self.pos;
+      let whitespace = self.consume_whitespace();
+      let combinator = match self.peek() {
+        None | Some(b',') | Some(b')') => {
+          self.pos = start;
+          break;
+        }
+        Some(b'>') => Some(self.finish_combinator(CombinatorKind::Child, start, 1)),
+        Some(b'+') => Some(self.finish_combinator(CombinatorKind::NextSibling, start, 1)),
+        Some(b'~') => Some(self.finish_combinator(CombinatorKind::SubsequentSibling, start, 1)),
+        Some(b'|') if self.peek_at(1) == Some(b'|') => {
+          Some(self.finish_combinator(CombinatorKind::Column, start, 2))
+        }
+        Some(_) if !whitespace.is_empty() && !compounds.is_empty() => Some(Combinator {
+          kind: CombinatorKind::Descendant,
+          raw: whitespace,
+        }),
+        Some(_) => None,
+      };
+      if combinator.is_none() && !compounds.is_empty() {
+        return Err(self.error("Expected a combinator"));
+      }
+      let selectors = self.parse_compound()?;
+      if selectors.is_empty() {
+        return Err(self.error("Expected a selector"));
+      }
+      compounds.push(CompoundSelector {
+        combinator,
+        selectors,
+      });
+    }
+    if compounds.is_empty() {
+      return Err(self.error("Expected a selector"));
+    }
+    let after = self.consume_whitespace();
+    Ok(ComplexSelector {
+      compounds,
+      before,
+      after,
+    })
+  }
+
+  fn finish_combinator(&mut self, kind: CombinatorKind, start: usize, len: usize) -> Combinator {
+    self.pos += len;
+    self.consume_whitespace();
+    Combinator {
+      kind,
+      raw: self.input[start..self.pos].to_string(),
+    }
+  }
+
+  fn parse_compound(&mut self) -> Result<Vec<SimpleSelector>, ParseError> {
+    let mut selectors = vec![];
+    loop {
+      let start = self.pos;
+      let selector = match self.peek() {
+        Some(b'#') => {
+          self.pos += 1;
+          SimpleSelector::Id(self.expect_ident()?)
+        }
+        Some(b'.') => {
+          self.pos += 1;
+          SimpleSelector::Class(self.expect_ident()?)
+        }
+        Some(b'[') => SimpleSelector::Attribute(self.parse_attribute()?),
+        Some(b':') => self.parse_pseudo()?,
+        Some(b'&') => {
+          self.pos += 1;
+          SimpleSelector::Nesting
+        }
+        // `||` is the column combinator, not an empty namespace.
+        Some(b'|') if self.peek_at(1) == Some(b'|') => break,
+        Some(b'*') | Some(b'|') => self.parse_type_or_universal()?,
+        Some(_) if self.at_ident_start() => self.parse_type_or_universal()?,
+        _ => break,
+      };
+      if self.pos == start {
+        return Err(self.error("Unexpected character"));
+      }
+      selectors.push(selector);
+    }
+    Ok(selectors)
+  }
+
+  fn parse_type_or_universal(&mut self) -> Result<SimpleSelector, ParseError> {
+    let first = self.parse_name_or_star()?;
+    if self.peek() == Some(b'|') && self.peek_at(1) != Some(b'|') && self.peek_at(1) != Some(b'=') {
+      self.pos += 1;
+      let name = self.parse_name_or_star()?;
+      return Ok(name_to_selector(Some(first), name));
+    }
+    Ok(name_to_selector(None, first))
+  }
+
+  /// Parses an identifier or `*`. An empty string is returned for the empty
+  /// namespace in `|name`.
+  fn parse_name_or_star(&mut self) -> Result<String, ParseError> {
+    match self.peek() {
+      Some(b'*') => {
+        self.pos += 1;
+        Ok("*".to_string())
+      }
+      Some(b'|') => Ok(String::new()),
+      _ => self.expect_ident(),
+    }
+  }
+
+  fn parse_attribute(&mut self) -> Result<AttributeSelector, ParseError> {
+    self.pos += 1; // [
+    self.consume_whitespace();
+    let mut namespace = None;
+    let mut name = self.parse_name_or_star()?;
+    if self.peek() == Some(b'|') && self.peek_at(1) != Some(b'=') {
+      self.pos += 1;
+      namespace = Some(name);
+      name = self.expect_ident()?;
+    }
+    if name == "*" {
+      return Err(self.error("Expected an attribute name"));
+    }
+    self.consume_whitespace();
+    let operator = match (self.peek(), self.peek_at(1)) {
+      (Some(b']'), _) => None,
+      (Some(b'='), _) => Some(AttributeOperator::Equal),
+      (Some(b'~'), Some(b'=')) => Some(AttributeOperator::Includes),
+      (Some(b'|'), Some(b'=')) => Some(AttributeOperator::DashMatch),
+      (Some(b'^'), Some(b'=')) => Some(AttributeOperator::Prefix),
+      (Some(b'$'), Some(b'=')) => Some(AttributeOperator::Suffix),
+      (Some(b'*'), Some(b'=')) => Some(AttributeOperator::Substring),
+      _ => return Err(self.error("Expected an attribute operator")),
+    };
+    let mut attribute = AttributeSelector {
+      namespace,
+      name,
+      operator,
+      value: None,
+      quote: None,
+      modifier: None,
+    };
+    if let Some(operator) = operator {
+      self.pos += operator.as_str().len();
+      self.consume_whitespace();
+      match self.peek() {
+        Some(quote @ (b'"' | b'\'')) => {
+          attribute.value = Some(self.parse_string()?);
+          attribute.quote = Some(quote as char);
+        }
+        _ => attribute.value = Some(self.parse_unquoted_value()?),
+      }
+      self.consume_whitespace();
+      if let Some(modifier @ (b'i' | b'I' | b's' | b'S')) = self.peek() {
+        self.pos += 1;
+        attribute.modifier = Some(modifier as char);
+        self.consume_whitespace();
+      }
+    }
+    if self.peek() != Some(b']') {
+      return Err(self.error("Expected ]"));
+    }
+    self.pos += 1;
+    Ok(attribute)
+  }
+
+  fn parse_pseudo(&mut self) -> Result<SimpleSelector, ParseError> {
+    self.pos += 1; // :
+    let element = self.peek() == Some(b':');
+    if element {
+      self.pos += 1;
+    }
+    let name = self.expect_ident()?;
+    let lower_name = name.to_ascii_lowercase();
+    let mut argument = None;
+    if self.peek() == Some(b'(') {
+      let start = self.pos + 1;
+      let end = self.find_closing_parenthesis(start)?;
+      let raw = &self.input[start..end];
+      let base = self.base + start;
+      self.pos = end + 1;
+      argument = Some(if element {
+        if SELECTOR_LIST_PSEUDO_ELEMENTS.contains(&lower_name.as_str()) {
+          parse_nested_list(raw, base)?
+        } else {
+          PseudoArgument::Raw(raw.to_string())
+        }
+      } else if SELECTOR_LIST_PSEUDOS.contains(&lower_name.as_str()) {
+        parse_nested_list(raw, base)?
+      } else if NTH_PSEUDOS.contains(&lower_name.as_str()) {
+        parse_nth(raw, base)?
+      } else {
+        PseudoArgument::Raw(raw.to_string())
+      });
+    }
+    let legacy = !element && LEGACY_PSEUDO_ELEMENTS.contains(&lower_name.as_str());
+    let pseudo = PseudoSelector {
+      name,
+      argument,
+      legacy,
+    };
+    Ok(if element || legacy {
+      SimpleSelector::PseudoElement(pseudo)
+    } else {
+      SimpleSelector::PseudoClass(pseudo)
+    })
+  }
+
+  /// Returns the offset of the `)` matching the `(` just before `start`.
+  fn find_closing_parenthesis(&self, start: usize) -> Result<usize, ParseError> {
+    let bytes = self.input.as_bytes();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut i = start;
+    while i < bytes.len() {
+      let byte = bytes[i];
+      match quote {
+        Some(q) => {
+          if byte == b'\\' {
+            i += 1;
+          } else if byte == q {
+            quote = None;
+          }
+        }
+        None => match byte {
+          b'\\' => i += 1,
+          b'"' | b'\'' => quote = Some(byte),
+          b'(' => depth += 1,
+          b')' if depth == 0 => return Ok(i),
+          b')' => depth -= 1,
+          _ => {}
+        },
+      }
+      i += 1;
+    }
+    Err(ParseError {
+      reason: "Unclosed bracket".to_string(),
+      offset: self.base + start - 1,
+    })
+  }
+
+  fn parse_string(&mut self) -> Result<String, ParseError> {
+    let bytes = self.input.as_bytes();
+    let quote = bytes[self.pos];
+    let start = self.pos + 1;
+    let mut i = start;
+    while i < bytes.len() {
+      match bytes[i] {
+        b'\\' => i += 2,
+        byte if byte == quote => {
+          self.pos = i + 1;
+          return Ok(self.input[start..i].to_string());
+        }
+        _ => i += 1,
+      }
+    }
+    Err(self.error("Unclosed string"))
+  }
+
+  /// Unquoted attribute values should be identifiers, but values like
+  /// `[tabindex=0]` are common enough to accept anything up to `]`.
+  fn parse_unquoted_value(&mut self) -> Result<String, ParseError> {
+    let start = self.pos;
+    while let Some(byte) = self.peek() {
+      match byte {
+        b']' | b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' => break,
+        b'\\' => self.pos += 2,
+        _ => self.pos += 1,
+      }
+    }
+    if start == self.pos {
+      return Err(self.error("Expected an attribute value"));
+    }
+    Ok(self.input[start..self.pos.min(self.input.len())].to_string())
+  }
+
+  fn at_ident_start(&self) -> bool {
+    match self.peek() {
+      Some(b'-') => match self.peek_at(1) {
+        Some(b'-') | Some(b'\\') => true,
+        Some(byte) => is_name_start(byte),
+        None => false,
+      },
+      Some(b'\\') => true,
+      Some(byte) => is_name_start(byte),
+      None => false,
+    }
+  }
+
+  /// Consumes an identifier, keeping escapes as written.
+  fn expect_ident(&mut self) -> Result<String, ParseError> {
+    if !self.at_ident_start() {
+      return Err(self.error("Expected an identifier"));
+    }
+    let bytes = self.input.as_bytes();
+    let start = self.pos;
+    while self.pos < bytes.len() {
+      let byte = bytes[self.pos];
+      if byte == b'\\' {
+        self.pos += 1;
+        if self.pos >= bytes.len() {
+          break;
+        }
+        if bytes[self.pos].is_ascii_hexdigit() {
+          let mut digits = 0;
+          while digits < 6 && self.pos < bytes.len() && bytes[self.pos].is_ascii_hexdigit() {
+            self.pos += 1;
+            digits += 1;
+          }
+          if matches!(self.peek(), Some(b' ' | b'\t' | b'\n')) {
+            self.pos += 1;
+          }
+        } else {
+          self.pos += utf8_len(bytes[self.pos]);
+        }
+      } else if is_name(byte) {
+        self.pos += utf8_len(byte);
+      } else {
+        break;
+      }
+    }
+    Ok(self.input[start..self.pos].to_string())
+  }
+
+  fn consume_whitespace(&mut self) -> String {
+    let start = self.pos;
+    let bytes = self.input.as_bytes();
+    loop {
+      match bytes.get(self.pos) {
+        Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0c') => self.pos += 1,
+        Some(b'/') if bytes.get(self.pos + 1) == Some(&b'*') => {
+          match self.input[self.pos + 2..].find("*/") {
+            Some(end) => self.pos += end + 4,
+            None => self.pos = bytes.len(),
+          }
+        }
+        _ => break,
+      }
+    }
+    self.input[start..self.pos].to_string()
+  }
+
+  #[inline]
+  fn peek(&self) -> Option<u8> {
+    self.input.as_bytes().get(self.pos).copied()
+  }
+
+  #[inline]
+  fn peek_at(&self, n: usize) -> Option<u8> {
+    self.input.as_bytes().get(self.pos + n).copied()
+  }
+
+  fn error(&self, reason: &str) -> ParseError {
+    ParseError {
+      reason: reason.to_string(),
+      offset: self.base + self.pos,
+    }
+  }
+}
+
+/// The name an identifier of a selector stands for, with its escapes
+/// resolved: `md\:flex` is `md:flex` and `\31 0` is `10`.
+pub fn unescape(ident: &str) -> String {
+  let mut name = String::with_capacity(ident.len());
+  let mut chars = ident.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      name.push(c);
+      continue;
+    }
+    let mut hex = String::new();
+    while hex.len() < 6 && chars.peek().is_some_and(char::is_ascii_hexdigit) {
+      hex.extend(chars.next());
+    }
+    if hex.is_empty() {
+      name.extend(chars.next());
+      continue;
+    }
+    if chars.peek().is_some_and(|c| matches!(c, ' ' | '\t' | '\n')) {
+      chars.next();
+    }
+    let code = u32::from_str_radix(&hex, 16).unwrap_or_default();
+    name.push(match char::from_u32(code) {
+      Some(c) if code != 0 => c,
+      _ => char::REPLACEMENT_CHARACTER,
+    });
+  }
+  name
+}
+
+fn name_to_selector(namespace: Option<String>, name: String) -> SimpleSelector {
+  if name == "*" {
+    SimpleSelector::Universal { namespace }
+  } else {
+    SimpleSelector::Type { namespace, name }
+  }
+}
+
+fn parse_nested_list(raw: &str, base: usize) -> Result<PseudoArgument, ParseError> {
+  let mut parser = Parser::with_base(raw, base);
+  Ok(PseudoArgument::Selectors(parser.parse_selector_list()?))
+}
+
+fn parse_nth(raw: &str, base: usize) -> Result<PseudoArgument, ParseError> {
+  let lower = raw.to_ascii_lowercase();
+  let of = lower.match_indices("of").map(|(i, _)| i).find(|&i| {
+    let before = lower[..i].chars().last();
+    let after = lower[i + 2..].chars().next();
+    matches!(before, Some(c) if c.is_whitespace()) && matches!(after, Some(c) if c.is_whitespace())
+  });
+  Ok(match of {
+    Some(i) => {
+      let mut parser = Parser::with_base(&raw[i + 2..], base + i + 2);
+      PseudoArgument::Nth {
+        expr: raw[..i].trim_end().to_string(),
+        of: Some(parser.parse_selector_list()?),
+      }
+    }
+    None => PseudoArgument::Nth {
+      expr: raw.to_string(),
+      of: None,
+    },
+  })
+}
+
+#[inline]
+fn is_name_start(byte: u8) -> bool {
+  byte.is_ascii_alphabetic() || byte == b'_' || byte >= 0x80
+}
+
+#[inline]
+fn is_name(byte: u8) -> bool {
+  is_name_start(byte) || byte.is_ascii_digit() || byte == b'-'
+}
+
+#[inline]
+fn utf8_len(byte: u8) -> usize {
+  match byte {
+    0xF0..=0xFF => 4,
+    0xE0..=0xEF => 3,
+    0xC0..=0xDF => 2,
+    _ => 1,
+  }
+}
@@ -0,0 +1,128 @@
+use crate::ast::*;
+use std::fmt;
+use std::ops::{Add, AddAssign};
+
+/// Selectors Level 4 specificity.
+///
+/// <https://drafts.csswg.org/selectors-4/#specificity-rules>
+///
+/// The derived ordering compares `a`, then `b`, then `c`, which is the order
+/// the cascade uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Specificity {
+  /// ID selectors.
+  pub a: u32,
+  /// Class selectors, attribute selectors and pseudo-classes.
+  pub b: u32,
+  /// Type selectors and pseudo-elements.
+  pub c: u32,
+}
+
+impl Specificity {
+  pub const fn new(a: u32, b: u32, c: u32) -> Self {
+    Self { a, b, c }
+  }
+}
+
+impl Add for Specificity {
+  type Output = Specificity;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    Specificity::new(self.a + rhs.a, self.b + rhs.b, self.c + rhs.c)
+  }
+}
+
+impl AddAssign for Specificity {
+  fn add_assign(&mut self, rhs: Self) {
+    *self = *self + rhs;
+  }
+}
+
+impl fmt::Display for Specificity {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "({}, {}, {})", self.a, self.b, self.c)
+  }
+}
+
+impl SelectorList {
+  /// The specificity of the most specific selector in the list, which is what
+  /// `:is()`, `:not()` and `:has()` contribute.
+  pub fn max_specificity(&self) -> Specificity {
+    self.max_specificity_without(&[])
+  }
+
+  /// Like [`max_specificity`](Self::max_specificity), see
+  /// [`ComplexSelector::specificity_without`].
+  pub fn max_specificity_without(&self, stripped: &[&str]) -> Specificity {
+    self
+      .selectors
+      .iter()
+      .map(|selector| selector.specificity_without(stripped))
+      .max()
+      .unwrap_or_default()
+  }
+}
+
+impl ComplexSelector {
+  /// The specificity of the selector.
+  ///
+  /// The nesting selector `&` counts as zero, as its specificity depends on the
+  /// parent rule. Replace it with `:is(<parent selectors>)` first to take the
+  /// parent into account.
+  pub fn specificity(&self) -> Specificity {
+    self.specificity_without(&[])
+  }
+
+  /// The specificity of the selector once a plugin strips the pseudo-classes
+  /// and pseudo-elements named in `stripped`, leaving only their argument,
+  /// e.g. the `:global()` of CSS Modules.
+  pub fn specificity_without(&self, stripped: &[&str]) -> Specificity {
+    self
+      .compounds
+      .iter()
+      .flat_map(|compound| compound.selectors.iter())
+      .map(|selector| selector.specificity_without(stripped))
+      .fold(Specificity::default(), Add::add)
+  }
+}
+
+impl SimpleSelector {
+  pub fn specificity(&self) -> Specificity {
+    self.specificity_without(&[])
+  }
+
+  /// See [`ComplexSelector::specificity_without`].
+  pub fn specificity_without(&self, stripped: &[&str]) -> Specificity {
+    match self {
+      SimpleSelector::Universal { .. } | SimpleSelector::Nesting => Specificity::default(),
+      SimpleSelector::Type { .. } => Specificity::new(0, 0, 1),
+      SimpleSelector::Id(_) => Specificity::new(1, 0, 0),
+      SimpleSelector::Class(_) | SimpleSelector::Attribute(_) => Specificity::new(0, 1, 0),
+      SimpleSelector::PseudoClass(pseudo) => pseudo_class_specificity(pseudo, stripped),
+      SimpleSelector::PseudoElement(pseudo) => {
+        let mut specificity = argument_specificity(pseudo, stripped);
+        if !stripped.contains(&pseudo.lower_name().as_str()) {
+          specificity += Specificity::new(0, 0, 1);
+        }
+        specificity
+      }
+    }
+  }
+}
+
+fn argument_specificity(pseudo: &PseudoSelector, stripped: &[&str]) -> Specificity {
+  pseudo
+    .selectors()
+    .map(|list| list.max_specificity_without(stripped))
+    .unwrap_or_default()
+}
+
+fn pseudo_class_specificity(pseudo: &PseudoSelector, stripped: &[&str]) -> Specificity {
+  let argument = argument_specificity(pseudo, stripped);
+  match pseudo.lower_name().as_str() {
+    "where" => Specificity::default(),
+    "is" | "not" | "has" | "matches" | "-webkit-any" | "-moz-any" => argument,
+    name if stripped.contains(&name) => argument,
+    _ => Specificity::new(0, 1, 0) + argument,
+  }
+}
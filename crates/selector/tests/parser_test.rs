@@ -0,0 +1,124 @@
+use selector::*;
+
+fn roundtrip(css: &str) {
+  assert_eq!(parse(css).unwrap().to_string(), css);
+}
+
+#[test]
+fn parses_compound_selectors() {
+  let list = parse("a.button#main[href]:hover").unwrap();
+  assert_eq!(
+    list.selectors[0].compounds[0].selectors,
+    vec![
+      SimpleSelector::Type {
+        namespace: None,
+        name: "a".into()
+      },
+      SimpleSelector::Class("button".into()),
+      SimpleSelector::Id("main".into()),
+      SimpleSelector::Attribute(AttributeSelector {
+        namespace: None,
+        name: "href".into(),
+        operator: None,
+        value: None,
+        quote: None,
+        modifier: None,
+      }),
+      SimpleSelector::PseudoClass(PseudoSelector::new("hover", None)),
+    ]
+  );
+}
+
+#[test]
+fn parses_combinators() {
+  let list = parse("ul  > li + li ~ p a").unwrap();
+  let kinds: Vec<_> = list.selectors[0]
+    .compounds
+    .iter()
+    .map(|compound| compound.combinator.as_ref().map(|c| c.kind))
+    .collect();
+  assert_eq!(
+    kinds,
+    vec![
+      None,
+      Some(CombinatorKind::Child),
+      Some(CombinatorKind::NextSibling),
+      Some(CombinatorKind::SubsequentSibling),
+      Some(CombinatorKind::Descendant),
+    ]
+  );
+}
+
+#[test]
+fn parses_column_combinators() {
+  for css in ["a||b", "a || b", "col||td"] {
+    let list = parse(css).unwrap();
+    let compounds = &list.selectors[0].compounds;
+    assert_eq!(compounds.len(), 2);
+    assert_eq!(
+      compounds[1].combinator.as_ref().map(|c| c.kind),
+      Some(CombinatorKind::Column)
+    );
+    roundtrip(css);
+  }
+  assert!(parse("a||").is_err());
+}
+
+#[test]
+fn parses_relative_selectors() {
+  let list = parse("a:has(> img, + p)").unwrap();
+  let compound = &list.selectors[0].compounds[0];
+  match &compound.selectors[1] {
+    SimpleSelector::PseudoClass(pseudo) => {
+      let args = pseudo.selectors().unwrap();
+      assert!(args.selectors.iter().all(ComplexSelector::is_relative));
+    }
+    other => panic!("unexpected {:?}", other),
+  }
+}
+
+#[test]
+fn parses_legacy_pseudo_elements() {
+  let list = parse("p:before").unwrap();
+  assert!(matches!(
+    &list.selectors[0].compounds[0].selectors[1],
+    SimpleSelector::PseudoElement(PseudoSelector { legacy: true, .. })
+  ));
+}
+
+#[test]
+fn keeps_formatting() {
+  roundtrip(".a>.b,\n.c ~ .d");
+  roundtrip("svg|rect, *|*, |a");
+  roundtrip("[data-foo=\"bar\" i]");
+  roundtrip("[tabindex=0]");
+  roundtrip(".sm\\:p-2:not(.a, .b)::before");
+  roundtrip("li:nth-child(2n + 1 of .item)");
+  roundtrip(":lang(en) &:hover");
+  roundtrip("input::-webkit-input-placeholder");
+}
+
+#[test]
+fn unescapes_names() {
+  assert_eq!(unescape("md\\:flex"), "md:flex");
+  assert_eq!(unescape("w-1\\/2"), "w-1/2");
+  assert_eq!(unescape("\\31 0"), "10");
+  assert_eq!(unescape("\\1F600x"), "\u{1F600}x");
+  assert_eq!(unescape("plain"), "plain");
+}
+
+#[test]
+fn splits_selectors() {
+  let list = parse("a,\n  b > c , d").unwrap();
+  assert_eq!(list.to_strings(), vec!["a", "b > c", "d"]);
+}
+
+#[test]
+fn reports_errors() {
+  assert!(parse("").is_err());
+  assert!(parse("a,").is_err());
+  assert!(parse("a >").is_err());
+  assert!(parse("[href").is_err());
+  assert_eq!(parse(":is(a").unwrap_err().offset, 3);
+  assert!(parse("50%").is_err());
+}
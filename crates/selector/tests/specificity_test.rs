@@ -0,0 +1,71 @@
+use selector::{parse, Specificity};
+
+fn specificity(css: &str) -> Vec<(u32, u32, u32)> {
+  parse(css)
+    .unwrap()
+    .selectors
+    .iter()
+    .map(|selector| {
+      let Specificity { a, b, c } = selector.specificity();
+      (a, b, c)
+    })
+    .collect()
+}
+
+#[test]
+fn counts_simple_selectors() {
+  assert_eq!(specificity("*"), vec![(0, 0, 0)]);
+  assert_eq!(specificity("li"), vec![(0, 0, 1)]);
+  assert_eq!(specificity("ul li"), vec![(0, 0, 2)]);
+  assert_eq!(specificity("ul ol+li"), vec![(0, 0, 3)]);
+  assert_eq!(specificity("h1 + *[rel=up]"), vec![(0, 1, 1)]);
+  assert_eq!(specificity("ul ol li.red"), vec![(0, 1, 3)]);
+  assert_eq!(specificity("li.red.level"), vec![(0, 2, 1)]);
+  assert_eq!(specificity("#x34y"), vec![(1, 0, 0)]);
+  assert_eq!(
+    specificity("a:hover, #s12:not(foo)"),
+    vec![(0, 1, 1), (1, 0, 1)]
+  );
+}
+
+#[test]
+fn uses_most_specific_argument() {
+  assert_eq!(specificity(":is(em, #foo)"), vec![(1, 0, 0)]);
+  assert_eq!(specificity(".qux:where(em, #foo#bar#baz)"), vec![(0, 1, 0)]);
+  assert_eq!(specificity(":not(em, strong#foo)"), vec![(1, 0, 1)]);
+  assert_eq!(specificity("a:has(> img.icon)"), vec![(0, 1, 2)]);
+  assert_eq!(
+    specificity(":nth-child(2n+1 of li.important)"),
+    vec![(0, 2, 1)]
+  );
+  assert_eq!(specificity(":nth-child(2n+1)"), vec![(0, 1, 0)]);
+}
+
+#[test]
+fn counts_pseudo_elements() {
+  assert_eq!(specificity("p::first-line"), vec![(0, 0, 2)]);
+  assert_eq!(specificity("p:before"), vec![(0, 0, 2)]);
+  assert_eq!(specificity("::slotted(.a)"), vec![(0, 1, 1)]);
+}
+
+#[test]
+fn orders_by_components() {
+  let low = parse(".a.b.c.d").unwrap().selectors[0].specificity();
+  let high = parse("#a").unwrap().selectors[0].specificity();
+  assert!(low < high);
+  assert_eq!(high.to_string(), "(1, 0, 0)");
+  assert_eq!(
+    parse("a, .b, #c").unwrap().max_specificity(),
+    Specificity::new(1, 0, 0)
+  );
+}
+
+#[test]
+fn strips_pseudo_classes_of_plugins() {
+  let selector = &parse(":global(.a) .b::v-deep(.c)").unwrap().selectors[0];
+  assert_eq!(selector.specificity(), Specificity::new(0, 4, 1));
+  assert_eq!(
+    selector.specificity_without(&["global", "v-deep"]),
+    Specificity::new(0, 3, 0)
+  );
+}
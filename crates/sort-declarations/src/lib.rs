@@ -0,0 +1,5 @@
+mod order;
+mod plugin;
+
+pub use crate::order::Order;
+pub use crate::plugin::{Options, SortDeclarations};
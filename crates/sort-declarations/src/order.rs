@@ -0,0 +1,323 @@
+use serde::Deserialize;
+
+/// The order declarations are sorted in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Order {
+  Alphabetical,
+  /// Positioning, then display and box model, typography, visual and
+  /// animation properties.
+  #[default]
+  Grouped,
+  /// The properties in this order.
+  List(Vec<String>),
+}
+
+/// The properties of [`Order::Grouped`], by group.
+const GROUPED: &[&str] = &[
+  // Positioning.
+  "position",
+  "inset",
+  "inset-block",
+  "inset-inline",
+  "top",
+  "right",
+  "bottom",
+  "left",
+  "z-index",
+  "float",
+  "clear",
+  // Display and box model.
+  "display",
+  "flex",
+  "flex-flow",
+  "flex-direction",
+  "flex-wrap",
+  "flex-grow",
+  "flex-shrink",
+  "flex-basis",
+  "grid",
+  "grid-area",
+  "grid-template",
+  "grid-template-areas",
+  "grid-template-rows",
+  "grid-template-columns",
+  "grid-row",
+  "grid-row-start",
+  "grid-row-end",
+  "grid-column",
+  "grid-column-start",
+  "grid-column-end",
+  "grid-auto-rows",
+  "grid-auto-columns",
+  "grid-auto-flow",
+  "gap",
+  "row-gap",
+  "column-gap",
+  "place-content",
+  "place-items",
+  "place-self",
+  "align-content",
+  "align-items",
+  "align-self",
+  "justify-content",
+  "justify-items",
+  "justify-self",
+  "order",
+  "box-sizing",
+  "width",
+  "min-width",
+  "max-width",
+  "height",
+  "min-height",
+  "max-height",
+  "inline-size",
+  "block-size",
+  "aspect-ratio",
+  "margin",
+  "margin-top",
+  "margin-right",
+  "margin-bottom",
+  "margin-left",
+  "margin-block",
+  "margin-inline",
+  "padding",
+  "padding-top",
+  "padding-right",
+  "padding-bottom",
+  "padding-left",
+  "padding-block",
+  "padding-inline",
+  "overflow",
+  "overflow-x",
+  "overflow-y",
+  "visibility",
+  // Typography.
+  "font",
+  "font-family",
+  "font-size",
+  "font-style",
+  "font-weight",
+  "font-variant",
+  "line-height",
+  "letter-spacing",
+  "word-spacing",
+  "color",
+  "text-align",
+  "text-decoration",
+  "text-indent",
+  "text-overflow",
+  "text-transform",
+  "text-shadow",
+  "white-space",
+  "word-break",
+  "overflow-wrap",
+  "vertical-align",
+  "list-style",
+  // Visual.
+  "background",
+  "background-color",
+  "background-image",
+  "background-position",
+  "background-size",
+  "background-repeat",
+  "border",
+  "border-color",
+  "border-style",
+  "border-width",
+  "border-top",
+  "border-right",
+  "border-bottom",
+  "border-left",
+  "border-radius",
+  "outline",
+  "outline-offset",
+  "box-shadow",
+  "opacity",
+  "filter",
+  "cursor",
+  "pointer-events",
+  // Animation.
+  "transform",
+  "transform-origin",
+  "transition",
+  "animation",
+  "will-change",
+];
+
+/// The property without its vendor prefix, in lowercase.
+pub(crate) fn unprefixed(prop: &str) -> String {
+  let prop = prop.to_ascii_lowercase();
+  match prop.strip_prefix('-').filter(|rest| !rest.starts_with('-')) {
+    Some(rest) => rest
+      .split_once('-')
+      .map_or(prop.clone(), |(_, name)| name.to_string()),
+    None => prop,
+  }
+}
+
+impl Order {
+  /// The sort key of a property, with the unlisted properties after the
+  /// listed ones, alphabetically.
+  pub(crate) fn key(&self, prop: &str) -> (usize, String) {
+    let name = unprefixed(prop);
+    let rank = match self {
+      Order::Alphabetical => Some(0),
+      Order::Grouped => GROUPED.iter().position(|listed| *listed == name),
+      Order::List(list) => list
+        .iter()
+        .position(|listed| listed.eq_ignore_ascii_case(&name)),
+    };
+    let len = match self {
+      Order::Alphabetical => 1,
+      Order::Grouped => GROUPED.len(),
+      Order::List(list) => list.len(),
+    };
+    (rank.unwrap_or(len), name)
+  }
+}
+
+/// The shorthands whose longhands start with their first word, like
+/// `border-color` for `border-top-color`.
+const SHORTHANDS: &[&str] = &[
+  "animation",
+  "background",
+  "border",
+  "border-block",
+  "border-bottom",
+  "border-color",
+  "border-image",
+  "border-inline",
+  "border-left",
+  "border-radius",
+  "border-right",
+  "border-style",
+  "border-top",
+  "border-width",
+  "column-rule",
+  "flex",
+  "flex-flow",
+  "font",
+  "font-variant",
+  "grid",
+  "grid-area",
+  "grid-column",
+  "grid-gap",
+  "grid-row",
+  "grid-template",
+  "inset",
+  "list-style",
+  "margin",
+  "mask",
+  "offset",
+  "outline",
+  "overflow",
+  "padding",
+  "scroll-margin",
+  "scroll-padding",
+  "text-decoration",
+  "text-emphasis",
+  "transition",
+];
+
+/// The longhands of shorthands that do not start with their first word.
+const LONGHANDS: &[(&str, &[&str])] = &[
+  ("columns", &["column-count", "column-width"]),
+  ("font", &["line-height"]),
+  (
+    "gap",
+    &[
+      "row-gap",
+      "column-gap",
+      "grid-gap",
+      "grid-row-gap",
+      "grid-column-gap",
+    ],
+  ),
+  ("grid-gap", &["row-gap", "column-gap", "gap"]),
+  ("inset", &["top", "right", "bottom", "left"]),
+  ("place-content", &["align-content", "justify-content"]),
+  ("place-items", &["align-items", "justify-items"]),
+  ("place-self", &["align-self", "justify-self"]),
+];
+
+/// The property with its sides or axis replaced by `*`, and `true` if it is
+/// a logical property: `margin-left` and `margin-inline-start` are both
+/// `margin-*`, `left` and `inset-inline-start` both `*`, and `width` and
+/// `inline-size` both `size`. `None` for the properties of no side or axis.
+fn sided(prop: &str) -> Option<(String, bool)> {
+  if prop.starts_with("--") {
+    return None;
+  }
+  let (prefix, size) = match prop
+    .strip_prefix("min-")
+    .or_else(|| prop.strip_prefix("max-"))
+  {
+    Some(rest) => (&prop[..4], rest),
+    None => ("", prop),
+  };
+  match size {
+    "width" | "height" => return Some((format!("{}size", prefix), false)),
+    "inline-size" | "block-size" => return Some((format!("{}size", prefix), true)),
+    _ => {}
+  }
+
+  let words: Vec<&str> = prop.split('-').collect();
+  let mut parts = vec![];
+  let (mut found, mut logical) = (false, false);
+  let mut i = 0;
+  while i < words.len() {
+    let side = match words[i] {
+      "top" | "right" | "bottom" | "left" => true,
+      "inline" | "block" => {
+        logical = true;
+        if matches!(words.get(i + 1), Some(&"start" | &"end")) {
+          i += 1;
+        }
+        true
+      }
+      // the corners of `border-start-end-radius`
+      "start" | "end" if words[0] == "border" => {
+        logical = true;
+        true
+      }
+      _ => false,
+    };
+    match side {
+      true if !found => parts.push("*"),
+      true => {}
+      false => parts.push(words[i]),
+    }
+    found |= side;
+    i += 1;
+  }
+  if !found {
+    return None;
+  }
+  if parts[0] == "inset" {
+    parts.remove(0);
+  }
+  Some((parts.join("-"), logical))
+}
+
+/// Returns `true` if the order of two declarations matters for the cascade:
+/// declarations of the same property, with or without a vendor prefix,
+/// shorthands and their longhands, `all` with any property, and logical
+/// properties with the physical ones they may set.
+pub(crate) fn conflicts(a: &str, b: &str) -> bool {
+  let (a, b) = (unprefixed(a), unprefixed(b));
+  let first = |prop: &str| prop.split('-').next().unwrap_or_default().to_string();
+  let shorthand = |shorthand: &str, longhand: &str| {
+    (SHORTHANDS.contains(&shorthand) && first(shorthand) == first(longhand))
+      || LONGHANDS
+        .iter()
+        .any(|(name, longhands)| *name == shorthand && longhands.contains(&longhand))
+  };
+  let logical = match (sided(&a), sided(&b)) {
+    (Some((a, a_logical)), Some((b, b_logical))) if a_logical || b_logical => {
+      a == b || a.starts_with(&format!("{}-", b)) || b.starts_with(&format!("{}-", a))
+    }
+    _ => false,
+  };
+  a == b || a == "all" || b == "all" || shorthand(&a, &b) || shorthand(&b, &a) || logical
+}
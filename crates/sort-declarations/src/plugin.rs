@@ -0,0 +1,116 @@
+use crate::order::{conflicts, Order};
+use node::tree::replace_children;
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+  pub order: Order,
+}
+
+/// Sorts the declarations of rules in an [`Order`].
+///
+/// Comments move with the declaration that follows them, and declarations
+/// are never moved across nested rules or at-rules. Custom properties come
+/// first, in their own order, and declarations whose order matters for the
+/// cascade, like `margin` and `margin-top` or `display: -webkit-box` and
+/// `display: flex`, keep it.
+#[derive(Debug, Clone, Default)]
+pub struct SortDeclarations {
+  pub options: Options,
+}
+
+/// A declaration with the comments before it.
+struct Item<'a> {
+  nodes: Vec<Rc<RefCell<Node<'a>>>>,
+  prop: String,
+  variable: bool,
+  key: (usize, String),
+}
+
+impl SortDeclarations {
+  /// Sorts the rules in `parent` and its descendants.
+  fn sort(&self, parent: &Rc<RefCell<Node>>) {
+    let children = match parent.borrow().as_shared().get_nodes() {
+      Some(children) => children,
+      None => return,
+    };
+    for child in &children {
+      self.sort(child);
+    }
+    if parent.borrow().as_rule().is_none() {
+      return;
+    }
+    let mut nodes = Vec::with_capacity(children.len());
+    let mut items = Vec::new();
+    let mut comments = Vec::new();
+    for child in children {
+      let item = match &*child.borrow() {
+        Node::Decl(decl) => Some((decl.prop.clone(), decl.variable)),
+        Node::Comment(_) => None,
+        _ => {
+          // A nested rule or at-rule ends the declarations sorted together.
+          nodes.extend(self.order(std::mem::take(&mut items)));
+          nodes.append(&mut comments);
+          nodes.push(child.clone());
+          continue;
+        }
+      };
+      match item {
+        Some((prop, variable)) => {
+          comments.push(child);
+          let key = match variable {
+            true => (0, String::new()),
+            false => {
+              let (rank, name) = self.options.order.key(&prop);
+              (rank + 1, name)
+            }
+          };
+          items.push(Item {
+            nodes: std::mem::take(&mut comments),
+            prop,
+            variable,
+            key,
+          });
+        }
+        None => comments.push(child),
+      }
+    }
+    nodes.extend(self.order(items));
+    nodes.append(&mut comments);
+    replace_children(parent, nodes);
+  }
+
+  /// The nodes of the items, sorted by key among the items whose
+  /// conflicting items before them are placed already.
+  fn order<'a>(&self, mut items: Vec<Item<'a>>) -> Vec<Rc<RefCell<Node<'a>>>> {
+    let mut nodes = Vec::new();
+    while !items.is_empty() {
+      let next = (0..items.len())
+        .filter(|&i| {
+          items[..i].iter().all(|earlier| {
+            !(earlier.variable && items[i].variable || conflicts(&earlier.prop, &items[i].prop))
+          })
+        })
+        .min_by(|&a, &b| items[a].key.cmp(&items[b].key))
+        .unwrap_or(0);
+      nodes.extend(items.remove(next).nodes);
+    }
+    nodes
+  }
+}
+
+impl Plugin for SortDeclarations {
+  fn name(&self) -> &str {
+    "sort-declarations"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    self.sort(root);
+    Ok(())
+  }
+}
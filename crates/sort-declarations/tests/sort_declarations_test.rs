@@ -0,0 +1,141 @@
+use postcss::Processor;
+use sort_declarations::{Options, Order, SortDeclarations};
+
+fn sort(order: Order, css: &str) -> String {
+  Processor::run_one(
+    SortDeclarations {
+      options: Options { order },
+    },
+    css,
+  )
+  .unwrap()
+  .css
+}
+
+#[test]
+fn sorts_alphabetically() {
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { z-index: 1; color: red; -webkit-appearance: none; background: blue }"
+    ),
+    "a { -webkit-appearance: none; background: blue; color: red; z-index: 1 }"
+  );
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a {\n  width: 1px;\n\n  color: red;\n}"
+    ),
+    "a {\n  color: red;\n\n  width: 1px;\n}"
+  );
+}
+
+#[test]
+fn sorts_by_group_or_list() {
+  assert_eq!(
+    sort(Order::Grouped, "a { color: red; width: 1px; position: absolute; zoom: 1; accent-color: red; transition: none }"),
+    "a { position: absolute; width: 1px; color: red; transition: none; accent-color: red; zoom: 1 }"
+  );
+  let list = Order::List(vec!["width".to_string(), "color".to_string()]);
+  assert_eq!(
+    sort(list, "a { top: 0; color: red; width: 1px }"),
+    "a { width: 1px; color: red; top: 0 }"
+  );
+}
+
+#[test]
+fn keeps_comments_with_their_declaration() {
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a {\n  /* b */\n  width: 1px;\n  /* a */\n  color: red;\n  /* end */\n}"
+    ),
+    "a {\n  /* a */\n  color: red;\n  /* b */\n  width: 1px;\n  /* end */\n}"
+  );
+}
+
+#[test]
+fn keeps_the_cascade() {
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { --z: 1; width: 1px; --a: 2; color: red }"
+    ),
+    "a { --z: 1; --a: 2; color: red; width: 1px }"
+  );
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { margin-top: 1px; margin: 0; color: red }"
+    ),
+    "a { color: red; margin-top: 1px; margin: 0 }"
+  );
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { display: flex; color: red; display: -webkit-box }"
+    ),
+    "a { color: red; display: flex; display: -webkit-box }"
+  );
+  assert_eq!(
+    sort(
+      Order::Grouped,
+      "a { line-height: 2; font: 12px serif; top: 0; inset: 0 }"
+    ),
+    "a { top: 0; inset: 0; line-height: 2; font: 12px serif }"
+  );
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { width: 1px; all: unset; color: red }"
+    ),
+    "a { width: 1px; all: unset; color: red }"
+  );
+  // physical and logical properties of the same side or axis
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { width: 1px; inline-size: 2px; color: red }"
+    ),
+    "a { color: red; width: 1px; inline-size: 2px }"
+  );
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { margin-left: 1px; margin-inline-start: 2px; color: red }"
+    ),
+    "a { color: red; margin-left: 1px; margin-inline-start: 2px }"
+  );
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { left: 0; inset-inline-start: 1px; color: red }"
+    ),
+    "a { color: red; left: 0; inset-inline-start: 1px }"
+  );
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { margin-right: 1px; margin-left: 2px; border-top-width: 0; border-block: none }"
+    ),
+    "a { border-top-width: 0; border-block: none; margin-left: 2px; margin-right: 1px }"
+  );
+}
+
+#[test]
+fn never_sorts_across_nested_rules() {
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "a { width: 1px; color: red; & b { z-index: 1; top: 0 } top: 0; bottom: 0 }"
+    ),
+    "a { color: red; width: 1px; & b { top: 0; z-index: 1 } bottom: 0; top: 0 }"
+  );
+  assert_eq!(
+    sort(
+      Order::Alphabetical,
+      "@media print { a { width: 1px; color: red } }"
+    ),
+    "@media print { a { color: red; width: 1px } }"
+  );
+}
@@ -3,9 +3,17 @@ mod stringifier;
 
 use node::Node;
 
-use crate::stringifier::{Builder, Stringifier};
+pub use crate::stringifier::Builder;
+use crate::stringifier::Stringifier;
 
 pub fn stringify(node: &Node, builder: Builder) {
-  let str = Stringifier::new(builder);
-  str.stringify(node, false);
+  let mut str = Stringifier::new(builder);
+  str.stringify(node, &[], false);
+}
+
+/// Stringifies the node into a `String`.
+pub fn to_string(node: &Node) -> String {
+  let mut result = String::new();
+  stringify(node, &mut |str, _, _| result.push_str(str));
+  result
 }
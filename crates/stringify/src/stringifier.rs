@@ -1,211 +1,268 @@
-#![allow(dead_code)]
-
 use crate::get_raw_value;
 use node::Node;
-use std::hint::unreachable_unchecked;
-use std::rc::Rc;
+use std::collections::HashMap;
+
+pub type Builder<'b> = &'b mut dyn FnMut(&str, Option<&Node>, Option<&str>);
 
-pub type Builder = fn(&str, Option<&Node>, Option<&str>);
+pub(crate) struct Stringifier<'b> {
+  pub builder: Builder<'b>,
 
-pub(crate) struct Stringifier {
-  pub builder: Builder,
+  /// Raws detected from other nodes of the stringified tree, by detect name.
+  raw_cache: HashMap<&'static str, String>,
+  semicolon_cache: Option<bool>,
 }
 
-impl Stringifier {
-  pub fn new(builder: Builder) -> Stringifier {
-    Stringifier { builder }
+impl<'b> Stringifier<'b> {
+  pub fn new(builder: Builder<'b>) -> Stringifier<'b> {
+    Stringifier {
+      builder,
+      raw_cache: HashMap::new(),
+      semicolon_cache: None,
+    }
   }
 
-  pub fn stringify(&self, node: &Node, semicolon: bool) {
+  /// Stringifies `node`, `parents` are its ancestors with the root first.
+  pub fn stringify<'a>(&mut self, node: &Node<'a>, parents: &[&Node<'a>], semicolon: bool) {
     match node {
-      Node::Document(_) => {
-        self.body(node);
-      }
-
+      Node::Document(_) => self.body(node, parents),
       Node::Root(root) => {
-        self.body(node);
+        self.body(node, parents);
         if let Some(after) = &root.raws.after {
-          (self.builder)(after, None, None);
+          if !after.is_empty() {
+            (self.builder)(after, None, None);
+          }
         }
       }
-
       Node::Comment(comment) => {
-        let left: &str = &comment
-          .raws
-          .left
-          .clone()
-          .unwrap_or_else(|| self.detect_str(node, "left", "commentLeft").into());
-        let right: &str = &comment
-          .raws
-          .right
-          .clone()
-          .unwrap_or_else(|| self.detect_str(node, "right", "commentRight").into());
-        (self.builder)(
-          &("/*".to_string() + left + &comment.text + right + "*/"),
-          Some(node),
-          None,
-        );
+        let left = self.raw(node, parents, "left", "commentLeft");
+        let right = self.raw(node, parents, "right", "commentRight");
+        let string = format!("/*{}{}{}*/", left, comment.text, right);
+        (self.builder)(&string, Some(node), None);
       }
-
       Node::Decl(decl) => {
-        let between: &str = &decl
-          .raws
-          .between
-          .clone()
-          .unwrap_or_else(|| self.detect_str(node, "between", "colon").into());
-        let value = get_raw_value!(decl, value);
+        let between = self.raw(node, parents, "between", "colon");
         let mut string = String::with_capacity(32);
-
         string.push_str(&decl.prop);
-        string.push_str(between);
-        string.push_str(value);
-
+        string.push_str(&between);
+        string.push_str(get_raw_value!(decl, value));
         if decl.important {
           match &decl.raws.important {
             Some(important) => string.push_str(important),
             None => string.push_str(" !important"),
           }
         }
-
         if semicolon {
           string.push(';');
         }
-
         (self.builder)(&string, Some(node), None);
       }
-
       Node::Rule(rule) => {
-        self.block(node, get_raw_value!(rule, selector));
-        if rule.raws.own_semicolon.unwrap_or(false) {
-          // (self.builder)(rule.raws.own_semicolon, Some(*node), Some("end".into()));
-          (self.builder)("", Some(node), Some("end"));
+        self.block(node, parents, get_raw_value!(rule, selector));
+        if let Some(own_semicolon) = &rule.raws.own_semicolon {
+          if !own_semicolon.is_empty() {
+            (self.builder)(own_semicolon, Some(node), Some("end"));
+          }
         }
       }
-
       Node::AtRule(at_rule) => {
         let mut name = String::with_capacity(32);
         name.push('@');
         name.push_str(&at_rule.name);
-        let params = get_raw_value!(at_rule, params);
-        match &at_rule.raws.after_name {
-          Some(after_name) => {
-            name.push_str(after_name);
-          }
-          None => {
-            if !params.is_empty() {
-              name.push(' ');
-            }
-          }
+        let params: &str = if at_rule.params.is_empty() {
+          ""
+        } else {
+          get_raw_value!(at_rule, params)
         };
-
+        match &at_rule.raws.after_name {
+          Some(after_name) => name.push_str(after_name),
+          None if !params.is_empty() => name.push(' '),
+          None => {}
+        }
         name.push_str(params);
 
-        match at_rule.nodes {
-          Some(_) => self.block(node, &(name + params)),
-          None => {
-            if let Some(ref between) = at_rule.raws.between {
-              name.push_str(between);
-            }
-
-            if semicolon {
-              name.push(';');
-            }
-
-            (self.builder)(&name, Some(node), None);
+        if at_rule.nodes.is_some() {
+          self.block(node, parents, &name);
+        } else {
+          if let Some(between) = &at_rule.raws.between {
+            name.push_str(between);
+          }
+          if semicolon {
+            name.push(';');
           }
+          (self.builder)(&name, Some(node), None);
         }
-      } // _ => {
-        //   println!("Unknown AST node type. Maybe you need to change PostCSS stringifier.")
-        // }
+      }
     }
   }
 
-  pub(crate) fn body(&self, node: &Node) {
-    let nodes = node.as_shared().get_nodes().unwrap();
-    let last = nodes.iter().rfind(|&node| !(**node).borrow().is_comment());
-    let semicolon = match node {
-      Node::Document(_) => Some(false), // FIXME(justjavac):
-      Node::Root(node) => node.raws.semicolon,
-      Node::Rule(node) => node.raws.semicolon,
-      Node::AtRule(node) => node.raws.semicolon,
-      _ => unsafe { unreachable_unchecked() },
-    };
-
-    let semicolon = semicolon.unwrap_or_else(|| self.detect_bool(node, "semicolon", "semicolon"));
-
-    for child in &nodes {
-      let child_content = &*(**child).borrow();
-      let before = match child_content {
-        Node::Rule(node) => node.raws.before.clone(),
-        Node::AtRule(node) => node.raws.before.clone(),
-        Node::Comment(node) => node.raws.before.clone(),
-        Node::Decl(node) => node.raws.before.clone(),
-        _ => unsafe { unreachable_unchecked() },
-      };
-
-      let before =
-        before.unwrap_or_else(|| self.detect_str(child_content, "before", "before").into());
+  fn body<'a>(&mut self, node: &Node<'a>, parents: &[&Node<'a>]) {
+    let nodes = node.as_shared().get_nodes().unwrap_or_default();
+    let mut last = nodes.len().saturating_sub(1);
+    while last > 0 && nodes[last].borrow().is_comment() {
+      last -= 1;
+    }
+    let semicolon = self.raw_semicolon(node, parents);
 
+    let mut children_parents = parents.to_vec();
+    children_parents.push(node);
+    for (i, child) in nodes.iter().enumerate() {
+      let child = child.borrow();
+      let before = self.raw(&child, &children_parents, "before", "before");
       if !before.is_empty() {
         (self.builder)(&before, None, None);
       }
-      self.stringify(
-        child_content,
-        last.is_none() || !Rc::ptr_eq(last.unwrap(), child) || semicolon,
-      );
+      self.stringify(&child, &children_parents, last != i || semicolon);
     }
   }
 
-  pub(crate) fn block(&self, node: &Node, start: &str) {
-    let between = match node {
-      Node::Rule(node) => node.raws.between.clone(),
-      Node::AtRule(node) => node.raws.between.clone(),
-      _ => unsafe { unreachable_unchecked() },
-    };
-    let between = between.unwrap_or_else(|| self.detect_str(node, "between", "beforeOpen").into());
-
+  fn block<'a>(&mut self, node: &Node<'a>, parents: &[&Node<'a>], start: &str) {
+    let between = self.raw(node, parents, "between", "beforeOpen");
     (self.builder)(
-      &(start.to_string() + &between + "{"),
+      &format!("{}{}{{", start, between),
       Some(node),
       Some("start"),
     );
 
-    let after = match node {
-      Node::Rule(node) => node.raws.between.clone(),
-      Node::AtRule(node) => node.raws.between.clone(),
-      _ => unsafe { unreachable_unchecked() },
+    let has_nodes = matches!(node.as_shared().get_nodes(), Some(nodes) if !nodes.is_empty());
+    let after = if has_nodes {
+      self.body(node, parents);
+      self.raw(node, parents, "after", "after")
+    } else {
+      self.raw(node, parents, "after", "emptyBody")
     };
-
-    let after = match node.as_shared().get_nodes() {
-      Some(_) => {
-        self.body(node);
-        after.unwrap_or_else(|| self.detect_str(node, "after", "after").into())
-      }
-      None => after.unwrap_or_else(|| self.detect_str(node, "after", "emptyBody").into()),
-    };
-
     if !after.is_empty() {
       (self.builder)(&after, None, None);
     }
     (self.builder)("}", Some(node), Some("end"));
   }
 
-  pub(crate) fn detect_str(&self, _node: &Node, _own: &str, _detect: &str) -> &str {
-    todo!()
+  /// Returns the `own` raw of the node, or detects it from other nodes of the
+  /// tree when it is not set, falling back to the default for `detect`.
+  fn raw<'a>(
+    &mut self,
+    node: &Node<'a>,
+    parents: &[&Node<'a>],
+    own: &str,
+    detect: &'static str,
+  ) -> String {
+    if let Some(value) = own_raw(node, own) {
+      return value;
+    }
+
+    let parent = parents.last();
+    if detect == "before" {
+      match parent {
+        // Hack for first rule in CSS
+        None => return "".to_string(),
+        Some(Node::Root(_)) if is_first_child(parent.unwrap(), node) => return "".to_string(),
+        // `root` nodes in `document` should use only their own raws
+        Some(Node::Document(_)) => return "".to_string(),
+        _ => {}
+      }
+    }
+    if parent.is_none() {
+      return default_raw(detect).to_string();
+    }
+
+    if let Some(value) = self.raw_cache.get(detect) {
+      return value.clone();
+    }
+    if detect == "before" || detect == "after" {
+      return self.before_after(node, parents, detect);
+    }
+
+    let root = parents[0];
+    let value = match detect {
+      "emptyBody" => raw_empty_body(root),
+      "indent" => raw_indent(root),
+      "beforeComment" => match raw_before_comment(root) {
+        Some(value) => Some(value),
+        None => Some(self.raw(node, parents, "", "beforeDecl")),
+      },
+      "beforeDecl" => match raw_before_decl(root) {
+        Some(value) => Some(value),
+        None => Some(self.raw(node, parents, "", "beforeRule")),
+      },
+      "beforeRule" => raw_before_rule(root),
+      "beforeClose" => raw_before_close(root),
+      "beforeOpen" => raw_before_open(root),
+      "colon" => raw_colon(root),
+      _ => {
+        let mut value = None;
+        walk(root, &mut |child, _| {
+          value = own_raw(child, own);
+          value.is_none()
+        });
+        value
+      }
+    };
+    let value = value.unwrap_or_else(|| default_raw(detect).to_string());
+    self.raw_cache.insert(detect, value.clone());
+    value
   }
 
-  pub(crate) fn detect_bool(&self, _node: &Node, _own: &str, _detect: &str) -> bool {
-    todo!()
+  fn raw_semicolon<'a>(&mut self, node: &Node<'a>, parents: &[&Node<'a>]) -> bool {
+    let own = match node {
+      Node::Root(root) => root.raws.semicolon,
+      Node::Rule(rule) => rule.raws.semicolon,
+      Node::AtRule(at_rule) => at_rule.raws.semicolon,
+      _ => None,
+    };
+    if let Some(semicolon) = own {
+      return semicolon;
+    }
+    if parents.is_empty() {
+      return false;
+    }
+    if let Some(semicolon) = self.semicolon_cache {
+      return semicolon;
+    }
+
+    let root = parents[0];
+    let mut value = false;
+    walk(root, &mut |child, _| {
+      let semicolon = match child {
+        Node::Rule(rule) => rule.raws.semicolon,
+        Node::AtRule(at_rule) => at_rule.raws.semicolon,
+        _ => None,
+      };
+      let last_is_decl = matches!(
+        child.as_shared().get_nodes(),
+        Some(nodes) if matches!(nodes.last(), Some(last) if last.borrow().is_decl())
+      );
+      match semicolon {
+        Some(semicolon) if last_is_decl => {
+          value = semicolon;
+          false
+        }
+        _ => true,
+      }
+    });
+    self.semicolon_cache = Some(value);
+    value
   }
 
-  pub(crate) fn before_after(&self, _node: &Node, _detect: &str) -> &str {
-    todo!()
+  fn before_after<'a>(&mut self, node: &Node<'a>, parents: &[&Node<'a>], detect: &str) -> String {
+    let mut value = match node {
+      Node::Decl(_) => self.raw(node, parents, "", "beforeDecl"),
+      Node::Comment(_) => self.raw(node, parents, "", "beforeComment"),
+      _ if detect == "before" => self.raw(node, parents, "", "beforeRule"),
+      _ => self.raw(node, parents, "", "beforeClose"),
+    };
+
+    let depth = parents.iter().filter(|parent| !parent.is_root()).count();
+    if value.contains('\n') {
+      let indent = self.raw(node, parents, "", "indent");
+      for _ in 0..depth {
+        value.push_str(&indent);
+      }
+    }
+    value
   }
 }
 
-#[inline]
-fn get_default_raw(s: &str) -> &str {
-  match s {
+fn default_raw(detect: &str) -> &'static str {
+  match detect {
     "colon" => ": ",
     "indent" => "    ",
     "beforeDecl" => "\n",
@@ -217,7 +274,172 @@ fn get_default_raw(s: &str) -> &str {
     "emptyBody" => "",
     "commentLeft" => " ",
     "commentRight" => " ",
-    "semicolon" => ";", // false
-    _ => "\0",
+    _ => "",
+  }
+}
+
+/// The string raw named `own` of the node, if it is set.
+fn own_raw<'a>(node: &Node<'a>, own: &str) -> Option<String> {
+  match (node, own) {
+    (Node::Root(root), "after") => root.raws.after.clone(),
+    (Node::AtRule(at_rule), "before") => at_rule.raws.before.clone(),
+    (Node::AtRule(at_rule), "after") => at_rule.raws.after.clone(),
+    (Node::AtRule(at_rule), "between") => at_rule.raws.between.clone(),
+    (Node::AtRule(at_rule), "afterName") => at_rule.raws.after_name.clone(),
+    (Node::Rule(rule), "before") => rule.raws.before.clone(),
+    (Node::Rule(rule), "after") => rule.raws.after.clone(),
+    (Node::Rule(rule), "between") => rule.raws.between.clone(),
+    (Node::Decl(decl), "before") => decl.raws.before.clone(),
+    (Node::Decl(decl), "between") => decl.raws.between.clone(),
+    (Node::Comment(comment), "before") => comment.raws.before.clone(),
+    (Node::Comment(comment), "left") => comment.raws.left.clone(),
+    (Node::Comment(comment), "right") => comment.raws.right.clone(),
+    _ => None,
+  }
+}
+
+fn is_first_child<'a>(parent: &Node<'a>, node: &Node<'a>) -> bool {
+  match parent.as_shared().get_nodes() {
+    Some(nodes) => match nodes.first() {
+      Some(first) => std::ptr::eq(&*first.borrow(), node),
+      None => false,
+    },
+    None => false,
+  }
+}
+
+/// Visits the descendants of `node` depth first with their parent, until the
+/// callback returns `false`.
+fn walk<'a>(node: &Node<'a>, callback: &mut dyn FnMut(&Node<'a>, &Node<'a>) -> bool) -> bool {
+  if let Some(nodes) = node.as_shared().get_nodes() {
+    for child in nodes.iter() {
+      let child = child.borrow();
+      if !callback(&child, node) || !walk(&child, callback) {
+        return false;
+      }
+    }
+  }
+  true
+}
+
+fn strip_last_line(value: String) -> String {
+  if value.contains('\n') {
+    value.trim_end_matches(|ch| ch != '\n').to_string()
+  } else {
+    value
+  }
+}
+
+fn remove_non_spaces(value: &str) -> String {
+  value.chars().filter(|ch| ch.is_whitespace()).collect()
+}
+
+fn is_container<'a>(node: &Node<'a>) -> bool {
+  node.as_shared().get_nodes().is_some()
+}
+
+fn raw_empty_body<'a>(root: &Node<'a>) -> Option<String> {
+  let mut value = None;
+  walk(root, &mut |child, _| {
+    if matches!(child.as_shared().get_nodes(), Some(nodes) if nodes.is_empty()) {
+      value = own_raw(child, "after");
+    }
+    value.is_none()
+  });
+  value
+}
+
+fn raw_indent<'a>(root: &Node<'a>) -> Option<String> {
+  let mut value = None;
+  walk(root, &mut |child, parent| {
+    let nested = !std::ptr::eq(parent, root) && is_direct_child(root, parent);
+    if nested {
+      if let Some(before) = own_raw(child, "before") {
+        let last_line = before.rsplit('\n').next().unwrap_or_default();
+        value = Some(remove_non_spaces(last_line));
+      }
+    }
+    value.is_none()
+  });
+  value
+}
+
+fn is_direct_child<'a>(parent: &Node<'a>, node: &Node<'a>) -> bool {
+  match parent.as_shared().get_nodes() {
+    Some(nodes) => nodes
+      .iter()
+      .any(|child| std::ptr::eq(&*child.borrow(), node)),
+    None => false,
   }
 }
+
+fn raw_before_comment<'a>(root: &Node<'a>) -> Option<String> {
+  let mut value = None;
+  walk(root, &mut |child, _| {
+    if child.is_comment() {
+      value = own_raw(child, "before").map(strip_last_line);
+    }
+    value.is_none()
+  });
+  value.map(|value| remove_non_spaces(&value))
+}
+
+fn raw_before_decl<'a>(root: &Node<'a>) -> Option<String> {
+  let mut value = None;
+  walk(root, &mut |child, _| {
+    if child.is_decl() {
+      value = own_raw(child, "before").map(strip_last_line);
+    }
+    value.is_none()
+  });
+  value.map(|value| remove_non_spaces(&value))
+}
+
+fn raw_before_rule<'a>(root: &Node<'a>) -> Option<String> {
+  let mut value = None;
+  walk(root, &mut |child, parent| {
+    if is_container(child) && (!std::ptr::eq(parent, root) || !is_first_child(root, child)) {
+      value = own_raw(child, "before").map(strip_last_line);
+    }
+    value.is_none()
+  });
+  value.map(|value| remove_non_spaces(&value))
+}
+
+fn raw_before_close<'a>(root: &Node<'a>) -> Option<String> {
+  let mut value = None;
+  walk(root, &mut |child, _| {
+    if matches!(child.as_shared().get_nodes(), Some(nodes) if !nodes.is_empty()) {
+      value = own_raw(child, "after").map(strip_last_line);
+    }
+    value.is_none()
+  });
+  value.map(|value| remove_non_spaces(&value))
+}
+
+fn raw_before_open<'a>(root: &Node<'a>) -> Option<String> {
+  let mut value = None;
+  walk(root, &mut |child, _| {
+    if !child.is_decl() {
+      value = own_raw(child, "between");
+    }
+    value.is_none()
+  });
+  value
+}
+
+fn raw_colon<'a>(root: &Node<'a>) -> Option<String> {
+  let mut value = None;
+  walk(root, &mut |child, _| {
+    if child.is_decl() {
+      value = own_raw(child, "between").map(|between| {
+        between
+          .chars()
+          .filter(|&ch| ch.is_whitespace() || ch == ':')
+          .collect()
+      });
+    }
+    value.is_none()
+  });
+  value
+}
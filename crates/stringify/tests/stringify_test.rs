@@ -0,0 +1,52 @@
+use parser::parse::parse;
+use stringify::to_string;
+
+fn roundtrip(css: &str) -> String {
+  let root = parse(css, None).unwrap();
+  let root = root.borrow();
+  to_string(&root)
+}
+
+#[test]
+fn stringifies_parsed_css_unchanged() {
+  let cases = [
+    "",
+    "a{}",
+    "a { color: red; }\n",
+    "a{color:red}b{}",
+    "/* c */\n@media screen { a { b: c } }\n",
+    "@import url(a.css);\n@charset \"utf-8\";",
+    "a { b: c !important; d: e ! important }",
+    "a { b: c/**/d; }",
+    ":root { --x: { a: b }; --y:  1px  ; }",
+    "a{};b{};",
+    "@font-face{font-family:x;src:url(x.woff)}",
+  ];
+  for css in cases {
+    assert_eq!(roundtrip(css), css);
+  }
+}
+
+#[test]
+fn stringifies_bootstrap_unchanged() {
+  let css = include_str!("../../../assets/bootstrap.css");
+  assert_eq!(roundtrip(css), css);
+}
+
+#[test]
+fn detects_missing_raws_from_siblings() {
+  let root = parse("a {\n  color: red;\n}\n", None).unwrap();
+  {
+    let root = root.borrow();
+    let rule = root.as_shared().get_nodes().unwrap()[0].clone();
+    let decl = rule.borrow().as_shared().get_nodes().unwrap()[0].clone();
+    let mut decl = decl.borrow().clone();
+    decl.as_decl_mut().unwrap().prop = "top".to_string();
+    decl.as_decl_mut().unwrap().raws = Default::default();
+    rule
+      .borrow_mut()
+      .push_child(std::rc::Rc::new(std::cell::RefCell::new(decl)));
+  }
+  let root = root.borrow();
+  assert_eq!(to_string(&root), "a {\n  color: red;\n  top: red;\n}\n");
+}
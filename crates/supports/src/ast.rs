@@ -0,0 +1,67 @@
+use std::fmt;
+
+/// The params of `@supports`, e.g. `(display: grid) and (not (gap: 1px))`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SupportsCondition {
+  /// `not (display: grid)`
+  Not(Box<SupportsCondition>),
+  /// `(display: grid) and (gap: 1px)`
+  And(Vec<SupportsCondition>),
+  /// `(display: grid) or (display: flex)`
+  Or(Vec<SupportsCondition>),
+  /// `(display: grid)`
+  Declaration {
+    /// The property name, lowercase unless it is a custom property.
+    property: String,
+    value: String,
+  },
+  /// `selector(:has(> img))`, the argument is kept verbatim.
+  Selector(String),
+  /// `font-tech(color-COLRv1)`
+  FontTech(Vec<String>),
+  /// `font-format(woff2)`
+  FontFormat(String),
+  /// A function or parenthesized block this parser does not understand, kept
+  /// verbatim, e.g. `(foo bar)` or `supports-foo(1)`.
+  GeneralEnclosed(String),
+}
+
+impl fmt::Display for SupportsCondition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SupportsCondition::Not(condition) => {
+        f.write_str("not ")?;
+        write_in_parens(f, condition)
+      }
+      SupportsCondition::And(conditions) => write_joined(f, conditions, " and "),
+      SupportsCondition::Or(conditions) => write_joined(f, conditions, " or "),
+      _ => write_in_parens(f, self),
+    }
+  }
+}
+
+fn write_joined(
+  f: &mut fmt::Formatter,
+  conditions: &[SupportsCondition],
+  separator: &str,
+) -> fmt::Result {
+  for (i, condition) in conditions.iter().enumerate() {
+    if i > 0 {
+      f.write_str(separator)?;
+    }
+    write_in_parens(f, condition)?;
+  }
+  Ok(())
+}
+
+/// Writes `<supports-in-parens>`.
+fn write_in_parens(f: &mut fmt::Formatter, condition: &SupportsCondition) -> fmt::Result {
+  match condition {
+    SupportsCondition::Declaration { property, value } => write!(f, "({}: {})", property, value),
+    SupportsCondition::Selector(selector) => write!(f, "selector({})", selector),
+    SupportsCondition::FontTech(techs) => write!(f, "font-tech({})", techs.join(", ")),
+    SupportsCondition::FontFormat(format) => write!(f, "font-format({})", format),
+    SupportsCondition::GeneralEnclosed(raw) => f.write_str(raw),
+    _ => write!(f, "({})", condition),
+  }
+}
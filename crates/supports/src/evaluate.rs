@@ -0,0 +1,109 @@
+use crate::ast::SupportsCondition;
+use media_query::MatchResult;
+use selector::{SelectorList, SimpleSelector};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// What the target browsers support.
+///
+/// Anything missing from these sets is considered unsupported, so a
+/// condition only evaluates to [`MatchResult::Unknown`] when it contains a
+/// `<general-enclosed>` block.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Support {
+  /// Supported property names, in lowercase. Custom properties are always
+  /// supported.
+  pub properties: HashSet<String>,
+
+  /// Supported keywords for some properties, e.g. `display` to `grid`.
+  ///
+  /// A declaration is supported if its property is, and either the property
+  /// has no entry here or the first word of the value is in its entry.
+  pub values: HashMap<String, HashSet<String>>,
+
+  /// Supported pseudo-classes and pseudo-elements, with their colons, e.g.
+  /// `:has` or `::backdrop`. Type, class, id and attribute selectors are
+  /// always supported.
+  pub selectors: HashSet<String>,
+
+  /// Supported font technologies for `font-tech()`, in lowercase.
+  pub font_techs: HashSet<String>,
+
+  /// Supported font formats for `font-format()`, in lowercase.
+  pub font_formats: HashSet<String>,
+}
+
+impl Support {
+  fn supports_declaration(&self, property: &str, value: &str) -> bool {
+    if property.starts_with("--") {
+      return true;
+    }
+    if !self.properties.contains(property) {
+      return false;
+    }
+    match self.values.get(property) {
+      Some(values) => {
+        let keyword = value
+          .split(|ch: char| ch.is_whitespace() || ch == '(' || ch == ',')
+          .next()
+          .unwrap_or_default();
+        values.contains(&keyword.to_ascii_lowercase())
+      }
+      None => true,
+    }
+  }
+
+  fn supports_selector(&self, input: &str) -> bool {
+    match selector::parse(input) {
+      Ok(list) => self.supports_selector_list(&list),
+      Err(_) => false,
+    }
+  }
+
+  fn supports_selector_list(&self, list: &SelectorList) -> bool {
+    let mut supported = true;
+    for selector in &list.selectors {
+      selector.walk(&mut |simple| {
+        let name = match simple {
+          SimpleSelector::PseudoClass(pseudo) => format!(":{}", pseudo.lower_name()),
+          SimpleSelector::PseudoElement(pseudo) => format!("::{}", pseudo.lower_name()),
+          _ => return,
+        };
+        supported &= self.selectors.contains(&name);
+      });
+    }
+    supported
+  }
+}
+
+impl SupportsCondition {
+  pub fn matches(&self, support: &Support) -> MatchResult {
+    match self {
+      SupportsCondition::Not(condition) => condition.matches(support).not(),
+      SupportsCondition::And(conditions) => conditions
+        .iter()
+        .fold(MatchResult::True, |result, condition| {
+          result.and(condition.matches(support))
+        }),
+      SupportsCondition::Or(conditions) => conditions
+        .iter()
+        .fold(MatchResult::False, |result, condition| {
+          result.or(condition.matches(support))
+        }),
+      SupportsCondition::Declaration { property, value } => {
+        support.supports_declaration(property, value).into()
+      }
+      SupportsCondition::Selector(selector) => support.supports_selector(selector).into(),
+      SupportsCondition::FontTech(techs) => techs
+        .iter()
+        .all(|tech| support.font_techs.contains(&tech.to_ascii_lowercase()))
+        .into(),
+      SupportsCondition::FontFormat(format) => support
+        .font_formats
+        .contains(&format.to_ascii_lowercase())
+        .into(),
+      SupportsCondition::GeneralEnclosed(_) => MatchResult::Unknown,
+    }
+  }
+}
@@ -0,0 +1,38 @@
+use crate::evaluate::Support;
+use media_query::MatchResult;
+use node::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Resolves every `@supports` at-rule of the tree for the target browsers.
+///
+/// The children of at-rules that are always true are inlined into their
+/// parent and at-rules that are always false are removed. At-rules with an
+/// unknown result or params that can not be parsed are left untouched.
+pub fn flatten(root: &Rc<RefCell<Node>>, support: &Support) {
+  let children = match root.borrow().as_shared().get_nodes() {
+    Some(children) => children,
+    None => return,
+  };
+
+  let mut nodes = Vec::with_capacity(children.len());
+  for child in children {
+    flatten(&child, support);
+
+    let result = match &*child.borrow() {
+      Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("supports") => {
+        crate::parse(&at_rule.params).map(|condition| condition.matches(support))
+      }
+      _ => Ok(MatchResult::Unknown),
+    };
+    match result {
+      Ok(MatchResult::True) => nodes.extend(child.borrow_mut().take_children()),
+      Ok(MatchResult::False) => {}
+      _ => nodes.push(child),
+    }
+  }
+
+  if let Some(children) = root.borrow_mut().as_shared_mut().get_nodes_mut() {
+    *children = nodes;
+  }
+}
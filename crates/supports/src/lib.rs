@@ -0,0 +1,19 @@
+mod ast;
+mod evaluate;
+mod flatten;
+mod parser;
+mod plugin;
+
+pub use crate::ast::*;
+pub use crate::evaluate::Support;
+pub use crate::flatten::flatten;
+pub use crate::parser::ParseError;
+pub use crate::plugin::SupportsFlatten;
+pub use media_query::MatchResult;
+
+use crate::parser::Parser;
+
+/// Parses the params of `@supports` into a [`SupportsCondition`].
+pub fn parse(params: &str) -> Result<SupportsCondition, ParseError> {
+  Parser::new(params, 0).parse()
+}
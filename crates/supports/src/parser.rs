@@ -0,0 +1,234 @@
+use crate::ast::SupportsCondition;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+  pub reason: String,
+  /// Byte offset in the parsed params.
+  pub offset: usize,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} at {}", self.reason, self.offset)
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+pub(crate) struct Parser<'a> {
+  input: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+  /// Offset of `input` in the params, for error positions.
+  base: usize,
+}
+
+impl<'a> Parser<'a> {
+  pub(crate) fn new(input: &'a str, base: usize) -> Self {
+    Self {
+      input,
+      bytes: input.as_bytes(),
+      pos: 0,
+      base,
+    }
+  }
+
+  /// Parses the whole input as a `<supports-condition>`.
+  pub(crate) fn parse(&mut self) -> Result<SupportsCondition, ParseError> {
+    let condition = self.parse_condition()?;
+    self.skip_whitespace();
+    if self.pos < self.bytes.len() {
+      return Err(self.error("Unexpected token"));
+    }
+    Ok(condition)
+  }
+
+  fn parse_condition(&mut self) -> Result<SupportsCondition, ParseError> {
+    self.skip_whitespace();
+    if self.eat_keyword("not") {
+      return Ok(SupportsCondition::Not(Box::new(self.parse_in_parens()?)));
+    }
+    let first = self.parse_in_parens()?;
+    self.skip_whitespace();
+    let keyword = if self.at_keyword("and") {
+      "and"
+    } else if self.at_keyword("or") {
+      "or"
+    } else {
+      return Ok(first);
+    };
+    let mut conditions = vec![first];
+    while self.eat_keyword(keyword) {
+      conditions.push(self.parse_in_parens()?);
+      self.skip_whitespace();
+    }
+    Ok(if keyword == "and" {
+      SupportsCondition::And(conditions)
+    } else {
+      SupportsCondition::Or(conditions)
+    })
+  }
+
+  /// Parses `<supports-in-parens>` and the supports functions.
+  fn parse_in_parens(&mut self) -> Result<SupportsCondition, ParseError> {
+    self.skip_whitespace();
+    let start = self.pos;
+    if self.bytes.get(start) == Some(&b'(') {
+      let end = self.find_close(start)?;
+      self.pos = end + 1;
+      let inner = &self.input[start + 1..end];
+      if let Ok(condition) = Parser::new(inner, self.base + start + 1).parse() {
+        return Ok(condition);
+      }
+      if let Some(declaration) = parse_declaration(inner) {
+        return Ok(declaration);
+      }
+      return Ok(SupportsCondition::GeneralEnclosed(
+        self.input[start..=end].to_string(),
+      ));
+    }
+
+    let name_end = consume_ident(self.bytes, start);
+    if name_end > start && self.bytes.get(name_end) == Some(&b'(') {
+      let end = self.find_close(name_end)?;
+      self.pos = end + 1;
+      let argument = self.input[name_end + 1..end].trim();
+      let name = self.input[start..name_end].to_ascii_lowercase();
+      return Ok(match name.as_str() {
+        "selector" => SupportsCondition::Selector(argument.to_string()),
+        "font-tech" => SupportsCondition::FontTech(
+          argument
+            .split(',')
+            .map(|tech| tech.trim().to_string())
+            .collect(),
+        ),
+        "font-format" => SupportsCondition::FontFormat(argument.to_string()),
+        _ => SupportsCondition::GeneralEnclosed(self.input[start..=end].to_string()),
+      });
+    }
+
+    Err(self.error("Expected ("))
+  }
+
+  /// Returns the index of the `)` matching the `(` at `open`.
+  fn find_close(&self, open: usize) -> Result<usize, ParseError> {
+    let mut depth = 0;
+    let mut pos = open;
+    while pos < self.bytes.len() {
+      match self.bytes[pos] {
+        b'(' => depth += 1,
+        b')' => {
+          depth -= 1;
+          if depth == 0 {
+            return Ok(pos);
+          }
+        }
+        b'\\' => pos += 1,
+        quote @ (b'"' | b'\'') => {
+          pos += 1;
+          while pos < self.bytes.len() && self.bytes[pos] != quote {
+            if self.bytes[pos] == b'\\' {
+              pos += 1;
+            }
+            pos += 1;
+          }
+        }
+        b'/' if self.bytes.get(pos + 1) == Some(&b'*') => {
+          pos = match self.input[pos + 2..].find("*/") {
+            Some(end) => pos + end + 3,
+            None => self.bytes.len(),
+          };
+        }
+        _ => {}
+      }
+      pos += 1;
+    }
+    Err(ParseError {
+      reason: "Unclosed bracket".to_string(),
+      offset: self.base + open,
+    })
+  }
+
+  fn skip_whitespace(&mut self) {
+    while self.pos < self.bytes.len() {
+      match self.bytes[self.pos] {
+        b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' => self.pos += 1,
+        b'/' if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+          self.pos = match self.input[self.pos + 2..].find("*/") {
+            Some(end) => self.pos + end + 4,
+            None => self.bytes.len(),
+          };
+        }
+        _ => break,
+      }
+    }
+  }
+
+  /// Returns `true` if a keyword followed by whitespace is at the position.
+  fn at_keyword(&self, keyword: &str) -> bool {
+    let end = self.pos + keyword.len();
+    self
+      .input
+      .get(self.pos..end)
+      .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+      && matches!(
+        self.bytes.get(end),
+        Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' | b'/')
+      )
+  }
+
+  fn eat_keyword(&mut self, keyword: &str) -> bool {
+    if self.at_keyword(keyword) {
+      self.pos += keyword.len();
+      self.skip_whitespace();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn error(&self, reason: &str) -> ParseError {
+    ParseError {
+      reason: reason.to_string(),
+      offset: self.base + self.pos,
+    }
+  }
+}
+
+/// Parses `<declaration>` without the parentheses, e.g. `display: grid`.
+fn parse_declaration(input: &str) -> Option<SupportsCondition> {
+  let trimmed = input.trim_start();
+  let bytes = trimmed.as_bytes();
+  let end = consume_ident(bytes, 0);
+  if end == 0 {
+    return None;
+  }
+  let property = &trimmed[..end];
+  let rest = trimmed[end..].trim_start().strip_prefix(':')?;
+  let value = rest.trim();
+  let custom = property.starts_with("--");
+  if value.is_empty() && !custom {
+    return None;
+  }
+  Some(SupportsCondition::Declaration {
+    property: if custom {
+      property.to_string()
+    } else {
+      property.to_ascii_lowercase()
+    },
+    value: value.to_string(),
+  })
+}
+
+fn consume_ident(bytes: &[u8], mut pos: usize) -> usize {
+  while pos < bytes.len() {
+    match bytes[pos] {
+      b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => pos += 1,
+      b'\\' if pos + 1 < bytes.len() => pos += 2,
+      byte if byte >= 0x80 => pos += 1,
+      _ => break,
+    }
+  }
+  pos
+}
@@ -0,0 +1,22 @@
+use crate::evaluate::Support;
+use node::Node;
+use postcss::{CssSyntaxError, Plugin, ProcessResult};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Runs [`flatten`](crate::flatten) for a fixed set of supported features.
+#[derive(Debug, Clone, Default)]
+pub struct SupportsFlatten {
+  pub support: Support,
+}
+
+impl Plugin for SupportsFlatten {
+  fn name(&self) -> &str {
+    "supports-flatten"
+  }
+
+  fn run(&self, root: &Rc<RefCell<Node>>, _: &mut ProcessResult) -> Result<(), CssSyntaxError> {
+    crate::flatten(root, &self.support);
+    Ok(())
+  }
+}
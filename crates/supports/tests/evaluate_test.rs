@@ -0,0 +1,77 @@
+use parser::parse::parse as parse_css;
+use std::collections::HashSet;
+use stringify::to_string;
+use supports::*;
+
+fn set(items: &[&str]) -> HashSet<String> {
+  items.iter().map(|item| item.to_string()).collect()
+}
+
+fn support() -> Support {
+  let mut support = Support {
+    properties: set(&["display", "gap", "color"]),
+    selectors: set(&[":hover", ":is", "::before"]),
+    font_techs: set(&["variations"]),
+    font_formats: set(&["woff2"]),
+    ..Default::default()
+  };
+  support
+    .values
+    .insert("display".into(), set(&["block", "flex", "grid"]));
+  support
+}
+
+fn matches(params: &str) -> MatchResult {
+  parse(params).unwrap().matches(&support())
+}
+
+#[test]
+fn evaluates_declarations() {
+  assert_eq!(matches("(display: grid)"), MatchResult::True);
+  assert_eq!(matches("(display: contents)"), MatchResult::False);
+  assert_eq!(matches("(gap: 1rem)"), MatchResult::True);
+  assert_eq!(matches("(container-type: size)"), MatchResult::False);
+  assert_eq!(matches("(--any: thing)"), MatchResult::True);
+  assert_eq!(matches("not (display: grid)"), MatchResult::False);
+  assert_eq!(matches("(display: grid) and (zoom: 1)"), MatchResult::False);
+  assert_eq!(matches("(display: grid) or (zoom: 1)"), MatchResult::True);
+}
+
+#[test]
+fn evaluates_selectors_and_fonts() {
+  assert_eq!(matches("selector(a:hover > .b)"), MatchResult::True);
+  assert_eq!(
+    matches("selector(:is(a, b:hover)::before)"),
+    MatchResult::True
+  );
+  assert_eq!(matches("selector(:has(> img))"), MatchResult::False);
+  assert_eq!(matches("selector(a >>> b)"), MatchResult::False);
+  assert_eq!(matches("font-tech(variations)"), MatchResult::True);
+  assert_eq!(matches("font-tech(color-COLRv1)"), MatchResult::False);
+  assert_eq!(matches("font-format(WOFF2)"), MatchResult::True);
+}
+
+#[test]
+fn treats_general_enclosed_as_unknown() {
+  assert_eq!(matches("(foo bar)"), MatchResult::Unknown);
+  assert_eq!(matches("(foo bar) or (display: grid)"), MatchResult::True);
+  assert_eq!(
+    matches("(foo bar) and (display: grid)"),
+    MatchResult::Unknown
+  );
+}
+
+#[test]
+fn flattens_supports_blocks() {
+  let css = "@supports (display: grid) {\n  a { display: grid }\n}\n\
+             @supports not (display: grid) { b {} }\n\
+             @supports (foo bar) { c {} }\n\
+             @media print { @supports selector(:has(a)) { d {} } }";
+  let root = parse_css(css, None).unwrap();
+  flatten(&root, &support());
+  let root = root.borrow();
+  assert_eq!(
+    to_string(&root),
+    "a { display: grid }\n@supports (foo bar) { c {} }\n@media print { }"
+  );
+}
@@ -0,0 +1,88 @@
+use supports::*;
+
+fn declaration(property: &str, value: &str) -> SupportsCondition {
+  SupportsCondition::Declaration {
+    property: property.into(),
+    value: value.into(),
+  }
+}
+
+#[test]
+fn parses_declarations() {
+  assert_eq!(
+    parse("(display: grid)").unwrap(),
+    declaration("display", "grid")
+  );
+  assert_eq!(
+    parse("( DISPLAY :inline grid )").unwrap(),
+    declaration("display", "inline grid")
+  );
+  assert_eq!(parse("(--Foo: )").unwrap(), declaration("--Foo", ""));
+}
+
+#[test]
+fn parses_not_and_or() {
+  assert_eq!(
+    parse("not (display: grid)").unwrap(),
+    SupportsCondition::Not(Box::new(declaration("display", "grid")))
+  );
+  assert_eq!(
+    parse("(a: b) and (c: d) AND (e: f)").unwrap(),
+    SupportsCondition::And(vec![
+      declaration("a", "b"),
+      declaration("c", "d"),
+      declaration("e", "f"),
+    ])
+  );
+  assert_eq!(
+    parse("((a: b) or (c: d)) and (not (e: f))").unwrap(),
+    SupportsCondition::And(vec![
+      SupportsCondition::Or(vec![declaration("a", "b"), declaration("c", "d")]),
+      SupportsCondition::Not(Box::new(declaration("e", "f"))),
+    ])
+  );
+}
+
+#[test]
+fn parses_functions() {
+  assert_eq!(
+    parse("selector(:has(> img, a:hover))").unwrap(),
+    SupportsCondition::Selector(":has(> img, a:hover)".into())
+  );
+  assert_eq!(
+    parse("font-tech(color-COLRv1, variations)").unwrap(),
+    SupportsCondition::FontTech(vec!["color-COLRv1".into(), "variations".into()])
+  );
+  assert_eq!(
+    parse("font-format(woff2)").unwrap(),
+    SupportsCondition::FontFormat("woff2".into())
+  );
+  assert_eq!(
+    parse("(foo bar) or supports-foo(1)").unwrap(),
+    SupportsCondition::Or(vec![
+      SupportsCondition::GeneralEnclosed("(foo bar)".into()),
+      SupportsCondition::GeneralEnclosed("supports-foo(1)".into()),
+    ])
+  );
+}
+
+#[test]
+fn serializes_conditions() {
+  for params in [
+    "(display: grid)",
+    "not (display: grid)",
+    "(a: b) and ((c: d) or (e: f))",
+    "selector(a > b) or font-tech(incremental)",
+    "(background: url(\"a)b\"))",
+  ] {
+    assert_eq!(parse(params).unwrap().to_string(), params);
+  }
+}
+
+#[test]
+fn reports_errors() {
+  assert_eq!(parse("(a: b").unwrap_err().offset, 0);
+  assert!(parse("(a: b) and (c: d) or (e: f)").is_err());
+  assert!(parse("display: grid").is_err());
+  assert!(parse("").is_err());
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// The CSS parser throws this error for broken CSS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssSyntaxError {
+  /// Error message.
+  pub reason: String,
+  /// Absolute path to the broken file.
+  pub file: Option<String>,
+  /// Source code of the broken file.
+  pub source: Option<String>,
+  /// Plugin name, if error came from plugin.
+  pub plugin: Option<String>,
+  /// Source line of the error, starting from 1.
+  pub line: u32,
+  /// Source column of the error, starting from 1.
+  pub column: u32,
+}
+
+impl CssSyntaxError {
+  pub fn new(reason: &str, line: u32, column: u32) -> Self {
+    Self {
+      reason: reason.to_string(),
+      file: None,
+      source: None,
+      plugin: None,
+      line,
+      column,
+    }
+  }
+
+  /// Returns a few lines of CSS source around the error, with a `>` on the
+  /// broken line and a `^` under the broken column.
+  ///
+  /// ```text
+  ///   1 | a {
+  /// > 2 |   b c;
+  ///     |   ^
+  ///   3 | }
+  /// ```
+  pub fn show_source_code(&self) -> Option<String> {
+    let source = self.source.as_deref()?;
+    let lines: Vec<&str> = source.split('\n').collect();
+    let line = self.line as usize;
+    let start = line.saturating_sub(3);
+    let end = (line + 2).min(lines.len());
+    let max_width = end.to_string().len();
+    let frame: Vec<String> = (start..end)
+      .map(|index| {
+        let text = lines[index].trim_end_matches('\r');
+        let number = index + 1;
+        let gutter = format!(" {:>width$} | ", number, width = max_width);
+        if number == line {
+          let spacing: String = text
+            .chars()
+            .take(self.column.saturating_sub(1) as usize)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+          let marker = format!("  {:>width$} | ", "", width = max_width);
+          format!(">{}{}\n{}{}^", gutter, text, marker, spacing)
+        } else {
+          format!(" {}{}", gutter, text)
+        }
+      })
+      .collect();
+    Some(frame.join("\n"))
+  }
+}
+
+impl fmt::Display for CssSyntaxError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(plugin) = &self.plugin {
+      write!(f, "{}: ", plugin)?;
+    }
+    write!(
+      f,
+      "{}:{}:{}: {}",
+      self.file.as_deref().unwrap_or("<css input>"),
+      self.line,
+      self.column,
+      self.reason
+    )
+  }
+}
+
+impl std::error::Error for CssSyntaxError {}
@@ -1,5 +1,9 @@
+use crate::error::CssSyntaxError;
 use once_cell::sync::Lazy;
+use std::borrow::Cow;
 use std::fmt;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Debug)]
 pub struct FilePosition {
@@ -30,7 +34,7 @@ impl fmt::Display for FilePosition {
 static DEFAULT_INPUT: Lazy<Input> = Lazy::new(Input::default);
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct Input<'a> {
-  pub css: &'a str,
+  pub css: Cow<'a, str>,
   // map: PreviousMap,
   file: Option<String>,
   id: Option<String>,
@@ -55,23 +59,93 @@ impl<'a> fmt::Display for Input<'a> {
   }
 }
 
-#[derive(Debug)]
-pub struct ProcessOptions {}
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessOptions {
+  /// The path of the CSS source file.
+  pub from: Option<String>,
+  /// The path where the result will be written, used for source maps.
+  pub to: Option<String>,
+  /// Source map options, no map is generated if it is `None`.
+  pub map: Option<MapOptions>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapOptions {
+  /// Embeds the map into the CSS as a data URI, instead of returning it separately.
+  pub inline: bool,
+  /// Adds a `sourceMappingURL` comment to the CSS.
+  pub annotation: bool,
+  /// Includes the origin CSS in the map.
+  pub sources_content: bool,
+}
+
+impl Default for MapOptions {
+  fn default() -> Self {
+    Self {
+      inline: true,
+      annotation: true,
+      sources_content: true,
+    }
+  }
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
 
 impl<'a> Input<'a> {
-  pub fn new(css: &'a str, _opts: Option<ProcessOptions>) -> Input<'a> {
+  pub fn new(css: &'a str, opts: Option<ProcessOptions>) -> Input<'a> {
+    let file = opts.and_then(|opts| opts.from).map(|from| {
+      let path = Path::new(&from);
+      if path.is_absolute() {
+        from
+      } else {
+        match std::env::current_dir() {
+          Ok(dir) => dir.join(path).to_string_lossy().into_owned(),
+          Err(_) => from,
+        }
+      }
+    });
+    let id = match file {
+      Some(_) => None,
+      None => Some(format!(
+        "<input css {}>",
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+      )),
+    };
     Input {
-      css,
-      file: Some(String::new()),
-      id: Some(String::from("123")),
+      css: Cow::Borrowed(css),
+      file,
+      id,
       has_bom: false,
       line: 1,
       column: 1,
     }
   }
 
+  /// A copy of the input owning its CSS, for the trees that outlive the
+  /// parsed text.
+  pub fn to_owned_input<'b>(&self) -> Input<'b> {
+    Input {
+      css: Cow::Owned(self.css.to_string()),
+      file: self.file.clone(),
+      id: self.id.clone(),
+      has_bom: self.has_bom,
+      line: self.line,
+      column: self.column,
+    }
+  }
+
+  /// The absolute path to the CSS source file, if it was given in `from`.
+  pub fn file(&self) -> Option<&str> {
+    self.file.as_deref()
+  }
+
+  /// The file path, or the unique id of an input without a file.
   pub fn from(&self) -> String {
-    String::from("/home/ai/a.css")
+    self
+      .file
+      .clone()
+      .or_else(|| self.id.clone())
+      .unwrap_or_default()
   }
 
   pub fn origin(&self, line: u32, column: u32) -> Option<FilePosition> {
@@ -84,6 +158,14 @@ impl<'a> Input<'a> {
     })
   }
 
+  /// Creates a [`CssSyntaxError`] pointing at `line` and `column` of this input.
+  pub fn error(&self, reason: &str, line: usize, column: usize) -> CssSyntaxError {
+    let mut error = CssSyntaxError::new(reason, line as u32, column as u32);
+    error.file = self.file.clone().filter(|file| !file.is_empty());
+    error.source = Some(self.css.to_string());
+    error
+  }
+
   pub fn from_offset(&self, offset: u32) -> Option<Position> {
     Some(Position {
       line: 1,
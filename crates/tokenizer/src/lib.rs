@@ -1,5 +1,8 @@
+pub mod error;
 pub mod input;
+pub mod lookahead;
 mod tokenizer;
+pub mod warning;
 
 pub mod ref_ring;
 pub use crate::tokenizer::*;
@@ -0,0 +1,66 @@
+use crate::{Token, TokenType, Tokenizer};
+use std::collections::VecDeque;
+
+/// The tokens of a stylesheet, with the ones read ahead of the current
+/// position kept for the parsers that look past the next token.
+pub struct Lookahead<'a> {
+  tokenizer: Tokenizer<'a>,
+  /// Tokens read ahead of the current position.
+  tokens: VecDeque<Token<'a>>,
+}
+
+impl<'a> Lookahead<'a> {
+  pub fn new(css: &'a str) -> Self {
+    Self {
+      tokenizer: Tokenizer::new(css, false),
+      tokens: VecDeque::new(),
+    }
+  }
+
+  /// The token `n` tokens ahead of the current one.
+  pub fn nth(&mut self, n: usize) -> Option<Token<'a>> {
+    while self.tokens.len() <= n {
+      if self.tokenizer.end_of_file() {
+        return None;
+      }
+      let token = self.tokenizer.next_token(false);
+      self.tokens.push_back(token);
+    }
+    self.tokens.get(n).cloned()
+  }
+
+  /// Tells a rule nested in a block from a declaration: the rule reaches a
+  /// `{` before the `;` or `}` ending the declaration. Custom properties are
+  /// always declarations, `rule` is the answer for an unclosed block.
+  pub fn is_nested_rule(&mut self, rule: bool) -> bool {
+    use TokenType::*;
+    if matches!(self.nth(0), Some(Token(Word, content, ..)) if content.starts_with("--")) {
+      return false;
+    }
+    let mut depth = 0usize;
+    let mut n = 0;
+    loop {
+      match self.nth(n).map(|token| token.0) {
+        None => return rule,
+        Some(OpenParentheses | OpenSquare) => depth += 1,
+        Some(CloseParentheses | CloseSquare) => depth = depth.saturating_sub(1),
+        Some(OpenCurly) if depth == 0 => return true,
+        Some(Semicolon | CloseCurly) if depth == 0 => return false,
+        _ => {}
+      }
+      n += 1;
+    }
+  }
+}
+
+impl<'a> Iterator for Lookahead<'a> {
+  type Item = Token<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    match self.tokens.pop_front() {
+      Some(token) => Some(token),
+      None if self.tokenizer.end_of_file() => None,
+      None => Some(self.tokenizer.next_token(false)),
+    }
+  }
+}
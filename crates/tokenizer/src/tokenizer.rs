@@ -343,17 +343,18 @@ impl<'a> Tokenizer<'a> {
     current_token
   }
 
-  /// return (line, column), use rope for simplicity
+  /// return (line, column), both starting from 1, use rope for simplicity
   pub fn from_offset(&mut self, offset: usize) -> (usize, usize) {
     let rope = if let Some(ref rope) = self.rope {
       rope
     } else {
       self.rope = Some(ropey::Rope::from_str(self.css));
-      &self.rope.as_ref().unwrap()
+      self.rope.as_ref().unwrap()
     };
-    let column = rope.byte_to_char(offset);
+    let offset = offset.min(self.length);
     let line = rope.byte_to_line(offset);
-    (line, column)
+    let column = rope.byte_to_char(offset) - rope.line_to_char(line);
+    (line + 1, column + 1)
   }
 }
 
@@ -0,0 +1,40 @@
+use std::fmt;
+
+/// Represents a plugin's warning. It can be created using `Warning::new`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+  /// Type to filter warnings from messages, always `"warning"`.
+  pub r#type: String,
+  /// The warning message.
+  pub text: String,
+  /// The name of the plugin that created this warning.
+  pub plugin: Option<String>,
+  /// Absolute path to the file with this warning's source, if it has one.
+  pub file: Option<String>,
+  /// Line in the input file with this warning's source, starting from 1.
+  pub line: u32,
+  /// Column in the input file with this warning's source, starting from 1.
+  pub column: u32,
+}
+
+impl Warning {
+  pub fn new(text: &str, line: u32, column: u32) -> Self {
+    Self {
+      r#type: "warning".to_string(),
+      text: text.to_string(),
+      plugin: None,
+      file: None,
+      line,
+      column,
+    }
+  }
+}
+
+impl fmt::Display for Warning {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(plugin) = &self.plugin {
+      write!(f, "{}: ", plugin)?;
+    }
+    write!(f, "{}:{}: {}", self.line, self.column, self.text)
+  }
+}
@@ -1,8 +1,7 @@
 use tokenizer::input::Input;
 use tokenizer::*;
 fn tokenize(css: &str, ignore_errors: bool) -> Vec<Token> {
-  let input = Input::new(css, None);
-  let processor = Tokenizer::new(input.css, ignore_errors);
+  let processor = Tokenizer::new(css, ignore_errors);
   let mut tokens = vec![];
   while !processor.end_of_file() {
     tokens.push(processor.next_token(false))
@@ -388,8 +387,7 @@ fn tokenizes_hexadecimal_escape() {
 #[test]
 fn ignore_unclosed_per_token_request() {
   fn token(css: &str) -> Vec<Token> {
-    let input = Input::new(css, None);
-    let processor = Tokenizer::new(input.css, false);
+    let processor = Tokenizer::new(css, false);
     let mut tokens = vec![];
     while !processor.end_of_file() {
       tokens.push(processor.next_token(true))
@@ -415,7 +413,7 @@ fn ignore_unclosed_per_token_request() {
 fn provides_correct_position() {
   let css = "Three tokens";
   let input = Input::new(css, None);
-  let processor = Tokenizer::new(input.css, false);
+  let processor = Tokenizer::new(&input.css, false);
   assert_eq!(processor.position(), 0);
   processor.next_token(false);
   assert_eq!(processor.position(), 5);
@@ -0,0 +1,176 @@
+use std::fmt;
+
+/// A parsed declaration value, e.g. `1px solid rgb(0 0 0 / 50%)`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Value {
+  pub nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+  /// A keyword, number, dimension, hash or operator, e.g. `solid`, `-1.5em`,
+  /// `#fff` or `+`.
+  Word(String),
+  /// A quoted string, with the escapes of its content kept as written.
+  String {
+    quote: char,
+    value: String,
+    unclosed: bool,
+  },
+  /// A function, or a parenthesized block with an empty name.
+  Function(Function),
+  /// Whitespace, kept as written.
+  Space(String),
+  /// `,`, `/` or `:`.
+  Div(char),
+  /// A comment, without `/*` and `*/`.
+  Comment { value: String, unclosed: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Function {
+  pub name: String,
+  /// The arguments, with the whitespace around them. The unquoted URL of
+  /// `url()` is a single word.
+  pub nodes: Vec<Node>,
+  pub unclosed: bool,
+}
+
+impl Node {
+  pub fn word(value: &str) -> Self {
+    Node::Word(value.to_string())
+  }
+
+  pub fn space() -> Self {
+    Node::Space(" ".to_string())
+  }
+
+  pub fn is_space(&self) -> bool {
+    matches!(self, Node::Space(_) | Node::Comment { .. })
+  }
+
+  pub fn as_word(&self) -> Option<&str> {
+    match self {
+      Node::Word(word) => Some(word),
+      _ => None,
+    }
+  }
+
+  pub fn as_function(&self) -> Option<&Function> {
+    match self {
+      Node::Function(function) => Some(function),
+      _ => None,
+    }
+  }
+}
+
+impl Function {
+  pub fn new(name: &str, nodes: Vec<Node>) -> Self {
+    Self {
+      name: name.to_string(),
+      nodes,
+      unclosed: false,
+    }
+  }
+
+  /// The name in lowercase, for comparisons.
+  pub fn lower_name(&self) -> String {
+    self.name.to_ascii_lowercase()
+  }
+
+  /// The arguments between commas, with their whitespace.
+  pub fn args(&self) -> Vec<&[Node]> {
+    split(&self.nodes, ',')
+  }
+}
+
+/// Splits nodes at a top-level divider, e.g. the layers of a `background`
+/// at `,`.
+pub fn split(nodes: &[Node], div: char) -> Vec<&[Node]> {
+  nodes.split(|node| *node == Node::Div(div)).collect()
+}
+
+/// The nodes without leading and trailing whitespace and comments.
+pub fn trim(nodes: &[Node]) -> &[Node] {
+  let start = nodes
+    .iter()
+    .position(|node| !node.is_space())
+    .unwrap_or(nodes.len());
+  let end = nodes
+    .iter()
+    .rposition(|node| !node.is_space())
+    .map_or(start, |end| end + 1);
+  &nodes[start..end]
+}
+
+/// Calls `visit` with the nodes and their descendants, depth first, and
+/// descends into a function only if `visit` returns `true` for it.
+pub fn walk<F: FnMut(&mut Node) -> bool>(nodes: &mut [Node], visit: &mut F) {
+  for node in nodes {
+    if visit(node) {
+      if let Node::Function(function) = node {
+        walk(&mut function.nodes, visit);
+      }
+    }
+  }
+}
+
+impl Value {
+  /// See [`walk`].
+  pub fn walk<F: FnMut(&mut Node) -> bool>(&mut self, mut visit: F) {
+    walk(&mut self.nodes, &mut visit);
+  }
+}
+
+impl fmt::Display for Value {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write_nodes(f, &self.nodes)
+  }
+}
+
+fn write_nodes(f: &mut fmt::Formatter, nodes: &[Node]) -> fmt::Result {
+  for node in nodes {
+    write!(f, "{}", node)?;
+  }
+  Ok(())
+}
+
+impl fmt::Display for Node {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Node::Word(word) => write!(f, "{}", word),
+      Node::String {
+        quote,
+        value,
+        unclosed,
+      } => {
+        write!(f, "{}{}", quote, value)?;
+        if !unclosed {
+          write!(f, "{}", quote)?;
+        }
+        Ok(())
+      }
+      Node::Function(function) => write!(f, "{}", function),
+      Node::Space(space) => write!(f, "{}", space),
+      Node::Div(div) => write!(f, "{}", div),
+      Node::Comment { value, unclosed } => {
+        write!(f, "/*{}", value)?;
+        if !unclosed {
+          write!(f, "*/")?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+impl fmt::Display for Function {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}(", self.name)?;
+    write_nodes(f, &self.nodes)?;
+    if !self.unclosed {
+      write!(f, ")")?;
+    }
+    Ok(())
+  }
+}
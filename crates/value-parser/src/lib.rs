@@ -0,0 +1,17 @@
+mod ast;
+mod number;
+mod parser;
+
+pub use crate::ast::*;
+pub use crate::number::{dimension, format_number, Dimension};
+
+use crate::parser::Parser;
+
+/// Parses a declaration value, such as the `value` of a `Declaration`.
+///
+/// Any input is accepted, and the value prints back exactly as written.
+pub fn parse(value: &str) -> Value {
+  Value {
+    nodes: Parser::new(value).parse_nodes(),
+  }
+}
@@ -0,0 +1,64 @@
+/// A number with its unit, `""` for a plain number and `"%"` for a
+/// percentage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dimension<'a> {
+  pub value: f64,
+  pub unit: &'a str,
+}
+
+/// Splits a word like `-1.5e2px` into its number and unit, or returns `None`
+/// if it does not start with a number or the unit is not an identifier.
+pub fn dimension(word: &str) -> Option<Dimension<'_>> {
+  let bytes = word.as_bytes();
+  let digits = |mut i: usize| {
+    while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+      i += 1;
+    }
+    i
+  };
+  let mut end = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+  let integer = digits(end);
+  let mut has_digits = integer > end;
+  end = integer;
+  if bytes.get(end) == Some(&b'.') {
+    let fraction = digits(end + 1);
+    if fraction > end + 1 {
+      has_digits = true;
+      end = fraction;
+    }
+  }
+  if !has_digits {
+    return None;
+  }
+  if matches!(bytes.get(end), Some(b'e' | b'E')) {
+    let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+    let exponent = digits(end + 1 + sign);
+    if exponent > end + 1 + sign {
+      end = exponent;
+    }
+  }
+  let value = word[..end].parse().ok()?;
+  let unit = &word[end..];
+  let valid = match unit.as_bytes() {
+    [] | [b'%'] => true,
+    [b'-', first, ..] | [first, ..] => {
+      first.is_ascii_alphabetic() || *first == b'_' || *first >= 0x80
+    }
+  };
+  valid.then_some(Dimension { value, unit })
+}
+
+/// Formats a number with at most `precision` decimals, without trailing
+/// zeros, e.g. `0.33333` for a third with a precision of 5.
+pub fn format_number(value: f64, precision: u32) -> String {
+  let factor = 10f64.powi(precision as i32);
+  let rounded = (value * factor).round() / factor;
+  let mut text = format!("{:.*}", precision as usize, rounded);
+  if text.contains('.') {
+    text = text.trim_end_matches('0').trim_end_matches('.').to_string();
+  }
+  if text == "-0" {
+    text = "0".to_string();
+  }
+  text
+}
@@ -0,0 +1,173 @@
+use crate::ast::{Function, Node};
+
+pub(crate) struct Parser<'a> {
+  input: &'a str,
+  pos: usize,
+}
+
+fn is_space(byte: u8) -> bool {
+  matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'\x0c')
+}
+
+/// Returns `true` for the bytes that end a word.
+fn is_delimiter(byte: u8) -> bool {
+  is_space(byte) || matches!(byte, b'"' | b'\'' | b'(' | b')' | b',' | b'/' | b':')
+}
+
+impl<'a> Parser<'a> {
+  pub(crate) fn new(input: &'a str) -> Self {
+    Self { input, pos: 0 }
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.input.as_bytes().get(self.pos).copied()
+  }
+
+  fn rest(&self) -> &'a str {
+    &self.input[self.pos..]
+  }
+
+  /// Parses nodes up to the end of the input, and the `)` closing a
+  /// function when `nested`, which it consumes. Returns `true` if it was
+  /// closed.
+  fn parse_until_close(&mut self, nested: bool) -> (Vec<Node>, bool) {
+    let mut nodes = vec![];
+    while let Some(byte) = self.peek() {
+      let node = match byte {
+        b')' if nested => {
+          self.pos += 1;
+          return (nodes, true);
+        }
+        b')' => {
+          self.pos += 1;
+          Node::word(")")
+        }
+        b',' | b'/' | b':' if !self.rest().starts_with("/*") => {
+          self.pos += 1;
+          Node::Div(byte as char)
+        }
+        b'/' => self.parse_comment(),
+        b'"' | b'\'' => self.parse_string(byte),
+        byte if is_space(byte) => {
+          let start = self.pos;
+          while self.peek().is_some_and(is_space) {
+            self.pos += 1;
+          }
+          Node::Space(self.input[start..self.pos].to_string())
+        }
+        b'(' => {
+          self.pos += 1;
+          Node::Function(self.parse_function(""))
+        }
+        _ => {
+          let word = self.parse_word();
+          if self.peek() == Some(b'(') {
+            self.pos += 1;
+            Node::Function(self.parse_function(word))
+          } else {
+            Node::word(word)
+          }
+        }
+      };
+      nodes.push(node);
+    }
+    (nodes, false)
+  }
+
+  pub(crate) fn parse_nodes(&mut self) -> Vec<Node> {
+    self.parse_until_close(false).0
+  }
+
+  /// Parses the arguments of a function, after its `(`.
+  fn parse_function(&mut self, name: &str) -> Function {
+    if name.eq_ignore_ascii_case("url") {
+      let rest = self.rest();
+      let content = rest.trim_start_matches(|c: char| is_space(c as u8));
+      if !content.starts_with(['"', '\'']) {
+        let (end, unclosed) = match rest.find(')') {
+          Some(end) => (end, false),
+          None => (rest.len(), true),
+        };
+        self.pos += end + usize::from(!unclosed);
+        let nodes = match &rest[..end] {
+          "" => vec![],
+          url => vec![Node::word(url)],
+        };
+        return Function {
+          name: name.to_string(),
+          nodes,
+          unclosed,
+        };
+      }
+    }
+    let (nodes, closed) = self.parse_until_close(true);
+    Function {
+      name: name.to_string(),
+      nodes,
+      unclosed: !closed,
+    }
+  }
+
+  fn parse_word(&mut self) -> &'a str {
+    let start = self.pos;
+    let bytes = self.input.as_bytes();
+    while let Some(&byte) = bytes.get(self.pos) {
+      if byte == b'\\' {
+        self.pos = (self.pos + 2).min(bytes.len());
+        // Escaped multi-byte characters are part of the word too.
+        while !self.input.is_char_boundary(self.pos) {
+          self.pos += 1;
+        }
+      } else if is_delimiter(byte) {
+        break;
+      } else {
+        self.pos += 1;
+      }
+    }
+    &self.input[start..self.pos]
+  }
+
+  fn parse_string(&mut self, quote: u8) -> Node {
+    self.pos += 1;
+    let start = self.pos;
+    let bytes = self.input.as_bytes();
+    while let Some(&byte) = bytes.get(self.pos) {
+      if byte == quote {
+        let value = self.input[start..self.pos].to_string();
+        self.pos += 1;
+        return Node::String {
+          quote: quote as char,
+          value,
+          unclosed: false,
+        };
+      }
+      self.pos += if byte == b'\\' { 2 } else { 1 };
+    }
+    self.pos = bytes.len();
+    Node::String {
+      quote: quote as char,
+      value: self.input[start..].to_string(),
+      unclosed: true,
+    }
+  }
+
+  fn parse_comment(&mut self) -> Node {
+    let rest = &self.rest()[2..];
+    match rest.find("*/") {
+      Some(end) => {
+        self.pos += end + 4;
+        Node::Comment {
+          value: rest[..end].to_string(),
+          unclosed: false,
+        }
+      }
+      None => {
+        self.pos = self.input.len();
+        Node::Comment {
+          value: rest.to_string(),
+          unclosed: true,
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,110 @@
+use value_parser::*;
+
+fn roundtrip(value: &str) {
+  assert_eq!(parse(value).to_string(), value);
+}
+
+#[test]
+fn parses_values() {
+  let value = parse("1px solid rgba(0, 0, 0, .5)");
+  assert_eq!(
+    value.nodes,
+    vec![
+      Node::word("1px"),
+      Node::space(),
+      Node::word("solid"),
+      Node::space(),
+      Node::Function(Function::new(
+        "rgba",
+        vec![
+          Node::word("0"),
+          Node::Div(','),
+          Node::space(),
+          Node::word("0"),
+          Node::Div(','),
+          Node::space(),
+          Node::word("0"),
+          Node::Div(','),
+          Node::space(),
+          Node::word(".5"),
+        ]
+      )),
+    ]
+  );
+  let function = value.nodes[4].as_function().unwrap();
+  assert_eq!(function.args().len(), 4);
+  assert_eq!(trim(function.args()[3]), [Node::word(".5")]);
+
+  assert_eq!(
+    parse("\"a\\\"b\"/*c*/ url( a b.png )").nodes,
+    vec![
+      Node::String {
+        quote: '"',
+        value: "a\\\"b".into(),
+        unclosed: false
+      },
+      Node::Comment {
+        value: "c".into(),
+        unclosed: false
+      },
+      Node::space(),
+      Node::Function(Function::new("url", vec![Node::word(" a b.png ")])),
+    ]
+  );
+}
+
+#[test]
+fn roundtrips_values() {
+  roundtrip("calc( (100% - 2*var(--gap, 1px)) / 3 )");
+  roundtrip("url('a.png') , url(b.png)   no-repeat");
+  roundtrip("16px/1.5 \"Helvetica Neue\", sans-serif");
+  roundtrip("progid:DXImageTransform.Microsoft.gradient(startColorstr='#000')");
+  roundtrip("a\\(b) \"unclosed");
+  roundtrip("rgb(1 2 3 /* comment");
+  roundtrip("fn(a, (b c)");
+  roundtrip("été \\é");
+}
+
+#[test]
+fn walks_values() {
+  let mut value = parse("a(b c(d)) e(f)");
+  let mut names = vec![];
+  value.walk(|node| match node {
+    Node::Function(function) => {
+      names.push(function.name.clone());
+      function.name != "a"
+    }
+    Node::Word(word) => {
+      *word = word.to_uppercase();
+      false
+    }
+    _ => false,
+  });
+  assert_eq!(names, ["a", "e"]);
+  assert_eq!(value.to_string(), "a(b c(d)) e(F)");
+}
+
+#[test]
+fn parses_dimensions() {
+  let dim = |value, unit| Some(Dimension { value, unit });
+  assert_eq!(dimension("-1.5em"), dim(-1.5, "em"));
+  assert_eq!(dimension("+.5"), dim(0.5, ""));
+  assert_eq!(dimension("1e3px"), dim(1000.0, "px"));
+  assert_eq!(dimension("2E-1"), dim(0.2, ""));
+  assert_eq!(dimension("50%"), dim(50.0, "%"));
+  assert_eq!(dimension("1-x"), dim(1.0, "-x"));
+  assert_eq!(dimension("10."), None);
+  assert_eq!(dimension("1+2"), None);
+  assert_eq!(dimension("#fff"), None);
+  assert_eq!(dimension("-"), None);
+}
+
+#[test]
+fn formats_numbers() {
+  assert_eq!(format_number(1.0 / 3.0, 5), "0.33333");
+  assert_eq!(format_number(2.0 / 3.0, 2), "0.67");
+  assert_eq!(format_number(12.0, 5), "12");
+  assert_eq!(format_number(-0.000001, 5), "0");
+  assert_eq!(format_number(1.25, 0), "1");
+  assert_eq!(format_number(-12.5, 3), "-12.5");
+}
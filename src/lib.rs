@@ -1,4 +0,0 @@
-pub mod error;
-// pub mod stringify;
-// mod ast;
-pub mod warning;
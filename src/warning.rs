@@ -1,9 +0,0 @@
-#[derive(Debug)]
-pub struct Warning {
-  r#type: String,
-  text: String,
-  line: u32,
-  column: u32,
-}
-
-// todo: toString